@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+
+/// Represents the event emitted when the operator of a program account changes.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OperatorshipTransferredEvent {
+    /// The account whose operator changed (e.g. the gateway root PDA)
+    pub config: Pubkey,
+    /// The operator before the transfer
+    pub previous_operator: Pubkey,
+    /// The operator after the transfer
+    pub new_operator: Pubkey,
+}
+
+/// Implemented by program state accounts that have a single operator.
+pub trait Ownable {
+    fn operator(&self) -> Pubkey;
+
+    fn set_operator(&mut self, operator: Pubkey);
+
+    /// Fails unless `signer` is the current operator.
+    fn ensure_operator(&self, signer: &Pubkey) -> Result<()> {
+        require_keys_eq!(self.operator(), *signer, OperatorError::NotOperator);
+        Ok(())
+    }
+
+    /// Hands operatorship to `new_operator` after checking `signer` is the current operator.
+    fn transfer_operatorship(
+        &mut self,
+        config: Pubkey,
+        signer: &Pubkey,
+        new_operator: Pubkey,
+    ) -> Result<OperatorshipTransferredEvent> {
+        self.ensure_operator(signer)?;
+        require_keys_neq!(
+            new_operator,
+            Pubkey::default(),
+            OperatorError::InvalidOperator
+        );
+        let previous_operator = self.operator();
+        self.set_operator(new_operator);
+        Ok(OperatorshipTransferredEvent {
+            config,
+            previous_operator,
+            new_operator,
+        })
+    }
+}
+
+/// Fails unless `signer` is the upgrade authority recorded in `program_data`,
+/// for instructions only the deployer may call, such as creating a config.
+pub fn ensure_upgrade_authority(program_data: &ProgramData, signer: &Pubkey) -> Result<()> {
+    require!(
+        program_data.upgrade_authority_address == Some(*signer),
+        OperatorError::NotUpgradeAuthority
+    );
+    Ok(())
+}
+
+// Offset keeps these codes clear of each program's own `#[error_code]` enum
+#[error_code(offset = 9000)]
+pub enum OperatorError {
+    #[msg("Signer is not the operator")]
+    NotOperator,
+    #[msg("New operator cannot be the default pubkey")]
+    InvalidOperator,
+    #[msg("Signer is not the program's upgrade authority")]
+    NotUpgradeAuthority,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Config {
+        operator: Pubkey,
+    }
+
+    impl Ownable for Config {
+        fn operator(&self) -> Pubkey {
+            self.operator
+        }
+
+        fn set_operator(&mut self, operator: Pubkey) {
+            self.operator = operator;
+        }
+    }
+
+    fn code(result: Result<impl std::fmt::Debug>) -> u32 {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => error.error_code_number,
+            Error::ProgramError(error) => panic!("unexpected program error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn error_codes_start_at_the_offset() {
+        assert_eq!(u32::from(OperatorError::NotOperator), 9000);
+        assert_eq!(u32::from(OperatorError::InvalidOperator), 9001);
+        assert_eq!(u32::from(OperatorError::NotUpgradeAuthority), 9002);
+    }
+
+    #[test]
+    fn only_the_operator_passes() {
+        let operator = Pubkey::new_unique();
+        let config = Config { operator };
+        assert!(config.ensure_operator(&operator).is_ok());
+        assert_eq!(
+            code(config.ensure_operator(&Pubkey::new_unique())),
+            u32::from(OperatorError::NotOperator)
+        );
+    }
+
+    #[test]
+    fn transfer_hands_over_and_reports_both_operators() {
+        let operator = Pubkey::new_unique();
+        let new_operator = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut config = Config { operator };
+
+        let event = config
+            .transfer_operatorship(key, &operator, new_operator)
+            .unwrap();
+        assert_eq!(
+            event,
+            OperatorshipTransferredEvent {
+                config: key,
+                previous_operator: operator,
+                new_operator,
+            }
+        );
+        assert_eq!(config.operator(), new_operator);
+        // The previous operator has no say any more
+        assert_eq!(
+            code(config.transfer_operatorship(key, &operator, operator)),
+            u32::from(OperatorError::NotOperator)
+        );
+    }
+
+    #[test]
+    fn transfer_rejects_the_default_pubkey() {
+        let operator = Pubkey::new_unique();
+        let mut config = Config { operator };
+        assert_eq!(
+            code(config.transfer_operatorship(Pubkey::new_unique(), &operator, Pubkey::default())),
+            u32::from(OperatorError::InvalidOperator)
+        );
+        assert_eq!(config.operator(), operator);
+    }
+
+    #[test]
+    fn only_the_upgrade_authority_passes() {
+        let authority = Pubkey::new_unique();
+        let program_data = ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(authority),
+        };
+        assert!(ensure_upgrade_authority(&program_data, &authority).is_ok());
+        assert_eq!(
+            code(ensure_upgrade_authority(
+                &program_data,
+                &Pubkey::new_unique()
+            )),
+            u32::from(OperatorError::NotUpgradeAuthority)
+        );
+
+        // A program made immutable has no one to init it
+        let immutable = ProgramData {
+            slot: 0,
+            upgrade_authority_address: None,
+        };
+        assert_eq!(
+            code(ensure_upgrade_authority(&immutable, &authority)),
+            u32::from(OperatorError::NotUpgradeAuthority)
+        );
+    }
+}
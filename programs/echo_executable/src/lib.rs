@@ -0,0 +1,168 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use program_tester::{IncomingMessage, Message};
+
+declare_id!("8831JUdSrKckGUptTDKKueoE78Q4jZy7qqrQrTxaVisU");
+
+/// Represents the event emitted when an executed payload is sent back to its source chain.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PayloadEchoedEvent {
+    /// Command id of the approved message that was executed
+    pub command_id: [u8; 32],
+    /// Chain the message came from and is echoed back to
+    pub source_chain: String,
+    /// Address the message came from and is echoed back to
+    pub source_address: String,
+    /// Hash of the echoed payload
+    pub payload_hash: [u8; 32],
+}
+
+#[program]
+pub mod echo_executable {
+    use super::*;
+
+    pub fn execute(
+        ctx: Context<Execute>,
+        command_id: [u8; 32],
+        message: Message,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        let incoming_message = &ctx.accounts.incoming_message;
+        let payload_hash = solana_program::keccak::hash(&payload).to_bytes();
+        require!(
+            incoming_message.status.is_approved(),
+            EchoError::MessageNotApproved
+        );
+        require!(
+            message.command_id() == command_id && message.hash() == incoming_message.message_hash,
+            EchoError::MessageMismatch
+        );
+        require!(
+            incoming_message.payload_hash == payload_hash,
+            EchoError::PayloadHashMismatch
+        );
+
+        // Each approved message is echoed once
+        ctx.accounts
+            .consumed_message_pda
+            .set_inner(ConsumedMessage {
+                bump: ctx.bumps.consumed_message_pda,
+            });
+        let source_chain = message.cc_id.chain;
+        let source_address = message.source_address;
+
+        // Create the CPI context for calling program_tester's call_contract
+        let cpi_program = ctx.accounts.program_tester_program.to_account_info();
+        let cpi_accounts = program_tester::cpi::accounts::CallContract {
+            calling_program: ctx.accounts.echo_program.to_account_info(),
+            signing_pda: ctx.accounts.signing_pda.to_account_info(),
+            gateway_root_pda: ctx.accounts.gateway_root_pda.to_account_info(),
+            payload_log: ctx
+                .accounts
+                .payload_log
+                .as_ref()
+                .map(|payload_log| payload_log.to_account_info()),
+            event_authority: ctx.accounts.gateway_event_authority.to_account_info(),
+            program: ctx.accounts.program_tester_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        // Send the same payload back to where it came from
+        program_tester::cpi::call_contract(
+            cpi_ctx,
+            source_chain.clone(),
+            source_address.clone(),
+            payload_hash,
+            payload,
+        )?;
+
+        anchor_lang::prelude::emit_cpi!(PayloadEchoedEvent {
+            command_id,
+            source_chain,
+            source_address,
+            payload_hash,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(command_id: [u8; 32])]
+pub struct Execute<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The approved message being executed
+    #[account(
+        seeds = [program_tester::seed_prefixes::INCOMING_MESSAGE_SEED, command_id.as_ref()],
+        bump = incoming_message.bump,
+        seeds::program = program_tester::ID
+    )]
+    pub incoming_message: Account<'info, IncomingMessage>,
+
+    /// Marks the message as echoed, so it can't be executed again
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<ConsumedMessage>(),
+        seeds = [seed_prefixes::CONSUMED_MESSAGE_SEED, command_id.as_ref()],
+        bump
+    )]
+    pub consumed_message_pda: Account<'info, ConsumedMessage>,
+
+    /// The program_tester program we're calling via CPI
+    /// CHECK: This is the program_tester program ID
+    #[account(address = program_tester::ID)]
+    pub program_tester_program: UncheckedAccount<'info>,
+
+    /// The echo program itself (used as calling_program)
+    /// CHECK: This is the echo program, verified by constraint
+    #[account(executable, address = crate::ID)]
+    pub echo_program: UncheckedAccount<'info>,
+
+    /// The signing PDA for the CPI call
+    /// CHECK: This PDA is derived from the echo program
+    pub signing_pda: UncheckedAccount<'info>,
+
+    /// The gateway root PDA from program_tester
+    /// CHECK: This is validated by the program_tester program
+    pub gateway_root_pda: UncheckedAccount<'info>,
+
+    /// The payload log PDA from program_tester, if it has been created
+    /// CHECK: This is validated by the program_tester program
+    #[account(mut)]
+    pub payload_log: Option<UncheckedAccount<'info>>,
+
+    /// Event authority of program_tester for its CPI event emission
+    /// CHECK: This is the event authority PDA for event-cpi
+    pub gateway_event_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Marks an incoming message as echoed.
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConsumedMessage {
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum EchoError {
+    #[msg("Incoming message is not approved")]
+    MessageNotApproved,
+    #[msg("Payload does not match the approved payload hash")]
+    PayloadHashMismatch,
+    #[msg("Message is not the approved incoming message")]
+    MessageMismatch,
+}
+
+pub mod seed_prefixes {
+    /// The seed prefix for deriving consumed message PDAs, keyed by command id
+    pub const CONSUMED_MESSAGE_SEED: &[u8] = b"consumed-message";
+}
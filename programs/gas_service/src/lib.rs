@@ -1,5 +1,9 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::pubkey::Pubkey;
+use common::Ownable;
 
 declare_id!("CJ9f8WFdm3q38pmg426xQf7uum7RqvrmS9R58usHwNX7");
 
@@ -23,7 +27,6 @@ pub struct GasPaidEvent {
     pub spl_token_account: Option<Pubkey>,
 }
 
-type MessageId = String;
 /// Represents the event emitted when native gas is added.
 #[event]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -71,6 +74,11 @@ pub mod gas_service {
             calling_program: ctx.accounts.gas_service_program.to_account_info(),
             signing_pda: ctx.accounts.signing_pda.to_account_info(),
             gateway_root_pda: ctx.accounts.gateway_root_pda.to_account_info(),
+            payload_log: ctx
+                .accounts
+                .payload_log
+                .as_ref()
+                .map(|payload_log| payload_log.to_account_info()),
             event_authority: ctx.accounts.event_authority.to_account_info(),
             program: ctx.accounts.program_tester_program.to_account_info(),
         };
@@ -88,6 +96,30 @@ pub mod gas_service {
         Ok(())
     }
 
+    /// Only the program's upgrade authority may create the config.
+    pub fn init_config(ctx: Context<InitConfig>, operator: Pubkey) -> Result<()> {
+        common::ensure_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.payer.key())?;
+        ctx.accounts.config_pda.set_inner(GasConfig {
+            operator,
+            bump: ctx.bumps.config_pda,
+        });
+        Ok(())
+    }
+
+    pub fn transfer_operatorship(
+        ctx: Context<TransferOperatorship>,
+        new_operator: Pubkey,
+    ) -> Result<()> {
+        let config_key = ctx.accounts.config_pda.key();
+        let event = ctx.accounts.config_pda.transfer_operatorship(
+            config_key,
+            &ctx.accounts.operator.key(),
+            new_operator,
+        )?;
+        anchor_lang::prelude::emit_cpi!(event);
+        Ok(())
+    }
+
     pub fn pay_native_for_contract_call(
         ctx: Context<PayNativeForContractCall>,
         destination_chain: String,
@@ -143,6 +175,37 @@ pub mod gas_service {
     }
 }
 
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<GasConfig>(),
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump
+    )]
+    pub config_pda: Account<'info, GasConfig>,
+    #[account(constraint = this_program.programdata_address()? == Some(program_data.key()))]
+    pub this_program: Program<'info, crate::program::GasService>,
+    /// Names the upgrade authority, the only signer allowed to init
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferOperatorship<'info> {
+    pub operator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump = config_pda.bump
+    )]
+    pub config_pda: Account<'info, GasConfig>,
+}
+
 #[event_cpi]
 #[derive(Accounts)]
 pub struct PayNativeForContractCall<'info> {
@@ -196,9 +259,37 @@ pub struct CpiCallContract<'info> {
     /// CHECK: This is validated by the program_tester program
     pub gateway_root_pda: UncheckedAccount<'info>,
 
+    /// The payload log PDA from program_tester, if it has been created
+    /// CHECK: This is validated by the program_tester program
+    #[account(mut)]
+    pub payload_log: Option<UncheckedAccount<'info>>,
+
     /// Event authority for CPI event emission
     /// CHECK: This is the event authority PDA for event-cpi
     pub event_authority: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
 }
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct GasConfig {
+    /// The operator allowed to manage the gas service
+    pub operator: Pubkey,
+    pub bump: u8,
+}
+
+impl Ownable for GasConfig {
+    fn operator(&self) -> Pubkey {
+        self.operator
+    }
+
+    fn set_operator(&mut self, operator: Pubkey) {
+        self.operator = operator;
+    }
+}
+
+pub mod seed_prefixes {
+    /// The seed prefix for deriving the gas service config PDA
+    pub const CONFIG_SEED: &[u8] = b"config";
+}
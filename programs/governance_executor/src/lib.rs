@@ -0,0 +1,332 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use common::Ownable;
+use program_tester::{IncomingMessage, Message};
+
+declare_id!("7WaoahnAduK6Wair96yzozRGncLeWczxQ5rFM327Y9o1");
+
+/// Represents the event emitted when an approved governance proposal is scheduled.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProposalScheduledEvent {
+    /// Hash of the borsh-serialized `GovernanceAction`
+    pub proposal_hash: [u8; 32],
+    /// Unix timestamp after which the proposal can be executed
+    pub eta: i64,
+}
+
+/// Represents the event emitted when a scheduled proposal is executed.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProposalExecutedEvent {
+    /// Hash of the borsh-serialized `GovernanceAction`
+    pub proposal_hash: [u8; 32],
+}
+
+/// Represents the event emitted when the governance minimum time delay changes.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MinimumTimeDelayUpdatedEvent {
+    /// The delay before the update, in seconds
+    pub previous_delay: u64,
+    /// The delay after the update, in seconds
+    pub new_delay: u64,
+}
+
+/// Represents the event emitted when a governance-approved operator takes over.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OperatorshipAcceptedEvent {
+    /// The operator before the change
+    pub previous_operator: Pubkey,
+    /// The operator after the change
+    pub new_operator: Pubkey,
+}
+
+#[program]
+pub mod governance_executor {
+    use super::*;
+
+    /// Creates the config, taking proposals only from `governance_address`
+    /// on `governance_chain`. Only the program's upgrade authority may call it.
+    pub fn init_config(
+        ctx: Context<InitConfig>,
+        operator: Pubkey,
+        governance_chain: String,
+        governance_address: String,
+        minimum_time_delay: u64,
+    ) -> Result<()> {
+        common::ensure_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.payer.key())?;
+        ctx.accounts.config_pda.set_inner(GovernanceConfig {
+            operator,
+            minimum_time_delay,
+            chain_hash: solana_program::keccak::hash(governance_chain.as_bytes()).to_bytes(),
+            address_hash: solana_program::keccak::hash(governance_address.as_bytes()).to_bytes(),
+            bump: ctx.bumps.config_pda,
+        });
+        Ok(())
+    }
+
+    /// Schedules the proposal carried by the approved `message`. Each message
+    /// schedules once: its command id is recorded as consumed.
+    pub fn schedule_proposal(
+        ctx: Context<ScheduleProposal>,
+        command_id: [u8; 32],
+        proposal_hash: [u8; 32],
+        message: Message,
+        payload: GovernancePayload,
+    ) -> Result<()> {
+        let incoming_message = &ctx.accounts.incoming_message;
+        require!(
+            incoming_message.status.is_approved(),
+            GovernanceError::MessageNotApproved
+        );
+        require!(
+            message.command_id() == command_id && message.hash() == incoming_message.message_hash,
+            GovernanceError::MessageMismatch
+        );
+
+        let config = &ctx.accounts.config_pda;
+        require!(
+            solana_program::keccak::hash(message.cc_id.chain.as_bytes()).to_bytes()
+                == config.chain_hash
+                && solana_program::keccak::hash(message.source_address.as_bytes()).to_bytes()
+                    == config.address_hash,
+            GovernanceError::NotGovernanceSource
+        );
+        require!(
+            incoming_message.payload_hash == payload.hash(),
+            GovernanceError::PayloadHashMismatch
+        );
+        require!(
+            payload.action.hash() == proposal_hash,
+            GovernanceError::ProposalHashMismatch
+        );
+
+        // Mocked timelock: never allow execution before the minimum delay elapses
+        let earliest =
+            Clock::get()?.unix_timestamp + ctx.accounts.config_pda.minimum_time_delay as i64;
+        let eta = payload.eta.max(earliest);
+
+        ctx.accounts
+            .consumed_message_pda
+            .set_inner(ConsumedMessage {
+                bump: ctx.bumps.consumed_message_pda,
+            });
+        ctx.accounts.proposal_pda.set_inner(Proposal {
+            proposal_hash,
+            eta,
+            bump: ctx.bumps.proposal_pda,
+        });
+
+        anchor_lang::prelude::emit_cpi!(ProposalScheduledEvent { proposal_hash, eta });
+        Ok(())
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>, action: GovernanceAction) -> Result<()> {
+        let proposal_hash = action.hash();
+        require!(
+            ctx.accounts.proposal_pda.proposal_hash == proposal_hash,
+            GovernanceError::ProposalHashMismatch
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= ctx.accounts.proposal_pda.eta,
+            GovernanceError::TimelockNotExpired
+        );
+
+        let config = &mut ctx.accounts.config_pda;
+        match action {
+            GovernanceAction::UpdateMinimumTimeDelay { new_delay } => {
+                let previous_delay = config.minimum_time_delay;
+                config.minimum_time_delay = new_delay;
+                anchor_lang::prelude::emit_cpi!(MinimumTimeDelayUpdatedEvent {
+                    previous_delay,
+                    new_delay,
+                });
+            }
+            GovernanceAction::TransferOperatorship { new_operator } => {
+                let previous_operator = config.operator();
+                config.set_operator(new_operator);
+                anchor_lang::prelude::emit_cpi!(OperatorshipAcceptedEvent {
+                    previous_operator,
+                    new_operator,
+                });
+            }
+        }
+
+        anchor_lang::prelude::emit_cpi!(ProposalExecutedEvent { proposal_hash });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<GovernanceConfig>(),
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump
+    )]
+    pub config_pda: Account<'info, GovernanceConfig>,
+    #[account(constraint = this_program.programdata_address()? == Some(program_data.key()))]
+    pub this_program: Program<'info, crate::program::GovernanceExecutor>,
+    /// Names the upgrade authority, the only signer allowed to init
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(command_id: [u8; 32], proposal_hash: [u8; 32])]
+pub struct ScheduleProposal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump = config_pda.bump
+    )]
+    pub config_pda: Account<'info, GovernanceConfig>,
+    /// The gateway-approved message carrying the governance payload
+    #[account(
+        seeds = [program_tester::seed_prefixes::INCOMING_MESSAGE_SEED, command_id.as_ref()],
+        bump = incoming_message.bump,
+        seeds::program = program_tester::ID
+    )]
+    pub incoming_message: Account<'info, IncomingMessage>,
+    /// Exists once the message has scheduled its proposal, so it can't again
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<ConsumedMessage>(),
+        seeds = [seed_prefixes::CONSUMED_MESSAGE_SEED, command_id.as_ref()],
+        bump
+    )]
+    pub consumed_message_pda: Account<'info, ConsumedMessage>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Proposal>(),
+        seeds = [seed_prefixes::PROPOSAL_SEED, proposal_hash.as_ref()],
+        bump
+    )]
+    pub proposal_pda: Account<'info, Proposal>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump = config_pda.bump
+    )]
+    pub config_pda: Account<'info, GovernanceConfig>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [seed_prefixes::PROPOSAL_SEED, proposal_pda.proposal_hash.as_ref()],
+        bump = proposal_pda.bump
+    )]
+    pub proposal_pda: Account<'info, Proposal>,
+}
+
+/// The actions a governance proposal can carry.
+#[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
+pub enum GovernanceAction {
+    UpdateMinimumTimeDelay { new_delay: u64 },
+    TransferOperatorship { new_operator: Pubkey },
+}
+
+impl GovernanceAction {
+    pub fn hash(&self) -> [u8; 32] {
+        let data = self.try_to_vec().expect("Serialization should not fail");
+        solana_program::keccak::hash(&data).to_bytes()
+    }
+}
+
+/// The GMP payload the governance chain sends through the gateway.
+#[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
+pub struct GovernancePayload {
+    pub action: GovernanceAction,
+    /// Requested execution time; raised to the minimum delay if too early
+    pub eta: i64,
+}
+
+impl GovernancePayload {
+    pub fn hash(&self) -> [u8; 32] {
+        let data = self.try_to_vec().expect("Serialization should not fail");
+        solana_program::keccak::hash(&data).to_bytes()
+    }
+}
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct GovernanceConfig {
+    pub operator: Pubkey,
+    /// Seconds that must pass between scheduling and executing a proposal
+    pub minimum_time_delay: u64,
+    /// Keccak of the name of the chain proposals must come from
+    pub chain_hash: [u8; 32],
+    /// Keccak of the governance contract's address on that chain
+    pub address_hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl Ownable for GovernanceConfig {
+    fn operator(&self) -> Pubkey {
+        self.operator
+    }
+
+    fn set_operator(&mut self, operator: Pubkey) {
+        self.operator = operator;
+    }
+}
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Proposal {
+    pub proposal_hash: [u8; 32],
+    pub eta: i64,
+    pub bump: u8,
+}
+
+/// Marks an incoming message as having scheduled its proposal.
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConsumedMessage {
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum GovernanceError {
+    #[msg("Incoming message is not approved")]
+    MessageNotApproved,
+    #[msg("Governance payload does not match the approved payload hash")]
+    PayloadHashMismatch,
+    #[msg("Action does not match the proposal hash")]
+    ProposalHashMismatch,
+    #[msg("Proposal timelock has not expired yet")]
+    TimelockNotExpired,
+    #[msg("Message is not the approved incoming message")]
+    MessageMismatch,
+    #[msg("Message is not from the governance chain and address")]
+    NotGovernanceSource,
+}
+
+pub mod seed_prefixes {
+    /// The seed prefix for deriving the governance config PDA
+    pub const CONFIG_SEED: &[u8] = b"governance";
+    /// The seed prefix for deriving scheduled proposal PDAs
+    pub const PROPOSAL_SEED: &[u8] = b"proposal";
+    /// The seed prefix for deriving consumed message PDAs, keyed by command id
+    pub const CONSUMED_MESSAGE_SEED: &[u8] = b"consumed-message";
+}
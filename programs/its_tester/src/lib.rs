@@ -0,0 +1,129 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use common::Ownable;
+
+declare_id!("8myfgZRq1FWcTkvUhuCrr3ZwGDr9oKWnMyJjWZoBESyf");
+
+/// Represents the event emitted when the operator changes a token's flow limit.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FlowLimitSet {
+    /// The token whose flow limit changed
+    pub token_id: [u8; 32],
+    /// The operator that set it
+    pub operator: Pubkey,
+    /// Tokens allowed to flow per epoch; `None` removes the limit
+    pub flow_limit: Option<u64>,
+}
+
+#[program]
+pub mod its_tester {
+    use super::*;
+
+    /// Only the program's upgrade authority may create the config.
+    pub fn init_config(ctx: Context<InitConfig>, operator: Pubkey) -> Result<()> {
+        common::ensure_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.payer.key())?;
+        ctx.accounts.config_pda.set_inner(ItsConfig {
+            operator,
+            bump: ctx.bumps.config_pda,
+        });
+        Ok(())
+    }
+
+    pub fn transfer_operatorship(
+        ctx: Context<TransferOperatorship>,
+        new_operator: Pubkey,
+    ) -> Result<()> {
+        let config_key = ctx.accounts.config_pda.key();
+        let event = ctx.accounts.config_pda.transfer_operatorship(
+            config_key,
+            &ctx.accounts.operator.key(),
+            new_operator,
+        )?;
+        anchor_lang::prelude::emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Mirrors ITS's operator-only `setFlowLimit`; no flows are tracked.
+    pub fn set_flow_limit(
+        ctx: Context<SetFlowLimit>,
+        token_id: [u8; 32],
+        flow_limit: Option<u64>,
+    ) -> Result<()> {
+        let operator = ctx.accounts.operator.key();
+        ctx.accounts.config_pda.ensure_operator(&operator)?;
+        anchor_lang::prelude::emit_cpi!(FlowLimitSet {
+            token_id,
+            operator,
+            flow_limit,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<ItsConfig>(),
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump
+    )]
+    pub config_pda: Account<'info, ItsConfig>,
+    #[account(constraint = this_program.programdata_address()? == Some(program_data.key()))]
+    pub this_program: Program<'info, crate::program::ItsTester>,
+    /// Names the upgrade authority, the only signer allowed to init
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferOperatorship<'info> {
+    pub operator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump = config_pda.bump
+    )]
+    pub config_pda: Account<'info, ItsConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFlowLimit<'info> {
+    pub operator: Signer<'info>,
+    #[account(
+        seeds = [seed_prefixes::CONFIG_SEED],
+        bump = config_pda.bump
+    )]
+    pub config_pda: Account<'info, ItsConfig>,
+}
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ItsConfig {
+    pub operator: Pubkey,
+    pub bump: u8,
+}
+
+impl Ownable for ItsConfig {
+    fn operator(&self) -> Pubkey {
+        self.operator
+    }
+
+    fn set_operator(&mut self, operator: Pubkey) {
+        self.operator = operator;
+    }
+}
+
+pub mod seed_prefixes {
+    /// The seed prefix for deriving the ITS config PDA
+    pub const CONFIG_SEED: &[u8] = b"its-config";
+}
@@ -0,0 +1,351 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+declare_id!("CLXtzehvQfnNggTPjFw4TrijUTcB79SM3xRD1UY41eVS");
+
+/// Maximum number of owners a multisig can have
+pub const MAX_OWNERS: usize = 10;
+
+/// Represents the event emitted when a multisig is created.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MultisigCreatedEvent {
+    /// The multisig account
+    pub multisig: Pubkey,
+    /// The PDA that signs executed transactions (set this as the operator)
+    pub multisig_signer: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+/// Represents the event emitted when an owner proposes a transaction.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TransactionProposedEvent {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub proposer: Pubkey,
+    /// The program the proposed instruction targets
+    pub program_id: Pubkey,
+}
+
+/// Represents the event emitted when an owner approves a transaction.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TransactionApprovedEvent {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub owner: Pubkey,
+    /// Number of approvals after this one
+    pub approvals: u8,
+}
+
+/// Represents the event emitted when a transaction reaches threshold and is executed.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TransactionExecutedEvent {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+}
+
+#[program]
+pub mod multisig_tester {
+    use super::*;
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        create_key: Pubkey,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            !owners.is_empty() && owners.len() <= MAX_OWNERS,
+            MultisigError::InvalidOwners
+        );
+        require!(
+            threshold > 0 && threshold as usize <= owners.len(),
+            MultisigError::InvalidThreshold
+        );
+        let mut unique = owners.clone();
+        unique.sort();
+        unique.dedup();
+        require!(unique.len() == owners.len(), MultisigError::InvalidOwners);
+
+        let multisig_key = ctx.accounts.multisig.key();
+        let (multisig_signer, signer_bump) = Pubkey::find_program_address(
+            &[seed_prefixes::MULTISIG_SIGNER_SEED, multisig_key.as_ref()],
+            &crate::ID,
+        );
+        ctx.accounts.multisig.set_inner(Multisig {
+            create_key,
+            owners: owners.clone(),
+            threshold,
+            transaction_count: 0,
+            bump: ctx.bumps.multisig,
+            signer_bump,
+        });
+
+        anchor_lang::prelude::emit_cpi!(MultisigCreatedEvent {
+            multisig: multisig_key,
+            multisig_signer,
+            owners,
+            threshold,
+        });
+        Ok(())
+    }
+
+    pub fn create_transaction(
+        ctx: Context<CreateTransaction>,
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let owner_index = multisig.owner_index(&ctx.accounts.proposer.key())?;
+
+        // The proposer implicitly approves
+        let mut approvals = vec![false; multisig.owners.len()];
+        approvals[owner_index] = true;
+
+        ctx.accounts.transaction.set_inner(MultisigTransaction {
+            multisig: multisig.key(),
+            program_id,
+            accounts,
+            data,
+            approvals,
+            did_execute: false,
+            bump: ctx.bumps.transaction,
+        });
+        multisig.transaction_count += 1;
+
+        anchor_lang::prelude::emit_cpi!(TransactionProposedEvent {
+            multisig: ctx.accounts.multisig.key(),
+            transaction: ctx.accounts.transaction.key(),
+            proposer: ctx.accounts.proposer.key(),
+            program_id,
+        });
+        Ok(())
+    }
+
+    pub fn approve(ctx: Context<Approve>) -> Result<()> {
+        let owner_index = ctx
+            .accounts
+            .multisig
+            .owner_index(&ctx.accounts.owner.key())?;
+        let transaction = &mut ctx.accounts.transaction;
+        require!(!transaction.did_execute, MultisigError::AlreadyExecuted);
+        transaction.approvals[owner_index] = true;
+        let approvals = transaction.approval_count();
+
+        anchor_lang::prelude::emit_cpi!(TransactionApprovedEvent {
+            multisig: ctx.accounts.multisig.key(),
+            transaction: ctx.accounts.transaction.key(),
+            owner: ctx.accounts.owner.key(),
+            approvals,
+        });
+        Ok(())
+    }
+
+    pub fn execute<'info>(ctx: Context<'_, '_, '_, 'info, Execute<'info>>) -> Result<()> {
+        let transaction = &ctx.accounts.transaction;
+        require!(!transaction.did_execute, MultisigError::AlreadyExecuted);
+        require!(
+            transaction.approval_count() >= ctx.accounts.multisig.threshold,
+            MultisigError::NotEnoughApprovals
+        );
+
+        let multisig_key = ctx.accounts.multisig.key();
+        let signer_seeds: &[&[u8]] = &[
+            seed_prefixes::MULTISIG_SIGNER_SEED,
+            multisig_key.as_ref(),
+            &[ctx.accounts.multisig.signer_bump],
+        ];
+        let instruction = transaction.to_instruction();
+
+        // Written through before the CPI, so a target that calls back into
+        // execute finds the transaction already executed
+        ctx.accounts.transaction.did_execute = true;
+        ctx.accounts.transaction.exit(ctx.program_id)?;
+
+        // The proposed instruction's accounts (and program) are passed as remaining accounts
+        invoke_signed(&instruction, ctx.remaining_accounts, &[signer_seeds])?;
+
+        anchor_lang::prelude::emit_cpi!(TransactionExecutedEvent {
+            multisig: multisig_key,
+            transaction: ctx.accounts.transaction.key(),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(create_key: Pubkey, owners: Vec<Pubkey>)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Multisig::SPACE,
+        seeds = [seed_prefixes::MULTISIG_SEED, create_key.as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(program_id: Pubkey, accounts: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct CreateTransaction<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::MULTISIG_SEED, multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        payer = proposer,
+        space = MultisigTransaction::space(multisig.owners.len(), accounts.len(), data.len()),
+        seeds = [
+            seed_prefixes::TRANSACTION_SEED,
+            multisig.key().as_ref(),
+            multisig.transaction_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct Approve<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [seed_prefixes::MULTISIG_SEED, multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(mut, has_one = multisig)]
+    pub transaction: Account<'info, MultisigTransaction>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct Execute<'info> {
+    #[account(
+        seeds = [seed_prefixes::MULTISIG_SEED, multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(mut, has_one = multisig)]
+    pub transaction: Account<'info, MultisigTransaction>,
+}
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Multisig {
+    /// Arbitrary key the multisig PDA is derived from
+    pub create_key: Pubkey,
+    pub owners: Vec<Pubkey>,
+    /// Number of owner approvals needed to execute a transaction
+    pub threshold: u8,
+    /// Number of transactions proposed so far, used to derive transaction PDAs
+    pub transaction_count: u64,
+    pub bump: u8,
+    /// Bump of the PDA that signs executed transactions
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    pub const SPACE: usize = 8 + 32 + 4 + 32 * MAX_OWNERS + 1 + 8 + 1 + 1;
+
+    pub fn owner_index(&self, owner: &Pubkey) -> Result<usize> {
+        self.owners
+            .iter()
+            .position(|o| o == owner)
+            .ok_or_else(|| MultisigError::NotAnOwner.into())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl From<&TransactionAccount> for AccountMeta {
+    fn from(account: &TransactionAccount) -> Self {
+        AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        }
+    }
+}
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct MultisigTransaction {
+    pub multisig: Pubkey,
+    /// The program the proposed instruction targets
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    /// One flag per multisig owner, in owner order
+    pub approvals: Vec<bool>,
+    pub did_execute: bool,
+    pub bump: u8,
+}
+
+impl MultisigTransaction {
+    pub fn space(owners: usize, accounts: usize, data_len: usize) -> usize {
+        8 + 32 + 32 + (4 + accounts * (32 + 1 + 1)) + (4 + data_len) + (4 + owners) + 1 + 1
+    }
+
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.iter().filter(|approved| **approved).count() as u8
+    }
+
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts.iter().map(AccountMeta::from).collect(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+#[error_code]
+pub enum MultisigError {
+    #[msg("Owners must be unique and between 1 and MAX_OWNERS")]
+    InvalidOwners,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Signer is not an owner of the multisig")]
+    NotAnOwner,
+    #[msg("Transaction was already executed")]
+    AlreadyExecuted,
+    #[msg("Transaction does not have enough approvals")]
+    NotEnoughApprovals,
+}
+
+pub mod seed_prefixes {
+    /// The seed prefix for deriving multisig PDAs
+    pub const MULTISIG_SEED: &[u8] = b"multisig";
+    /// The seed prefix for deriving the PDA that signs on behalf of a multisig
+    pub const MULTISIG_SIGNER_SEED: &[u8] = b"multisig-signer";
+    /// The seed prefix for deriving multisig transaction PDAs
+    pub const TRANSACTION_SEED: &[u8] = b"multisig-tx";
+}
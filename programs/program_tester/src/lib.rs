@@ -1,6 +1,10 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`,
+// and to one function per instruction taking all of its arguments
+#![allow(deprecated, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::pubkey::Pubkey;
-use anyhow::anyhow;
+use common::Ownable;
 
 declare_id!("8YsLGnLV2KoyxdksgiAi3gh1WvhMrznA2toKWqyz91bR");
 
@@ -45,6 +49,18 @@ pub struct CallContractEvent {
     pub payload: Vec<u8>,
 }
 
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CallContractWithTokenEvent {
+    pub sender: Pubkey,
+    pub payload_hash: [u8; 32],
+    pub destination_chain: String,
+    pub destination_contract_address: String,
+    pub payload: Vec<u8>,
+    pub symbol: String,
+    pub amount: u64,
+}
+
 #[event]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct InterchainTransfer {
@@ -86,6 +102,13 @@ pub struct TokenMetadataRegistered {
     pub decimals: u8,
 }
 
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ComputeBurnedEvent {
+    pub iterations: u32,
+    pub result_hash: [u8; 32],
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
 pub struct U256(pub [u8; 32]);
 
@@ -102,6 +125,14 @@ pub mod program_tester {
         payload_hash: [u8; 32],
         payload: Vec<u8>,
     ) -> Result<()> {
+        if let Some(payload_log) = ctx.accounts.payload_log.as_mut() {
+            payload_log.record(PayloadLogEntry {
+                payload_hash,
+                sender: ctx.accounts.calling_program.key(),
+                destination_chain: destination_chain.clone(),
+            });
+        }
+
         anchor_lang::prelude::emit_cpi!(CallContractEvent {
             sender: ctx.accounts.calling_program.key(),
             destination_chain,
@@ -112,11 +143,77 @@ pub mod program_tester {
         Ok(())
     }
 
+    pub fn call_contract_with_token(
+        ctx: Context<CallContractWithToken>,
+        destination_chain: String,
+        destination_contract_address: String,
+        payload_hash: [u8; 32],
+        payload: Vec<u8>,
+        symbol: String,
+        amount: u64,
+    ) -> Result<()> {
+        // Mirrors the EVM gateway's token-bearing GMP call; no tokens are moved
+        if let Some(payload_log) = ctx.accounts.payload_log.as_mut() {
+            payload_log.record(PayloadLogEntry {
+                payload_hash,
+                sender: ctx.accounts.calling_program.key(),
+                destination_chain: destination_chain.clone(),
+            });
+        }
+        anchor_lang::prelude::emit_cpi!(CallContractWithTokenEvent {
+            sender: ctx.accounts.calling_program.key(),
+            destination_chain,
+            destination_contract_address,
+            payload_hash,
+            payload,
+            symbol,
+            amount,
+        });
+        Ok(())
+    }
+
+    pub fn call_contract_with_its_payload(
+        ctx: Context<CallContract>,
+        destination_chain: String,
+        destination_contract_address: String,
+        message: ItsMessage,
+        hub_destination_chain: Option<String>,
+    ) -> Result<()> {
+        // Wrap in SEND_TO_HUB when routing through the ITS hub
+        let payload = match hub_destination_chain {
+            Some(chain) => ItsMessage::encode_send_to_hub(&chain, &message.abi_encode()),
+            None => message.abi_encode(),
+        };
+        let payload_hash = solana_program::keccak::hash(&payload).to_bytes();
+
+        if let Some(payload_log) = ctx.accounts.payload_log.as_mut() {
+            payload_log.record(PayloadLogEntry {
+                payload_hash,
+                sender: ctx.accounts.calling_program.key(),
+                destination_chain: destination_chain.clone(),
+            });
+        }
+
+        anchor_lang::prelude::emit_cpi!(CallContractEvent {
+            sender: ctx.accounts.calling_program.key(),
+            destination_chain,
+            destination_contract_address,
+            payload_hash,
+            payload,
+        });
+        Ok(())
+    }
+
+    // `payload_merkle_root` is only used by the accounts' seeds
+    #[allow(unused_variables)]
     pub fn approve_message(
         ctx: Context<ApproveMessage>,
         message: MerkleisedMessage,
         payload_merkle_root: [u8; 32],
     ) -> Result<()> {
+        #[cfg(feature = "relayer-whitelist")]
+        check_relayer_whitelisted(ctx.remaining_accounts, &ctx.accounts.funder.key())?;
+
         let cc_id = &message.leaf.message.cc_id;
         let destination_address =
             Pubkey::from_str(&message.leaf.message.destination_address).unwrap();
@@ -182,6 +279,30 @@ pub mod program_tester {
         Ok(())
     }
 
+    pub fn init_payload_log(ctx: Context<InitPayloadLog>) -> Result<()> {
+        ctx.accounts.payload_log.set_inner(PayloadLog {
+            head: 0,
+            total_recorded: 0,
+            entries: Vec::with_capacity(PAYLOAD_LOG_CAPACITY),
+            bump: ctx.bumps.payload_log,
+        });
+        Ok(())
+    }
+
+    pub fn transfer_operatorship(
+        ctx: Context<TransferOperatorship>,
+        new_operator: Pubkey,
+    ) -> Result<()> {
+        let config_key = ctx.accounts.gateway_root_pda.key();
+        let event = ctx.accounts.gateway_root_pda.transfer_operatorship(
+            config_key,
+            &ctx.accounts.operator.key(),
+            new_operator,
+        )?;
+        anchor_lang::prelude::emit_cpi!(event);
+        Ok(())
+    }
+
     pub fn init_verification_session(
         ctx: Context<InitVerificationSession>,
         _payload_merkle_root: [u8; 32],
@@ -199,6 +320,111 @@ pub mod program_tester {
         Ok(())
     }
 
+    /// Registers `verifier_set_hash` as the gateway's next verifier set, which
+    /// `verify_signature` then accepts for as long as the retention allows.
+    pub fn register_verifier_set(
+        ctx: Context<RegisterVerifierSet>,
+        verifier_set_hash: VerifierSetHash,
+    ) -> Result<()> {
+        let gateway = &mut ctx.accounts.gateway_root_pda;
+        gateway.ensure_operator(&ctx.accounts.operator.key())?;
+        gateway.current_epoch += 1;
+        ctx.accounts
+            .verifier_set_tracker_pda
+            .set_inner(VerifierSetTracker {
+                bump: ctx.bumps.verifier_set_tracker_pda,
+                epoch: gateway.current_epoch,
+                verifier_set_hash,
+            });
+
+        let mut epoch_le = [0u8; 32];
+        epoch_le[..8].copy_from_slice(&gateway.current_epoch.to_le_bytes());
+        anchor_lang::prelude::emit_cpi!(VerifierSetRotatedEvent {
+            epoch: U256(epoch_le),
+            verifier_set_hash,
+        });
+        Ok(())
+    }
+
+    /// Checks one verifier's signature over `payload_merkle_root`: the
+    /// verifier must be in `verifier_set_hash`, proven by
+    /// `verifier_merkle_proof`, and the set registered and not retired.
+    pub fn verify_signature(
+        ctx: Context<VerifySignature>,
+        payload_merkle_root: [u8; 32],
+        verifier_set_hash: VerifierSetHash,
+        verifier_info: VerifierSetLeaf,
+        verifier_merkle_proof: Vec<u8>,
+        signature: [u8; 64],
+        recovery_id: u8,
+    ) -> Result<()> {
+        let gateway = &ctx.accounts.gateway_root_pda;
+        let tracker = &ctx.accounts.verifier_set_tracker_pda;
+        require!(
+            gateway.current_epoch.saturating_sub(tracker.epoch)
+                <= gateway.previous_verifier_set_retention,
+            ProgramTesterError::VerifierSetTooOld
+        );
+        require!(
+            verifier_info.domain_separator == gateway.domain_separator,
+            ProgramTesterError::DomainSeparatorMismatch
+        );
+        // Each slot bit marks a verifier position that already signed
+        let position = verifier_info.position as usize;
+        require!(
+            verifier_info.position < verifier_info.set_size
+                && position < SignatureVerification::MAX_VERIFIERS,
+            ProgramTesterError::InvalidVerifierPosition
+        );
+        require!(
+            verifier_set::verify_proof(
+                &verifier_info.hash(),
+                &verifier_merkle_proof,
+                verifier_info.position,
+                verifier_info.set_size,
+                &verifier_set_hash,
+            ),
+            ProgramTesterError::InvalidMerkleProof
+        );
+
+        let message_hash = verifier_set::signed_message_hash(&payload_merkle_root);
+        let recovered = solana_program::secp256k1_recover::secp256k1_recover(
+            &message_hash,
+            recovery_id,
+            &signature,
+        )
+        .map_err(|_| ProgramTesterError::InvalidSignature)?;
+        require!(
+            recovered.to_bytes() == verifier_info.signer_pubkey,
+            ProgramTesterError::InvalidSignature
+        );
+
+        let verification = &mut ctx
+            .accounts
+            .verification_session_account
+            .signature_verification;
+        // The set is registered, so whichever one signs first holds the
+        // session to it
+        if verification.signing_verifier_set_hash == [0u8; 32] {
+            verification.signing_verifier_set_hash = verifier_set_hash;
+        }
+        require!(
+            verification.signing_verifier_set_hash == verifier_set_hash,
+            ProgramTesterError::VerifierSetMismatch
+        );
+
+        let (byte, bit) = (position / 8, 1u8 << (position % 8));
+        require!(
+            verification.signature_slots[byte] & bit == 0,
+            ProgramTesterError::SlotAlreadyVerified
+        );
+        verification.signature_slots[byte] |= bit;
+        verification.accumulated_threshold = verification
+            .accumulated_threshold
+            .saturating_add(verifier_info.signer_weight);
+        Ok(())
+    }
+
     pub fn interchain_transfer(
         ctx: Context<InterchainTransferCtx>,
         token_id: [u8; 32],
@@ -273,6 +499,21 @@ pub mod program_tester {
         Ok(())
     }
 
+    pub fn burn_compute(ctx: Context<BurnComputeCtx>, iterations: u32) -> Result<()> {
+        // Chain the hashes so the loop can't be optimized away
+        let mut result_hash = [0u8; 32];
+        for i in 0..iterations {
+            result_hash =
+                solana_program::keccak::hashv(&[&result_hash, &i.to_le_bytes()]).to_bytes();
+        }
+
+        anchor_lang::prelude::emit_cpi!(ComputeBurnedEvent {
+            iterations,
+            result_hash,
+        });
+        Ok(())
+    }
+
     pub fn signers_rotated(
         ctx: Context<SignersRotatedCtx>,
         epoch_le: [u8; 32],
@@ -286,6 +527,54 @@ pub mod program_tester {
     }
 }
 
+/// When built with the `relayer-whitelist` feature, `approve_message` expects the
+/// relayer whitelist PDA as its first remaining account and rejects funders that
+/// are not on it.
+#[cfg(feature = "relayer-whitelist")]
+fn check_relayer_whitelisted(remaining_accounts: &[AccountInfo], relayer: &Pubkey) -> Result<()> {
+    let whitelist_info = remaining_accounts
+        .first()
+        .ok_or(ProgramTesterError::MissingRelayerWhitelist)?;
+    let (expected_pda, _) = Pubkey::find_program_address(
+        &[relayer_whitelist::seed_prefixes::WHITELIST_SEED],
+        &relayer_whitelist::ID,
+    );
+    require_keys_eq!(
+        whitelist_info.key(),
+        expected_pda,
+        ProgramTesterError::MissingRelayerWhitelist
+    );
+    let whitelist: Account<relayer_whitelist::RelayerWhitelist> =
+        Account::try_from(whitelist_info)?;
+    require!(
+        whitelist.contains(relayer),
+        ProgramTesterError::RelayerNotWhitelisted
+    );
+    Ok(())
+}
+
+#[error_code]
+pub enum ProgramTesterError {
+    #[msg("Relayer whitelist PDA missing from remaining accounts")]
+    MissingRelayerWhitelist,
+    #[msg("Relayer is not whitelisted")]
+    RelayerNotWhitelisted,
+    #[msg("Signature does not match the verifier's public key")]
+    InvalidSignature,
+    #[msg("Verifier set does not match the one used by this session")]
+    VerifierSetMismatch,
+    #[msg("Verifier position out of range")]
+    InvalidVerifierPosition,
+    #[msg("Verifier already signed for this session")]
+    SlotAlreadyVerified,
+    #[msg("Verifier is not in the verifier set")]
+    InvalidMerkleProof,
+    #[msg("Verifier set is older than the gateway retains")]
+    VerifierSetTooOld,
+    #[msg("Verifier's domain separator is not the gateway's")]
+    DomainSeparatorMismatch,
+}
+
 #[derive(Accounts)]
 #[event_cpi]
 pub struct CallContract<'info> {
@@ -298,6 +587,35 @@ pub struct CallContract<'info> {
     /// The gateway configuration PDA being initialized
     #[account()]
     pub gateway_root_pda: Account<'info, GatewayConfig>,
+    /// Ring buffer of the most recent calls; left out until `init_payload_log`
+    /// has created it, in which case the call isn't recorded. Callers may
+    /// leave it out anyway, so the log only covers the calls that pass it
+    #[account(
+        mut,
+        seeds = [seed_prefixes::PAYLOAD_LOG_SEED],
+        bump = payload_log.bump
+    )]
+    pub payload_log: Option<Account<'info, PayloadLog>>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct CallContractWithToken<'info> {
+    /// The program that wants to call us
+    /// CHECK: Only used as the event sender
+    pub calling_program: UncheckedAccount<'info>,
+    /// The standardized PDA that must sign - derived from the calling program
+    /// CHECK: This account is a PDA derived from the calling program for signing purposes
+    pub signing_pda: UncheckedAccount<'info>,
+    #[account()]
+    pub gateway_root_pda: Account<'info, GatewayConfig>,
+    /// Same ring buffer as `CallContract::payload_log`
+    #[account(
+        mut,
+        seeds = [seed_prefixes::PAYLOAD_LOG_SEED],
+        bump = payload_log.bump
+    )]
+    pub payload_log: Option<Account<'info, PayloadLog>>,
 }
 
 #[derive(Accounts)]
@@ -315,6 +633,33 @@ pub struct InitGatewayRoot<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitPayloadLog<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        init,
+        payer = funder,
+        space = PayloadLog::SPACE,
+        seeds = [seed_prefixes::PAYLOAD_LOG_SEED],
+        bump
+    )]
+    pub payload_log: Account<'info, PayloadLog>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct TransferOperatorship<'info> {
+    pub operator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::GATEWAY_SEED],
+        bump = gateway_root_pda.bump
+    )]
+    pub gateway_root_pda: Account<'info, GatewayConfig>,
+}
+
 #[derive(Accounts)]
 #[instruction(payload_merkle_root: [u8; 32])]
 pub struct InitVerificationSession<'info> {
@@ -331,6 +676,52 @@ pub struct InitVerificationSession<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[event_cpi]
+#[instruction(verifier_set_hash: VerifierSetHash)]
+pub struct RegisterVerifierSet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub operator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::GATEWAY_SEED],
+        bump = gateway_root_pda.bump
+    )]
+    pub gateway_root_pda: Account<'info, GatewayConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<VerifierSetTracker>(),
+        seeds = [seed_prefixes::VERIFIER_SET_TRACKER_SEED, verifier_set_hash.as_ref()],
+        bump
+    )]
+    pub verifier_set_tracker_pda: Account<'info, VerifierSetTracker>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(payload_merkle_root: [u8; 32], verifier_set_hash: VerifierSetHash)]
+pub struct VerifySignature<'info> {
+    #[account(
+        seeds = [seed_prefixes::GATEWAY_SEED],
+        bump = gateway_root_pda.bump
+    )]
+    pub gateway_root_pda: Account<'info, GatewayConfig>,
+    /// Only exists for a registered verifier set
+    #[account(
+        seeds = [seed_prefixes::VERIFIER_SET_TRACKER_SEED, verifier_set_hash.as_ref()],
+        bump = verifier_set_tracker_pda.bump
+    )]
+    pub verifier_set_tracker_pda: Account<'info, VerifierSetTracker>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::SIGNATURE_VERIFICATION_SEED, payload_merkle_root.as_ref()],
+        bump = verification_session_account.bump
+    )]
+    pub verification_session_account: Account<'info, VerificationSessionAccount>,
+}
+
 #[account]
 #[derive(Debug, PartialEq, Eq)]
 pub struct GatewayConfig {
@@ -343,6 +734,76 @@ pub struct GatewayConfig {
     pub bump: u8,
 }
 
+impl Ownable for GatewayConfig {
+    fn operator(&self) -> Pubkey {
+        self.operator
+    }
+
+    fn set_operator(&mut self, operator: Pubkey) {
+        self.operator = operator;
+    }
+}
+
+/// Number of recent calls kept by the payload log
+pub const PAYLOAD_LOG_CAPACITY: usize = 16;
+/// Longest destination chain name the payload log stores; longer names are
+/// truncated
+pub const MAX_CHAIN_NAME_LEN: usize = 32;
+
+#[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
+pub struct PayloadLogEntry {
+    pub payload_hash: [u8; 32],
+    pub sender: Pubkey,
+    pub destination_chain: String,
+}
+
+impl PayloadLogEntry {
+    pub const SPACE: usize = 32 + 32 + 4 + MAX_CHAIN_NAME_LEN;
+}
+
+/// Ring buffer of the last `PAYLOAD_LOG_CAPACITY` `call_contract` invocations
+/// that passed it.
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct PayloadLog {
+    /// Index the next entry will be written to once the buffer is full
+    pub head: u16,
+    /// Number of calls recorded since initialization
+    pub total_recorded: u64,
+    pub entries: Vec<PayloadLogEntry>,
+    pub bump: u8,
+}
+
+impl PayloadLog {
+    pub const SPACE: usize = 8 + 2 + 8 + 4 + PAYLOAD_LOG_CAPACITY * PayloadLogEntry::SPACE + 1;
+
+    /// Never fails, so recording can't reject a call: chain names longer than
+    /// `MAX_CHAIN_NAME_LEN` bytes are cut at the last character that fits.
+    pub fn record(&mut self, mut entry: PayloadLogEntry) {
+        let chain = &mut entry.destination_chain;
+        if chain.len() > MAX_CHAIN_NAME_LEN {
+            let end = (0..=MAX_CHAIN_NAME_LEN)
+                .rev()
+                .find(|&end| chain.is_char_boundary(end))
+                .unwrap_or(0);
+            chain.truncate(end);
+        }
+        if self.entries.len() < PAYLOAD_LOG_CAPACITY {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.head as usize] = entry;
+            self.head = ((self.head as usize + 1) % PAYLOAD_LOG_CAPACITY) as u16;
+        }
+        self.total_recorded += 1;
+    }
+
+    /// Entries from oldest to newest.
+    pub fn ordered_entries(&self) -> impl Iterator<Item = &PayloadLogEntry> {
+        let (newer, older) = self.entries.split_at(self.head as usize);
+        older.iter().chain(newer.iter())
+    }
+}
+
 pub type Timestamp = u64;
 /// Seconds that need to pass between signer rotations
 pub type RotationDelaySecs = u64;
@@ -419,6 +880,13 @@ pub struct TokenMetadataRegisteredCtx<'info> {
     pub payer: Signer<'info>,
 }
 
+#[derive(Accounts)]
+#[event_cpi]
+pub struct BurnComputeCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[event_cpi]
 pub struct SignersRotatedCtx<'info> {
@@ -457,7 +925,8 @@ pub struct MessageLeaf {
 }
 
 impl MessageLeaf {
-    // placeholder hash function
+    /// Keccak of the borsh encoding. This program's own, not the production
+    /// gateway's: nothing here checks message proofs against real signers.
     pub fn hash(&self) -> [u8; 32] {
         // Use borsh serialization (matches how Anchor serializes data)
         let data = self.try_to_vec().expect("Serialization should not fail");
@@ -466,7 +935,8 @@ impl MessageLeaf {
 }
 
 impl Message {
-    // placeholder hash function
+    /// Keccak of the borsh encoding, stored as an approved message's hash.
+    /// Like `MessageLeaf::hash`, this program's own encoding.
     pub fn hash(&self) -> [u8; 32] {
         // Use borsh serialization (matches how Anchor serializes data)
         let data = self.try_to_vec().expect("Serialization should not fail");
@@ -474,13 +944,16 @@ impl Message {
     }
 
     pub fn command_id(&self) -> [u8; 32] {
-        let cc_id = &self.cc_id;
-        let command_id =
-            solana_program::keccak::hashv(&[cc_id.chain.as_bytes(), b"-", cc_id.id.as_bytes()]).0;
-        return command_id;
+        command_id(&self.cc_id.chain, &self.cc_id.id)
     }
 }
 
+/// Identifier of an incoming message, derived from its cross-chain id as
+/// `keccak(chain || "-" || id)`.
+pub fn command_id(chain: &str, id: &str) -> [u8; 32] {
+    solana_program::keccak::hashv(&[chain.as_bytes(), b"-", id.as_bytes()]).0
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
 pub struct CrossChainId {
     /// The name or identifier of the source blockchain.
@@ -524,6 +997,127 @@ pub struct SignatureVerification {
     pub signing_verifier_set_hash: VerifierSetHash,
 }
 
+impl SignatureVerification {
+    /// Largest verifier set a session can track, one slot bit per verifier.
+    pub const MAX_VERIFIERS: usize = 32 * 8;
+}
+
+/// A verifier set the gateway accepts signatures of, at the epoch it was
+/// registered in.
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifierSetTracker {
+    pub bump: u8,
+    pub epoch: VerifierSetEpoch,
+    pub verifier_set_hash: VerifierSetHash,
+}
+
+/// A single verifier entry of a verifier set.
+#[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
+pub struct VerifierSetLeaf {
+    /// Nonce of the verifier set this entry belongs to.
+    pub nonce: u64,
+
+    /// Total weight required for the verifier set to reach quorum.
+    pub quorum: u128,
+
+    /// Uncompressed secp256k1 public key of the signer, without the 0x04 prefix.
+    pub signer_pubkey: [u8; 64],
+
+    /// The voting weight of this signer.
+    pub signer_weight: u128,
+
+    /// The position of this signer within the verifier set.
+    pub position: u16,
+
+    /// The total number of signers in the verifier set.
+    pub set_size: u16,
+
+    /// A domain separator used to ensure the uniqueness of hashes across
+    /// different contexts.
+    pub domain_separator: [u8; 32],
+}
+
+impl VerifierSetLeaf {
+    /// The leaf's hash in the verifier set's Merkle tree: keccak of the borsh
+    /// encoding. This program's own encoding rather than the production
+    /// gateway's, as its signers are the scripts' test verifiers only.
+    pub fn hash(&self) -> [u8; 32] {
+        // Use borsh serialization (matches how Anchor serializes data)
+        let data = self.try_to_vec().expect("Serialization should not fail");
+        solana_program::keccak::hash(&data).to_bytes()
+    }
+}
+
+pub mod verifier_set {
+    use super::{VerifierSetHash, VerifierSetLeaf};
+
+    /// Prefix prepended to the payload Merkle root before it is signed by verifiers
+    pub const SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Axelar Solana Signed Message:\n";
+
+    /// The digest a verifier signs for a given payload Merkle root.
+    pub fn signed_message_hash(payload_merkle_root: &[u8; 32]) -> [u8; 32] {
+        solana_program::keccak::hashv(&[SIGNED_MESSAGE_PREFIX, payload_merkle_root]).to_bytes()
+    }
+
+    /// Hash of a whole verifier set: the Merkle root over its leaves in
+    /// position order, built like the payload tree of the scripts' `merkle`
+    /// module: inner nodes are `keccak(left || right)` and a node without a
+    /// sibling moves up unchanged.
+    pub fn verifier_set_hash(leaves: &[VerifierSetLeaf]) -> VerifierSetHash {
+        let mut level: Vec<[u8; 32]> = leaves.iter().map(VerifierSetLeaf::hash).collect();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_nodes(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        level.first().copied().unwrap_or_default()
+    }
+
+    /// Whether `proof`, the sibling hashes from the leaf up concatenated,
+    /// leads from `leaf` at `position` of a set of `set_size` to `root`.
+    pub fn verify_proof(
+        leaf: &[u8; 32],
+        proof: &[u8],
+        position: u16,
+        set_size: u16,
+        root: &VerifierSetHash,
+    ) -> bool {
+        let mut siblings = proof.chunks_exact(32);
+        if position >= set_size || !siblings.remainder().is_empty() {
+            return false;
+        }
+        let mut node = *leaf;
+        let (mut index, mut width) = (position as usize, set_size as usize);
+        while width > 1 {
+            // The last node of an odd level has no sibling
+            if index ^ 1 < width {
+                let Some(sibling) = siblings.next() else {
+                    return false;
+                };
+                let sibling: [u8; 32] = sibling.try_into().unwrap();
+                node = if index % 2 == 0 {
+                    hash_nodes(&node, &sibling)
+                } else {
+                    hash_nodes(&sibling, &node)
+                };
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none() && node == *root
+    }
+
+    fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        solana_program::keccak::hashv(&[left, right]).to_bytes()
+    }
+}
+
 #[account]
 #[derive(Debug, PartialEq, Eq)]
 pub struct IncomingMessage {
@@ -547,6 +1141,244 @@ pub mod seed_prefixes {
     pub const INCOMING_MESSAGE_SEED: &[u8] = b"incoming message";
     /// The seed prefix for deriving message payload PDAs
     pub const MESSAGE_PAYLOAD_SEED: &[u8] = b"message-payload";
+    /// The seed prefix for deriving the payload log PDA
+    pub const PAYLOAD_LOG_SEED: &[u8] = b"payload-log";
+}
+
+/// ITS hub messages that `call_contract_with_its_payload` ABI-encodes on-chain.
+#[derive(Clone, PartialEq, Eq, Debug, AnchorDeserialize, AnchorSerialize)]
+pub enum ItsMessage {
+    InterchainTransfer {
+        token_id: [u8; 32],
+        source_address: Vec<u8>,
+        destination_address: Vec<u8>,
+        amount: u64,
+        data: Vec<u8>,
+    },
+    DeployInterchainToken {
+        token_id: [u8; 32],
+        name: String,
+        symbol: String,
+        decimals: u8,
+        minter: Vec<u8>,
+    },
+    LinkToken {
+        token_id: [u8; 32],
+        token_manager_type: u8,
+        source_token_address: Vec<u8>,
+        destination_token_address: Vec<u8>,
+        params: Vec<u8>,
+    },
+    RegisterTokenMetadata {
+        token_address: Vec<u8>,
+        decimals: u8,
+    },
+}
+
+impl ItsMessage {
+    pub const MESSAGE_TYPE_INTERCHAIN_TRANSFER: u64 = 0;
+    pub const MESSAGE_TYPE_DEPLOY_INTERCHAIN_TOKEN: u64 = 1;
+    pub const MESSAGE_TYPE_SEND_TO_HUB: u64 = 3;
+    pub const MESSAGE_TYPE_LINK_TOKEN: u64 = 5;
+    pub const MESSAGE_TYPE_REGISTER_TOKEN_METADATA: u64 = 6;
+
+    /// Encodes the message the same way the EVM ITS contract does with `abi.encode`.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        use abi::Token;
+        let tokens = match self {
+            ItsMessage::InterchainTransfer {
+                token_id,
+                source_address,
+                destination_address,
+                amount,
+                data,
+            } => vec![
+                Token::Uint(Self::MESSAGE_TYPE_INTERCHAIN_TRANSFER),
+                Token::FixedBytes(*token_id),
+                Token::Bytes(source_address.clone()),
+                Token::Bytes(destination_address.clone()),
+                Token::Uint(*amount),
+                Token::Bytes(data.clone()),
+            ],
+            ItsMessage::DeployInterchainToken {
+                token_id,
+                name,
+                symbol,
+                decimals,
+                minter,
+            } => vec![
+                Token::Uint(Self::MESSAGE_TYPE_DEPLOY_INTERCHAIN_TOKEN),
+                Token::FixedBytes(*token_id),
+                Token::String(name.clone()),
+                Token::String(symbol.clone()),
+                Token::Uint(*decimals as u64),
+                Token::Bytes(minter.clone()),
+            ],
+            ItsMessage::LinkToken {
+                token_id,
+                token_manager_type,
+                source_token_address,
+                destination_token_address,
+                params,
+            } => vec![
+                Token::Uint(Self::MESSAGE_TYPE_LINK_TOKEN),
+                Token::FixedBytes(*token_id),
+                Token::Uint(*token_manager_type as u64),
+                Token::Bytes(source_token_address.clone()),
+                Token::Bytes(destination_token_address.clone()),
+                Token::Bytes(params.clone()),
+            ],
+            ItsMessage::RegisterTokenMetadata {
+                token_address,
+                decimals,
+            } => vec![
+                Token::Uint(Self::MESSAGE_TYPE_REGISTER_TOKEN_METADATA),
+                Token::Bytes(token_address.clone()),
+                Token::Uint(*decimals as u64),
+            ],
+        };
+        abi::encode(&tokens)
+    }
+
+    /// Wraps an encoded ITS message into a SEND_TO_HUB message for `destination_chain`.
+    pub fn encode_send_to_hub(destination_chain: &str, payload: &[u8]) -> Vec<u8> {
+        use abi::Token;
+        abi::encode(&[
+            Token::Uint(Self::MESSAGE_TYPE_SEND_TO_HUB),
+            Token::String(destination_chain.to_string()),
+            Token::Bytes(payload.to_vec()),
+        ])
+    }
+}
+
+/// Minimal Solidity ABI encoder covering the types used by ITS messages.
+pub mod abi {
+    const WORD: usize = 32;
+
+    pub enum Token {
+        Uint(u64),
+        FixedBytes([u8; 32]),
+        Bytes(Vec<u8>),
+        String(String),
+    }
+
+    impl Token {
+        fn is_dynamic(&self) -> bool {
+            matches!(self, Token::Bytes(_) | Token::String(_))
+        }
+    }
+
+    fn uint_word(value: u64) -> [u8; WORD] {
+        let mut word = [0u8; WORD];
+        word[WORD - 8..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    fn encode_dynamic(data: &[u8], out: &mut Vec<u8>) {
+        out.extend_from_slice(&uint_word(data.len() as u64));
+        out.extend_from_slice(data);
+        let padding = (WORD - data.len() % WORD) % WORD;
+        out.resize(out.len() + padding, 0);
+    }
+
+    /// Equivalent of Solidity's `abi.encode(tokens...)`.
+    pub fn encode(tokens: &[Token]) -> Vec<u8> {
+        let mut head = Vec::with_capacity(tokens.len() * WORD);
+        let mut tail = Vec::new();
+        for token in tokens {
+            if token.is_dynamic() {
+                head.extend_from_slice(&uint_word((tokens.len() * WORD + tail.len()) as u64));
+            }
+            match token {
+                Token::Uint(value) => head.extend_from_slice(&uint_word(*value)),
+                Token::FixedBytes(bytes) => head.extend_from_slice(bytes),
+                Token::Bytes(bytes) => encode_dynamic(bytes, &mut tail),
+                Token::String(string) => encode_dynamic(string.as_bytes(), &mut tail),
+            }
+        }
+        head.extend_from_slice(&tail);
+        head
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::ItsMessage;
+
+        fn word(value: u64) -> Vec<u8> {
+            uint_word(value).to_vec()
+        }
+
+        fn padded(data: &[u8]) -> Vec<u8> {
+            let mut out = data.to_vec();
+            out.resize(data.len().div_ceil(WORD) * WORD, 0);
+            out
+        }
+
+        #[test]
+        fn dynamic_values_go_after_the_head() {
+            let encoded = encode(&[Token::Uint(1), Token::Bytes(b"abc".to_vec())]);
+            let expected = [word(1), word(0x40), word(3), padded(b"abc")].concat();
+            assert_eq!(encoded, expected);
+        }
+
+        #[test]
+        fn offsets_count_the_earlier_tails() {
+            let encoded = ItsMessage::encode_send_to_hub("ethereum", &[0xde, 0xad]);
+            let expected = [
+                word(ItsMessage::MESSAGE_TYPE_SEND_TO_HUB),
+                word(0x60),
+                word(0xa0),
+                word(8),
+                padded(b"ethereum"),
+                word(2),
+                padded(&[0xde, 0xad]),
+            ]
+            .concat();
+            assert_eq!(encoded, expected);
+        }
+
+        #[test]
+        fn whole_and_empty_words_are_not_padded() {
+            assert_eq!(
+                encode(&[Token::Bytes(vec![])]),
+                [word(0x20), word(0)].concat()
+            );
+            assert_eq!(
+                encode(&[Token::Bytes(vec![1; 32])]),
+                [word(0x20), word(32), vec![1; 32]].concat()
+            );
+        }
+
+        #[test]
+        fn its_messages_start_with_their_type() {
+            let message = ItsMessage::RegisterTokenMetadata {
+                token_address: vec![0xaa; 20],
+                decimals: 6,
+            };
+            let expected = [
+                word(ItsMessage::MESSAGE_TYPE_REGISTER_TOKEN_METADATA),
+                word(0x60),
+                word(6),
+                word(20),
+                padded(&[0xaa; 20]),
+            ]
+            .concat();
+            assert_eq!(message.abi_encode(), expected);
+
+            let token_id = [7u8; 32];
+            let encoded = ItsMessage::LinkToken {
+                token_id,
+                token_manager_type: 2,
+                source_token_address: vec![1],
+                destination_token_address: vec![2],
+                params: vec![],
+            }
+            .abi_encode();
+            assert_eq!(encoded[..32], word(ItsMessage::MESSAGE_TYPE_LINK_TOKEN)[..]);
+            assert_eq!(encoded[32..64], token_id);
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, AnchorSerialize, AnchorDeserialize)]
@@ -567,3 +1399,59 @@ impl MessageStatus {
         self.0 == 0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(destination_chain: &str) -> PayloadLogEntry {
+        PayloadLogEntry {
+            payload_hash: [0u8; 32],
+            sender: Pubkey::default(),
+            destination_chain: destination_chain.to_string(),
+        }
+    }
+
+    fn empty_log() -> PayloadLog {
+        PayloadLog {
+            head: 0,
+            total_recorded: 0,
+            entries: vec![],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn long_chain_names_are_truncated() {
+        let mut log = empty_log();
+        log.record(entry("ethereum"));
+        log.record(entry(&"a".repeat(MAX_CHAIN_NAME_LEN + 1)));
+        // 'é' is two bytes, so the one straddling the limit is dropped whole
+        log.record(entry(&format!("{}é", "a".repeat(MAX_CHAIN_NAME_LEN - 1))));
+
+        let chains: Vec<_> = log
+            .ordered_entries()
+            .map(|entry| entry.destination_chain.clone())
+            .collect();
+        assert_eq!(
+            chains,
+            [
+                "ethereum".to_string(),
+                "a".repeat(MAX_CHAIN_NAME_LEN),
+                "a".repeat(MAX_CHAIN_NAME_LEN - 1),
+            ]
+        );
+        assert_eq!(log.total_recorded, 3);
+    }
+
+    #[test]
+    fn the_oldest_entry_is_overwritten_when_full() {
+        let mut log = empty_log();
+        for index in 0..=PAYLOAD_LOG_CAPACITY {
+            log.record(entry(&index.to_string()));
+        }
+        let first = log.ordered_entries().next().unwrap();
+        assert_eq!(first.destination_chain, "1");
+        assert_eq!(log.entries.len(), PAYLOAD_LOG_CAPACITY);
+    }
+}
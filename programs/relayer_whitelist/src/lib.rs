@@ -0,0 +1,190 @@
+// `#[program]` expands to code calling the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use common::Ownable;
+
+declare_id!("FbBr5h3sSgF9egyq7aBoW6262uV3HHZ2dAoSzjgehZni");
+
+/// Maximum number of relayers the whitelist PDA can hold
+pub const MAX_RELAYERS: usize = 32;
+
+/// Represents the event emitted when a relayer is added to the whitelist.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RelayerAddedEvent {
+    /// The authority that performed the update
+    pub authority: Pubkey,
+    /// The relayer that was whitelisted
+    pub relayer: Pubkey,
+}
+
+/// Represents the event emitted when a relayer is removed from the whitelist.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RelayerRemovedEvent {
+    /// The authority that performed the update
+    pub authority: Pubkey,
+    /// The relayer that was removed
+    pub relayer: Pubkey,
+}
+
+/// Represents the event emitted when a relayer is checked against the whitelist.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RelayerCheckedEvent {
+    /// The relayer that was checked
+    pub relayer: Pubkey,
+    /// Whether the relayer is currently whitelisted
+    pub is_whitelisted: bool,
+}
+
+#[program]
+pub mod relayer_whitelist {
+    use super::*;
+
+    /// Only the program's upgrade authority may create the whitelist.
+    pub fn init_whitelist(ctx: Context<InitWhitelist>) -> Result<()> {
+        common::ensure_upgrade_authority(
+            &ctx.accounts.program_data,
+            &ctx.accounts.authority.key(),
+        )?;
+        ctx.accounts.whitelist_pda.set_inner(RelayerWhitelist {
+            authority: ctx.accounts.authority.key(),
+            relayers: Vec::new(),
+            bump: ctx.bumps.whitelist_pda,
+        });
+        Ok(())
+    }
+
+    pub fn add_relayer(ctx: Context<UpdateWhitelist>, relayer: Pubkey) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist_pda;
+        whitelist.ensure_operator(&ctx.accounts.authority.key())?;
+        require!(
+            !whitelist.contains(&relayer),
+            WhitelistError::RelayerAlreadyWhitelisted
+        );
+        require!(
+            whitelist.relayers.len() < MAX_RELAYERS,
+            WhitelistError::WhitelistFull
+        );
+        whitelist.relayers.push(relayer);
+
+        anchor_lang::prelude::emit_cpi!(RelayerAddedEvent {
+            authority: ctx.accounts.authority.key(),
+            relayer,
+        });
+        Ok(())
+    }
+
+    pub fn remove_relayer(ctx: Context<UpdateWhitelist>, relayer: Pubkey) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist_pda;
+        whitelist.ensure_operator(&ctx.accounts.authority.key())?;
+        let position = whitelist
+            .relayers
+            .iter()
+            .position(|r| r == &relayer)
+            .ok_or(WhitelistError::RelayerNotWhitelisted)?;
+        whitelist.relayers.swap_remove(position);
+
+        anchor_lang::prelude::emit_cpi!(RelayerRemovedEvent {
+            authority: ctx.accounts.authority.key(),
+            relayer,
+        });
+        Ok(())
+    }
+
+    pub fn check_relayer(ctx: Context<CheckRelayer>, relayer: Pubkey) -> Result<()> {
+        anchor_lang::prelude::emit_cpi!(RelayerCheckedEvent {
+            relayer,
+            is_whitelisted: ctx.accounts.whitelist_pda.contains(&relayer),
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitWhitelist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = RelayerWhitelist::SPACE,
+        seeds = [seed_prefixes::WHITELIST_SEED],
+        bump
+    )]
+    pub whitelist_pda: Account<'info, RelayerWhitelist>,
+    #[account(constraint = this_program.programdata_address()? == Some(program_data.key()))]
+    pub this_program: Program<'info, crate::program::RelayerWhitelist>,
+    /// Names the upgrade authority, the only signer allowed to init
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct UpdateWhitelist<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seed_prefixes::WHITELIST_SEED],
+        bump = whitelist_pda.bump
+    )]
+    pub whitelist_pda: Account<'info, RelayerWhitelist>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct CheckRelayer<'info> {
+    #[account(
+        seeds = [seed_prefixes::WHITELIST_SEED],
+        bump = whitelist_pda.bump
+    )]
+    pub whitelist_pda: Account<'info, RelayerWhitelist>,
+}
+
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct RelayerWhitelist {
+    /// The account allowed to add and remove relayers
+    pub authority: Pubkey,
+    /// The currently whitelisted relayers
+    pub relayers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl RelayerWhitelist {
+    /// Discriminator + authority + vec length prefix + relayers + bump
+    pub const SPACE: usize = 8 + 32 + 4 + 32 * MAX_RELAYERS + 1;
+
+    pub fn contains(&self, relayer: &Pubkey) -> bool {
+        self.relayers.iter().any(|r| r == relayer)
+    }
+}
+
+impl Ownable for RelayerWhitelist {
+    fn operator(&self) -> Pubkey {
+        self.authority
+    }
+
+    fn set_operator(&mut self, operator: Pubkey) {
+        self.authority = operator;
+    }
+}
+
+#[error_code]
+pub enum WhitelistError {
+    #[msg("Relayer is already whitelisted")]
+    RelayerAlreadyWhitelisted,
+    #[msg("Relayer is not whitelisted")]
+    RelayerNotWhitelisted,
+    #[msg("Whitelist is full")]
+    WhitelistFull,
+}
+
+pub mod seed_prefixes {
+    /// The seed prefix for deriving the relayer whitelist PDA
+    pub const WHITELIST_SEED: &[u8] = b"relayer-whitelist";
+}
@@ -0,0 +1,87 @@
+-- The Postgres schema in SQLite's types: JSON is TEXT, bytes a BLOB.
+
+-- Every event `listen` stored, whatever its family.
+CREATE TABLE events (
+    signature TEXT NOT NULL,
+    -- The message id of an emit_cpi! event, or the log line of an emit! one
+    location TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    program TEXT NOT NULL,
+    program_id TEXT NOT NULL,
+    event_type TEXT,
+    message_id TEXT,
+    -- See EventRecord::key
+    event_key TEXT NOT NULL,
+    fields TEXT,
+    data BLOB NOT NULL,
+    gmp TEXT,
+    stored_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (signature, location)
+);
+CREATE INDEX events_slot ON events (slot);
+CREATE INDEX events_event_key ON events (event_key);
+CREATE INDEX events_event_type ON events (event_type);
+
+-- The family tables' columns other than the key and the event type are
+-- nullable, so an event whose IDL lacks one of the fields is still stored.
+
+-- CallContractEvent and CallContractWithTokenEvent
+CREATE TABLE contract_calls (
+    signature TEXT NOT NULL,
+    location TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    message_id TEXT,
+    sender TEXT,
+    destination_chain TEXT,
+    destination_contract_address TEXT,
+    payload_hash TEXT,
+    payload TEXT,
+    symbol TEXT,
+    amount NUMERIC,
+    PRIMARY KEY (signature, location),
+    FOREIGN KEY (signature, location) REFERENCES events (signature, location)
+);
+CREATE INDEX contract_calls_message_id ON contract_calls (message_id);
+CREATE INDEX contract_calls_payload_hash ON contract_calls (payload_hash);
+
+-- MessageApprovedEvent and MessageExecutedEvent
+CREATE TABLE gateway_messages (
+    signature TEXT NOT NULL,
+    location TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    -- approved or executed
+    status TEXT NOT NULL,
+    command_id TEXT,
+    cc_id TEXT,
+    source_chain TEXT,
+    source_address TEXT,
+    destination_chain TEXT,
+    destination_address TEXT,
+    payload_hash TEXT,
+    PRIMARY KEY (signature, location),
+    FOREIGN KEY (signature, location) REFERENCES events (signature, location)
+);
+CREATE INDEX gateway_messages_command_id ON gateway_messages (command_id);
+CREATE INDEX gateway_messages_cc_id ON gateway_messages (source_chain, cc_id);
+
+-- GasPaidEvent, GasAddedEvent and GasRefundedEvent
+CREATE TABLE gas_payments (
+    signature TEXT NOT NULL,
+    location TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    -- The message paid for, added to or refunded
+    message_id TEXT,
+    sender TEXT,
+    receiver TEXT,
+    amount NUMERIC,
+    refund_address TEXT,
+    spl_token_account TEXT,
+    destination_chain TEXT,
+    destination_address TEXT,
+    payload_hash TEXT,
+    PRIMARY KEY (signature, location),
+    FOREIGN KEY (signature, location) REFERENCES events (signature, location)
+);
+CREATE INDEX gas_payments_message_id ON gas_payments (message_id);
@@ -0,0 +1,178 @@
+//! Fetch-and-deserialize helpers for program_tester and gas_service state accounts.
+//!
+//! The 8-byte Anchor discriminator is checked before the body is decoded, so a
+//! wrong address fails with a clear error instead of garbage fields.
+//! `decode_known_account` tells the account types apart by that discriminator.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use gas_service::GasConfig;
+use program_tester::{GatewayConfig, IncomingMessage, PayloadLog, VerificationSessionAccount};
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{AccountError, RpcError};
+use crate::events::ToJson;
+use crate::pdas::{
+    gas_config_pda, gateway_root_pda, incoming_message_pda, verification_session_pda,
+};
+
+/// Fetches `address` and decodes it as `T` after checking its discriminator.
+pub async fn fetch_account<T>(rpc: &RpcClient, address: &Pubkey) -> Result<T, AccountError>
+where
+    T: AccountDeserialize + Discriminator,
+{
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())
+        .await
+        .map_err(RpcError::from)?
+        .value
+        .ok_or(AccountError::NotFound { address: *address })?;
+    decode_account(address, &account.data)
+}
+
+/// Decodes raw account data as `T` after checking its discriminator.
+pub fn decode_account<T>(address: &Pubkey, data: &[u8]) -> Result<T, AccountError>
+where
+    T: AccountDeserialize + Discriminator,
+{
+    let actual = data.get(..T::DISCRIMINATOR.len()).unwrap_or(data);
+    if actual != T::DISCRIMINATOR {
+        return Err(AccountError::Discriminator {
+            address: *address,
+            expected: T::DISCRIMINATOR.to_vec(),
+            actual: actual.to_vec(),
+        });
+    }
+    T::try_deserialize(&mut &data[..]).map_err(|e| AccountError::Deserialize {
+        address: *address,
+        reason: e.to_string(),
+    })
+}
+
+pub async fn fetch_gateway_config(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<GatewayConfig, AccountError> {
+    fetch_account(rpc, &gateway_root_pda(program_id).0).await
+}
+
+pub async fn fetch_verification_session(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    payload_merkle_root: &[u8; 32],
+) -> Result<VerificationSessionAccount, AccountError> {
+    fetch_account(
+        rpc,
+        &verification_session_pda(program_id, payload_merkle_root).0,
+    )
+    .await
+}
+
+pub async fn fetch_incoming_message(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    command_id: &[u8; 32],
+) -> Result<IncomingMessage, AccountError> {
+    fetch_account(rpc, &incoming_message_pda(program_id, command_id).0).await
+}
+
+pub async fn fetch_gas_config(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<GasConfig, AccountError> {
+    fetch_account(rpc, &gas_config_pda(program_id).0).await
+}
+
+/// An account of one of the types the dummy programs keep.
+#[derive(Debug, Clone)]
+pub enum KnownAccount {
+    GatewayConfig(GatewayConfig),
+    VerificationSession(VerificationSessionAccount),
+    IncomingMessage(IncomingMessage),
+    PayloadLog(PayloadLog),
+    GasConfig(GasConfig),
+}
+
+impl KnownAccount {
+    /// The account type's name in its program.
+    pub fn name(&self) -> &'static str {
+        match self {
+            KnownAccount::GatewayConfig(_) => "GatewayConfig",
+            KnownAccount::VerificationSession(_) => "VerificationSessionAccount",
+            KnownAccount::IncomingMessage(_) => "IncomingMessage",
+            KnownAccount::PayloadLog(_) => "PayloadLog",
+            KnownAccount::GasConfig(_) => "GasConfig",
+        }
+    }
+
+    /// The account's fields as a JSON object, encoded as `ToJson` does;
+    /// `u128`s are strings.
+    pub fn to_json(&self) -> Value {
+        match self {
+            KnownAccount::GatewayConfig(config) => json!({
+                "current_epoch": config.current_epoch,
+                "previous_verifier_set_retention": config.previous_verifier_set_retention,
+                "minimum_rotation_delay": config.minimum_rotation_delay,
+                "last_rotation_timestamp": config.last_rotation_timestamp,
+                "operator": config.operator.to_json(),
+                "domain_separator": config.domain_separator.to_json(),
+                "bump": config.bump,
+            }),
+            KnownAccount::VerificationSession(session) => {
+                let verification = &session.signature_verification;
+                json!({
+                    "accumulated_threshold": verification.accumulated_threshold.to_string(),
+                    "signature_slots": verification.signature_slots.to_json(),
+                    "signing_verifier_set_hash": verification.signing_verifier_set_hash.to_json(),
+                    "bump": session.bump,
+                })
+            }
+            KnownAccount::IncomingMessage(message) => json!({
+                "status": if message.status.is_approved() { "approved" } else { "executed" },
+                "message_hash": message.message_hash.to_json(),
+                "payload_hash": message.payload_hash.to_json(),
+                "bump": message.bump,
+                "signing_pda_bump": message.signing_pda_bump,
+            }),
+            KnownAccount::PayloadLog(log) => json!({
+                "head": log.head,
+                "total_recorded": log.total_recorded,
+                "entries": log
+                    .ordered_entries()
+                    .map(|entry| json!({
+                        "payload_hash": entry.payload_hash.to_json(),
+                        "sender": entry.sender.to_json(),
+                        "destination_chain": entry.destination_chain,
+                    }))
+                    .collect::<Vec<_>>(),
+                "bump": log.bump,
+            }),
+            KnownAccount::GasConfig(config) => json!({
+                "operator": config.operator.to_json(),
+                "bump": config.bump,
+            }),
+        }
+    }
+}
+
+/// Decodes raw account data as whichever known type its discriminator names.
+pub fn decode_known_account(address: &Pubkey, data: &[u8]) -> Result<KnownAccount, AccountError> {
+    let discriminator = data.get(..8).unwrap_or(data);
+    if discriminator == GatewayConfig::DISCRIMINATOR {
+        decode_account(address, data).map(KnownAccount::GatewayConfig)
+    } else if discriminator == VerificationSessionAccount::DISCRIMINATOR {
+        decode_account(address, data).map(KnownAccount::VerificationSession)
+    } else if discriminator == IncomingMessage::DISCRIMINATOR {
+        decode_account(address, data).map(KnownAccount::IncomingMessage)
+    } else if discriminator == PayloadLog::DISCRIMINATOR {
+        decode_account(address, data).map(KnownAccount::PayloadLog)
+    } else if discriminator == GasConfig::DISCRIMINATOR {
+        decode_account(address, data).map(KnownAccount::GasConfig)
+    } else {
+        Err(AccountError::Unknown {
+            address: *address,
+            discriminator: discriminator.to_vec(),
+        })
+    }
+}
@@ -0,0 +1,87 @@
+//! Address lookup tables for v0 transactions.
+//!
+//! A new or extended table only resolves addresses from the slot after the
+//! change, so callers wait a slot before sending transactions that use it.
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::instruction::{create_lookup_table, extend_lookup_table};
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::message::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+
+use crate::error::{AccountError, RpcError};
+use crate::pdas::{
+    call_contract_signing_pda, event_authority_pda, gas_config_pda, gateway_root_pda,
+    payload_log_pda,
+};
+use crate::rpc::ResilientRpc;
+
+/// Addresses per extend instruction, which keeps each transaction well under
+/// the size limit.
+const EXTEND_CHUNK: usize = 20;
+
+/// The program ids and singleton PDAs most gateway and gas instructions touch.
+pub fn common_addresses(gateway_program_id: &Pubkey, gas_program_id: &Pubkey) -> Vec<Pubkey> {
+    vec![
+        *gateway_program_id,
+        *gas_program_id,
+        system_program::id(),
+        gateway_root_pda(gateway_program_id).0,
+        event_authority_pda(gateway_program_id).0,
+        payload_log_pda(gateway_program_id).0,
+        call_contract_signing_pda(gateway_program_id).0,
+        gas_config_pda(gas_program_id).0,
+        event_authority_pda(gas_program_id).0,
+    ]
+}
+
+/// Creates a lookup table owned by `payer` holding `addresses`.
+pub async fn create_table(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    addresses: &[Pubkey],
+) -> Result<Pubkey, RpcError> {
+    let recent_slot = rpc.retry(|| rpc.get_slot()).await?;
+    let (create_ix, table) = create_lookup_table(payer.pubkey(), payer.pubkey(), recent_slot);
+    rpc.send_ix(payer, &[create_ix]).await?;
+    extend_table(rpc, payer, &table, addresses).await?;
+    Ok(table)
+}
+
+/// Appends `addresses` to `table`, which must be owned by `payer`.
+pub async fn extend_table(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    table: &Pubkey,
+    addresses: &[Pubkey],
+) -> Result<(), RpcError> {
+    for chunk in addresses.chunks(EXTEND_CHUNK) {
+        let ix = extend_lookup_table(*table, payer.pubkey(), Some(payer.pubkey()), chunk.to_vec());
+        rpc.send_ix(payer, &[ix]).await?;
+    }
+    Ok(())
+}
+
+/// Fetches a table in the form `TxBuilder::lookup_table` takes.
+pub async fn fetch_table(
+    rpc: &RpcClient,
+    table: &Pubkey,
+) -> Result<AddressLookupTableAccount, AccountError> {
+    let account = rpc
+        .get_account_with_commitment(table, rpc.commitment())
+        .await
+        .map_err(RpcError::from)?
+        .value
+        .ok_or(AccountError::NotFound { address: *table })?;
+    let state =
+        AddressLookupTable::deserialize(&account.data).map_err(|e| AccountError::Deserialize {
+            address: *table,
+            reason: e.to_string(),
+        })?;
+    Ok(AddressLookupTableAccount {
+        key: *table,
+        addresses: state.addresses.to_vec(),
+    })
+}
@@ -0,0 +1,48 @@
+//! Sends many independent transactions at once.
+//!
+//! `send_ix` in a loop waits for each confirmation before sending the next; a
+//! `BatchSender` keeps up to `concurrency` transactions in flight instead. Each
+//! transaction is retried on its own (see `ResilientRpc::send`), so a failing
+//! one neither stops nor resends the others.
+
+use futures::stream::{self, StreamExt};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature};
+
+use crate::error::RpcError;
+use crate::rpc::ResilientRpc;
+
+pub const DEFAULT_CONCURRENCY: usize = 16;
+
+pub struct BatchSender<'a> {
+    rpc: &'a ResilientRpc,
+    payer: &'a Keypair,
+    concurrency: usize,
+}
+
+impl<'a> BatchSender<'a> {
+    pub fn new(rpc: &'a ResilientRpc, payer: &'a Keypair) -> Self {
+        Self {
+            rpc,
+            payer,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// At most `concurrency` transactions are sent or confirming at a time;
+    /// 1 sends them one after another.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sends each entry of `txs` as one transaction. The results come back in
+    /// the order of `txs`, whatever order the transactions landed in.
+    pub async fn send_all(&self, txs: Vec<Vec<Instruction>>) -> Vec<Result<Signature, RpcError>> {
+        stream::iter(txs)
+            .map(|ixs| async move { self.rpc.send_ix(self.payer, &ixs).await })
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+}
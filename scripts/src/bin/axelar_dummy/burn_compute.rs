@@ -0,0 +1,29 @@
+//! `burn-compute`: hashes in a loop on chain to see how far a compute unit
+//! limit goes; raise it with the global `--cu-limit`.
+
+use anyhow::Result;
+use clap::Args;
+use scripts::config::Config;
+use scripts::gateway::build_burn_compute_ix;
+use solana_sdk::signature::Signer;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct BurnComputeArgs {
+    /// Hashing rounds to run
+    #[arg(long, env = "ITERATIONS", default_value_t = 1_000)]
+    iterations: u32,
+}
+
+pub async fn run(config: &Config, args: BurnComputeArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let ix = build_burn_compute_ix(&program_id, &payer.pubkey(), args.iterations);
+    report.send(&rpc, &payer, "burn_compute", &[ix]).await?;
+
+    report.field("iterations", args.iterations);
+    Ok(())
+}
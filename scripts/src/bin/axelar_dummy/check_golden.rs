@@ -0,0 +1,16 @@
+//! `check-golden`: checks the golden Borsh vectors against the programs'
+//! current layouts, see `scripts::golden`. Needs no cluster.
+
+use anyhow::Result;
+use scripts::golden::check_all;
+
+use crate::report::Report;
+
+pub fn run(report: &mut Report) -> Result<()> {
+    let checked = check_all()?;
+    for name in &checked {
+        report.note(format!("ok  {name}"));
+    }
+    report.field("checked", checked.len());
+    Ok(())
+}
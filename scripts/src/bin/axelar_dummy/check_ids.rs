@@ -0,0 +1,96 @@
+//! `check-ids`: compares every program's `declare_id!` with its keypair in
+//! `target/deploy`, `Anchor.toml` and the config, and checks that the
+//! configured gateway and gas service are deployed, see `scripts::ids`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use scripts::cluster::Cluster;
+use scripts::config::Config;
+use scripts::ids::{collect, workspace_root, IdSource};
+use serde_json::{json, Map, Value};
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct CheckIdsArgs {
+    /// Workspace root; the nearest directory up from here with an `Anchor.toml` by default
+    #[arg(long)]
+    root: Option<PathBuf>,
+    /// Don't check that the configured programs are deployed
+    #[arg(long)]
+    offline: bool,
+}
+
+pub async fn run(config: &Config, args: CheckIdsArgs, report: &mut Report) -> Result<()> {
+    let root = match args.root {
+        Some(root) => root,
+        None => workspace_root(&std::env::current_dir()?)
+            .ok_or_else(|| anyhow!("no Anchor.toml here or above; pass --root"))?,
+    };
+    check(config, &root, args.offline, report).await
+}
+
+/// `check-ids` for the workspace at `root`.
+pub async fn check(config: &Config, root: &Path, offline: bool, report: &mut Report) -> Result<()> {
+    let cluster = config.cluster.unwrap_or(Cluster::Localnet);
+    let rpc = config.rpc();
+
+    let mut problems = Vec::new();
+    let mut results = Vec::new();
+    for program in collect(root, cluster, config)? {
+        let mut ids = Map::new();
+        for (source, id) in &program.ids {
+            ids.insert(source.to_string(), Value::String(id.to_string()));
+        }
+        let mismatches = program.mismatches();
+        for (source, id) in &mismatches {
+            problems.push(format!(
+                "{}: {} is {}, declare_id! is {}",
+                program.program,
+                source,
+                id,
+                program.id(IdSource::DeclareId).unwrap_or_default()
+            ));
+        }
+
+        let mut deployed = Value::Null;
+        if let Some(id) = program.id(IdSource::Config).filter(|_| !offline) {
+            let account = rpc
+                .retry(|| rpc.get_account_with_commitment(&id, rpc.commitment()))
+                .await?
+                .value;
+            let executable = account.is_some_and(|account| account.executable);
+            if !executable {
+                problems.push(format!(
+                    "{}: nothing is deployed at the configured {} on {}",
+                    program.program, id, config.rpc_url
+                ));
+            }
+            deployed = executable.into();
+        }
+        results.push(json!({
+            "program": program.program,
+            "ids": ids,
+            "deployed": deployed,
+            "ok": mismatches.is_empty() && deployed != Value::Bool(false),
+        }));
+    }
+    report.field("root", root.display().to_string());
+    report.field("cluster", cluster.to_string());
+    report.field("programs", results);
+
+    if problems.is_empty() {
+        report.note("All program ids agree");
+        Ok(())
+    } else {
+        for problem in &problems {
+            report.note(problem);
+        }
+        Err(anyhow!(
+            "{} program id problem(s); `anchor keys sync` rewrites declare_id! and Anchor.toml from the keypairs",
+            problems.len()
+        ))
+    }
+}
@@ -0,0 +1,150 @@
+//! `decode-data`: decodes raw instruction data, as explorers show it, into the
+//! instruction name and arguments through the programs' IDLs (see
+//! `scripts::idl`).
+//!
+//! The IDL comes from `--idl`, or from `target/idl` for the program named by
+//! `--program`. Without either, every IDL in `target/idl` is tried.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::{Args, ValueEnum};
+use scripts::codec::{bs58_decode, hex_decode, hex_encode_prefixed};
+use scripts::config::Config;
+use scripts::idl::IdlCodec;
+use scripts::ids::workspace_root;
+use scripts::Mode;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct DecodeDataArgs {
+    /// Instruction data as base58, base64 or hex
+    data: String,
+    /// How `data` is encoded
+    #[arg(long, value_enum, default_value_t = Encoding::Auto)]
+    encoding: Encoding,
+    /// Program the data is for: `gateway`, `gas`, a crate name like
+    /// `program_tester`, or the configured gateway or gas service id
+    #[arg(long)]
+    program: Option<String>,
+    /// IDL to decode with, instead of the ones in `target/idl`; wins over `--program`
+    #[arg(long, env = "IDL_PATH")]
+    idl: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Encoding {
+    /// `0x` or bare hex, then base58, then base64
+    Auto,
+    Base58,
+    Base64,
+    Hex,
+}
+
+pub async fn run(config: &Config, args: DecodeDataArgs, report: &mut Report) -> Result<()> {
+    let data = decode_input(&args.data, args.encoding)?;
+    report.field("length", data.len());
+    report.field(
+        "discriminator",
+        data.get(..8).map(hex_encode_prefixed).unwrap_or_default(),
+    );
+
+    let idls = match (&args.idl, &args.program) {
+        (Some(path), _) => vec![path.clone()],
+        (None, Some(program)) => {
+            vec![idl_dir()?.join(format!("{}.json", crate_name(config, program)?))]
+        }
+        (None, None) => all_idls(&idl_dir()?)?,
+    };
+
+    for path in &idls {
+        let idl = IdlCodec::load(path)?;
+        if let Some(decoded) = idl
+            .decode_instruction(&data)
+            .with_context(|| format!("decoding with {}", path.display()))?
+        {
+            report.field("idl", path.display().to_string());
+            report.field("instruction", decoded.name);
+            if report.json() {
+                report.field("args", decoded.value);
+            } else {
+                report.note(format!("{:#}", decoded.value));
+            }
+            return Ok(());
+        }
+    }
+    let tried: Vec<String> = idls.iter().map(|p| p.display().to_string()).collect();
+    Err(anyhow!(
+        "no instruction with this discriminator in {}",
+        tried.join(", ")
+    ))
+}
+
+fn decode_input(input: &str, encoding: Encoding) -> Result<Vec<u8>> {
+    // Explorers group hex in words
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let encoding = match encoding {
+        Encoding::Auto if input.starts_with("0x") || is_hex(&input) => Encoding::Hex,
+        Encoding::Auto if bs58_decode(&input, Mode::Strict).is_ok() => Encoding::Base58,
+        Encoding::Auto => Encoding::Base64,
+        encoding => encoding,
+    };
+    Ok(match encoding {
+        Encoding::Hex => hex_decode(&input, Mode::Strict)?,
+        Encoding::Base58 => bs58_decode(&input, Mode::Strict)?,
+        Encoding::Base64 | Encoding::Auto => STANDARD
+            .decode(&input)
+            .with_context(|| format!("invalid base64 {input:?}"))?,
+    })
+}
+
+/// Even-length hex without a prefix, which base58 data almost never is.
+fn is_hex(input: &str) -> bool {
+    !input.is_empty()
+        && input.len().is_multiple_of(2)
+        && input.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The crate, and so IDL, name for `program`.
+fn crate_name(config: &Config, program: &str) -> Result<String> {
+    Ok(match program {
+        "gateway" => "program_tester".to_string(),
+        "gas" => "gas_service".to_string(),
+        _ => match Pubkey::from_str(program) {
+            Ok(id) if id == config.gateway_program_id => "program_tester".to_string(),
+            Ok(id) if id == config.gas_program_id => "gas_service".to_string(),
+            Ok(id) => return Err(anyhow!("{id} is not a configured program; pass --idl")),
+            Err(_) => program.to_string(),
+        },
+    })
+}
+
+/// `target/idl` of the workspace, where `anchor build` writes the IDLs.
+fn idl_dir() -> Result<PathBuf> {
+    let root = workspace_root(&std::env::current_dir()?)
+        .ok_or_else(|| anyhow!("no Anchor.toml here or above; pass --idl"))?;
+    Ok(root.join("target/idl"))
+}
+
+fn all_idls(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir).with_context(|| {
+        format!(
+            "failed to read {}; run `anchor build` or pass --idl",
+            dir.display()
+        )
+    })?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
@@ -0,0 +1,113 @@
+//! `decode-tx`: prints every Anchor event in a transaction, from its inner
+//! instructions and its logs, with the emitting program, the discriminator and
+//! the decoded fields; see `scripts::inspect`.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use scripts::codec::hex_encode_prefixed;
+use scripts::config::Config;
+use scripts::idl::IdlCodec;
+use scripts::inspect::{fetch_tx_events, EventSource, FoundEvent};
+use serde_json::{json, Value};
+use solana_sdk::signature::Signature;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct DecodeTxArgs {
+    /// Signature of the transaction
+    signature: Signature,
+    /// IDL to decode events the compiled-in registry doesn't know
+    #[arg(long, env = "IDL_PATH")]
+    idl: Option<PathBuf>,
+    /// Print the transaction's logs too
+    #[arg(long)]
+    logs: bool,
+}
+
+pub async fn run(config: &Config, args: DecodeTxArgs, report: &mut Report) -> Result<()> {
+    let rpc = config.rpc();
+    let idl = args.idl.map(IdlCodec::load).transpose()?;
+    let tx = fetch_tx_events(&rpc, &args.signature).await?;
+
+    report.field("signature", tx.signature.to_string());
+    report.field("slot", tx.slot);
+    if let Some(err) = &tx.err {
+        report.field("error", err.clone());
+    }
+    if args.logs && report.json() {
+        report.field("logs", tx.logs.clone());
+    } else if args.logs {
+        report.note("Logs:");
+        for log in &tx.logs {
+            report.note(format!("  {log}"));
+        }
+    }
+
+    let mut events = Vec::new();
+    for found in &tx.events {
+        let (name, fields, decoded_by) = decode(found, idl.as_ref());
+        let (location, source) = match found.source {
+            EventSource::Cpi(message_id) => (message_id.to_string(), "cpi"),
+            EventSource::Log(line) => (format!("log line {}", line + 1), "log"),
+        };
+        let program = found.program_id.map(|id| id.to_string());
+        let discriminator = found.discriminator().map(hex_encode_prefixed);
+        if !report.json() {
+            report.note(format!(
+                "{} from {} ({})",
+                name.as_deref().unwrap_or("Unknown event"),
+                program.as_deref().unwrap_or("an unknown program"),
+                location
+            ));
+            report.note(format!(
+                "  discriminator: {}",
+                discriminator.as_deref().unwrap_or("none")
+            ));
+            match &fields {
+                Some(fields) => report.note(format!("  {fields:#}").replace('\n', "\n  ")),
+                None => report.note(format!("  data: {}", hex_encode_prefixed(&found.data))),
+            }
+        }
+        events.push(json!({
+            "source": source,
+            "location": location,
+            "program": program,
+            "discriminator": discriminator,
+            "name": name,
+            "fields": fields,
+            "decoded_by": decoded_by,
+            "data": fields.is_none().then(|| hex_encode_prefixed(&found.data)),
+        }));
+    }
+    if events.is_empty() {
+        report.note("No Anchor events");
+    }
+    report.field("events", events);
+    Ok(())
+}
+
+/// The event's name and fields, from the registry or else the IDL, and which
+/// of the two decoded it.
+pub(crate) fn decode(
+    found: &FoundEvent,
+    idl: Option<&IdlCodec>,
+) -> (Option<String>, Option<Value>, Option<&'static str>) {
+    if let Some(event) = &found.event {
+        return (
+            Some(event.name().to_string()),
+            Some(event.to_json()),
+            Some("registry"),
+        );
+    }
+    match idl.map(|idl| idl.decode_event(&found.data)) {
+        Some(Ok(Some(event))) => (Some(event.name), Some(event.value), Some("idl")),
+        Some(Err(e)) => {
+            eprintln!("Failed to decode event via IDL: {e}");
+            (None, None, None)
+        }
+        Some(Ok(None)) | None => (None, None, None),
+    }
+}
@@ -0,0 +1,127 @@
+//! `deploy`: deploys or upgrades the programs `anchor build` left in
+//! `target/deploy`, each at the address of its `<name>-keypair.json`, and
+//! records the gateway and gas service ids in the config file.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use scripts::batch::DEFAULT_CONCURRENCY;
+use scripts::config::{Config, DEFAULT_CONFIG_PATH};
+use scripts::deploy::{deploy_program, read_program};
+use scripts::read_keypair;
+use serde_json::json;
+use solana_sdk::signature::Signer;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct DeployArgs {
+    /// Where the `.so` files and their keypairs are
+    #[arg(long, default_value = "target/deploy")]
+    pub(crate) dir: PathBuf,
+    /// Program to deploy, e.g. `program_tester`; every `.so` in the directory by default
+    #[arg(long = "program")]
+    programs: Vec<String>,
+    /// Buffer writes in flight at once
+    #[arg(long, default_value_t = DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+    /// Leave the config file alone
+    #[arg(long)]
+    no_record: bool,
+}
+
+pub async fn run(
+    config: &Config,
+    args: DeployArgs,
+    config_path: Option<&Path>,
+    report: &mut Report,
+) -> Result<()> {
+    if report.dry_run() {
+        return Err(anyhow!("deployments can't be simulated; drop --dry-run"));
+    }
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let programs = if args.programs.is_empty() {
+        built_programs(&args.dir)?
+    } else {
+        args.programs
+    };
+    if programs.is_empty() {
+        return Err(anyhow!(
+            "no programs in {}; run `anchor build` first",
+            args.dir.display()
+        ));
+    }
+
+    let mut deployed = Vec::new();
+    let (mut gateway, mut gas) = (None, None);
+    for name in &programs {
+        let so = args.dir.join(format!("{name}.so"));
+        let keypair = read_keypair(args.dir.join(format!("{name}-keypair.json")))?;
+        let program = read_program(&so)?;
+        report.note(format!(
+            "Deploying {} ({} bytes) to {}",
+            name,
+            program.len(),
+            keypair.pubkey()
+        ));
+        let deployment = deploy_program(&rpc, &payer, &keypair, &program, args.concurrency)
+            .await
+            .with_context(|| format!("deploying {name}"))?;
+        let action = if deployment.upgraded {
+            "upgrade"
+        } else {
+            "deploy"
+        };
+        report
+            .tx(
+                &rpc,
+                &format!("{action} {name}"),
+                deployment.signature,
+                None,
+            )
+            .await?;
+        match name.as_str() {
+            "program_tester" => gateway = Some(deployment.program_id),
+            "gas_service" => gas = Some(deployment.program_id),
+            _ => {}
+        }
+        deployed.push(json!({
+            "program": name,
+            "program_id": deployment.program_id.to_string(),
+            "upgraded": deployment.upgraded,
+            "slot": deployment.slot,
+        }));
+    }
+    report.field("programs", deployed);
+
+    if !args.no_record && (gateway.is_some() || gas.is_some()) {
+        let path = config_path.unwrap_or(Path::new(DEFAULT_CONFIG_PATH));
+        Config::record_program_ids(path, gateway, gas)?;
+        report.field("config", path.display().to_string());
+    }
+    Ok(())
+}
+
+/// Names of the programs with a `.so` and a keypair in `dir`, sorted.
+fn built_programs(dir: &Path) -> Result<Vec<String>> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("so") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if dir.join(format!("{name}-keypair.json")).exists() {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
@@ -0,0 +1,120 @@
+//! `derive-pda`: prints every PDA of the configured programs with its bump,
+//! from the same `scripts::pdas` derivations the other commands use.
+//!
+//! The verification session and incoming message PDAs are salted, so they
+//! are only printed for the roots and messages passed in.
+
+use anyhow::Result;
+use clap::Args;
+use scripts::codec::hex_encode_prefixed;
+use scripts::config::Config;
+use scripts::hashes::command_id;
+use scripts::pdas::{
+    call_contract_signing_pda, event_authority_pda, gas_config_pda, gateway_root_pda,
+    incoming_message_pda, payload_log_pda, verification_session_pda,
+};
+use scripts::{hex_array, Mode};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct DerivePdaArgs {
+    /// Payload merkle root to derive the verification session for, as hex
+    #[arg(long = "root", value_name = "HEX")]
+    roots: Vec<String>,
+    /// Source chain of the message to derive the incoming message for
+    #[arg(long, requires = "message_id")]
+    source_chain: Option<String>,
+    /// Id of the message to derive the incoming message for
+    #[arg(long, requires = "source_chain")]
+    message_id: Option<String>,
+}
+
+/// One PDA of the table.
+struct Row {
+    name: String,
+    program: Pubkey,
+    address: Pubkey,
+    bump: u8,
+}
+
+impl Row {
+    fn new(name: impl Into<String>, program: Pubkey, (address, bump): (Pubkey, u8)) -> Self {
+        Row {
+            name: name.into(),
+            program,
+            address,
+            bump,
+        }
+    }
+}
+
+pub async fn run(config: &Config, args: DerivePdaArgs, report: &mut Report) -> Result<()> {
+    let gateway = config.gateway_program_id;
+    let gas = config.gas_program_id;
+    report.field("gateway", gateway.to_string());
+    report.field("gas_service", gas.to_string());
+
+    let mut rows = vec![
+        Row::new("gateway_root", gateway, gateway_root_pda(&gateway)),
+        Row::new("payload_log", gateway, payload_log_pda(&gateway)),
+        Row::new(
+            "call_contract_signing",
+            gateway,
+            call_contract_signing_pda(&gateway),
+        ),
+        Row::new(
+            "gateway_event_authority",
+            gateway,
+            event_authority_pda(&gateway),
+        ),
+        Row::new("gas_config", gas, gas_config_pda(&gas)),
+        Row::new("gas_event_authority", gas, event_authority_pda(&gas)),
+    ];
+    for root in &args.roots {
+        let root = hex_array::<32>(root, Mode::Strict)?;
+        rows.push(Row::new(
+            format!("verification_session {}", hex_encode_prefixed(&root)),
+            gateway,
+            verification_session_pda(&gateway, &root),
+        ));
+    }
+    if let (Some(chain), Some(id)) = (&args.source_chain, &args.message_id) {
+        let command_id = command_id(chain, id);
+        report.field("command_id", hex_encode_prefixed(&command_id));
+        rows.push(Row::new(
+            format!("incoming_message {chain}-{id}"),
+            gateway,
+            incoming_message_pda(&gateway, &command_id),
+        ));
+    }
+
+    if report.json() {
+        for row in &rows {
+            report.pda(&row.name, row.address);
+        }
+        let derived: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                json!({
+                    "name": row.name,
+                    "program": row.program.to_string(),
+                    "address": row.address.to_string(),
+                    "bump": row.bump,
+                })
+            })
+            .collect();
+        report.field("derived", derived);
+        return Ok(());
+    }
+    let width = rows.iter().map(|row| row.name.len()).max().unwrap_or(0);
+    for row in &rows {
+        report.note(format!(
+            "{:<width$}  {:<44}  bump {:<3}  program {}",
+            row.name, row.address, row.bump, row.program
+        ));
+    }
+    Ok(())
+}
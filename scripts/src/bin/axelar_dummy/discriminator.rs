@@ -0,0 +1,124 @@
+//! `discriminator of|lookup`: computes Anchor discriminators for names, and
+//! finds the workspace instruction, event or account an 8-byte prefix belongs
+//! to; see `scripts::discriminators`.
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use scripts::codec::hex_encode_prefixed;
+use scripts::discriminators::{lookup as find_names, workspace_names, Kind};
+use scripts::ids::workspace_root;
+use scripts::{hex_decode, Mode};
+use serde_json::json;
+
+use crate::report::Report;
+
+#[derive(Subcommand)]
+pub enum DiscriminatorCommand {
+    /// Compute the discriminators of instruction, event or account names
+    Of(OfArgs),
+    /// Find the workspace names whose discriminator prefixes some data
+    Lookup(LookupArgs),
+}
+
+#[derive(Args)]
+pub struct OfArgs {
+    /// Instruction names as in Rust (`call_contract`), or event and account
+    /// type names (`CallContractEvent`)
+    #[arg(required = true)]
+    names: Vec<String>,
+    /// Only compute this kind; all three by default
+    #[arg(long, value_enum)]
+    kind: Option<KindArg>,
+}
+
+#[derive(Args)]
+pub struct LookupArgs {
+    /// Discriminator, or data starting with one, as hex
+    data: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum KindArg {
+    Instruction,
+    Event,
+    Account,
+}
+
+impl From<KindArg> for Kind {
+    fn from(kind: KindArg) -> Self {
+        match kind {
+            KindArg::Instruction => Kind::Instruction,
+            KindArg::Event => Kind::Event,
+            KindArg::Account => Kind::Account,
+        }
+    }
+}
+
+pub fn run(command: DiscriminatorCommand, report: &mut Report) -> Result<()> {
+    match command {
+        DiscriminatorCommand::Of(args) => of(args, report),
+        DiscriminatorCommand::Lookup(args) => lookup(args, report),
+    }
+}
+
+fn of(args: OfArgs, report: &mut Report) -> Result<()> {
+    let kinds = match args.kind {
+        Some(kind) => vec![kind.into()],
+        None => Kind::ALL.to_vec(),
+    };
+    let mut computed = Vec::new();
+    for name in &args.names {
+        for &kind in &kinds {
+            let discriminator = hex_encode_prefixed(&kind.discriminator(name));
+            report.note(format!("{kind:<11}  {name}  {discriminator}"));
+            computed.push(json!({
+                "name": name,
+                "kind": kind.to_string(),
+                "discriminator": discriminator,
+            }));
+        }
+    }
+    report.field("discriminators", computed);
+    Ok(())
+}
+
+fn lookup(args: LookupArgs, report: &mut Report) -> Result<()> {
+    let data = hex_decode(&args.data, Mode::Lenient)?;
+    let Some(prefix) = data.get(..8) else {
+        return Err(anyhow!(
+            "{} is {} bytes; a discriminator is 8",
+            args.data,
+            data.len()
+        ));
+    };
+    let root = workspace_root(&std::env::current_dir()?)
+        .ok_or_else(|| anyhow!("no Anchor.toml here or above"))?;
+    let names = workspace_names(&root)?;
+    let found = find_names(&names, prefix);
+
+    report.field("discriminator", hex_encode_prefixed(prefix));
+    if found.is_empty() {
+        return Err(anyhow!(
+            "{} is none of the {} instructions, events and accounts in {}",
+            hex_encode_prefixed(prefix),
+            names.len(),
+            root.join("programs").display()
+        ));
+    }
+    let matches: Vec<_> = found
+        .iter()
+        .map(|known| {
+            report.note(format!(
+                "{} {} of {}",
+                known.kind, known.name, known.program
+            ));
+            json!({
+                "program": known.program,
+                "kind": known.kind.to_string(),
+                "name": known.name,
+            })
+        })
+        .collect();
+    report.field("matches", matches);
+    Ok(())
+}
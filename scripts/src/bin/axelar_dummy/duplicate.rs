@@ -0,0 +1,191 @@
+//! `approve-duplicate`: approves the same cc_id twice in quick succession, for
+//! testing the gateway's idempotency and the indexer's duplicate handling.
+//!
+//! The gateway creates the incoming message PDA with `init`, so exactly one
+//! approval should land with a `MessageApprovedEvent` and the other fail with
+//! the system program's "account already in use". With `--same-tx` both
+//! approvals go in one transaction, which then fails as a whole and emits
+//! nothing. The transactions skip preflight so the duplicate lands as a
+//! failure instead of being rejected by the node.
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use program_tester::{CrossChainId, Message, MessageLeaf};
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::gateway::{build_approve_message_ix, build_init_verification_session_ix};
+use scripts::merkle::merkleise;
+use scripts::pdas::{incoming_message_pda, verification_session_pda};
+use scripts::receipt::Receipt;
+use scripts::tx::TxBuilder;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::system_instruction::SystemError;
+use solana_sdk::transaction::TransactionError;
+
+use crate::gateway::ensure_gateway_root;
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct DuplicateArgs {
+    /// Message id of the cc_id to approve twice; a fresh one by default
+    #[arg(long)]
+    message_id: Option<String>,
+    #[arg(long, env = "SOURCE_CHAIN", default_value = "ethereum")]
+    source_chain: String,
+    /// Put both approvals in one transaction instead of two
+    #[arg(long)]
+    same_tx: bool,
+}
+
+/// Compute unit limit of the first transaction; the second asks for one more
+/// so the two don't share a signature and get deduplicated by the cluster.
+const UNIT_LIMIT: u32 = 200_000;
+
+pub async fn run(config: &Config, args: DuplicateArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: args.source_chain,
+            id: args
+                .message_id
+                .unwrap_or_else(|| format!("approve-duplicate-{}", timestamp)),
+        },
+        source_address: payer.pubkey().to_string(),
+        destination_chain: "solana".to_string(),
+        destination_address: payer.pubkey().to_string(),
+        payload_hash: Sha256::digest(timestamp.to_le_bytes()).into(),
+    };
+    let command_id = message.command_id();
+    let leaf = MessageLeaf {
+        message,
+        position: 0,
+        set_size: 1,
+        domain_separator: [0u8; 32],
+        signing_verifier_set: [0u8; 32],
+    };
+    let (payload_merkle_root, mut messages) =
+        merkleise(vec![leaf]).ok_or_else(|| anyhow!("no messages to approve"))?;
+    let message = messages.remove(0);
+
+    let (verification_session, _) = verification_session_pda(&program_id, &payload_merkle_root);
+    report.pda("verification_session", verification_session);
+    if rpc.get_account(&verification_session).await.is_err() {
+        let ix =
+            build_init_verification_session_ix(&program_id, &payer.pubkey(), payload_merkle_root);
+        report
+            .send(&rpc, &payer, "init_verification_session", &[ix])
+            .await?;
+    }
+    let incoming_message = incoming_message_pda(&program_id, &command_id).0;
+    report.pda("incoming_message", incoming_message);
+    report.field("command_id", command_id.to_json());
+    let already_approved = rpc.get_account(&incoming_message).await.is_ok();
+    if already_approved {
+        report.note("The message is already approved, so no approval should land");
+    }
+
+    let ix = build_approve_message_ix(&program_id, &payer.pubkey(), message, payload_merkle_root);
+    if report.dry_run() {
+        let txs = if args.same_tx {
+            vec![vec![ix.clone(), ix]]
+        } else {
+            vec![vec![ix.clone()], vec![ix]]
+        };
+        for ixs in txs {
+            report.send(&rpc, &payer, "approve_message", &ixs).await?;
+        }
+        return Ok(());
+    }
+
+    let outcomes = if args.same_tx {
+        let builder = TxBuilder::new(&payer).instructions([ix.clone(), ix]);
+        let (signature, err) = rpc.send_unchecked(&builder).await?;
+        let receipt = report
+            .tx(&rpc, "approve_message (twice)", signature, None)
+            .await?;
+        vec![(signature, err, receipt)]
+    } else {
+        let first = TxBuilder::new(&payer)
+            .instruction(ix.clone())
+            .compute_unit_limit(UNIT_LIMIT);
+        let second = TxBuilder::new(&payer)
+            .instruction(ix)
+            .compute_unit_limit(UNIT_LIMIT + 1);
+        let (first, second) = tokio::join!(rpc.send_unchecked(&first), rpc.send_unchecked(&second));
+        let mut outcomes = Vec::new();
+        for (name, result) in [
+            ("approve_message", first?),
+            ("approve_message (again)", second?),
+        ] {
+            let (signature, err) = result;
+            let receipt = report.tx(&rpc, name, signature, None).await?;
+            outcomes.push((signature, err, receipt));
+        }
+        outcomes
+    };
+
+    let approvals: Vec<_> = outcomes
+        .iter()
+        .map(|(signature, err, receipt)| outcome(*signature, err.as_ref(), receipt))
+        .collect();
+    let landed = outcomes.iter().filter(|(_, err, _)| err.is_none()).count();
+    let duplicates_rejected = outcomes
+        .iter()
+        .filter(|(_, err, _)| err.as_ref().is_some_and(is_already_in_use))
+        .count();
+    report.field("approvals", approvals);
+
+    let expected = match (args.same_tx, already_approved) {
+        (true, _) => (0, 1),
+        (false, false) => (1, 1),
+        (false, true) => (0, 2),
+    };
+    if (landed, duplicates_rejected) != expected {
+        return Err(anyhow!(
+            "expected {} approval(s) to land and {} to be rejected as a duplicate, got {} and {}",
+            expected.0,
+            expected.1,
+            landed,
+            duplicates_rejected
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `err` is the system program refusing to create the incoming
+/// message PDA a second time, in whichever instruction of the transaction.
+fn is_already_in_use(err: &TransactionError) -> bool {
+    matches!(
+        err,
+        TransactionError::InstructionError(_, InstructionError::Custom(code))
+            if *code == SystemError::AccountAlreadyInUse as u32
+    )
+}
+
+fn outcome(
+    signature: Signature,
+    err: Option<&TransactionError>,
+    receipt: &Receipt,
+) -> serde_json::Value {
+    json!({
+        "signature": signature.to_string(),
+        "error": err.map(|e| e.to_string()),
+        "approved_events": receipt
+            .events
+            .iter()
+            .filter(|e| e.event.name() == "MessageApprovedEvent")
+            .count(),
+    })
+}
@@ -0,0 +1,70 @@
+//! `echo`: approves a message for echo_executable and executes it, which sends
+//! the payload back to the source chain through the gateway.
+
+use anyhow::Result;
+use clap::Args;
+use program_tester::{CrossChainId, Message};
+use scripts::config::Config;
+use scripts::echo::build_execute_ix;
+use scripts::events::ToJson;
+use scripts::pdas::echo_consumed_message_pda;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::gateway::{approve_message, ensure_gateway_root, ensure_payload_log, ZERO_ADDRESS};
+use crate::report::Report;
+use crate::PayloadArgs;
+
+#[derive(Args)]
+pub struct EchoArgs {
+    #[arg(long, env = "ECHO_PROGRAM_ID", default_value_t = echo_executable::ID)]
+    program_id: Pubkey,
+    /// Chain the message comes from, and the echo goes back to
+    #[arg(long, env = "SRC_CHAIN", default_value = "ethereum")]
+    source_chain: String,
+    #[arg(long, env = "SRC_ADDR", default_value = ZERO_ADDRESS)]
+    source_address: String,
+    /// Echo only executes a payload whose keccak256 is the approved hash
+    #[command(flatten)]
+    payload: PayloadArgs,
+}
+
+pub async fn run(config: &Config, args: EchoArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program_id;
+    let gateway = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let payload = args.payload.payload()?;
+    let payload_hash = args.payload.payload_hash(&payload)?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: args.source_chain.clone(),
+            id: format!("echo-{timestamp}"),
+        },
+        source_address: args.source_address.clone(),
+        destination_chain: "solana".to_string(),
+        destination_address: program_id.to_string(),
+        payload_hash,
+    };
+    let command_id = message.command_id();
+
+    ensure_gateway_root(&rpc, &gateway, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway, &payer, report).await?;
+    approve_message(&rpc, &gateway, &payer, message.clone(), report).await?;
+
+    let ix = build_execute_ix(&program_id, &gateway, &payer.pubkey(), message, payload);
+    report.send(&rpc, &payer, "echo_execute", &[ix]).await?;
+
+    report.pda(
+        "consumed_message",
+        echo_consumed_message_pda(&program_id, &command_id).0,
+    );
+    report.field("command_id", command_id.to_json());
+    report.field("payload_hash", payload_hash.to_json());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! `export-events`: turns the JSON lines `listen` captured into CSV or Parquet
+//! files partitioned by day, for notebooks; see `scripts::export`.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use scripts::export::{partition, read_events, write_partition, ExportFormat};
+use serde_json::json;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct ExportEventsArgs {
+    /// Files of events as `listen --json` prints them or a `file:` sink appends them
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// Directory to write the `day=YYYY-MM-DD` partitions to
+    #[arg(long, default_value = "events")]
+    out_dir: PathBuf,
+    /// Parquet needs the `parquet` feature
+    #[arg(long, value_enum, default_value_t = FormatArg::Csv)]
+    format: FormatArg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Csv,
+    Parquet,
+}
+
+impl From<FormatArg> for ExportFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Csv => ExportFormat::Csv,
+            FormatArg::Parquet => ExportFormat::Parquet,
+        }
+    }
+}
+
+pub fn run(args: ExportEventsArgs, report: &mut Report) -> Result<()> {
+    let mut rows = Vec::new();
+    for input in &args.inputs {
+        rows.extend(read_events(input)?);
+    }
+    let total = rows.len();
+    let mut written = Vec::new();
+    for (day, rows) in partition(rows) {
+        let path = write_partition(&args.out_dir, &day, &rows, args.format.into())?;
+        report.note(format!(
+            "{day}: {} events to {}",
+            rows.len(),
+            path.display()
+        ));
+        written.push(json!({
+            "day": day,
+            "events": rows.len(),
+            "path": path.display().to_string(),
+        }));
+    }
+    report.field("events", total);
+    report.field("partitions", written);
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! `export-idl`: copies the gateway and gas service IDLs that `anchor build`
+//! writes to `target/idl` into `idl/`, and with `--publish` into the programs'
+//! on-chain IDL accounts; see `scripts::idl_account`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use scripts::config::Config;
+use scripts::idl_account::{idl_address, publish_idl, IdlPublish};
+use scripts::ids::workspace_root;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct ExportIdlArgs {
+    /// Directory to write the IDLs to [default: idl/ in the workspace]
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+    /// Run `anchor idl build` for each program first instead of taking the last build's IDLs
+    #[arg(long)]
+    build: bool,
+    /// Also publish each IDL to its program's IDL account, with the payer as authority
+    #[arg(long)]
+    publish: bool,
+}
+
+pub async fn run(config: &Config, args: ExportIdlArgs, report: &mut Report) -> Result<()> {
+    let root = workspace_root(&std::env::current_dir()?)
+        .ok_or_else(|| anyhow!("no Anchor.toml here or above"))?;
+    let out_dir = args.out_dir.unwrap_or_else(|| root.join("idl"));
+    std::fs::create_dir_all(&out_dir)
+        .with_context(|| format!("failed to create {}", out_dir.display()))?;
+    let programs = [
+        ("program_tester", config.gateway_program_id),
+        ("gas_service", config.gas_program_id),
+    ];
+
+    let mut exported = Vec::new();
+    for (name, program_id) in programs {
+        let built = root.join("target/idl").join(format!("{name}.json"));
+        if args.build {
+            anchor_idl_build(&root, name, &built)?;
+        }
+        let raw = std::fs::read_to_string(&built).with_context(|| {
+            format!(
+                "failed to read {}; run `anchor build` or pass --build",
+                built.display()
+            )
+        })?;
+        let idl: Value = serde_json::from_str(&raw)
+            .with_context(|| format!("{} is not JSON", built.display()))?;
+        let path = out_dir.join(format!("{name}.json"));
+        std::fs::write(&path, format!("{idl:#}\n"))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        report.note(format!("Wrote {}", path.display()));
+
+        let published = if args.publish {
+            Some(publish(config, report, name, &program_id, &idl).await?)
+        } else {
+            None
+        };
+        exported.push(json!({
+            "program": name,
+            "program_id": program_id.to_string(),
+            "path": path.display().to_string(),
+            "idl_account": idl_address(&program_id).to_string(),
+            "published": published,
+        }));
+    }
+    report.field("idls", exported);
+    Ok(())
+}
+
+fn anchor_idl_build(root: &Path, name: &str, out: &Path) -> Result<()> {
+    let status = Command::new("anchor")
+        .current_dir(root)
+        .args(["idl", "build", "--program-name", name, "--out"])
+        .arg(out)
+        .status()
+        .context("failed to run `anchor idl build`; is the Anchor CLI installed?")?;
+    if !status.success() {
+        return Err(anyhow!(
+            "`anchor idl build` for {name} failed with {status}"
+        ));
+    }
+    Ok(())
+}
+
+async fn publish(
+    config: &Config,
+    report: &mut Report,
+    name: &str,
+    program_id: &Pubkey,
+    idl: &Value,
+) -> Result<&'static str> {
+    if report.dry_run() {
+        report.note(format!(
+            "Would publish the {name} IDL to {}",
+            idl_address(program_id)
+        ));
+        return Ok("skipped");
+    }
+    let payer = config.payer()?;
+    // IDL writes are plain transactions, never nonce-based
+    let rpc = config.rpc().with_durable_nonce(None);
+    let outcome = publish_idl(&rpc, &payer, program_id, idl)
+        .await
+        .with_context(|| format!("publishing the {name} IDL"))?;
+    let outcome = match outcome {
+        IdlPublish::Created => "created",
+        IdlPublish::Upgraded => "upgraded",
+        IdlPublish::Recreated => "recreated",
+        IdlPublish::Unchanged => "unchanged",
+    };
+    report.note(format!(
+        "{name} IDL account {}: {outcome}",
+        idl_address(program_id)
+    ));
+    Ok(outcome)
+}
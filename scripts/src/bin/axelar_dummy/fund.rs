@@ -0,0 +1,53 @@
+//! `fund`: airdrops to an account where the cluster has a faucet, or transfers
+//! from the configured treasury keypair, see `scripts::funding`.
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use scripts::config::Config;
+use scripts::funding::{Funder, DEFAULT_FUND_AMOUNT};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct FundArgs {
+    /// Account to fund; the payer by default
+    #[arg(long)]
+    to: Option<Pubkey>,
+    /// Lamports to send
+    #[arg(long, default_value_t = DEFAULT_FUND_AMOUNT)]
+    amount: u64,
+    /// Only fund the account if it holds fewer lamports than this
+    #[arg(long)]
+    below: Option<u64>,
+}
+
+pub async fn run(config: &Config, args: FundArgs, report: &mut Report) -> Result<()> {
+    if report.dry_run() {
+        return Err(anyhow!("funding can't be simulated; drop --dry-run"));
+    }
+    let rpc = config.rpc();
+    let to = match args.to {
+        Some(to) => to,
+        None => config.payer()?.pubkey(),
+    };
+    let funder = Funder::from_config(config)?.ok_or_else(|| {
+        anyhow!("the cluster has no faucet; set treasury in the config or TREASURY_KEYPAIR")
+    })?;
+
+    let balance = rpc.retry(|| rpc.get_balance(&to)).await?;
+    report.field("account", to.to_string());
+    report.field("funder", funder.to_string());
+    report.field("balance_before", balance);
+    if args.below.is_some_and(|below| balance >= below) {
+        report.note("Balance is above --below, nothing to do");
+        return Ok(());
+    }
+
+    for signature in funder.fund(&rpc, &[to], args.amount).await? {
+        report.tx(&rpc, "fund", signature, None).await?;
+    }
+    report.field("balance_after", rpc.retry(|| rpc.get_balance(&to)).await?);
+    Ok(())
+}
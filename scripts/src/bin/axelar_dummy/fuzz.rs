@@ -0,0 +1,295 @@
+//! `fuzz-events`: sends random but schema-valid events for indexer soak tests.
+//!
+//! Everything is drawn from a ChaCha stream seeded with `--seed`, so a run is
+//! reproduced exactly by passing the same seed and count. Events are turned
+//! into instructions by `replay::instruction`, as if read from an events file,
+//! and sent up to `--concurrency` at a time. Approvals and operatorship
+//! transfers are left out since they need state set up first.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use program_tester::MAX_CHAIN_NAME_LEN;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use scripts::batch::DEFAULT_CONCURRENCY;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use serde_json::{Map, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::gateway::{ensure_gateway_root, ensure_payload_log};
+use crate::replay::{instruction, InputEvent};
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct FuzzArgs {
+    /// Seed of the generator; the same seed and count send the same events
+    #[arg(long, env = "FUZZ_SEED", default_value_t = 0)]
+    seed: u64,
+    /// Number of events to send
+    #[arg(long, env = "FUZZ_COUNT", default_value_t = 100)]
+    count: usize,
+    /// Transactions in flight at once; 1 sends them one after another
+    #[arg(long, default_value_t = DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+}
+
+const KINDS: &[&str] = &[
+    "CallContractEvent",
+    "CallContractWithTokenEvent",
+    "MessageExecutedEvent",
+    "VerifierSetRotatedEvent",
+    "InterchainTransfer",
+    "LinkTokenStarted",
+    "InterchainTokenDeploymentStarted",
+    "TokenMetadataRegistered",
+    "ComputeBurnedEvent",
+    "GasPaidEvent",
+    "GasAddedEvent",
+    "GasRefundedEvent",
+];
+
+const CHAINS: &[&str] = &[
+    "ethereum",
+    "avalanche",
+    "polygon",
+    "arbitrum",
+    "axelar",
+    "solana",
+    "solana-5",
+];
+
+/// Characters for generated strings, from one to four UTF-8 bytes each.
+const ALPHABET: &[char] = &[
+    'a', 'Z', '0', '-', '_', ' ', '.', 'é', 'ß', 'Ω', 'ж', 'ع', 'あ', '中', '한', '€', '🚀', '🦀',
+    '𝔸',
+];
+
+/// Keeps each transaction well under the 1232-byte limit.
+const MAX_PAYLOAD: usize = 256;
+const MAX_TEXT: usize = 24;
+
+pub async fn run(config: &Config, args: FuzzArgs, report: &mut Report) -> Result<()> {
+    let gateway = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &gateway, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway, &payer, report).await?;
+
+    report.field("seed", args.seed);
+    let mut fuzzer = Fuzzer::new(args.seed);
+    let txs = (0..args.count)
+        .map(|index| {
+            let event = fuzzer.event();
+            let ix = instruction(config, &payer.pubkey(), &event)?;
+            Ok((format!("{}#{}", event.kind, index), vec![ix]))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    report
+        .send_batch(&rpc, &payer, txs, args.concurrency)
+        .await
+        .with_context(|| format!("seed {}", args.seed))?;
+    report.field("sent", args.count);
+    Ok(())
+}
+
+/// Generates the events of `fuzz-events` and `loadgen`.
+pub struct Fuzzer {
+    rng: ChaCha8Rng,
+}
+
+impl Fuzzer {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn event(&mut self) -> InputEvent {
+        let kind = *KINDS.choose(&mut self.rng).unwrap();
+        let mut fields = Map::new();
+        let mut set = |key: &str, value: Value| {
+            fields.insert(key.to_string(), value);
+        };
+        match kind {
+            "CallContractEvent" | "CallContractWithTokenEvent" => {
+                let payload = self.bytes(MAX_PAYLOAD);
+                set("destination_chain", self.chain().to_json());
+                set("destination_contract_address", self.text().to_json());
+                set("payload_hash", self.hash().to_json());
+                set("payload", payload.to_json());
+                if kind == "CallContractWithTokenEvent" {
+                    set("symbol", self.text().to_json());
+                    set("amount", self.rng.gen::<u64>().to_json());
+                }
+            }
+            "MessageExecutedEvent" => {
+                set("command_id", self.hash().to_json());
+                set("source_chain", self.chain().to_json());
+                set("cc_id", self.text().to_json());
+                set("source_address", self.text().to_json());
+                set("destination_chain", self.chain().to_json());
+                // execute_message parses this as the destination program
+                set("destination_address", self.pubkey().to_json());
+                set("payload_hash", self.hash().to_json());
+            }
+            "VerifierSetRotatedEvent" => {
+                set("epoch", self.hash().to_json());
+                set("verifier_set_hash", self.hash().to_json());
+            }
+            "InterchainTransfer" => {
+                set("token_id", self.hash().to_json());
+                set("source_address", self.pubkey().to_json());
+                set("source_token_account", self.pubkey().to_json());
+                set("destination_chain", self.chain().to_json());
+                set("destination_address", self.bytes(64).to_json());
+                set("amount", self.rng.gen::<u64>().to_json());
+                set("data_hash", self.hash().to_json());
+            }
+            "LinkTokenStarted" => {
+                set("token_id", self.hash().to_json());
+                set("destination_chain", self.chain().to_json());
+                set("source_token_address", self.pubkey().to_json());
+                set("destination_token_address", self.bytes(64).to_json());
+                set("token_manager_type", self.rng.gen::<u8>().to_json());
+                set("params", self.bytes(MAX_PAYLOAD).to_json());
+            }
+            "InterchainTokenDeploymentStarted" => {
+                set("token_id", self.hash().to_json());
+                set("token_name", self.text().to_json());
+                set("token_symbol", self.text().to_json());
+                set("token_decimals", self.rng.gen::<u8>().to_json());
+                set("minter", self.bytes(64).to_json());
+                set("destination_chain", self.chain().to_json());
+            }
+            "TokenMetadataRegistered" => {
+                set("token_address", self.pubkey().to_json());
+                set("decimals", self.rng.gen::<u8>().to_json());
+            }
+            "ComputeBurnedEvent" => {
+                set("iterations", self.rng.gen_range(0..100u32).to_json());
+            }
+            "GasPaidEvent" => {
+                set("destination_chain", self.chain().to_json());
+                set("destination_address", self.text().to_json());
+                set("payload_hash", self.hash().to_json());
+                set("amount", self.rng.gen::<u64>().to_json());
+                set("refund_address", self.pubkey().to_json());
+            }
+            "GasAddedEvent" => {
+                set("message_id", self.text().to_json());
+                set("amount", self.rng.gen::<u64>().to_json());
+                set("refund_address", self.pubkey().to_json());
+            }
+            "GasRefundedEvent" => {
+                set("receiver", self.pubkey().to_json());
+                set("message_id", self.text().to_json());
+                set("amount", self.rng.gen::<u64>().to_json());
+            }
+            _ => unreachable!("{kind} is not in KINDS"),
+        }
+        InputEvent {
+            kind: kind.to_string(),
+            fields,
+        }
+    }
+
+    /// A known chain name most of the time, otherwise arbitrary text short
+    /// enough for the payload log.
+    fn chain(&mut self) -> String {
+        if self.rng.gen_bool(0.8) {
+            CHAINS.choose(&mut self.rng).unwrap().to_string()
+        } else {
+            self.text_within(MAX_CHAIN_NAME_LEN)
+        }
+    }
+
+    fn text(&mut self) -> String {
+        self.text_within(usize::MAX)
+    }
+
+    /// Up to `MAX_TEXT` characters, cut short before going over `max_bytes`.
+    fn text_within(&mut self, max_bytes: usize) -> String {
+        let len = self.rng.gen_range(0..=MAX_TEXT);
+        let mut text = String::new();
+        for _ in 0..len {
+            let c = *ALPHABET.choose(&mut self.rng).unwrap();
+            if text.len() + c.len_utf8() > max_bytes {
+                break;
+            }
+            text.push(c);
+        }
+        text
+    }
+
+    fn bytes(&mut self, max: usize) -> Vec<u8> {
+        let len = self.rng.gen_range(0..=max);
+        (0..len).map(|_| self.rng.gen()).collect()
+    }
+
+    fn hash(&mut self) -> [u8; 32] {
+        self.rng.gen()
+    }
+
+    fn pubkey(&mut self) -> Pubkey {
+        Pubkey::new_from_array(self.rng.gen())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use solana_sdk::packet::PACKET_DATA_SIZE;
+    use solana_sdk::transaction::Transaction;
+
+    use super::*;
+
+    fn events(seed: u64, count: usize) -> Vec<(String, Map<String, Value>)> {
+        let mut fuzzer = Fuzzer::new(seed);
+        (0..count)
+            .map(|_| {
+                let event = fuzzer.event();
+                (event.kind, event.fields)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_events() {
+        assert_eq!(events(7, 200), events(7, 200));
+        assert_ne!(events(7, 200), events(8, 200));
+    }
+
+    #[test]
+    fn events_pass_the_on_chain_checks() {
+        let config = Config::default();
+        let payer = Pubkey::new_unique();
+        for seed in 0..10 {
+            let mut fuzzer = Fuzzer::new(seed);
+            for _ in 0..500 {
+                let event = fuzzer.event();
+                let fields = &event.fields;
+                let text = |key: &str| fields[key].as_str().unwrap().to_string();
+                match event.kind.as_str() {
+                    // The payload log stores the destination chain
+                    "CallContractEvent" | "CallContractWithTokenEvent" => {
+                        assert!(text("destination_chain").len() <= MAX_CHAIN_NAME_LEN);
+                    }
+                    "MessageExecutedEvent" => {
+                        assert!(Pubkey::from_str(&text("destination_address")).is_ok());
+                    }
+                    _ => {}
+                }
+
+                let ix = instruction(&config, &payer, &event).unwrap();
+                let tx = Transaction::new_with_payer(&[ix], Some(&payer));
+                let size = bincode::serialized_size(&tx).unwrap() as usize;
+                assert!(size <= PACKET_DATA_SIZE, "{} is {size} bytes", event.kind);
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use scripts::alt::fetch_table;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::gas::{
+    build_add_native_gas_ix, build_cpi_call_contract_ix, build_pay_native_ix, build_refund_ix,
+};
+use scripts::gateway::build_call_contract_ix;
+use scripts::pdas::gas_config_pda;
+use scripts::MessageId;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_program;
+
+use crate::gateway::{call_message_id, ensure_gateway_root, ensure_payload_log, ZERO_ADDRESS};
+use crate::report::Report;
+use crate::PayloadArgs;
+
+#[derive(Subcommand)]
+pub enum GasCommand {
+    /// Pay native gas for a contract call and make the call, in one transaction
+    Pay(PayArgs),
+    /// Add native gas to a contract call, making a new call when no message id is given
+    Add(AddArgs),
+    /// Refund native gas paid for a message to the payer
+    Refund(RefundArgs),
+    /// Call a contract through gas_service, which calls the gateway by CPI
+    CpiCall(CpiCallArgs),
+}
+
+#[derive(Args)]
+pub struct PayArgs {
+    #[arg(long, env = "DEST_CHAIN", default_value = "solana-5")]
+    destination_chain: String,
+    #[arg(
+        long,
+        env = "DEST_ADDRESS",
+        default_value = "CJ9f8WFdm3q38pmg426xQf7uum7RqvrmS9R58usHwNX7"
+    )]
+    destination_address: String,
+    #[command(flatten)]
+    payload: PayloadArgs,
+    /// Lamports to pay
+    #[arg(long, env = "GAS_FEE_AMOUNT", default_value_t = 1_000)]
+    amount: u64,
+    /// Payments and calls to put in the transaction
+    #[arg(long, default_value_t = 1)]
+    calls: usize,
+    /// Send a v0 transaction through this lookup table, see `lookup-table`
+    #[arg(long, env = "LOOKUP_TABLE")]
+    lookup_table: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct AddArgs {
+    /// Message to add gas to, `<signature>-<ix>.<event>`
+    #[arg(long, env = "MESSAGE_ID")]
+    message_id: Option<MessageId>,
+    #[arg(long, env = "DEST_CHAIN", default_value = "ethereum")]
+    destination_chain: String,
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+    #[command(flatten)]
+    payload: PayloadArgs,
+    /// Lamports to add
+    #[arg(long, env = "GAS_FEE_AMOUNT", default_value_t = 1_000)]
+    amount: u64,
+}
+
+#[derive(Args)]
+pub struct RefundArgs {
+    /// Message to refund, `<signature>-<ix>.<event>`
+    #[arg(long, env = "MESSAGE_ID")]
+    message_id: MessageId,
+    /// Lamports to refund
+    #[arg(long, env = "REFUND_AMOUNT", default_value_t = 500)]
+    amount: u64,
+}
+
+#[derive(Args)]
+pub struct CpiCallArgs {
+    #[arg(long, env = "DEST_CHAIN", default_value = "ethereum")]
+    destination_chain: String,
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+    #[command(flatten)]
+    payload: PayloadArgs,
+}
+
+pub async fn run(config: &Config, command: GasCommand, report: &mut Report) -> Result<()> {
+    match command {
+        GasCommand::Pay(args) => pay(config, args, report).await,
+        GasCommand::Add(args) => add(config, args, report).await,
+        GasCommand::Refund(args) => refund(config, args, report).await,
+        GasCommand::CpiCall(args) => cpi_call(config, args, report).await,
+    }
+}
+
+async fn pay(config: &Config, args: PayArgs, report: &mut Report) -> Result<()> {
+    let gas_program_id = config.gas_program_id;
+    let gateway_program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = match args.lookup_table {
+        Some(table) => {
+            let table = fetch_table(&config.rpc(), &table).await?;
+            config.rpc().with_lookup_table(Some(table))
+        }
+        None => config.rpc(),
+    };
+    report.pda("gas_config", gas_config_pda(&gas_program_id).0);
+
+    let payload = args.payload.payload()?;
+    let payload_hash = args.payload.payload_hash(&payload)?;
+
+    let ix_pay_native = build_pay_native_ix(
+        &gas_program_id,
+        &payer.pubkey(),
+        args.destination_chain.clone(),
+        args.destination_address.clone(),
+        payload_hash,
+        args.amount,
+        payer.pubkey(),
+    );
+
+    ensure_gateway_root(&rpc, &gateway_program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway_program_id, &payer, report).await?;
+
+    let ix_call = build_call_contract_ix(
+        &gateway_program_id,
+        &system_program::id(),
+        args.destination_chain,
+        args.destination_address,
+        payload_hash,
+        payload,
+    );
+
+    let ixs: Vec<_> = [ix_pay_native, ix_call]
+        .iter()
+        .cycle()
+        .take(2 * args.calls)
+        .cloned()
+        .collect();
+    report
+        .send(
+            &rpc,
+            &payer,
+            "pay_native_for_contract_call + call_contract",
+            &ixs,
+        )
+        .await?;
+    report.field("payload_hash", payload_hash.to_json());
+    report.field("amount", args.amount);
+    Ok(())
+}
+
+async fn add(config: &Config, args: AddArgs, report: &mut Report) -> Result<()> {
+    let gas_program_id = config.gas_program_id;
+    let gateway_program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+    report.pda("gas_config", gas_config_pda(&gas_program_id).0);
+
+    let message_id = match args.message_id {
+        Some(message_id) => message_id,
+        None => {
+            report.note("Calling contract...");
+            let payload = args.payload.payload()?;
+            let payload_hash = args.payload.payload_hash(&payload)?;
+            let ix_call = build_call_contract_ix(
+                &gateway_program_id,
+                &payer.pubkey(),
+                args.destination_chain,
+                args.destination_address,
+                payload_hash,
+                payload,
+            );
+            let receipt = report
+                .send(&rpc, &payer, "call_contract", &[ix_call])
+                .await?;
+            call_message_id(receipt.as_ref())?
+        }
+    };
+
+    report.note("Adding native gas...");
+    let ix_add_gas = build_add_native_gas_ix(
+        &gas_program_id,
+        &payer.pubkey(),
+        message_id.to_string(),
+        args.amount,
+        payer.pubkey(),
+    );
+    report
+        .send(&rpc, &payer, "add_native_gas", &[ix_add_gas])
+        .await?;
+    report.field("message_id", message_id.to_string());
+    report.field("amount", args.amount);
+    Ok(())
+}
+
+async fn refund(config: &Config, args: RefundArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gas_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+    report.pda("gas_config", gas_config_pda(&program_id).0);
+
+    let ix = build_refund_ix(
+        &program_id,
+        &payer.pubkey(),
+        args.message_id.to_string(),
+        args.amount,
+    );
+    report
+        .send(&rpc, &payer, "refund_native_fees", &[ix])
+        .await?;
+    report.field("message_id", args.message_id.to_string());
+    report.field("amount", args.amount);
+    Ok(())
+}
+
+async fn cpi_call(config: &Config, args: CpiCallArgs, report: &mut Report) -> Result<()> {
+    let gas_program_id = config.gas_program_id;
+    let gateway_program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let payload = args.payload.payload()?;
+    let payload_hash = args.payload.payload_hash(&payload)?;
+
+    ensure_gateway_root(&rpc, &gateway_program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway_program_id, &payer, report).await?;
+
+    // The gateway emits CallContractEvent from inside the CPI, with gas_service
+    // as the sender
+    let ix = build_cpi_call_contract_ix(
+        &gas_program_id,
+        &gateway_program_id,
+        &payer.pubkey(),
+        args.destination_chain,
+        args.destination_address,
+        payload_hash,
+        payload,
+    );
+    report
+        .send(&rpc, &payer, "cpi_call_contract", &[ix])
+        .await?;
+    report.field("payload_hash", payload_hash.to_json());
+    Ok(())
+}
@@ -0,0 +1,374 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use program_tester::{CrossChainId, Message, MessageLeaf};
+use scripts::accounts::fetch_incoming_message;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::gateway::{
+    build_approve_message_ix, build_call_contract_ix, build_call_contract_with_token_ix,
+    build_execute_message_ix, build_init_gateway_root_ix, build_init_payload_log_ix,
+    build_init_verification_session_ix,
+};
+use scripts::golden::{self, assert_round_trip};
+use scripts::merkle::merkleise;
+use scripts::pdas::{
+    gateway_root_pda, incoming_message_pda, payload_log_pda, verification_session_pda,
+};
+use scripts::receipt::Receipt;
+use scripts::rpc::ResilientRpc;
+use scripts::MessageId;
+use solana_program::keccak;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_program;
+
+use crate::report::Report;
+use crate::PayloadArgs;
+
+#[derive(Args)]
+pub struct CallContractArgs {
+    #[arg(long, env = "DEST_CHAIN", default_value = "ethereum")]
+    destination_chain: String,
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+    #[command(flatten)]
+    payload: PayloadArgs,
+    /// Send `amount` of this token along, through call_contract_with_token
+    #[arg(long)]
+    token: Option<String>,
+    #[arg(long, requires = "token", default_value_t = 1_000_000)]
+    amount: u64,
+}
+
+#[derive(Args)]
+pub struct ApproveArgs {
+    #[arg(long, env = "SRC_CHAIN", default_value = "ethereum")]
+    source_chain: String,
+    /// Message id on the source chain; defaults to a timestamp so every run
+    /// gets a fresh payload root and verification session
+    #[arg(long, env = "SRC_ID")]
+    message_id: Option<String>,
+    #[arg(long, env = "SRC_ADDR", default_value = "0xdead")]
+    source_address: String,
+    #[arg(long, env = "DEST_CHAIN", default_value = "solana")]
+    destination_chain: String,
+    /// Defaults to the payer
+    #[arg(long, env = "DEST_ADDR")]
+    destination_address: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ExecuteArgs {
+    #[arg(long, env = "SRC_CHAIN", default_value = "ethereum")]
+    source_chain: String,
+    #[arg(long, env = "SRC_ID", default_value = "0xabc")]
+    message_id: String,
+    #[arg(long, env = "SRC_ADDR", default_value = "0xdead")]
+    source_address: String,
+    #[arg(long, env = "DEST_CHAIN", default_value = "solana")]
+    destination_chain: String,
+    /// Defaults to the payer
+    #[arg(long, env = "DEST_ADDR")]
+    destination_address: Option<String>,
+}
+
+#[derive(Args)]
+pub struct LifecycleArgs {
+    /// Source chain of the approved message, i.e. the chain the call went out from
+    #[arg(long, env = "SRC_CHAIN", default_value = "solana")]
+    source_chain: String,
+    #[arg(long, env = "DEST_CHAIN", default_value = "solana")]
+    destination_chain: String,
+    /// Defaults to the payer
+    #[arg(long, env = "DEST_ADDR")]
+    destination_address: Option<String>,
+    #[command(flatten)]
+    payload: PayloadArgs,
+}
+
+pub const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+pub async fn call_contract(
+    config: &Config,
+    args: CallContractArgs,
+    report: &mut Report,
+) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let payload = args.payload.payload()?;
+    let payload_hash = args.payload.payload_hash(&payload)?;
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let (name, ix) = match &args.token {
+        Some(symbol) => (
+            "call_contract_with_token",
+            build_call_contract_with_token_ix(
+                &program_id,
+                &system_program::id(),
+                args.destination_chain.clone(),
+                args.destination_address.clone(),
+                payload_hash,
+                payload.clone(),
+                symbol.clone(),
+                args.amount,
+            ),
+        ),
+        None => (
+            "call_contract",
+            build_call_contract_ix(
+                &program_id,
+                &system_program::id(),
+                args.destination_chain.clone(),
+                args.destination_address.clone(),
+                payload_hash,
+                payload.clone(),
+            ),
+        ),
+    };
+    report.send(&rpc, &payer, name, &[ix]).await?;
+
+    report.field("destination_chain", args.destination_chain);
+    report.field("destination_address", args.destination_address);
+    report.field("payload_hash", payload_hash.to_json());
+    report.field("payload_length", payload.len());
+    if let Some(symbol) = args.token {
+        report.field("token", symbol);
+        report.field("amount", args.amount);
+    }
+    Ok(())
+}
+
+pub async fn approve(config: &Config, args: ApproveArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+
+    let message_id = args.message_id.unwrap_or_else(|| {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        format!("0x{:x}", timestamp)
+    });
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: args.source_chain,
+            id: message_id,
+        },
+        source_address: args.source_address,
+        destination_chain: args.destination_chain,
+        destination_address: args
+            .destination_address
+            .unwrap_or_else(|| payer.pubkey().to_string()),
+        payload_hash: keccak::hash(b"payload").to_bytes(),
+    };
+
+    let command_id = message.command_id();
+    approve_message(&rpc, &program_id, &payer, message, report).await?;
+    report.field("command_id", command_id.to_json());
+    Ok(())
+}
+
+pub async fn execute(config: &Config, args: ExecuteArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: args.source_chain,
+            id: args.message_id,
+        },
+        source_address: args.source_address,
+        destination_chain: args.destination_chain,
+        destination_address: args
+            .destination_address
+            .unwrap_or_else(|| payer.pubkey().to_string()),
+        payload_hash: keccak::hashv(&[b"test_payload"]).0,
+    };
+
+    report.field("command_id", message.command_id().to_json());
+    report.field("payload_hash", message.payload_hash.to_json());
+    execute_message(&rpc, &program_id, &payer, message, report).await?;
+    Ok(())
+}
+
+/// Sends a contract call, then approves and executes the message it produced,
+/// all under the command id of the call's `CallContractEvent`.
+pub async fn lifecycle(config: &Config, args: LifecycleArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let payload = args.payload.payload()?;
+    let payload_hash = args.payload.payload_hash(&payload)?;
+    let destination_address = args
+        .destination_address
+        .unwrap_or_else(|| payer.pubkey().to_string());
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let ix = build_call_contract_ix(
+        &program_id,
+        &system_program::id(),
+        args.destination_chain.clone(),
+        destination_address.clone(),
+        payload_hash,
+        payload,
+    );
+    let receipt = report.send(&rpc, &payer, "call_contract", &[ix]).await?;
+    let message_id = call_message_id(receipt.as_ref())?;
+
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: args.source_chain,
+            id: message_id.to_string(),
+        },
+        source_address: payer.pubkey().to_string(),
+        destination_chain: args.destination_chain,
+        destination_address,
+        payload_hash,
+    };
+    let command_id = message.command_id();
+    report.field("message_id", message_id.to_string());
+    report.field("command_id", command_id.to_json());
+    report.field("payload_hash", payload_hash.to_json());
+
+    approve_message(&rpc, &program_id, &payer, message.clone(), report).await?;
+    execute_message(&rpc, &program_id, &payer, message, report).await?;
+    Ok(())
+}
+
+/// The id Axelar gives the contract call sent in `receipt`. A dry run has no
+/// receipt, so the id only shows the shape of one.
+pub fn call_message_id(receipt: Option<&Receipt>) -> Result<MessageId> {
+    match receipt {
+        Some(receipt) => receipt
+            .message_id("CallContractEvent")
+            .ok_or_else(|| anyhow!("{} emitted no CallContractEvent", receipt.signature)),
+        None => Ok(MessageId::new(Signature::default(), 1, 1)),
+    }
+}
+
+/// Approves `message` as the only leaf of a fresh payload root, opening the
+/// root's verification session first, and checks the incoming message the
+/// gateway stores for it. Returns the receipt of the approval.
+pub async fn approve_message(
+    rpc: &ResilientRpc,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    message: Message,
+    report: &mut Report,
+) -> Result<Option<Receipt>> {
+    let leaf = MessageLeaf {
+        message,
+        position: 0,
+        set_size: 1,
+        domain_separator: [0u8; 32],
+        signing_verifier_set: [0u8; 32],
+    };
+
+    let (payload_merkle_root, mut messages) =
+        merkleise(vec![leaf]).ok_or_else(|| anyhow!("no messages to approve"))?;
+    let message = messages.remove(0);
+
+    let (verification_session_account, _) =
+        verification_session_pda(program_id, &payload_merkle_root);
+    report.pda("verification_session", verification_session_account);
+    if rpc
+        .get_account(&verification_session_account)
+        .await
+        .is_err()
+    {
+        let ix =
+            build_init_verification_session_ix(program_id, &payer.pubkey(), payload_merkle_root);
+        report
+            .send(rpc, payer, "init_verification_session", &[ix])
+            .await?;
+    }
+
+    // Make sure our MerkleisedMessage encoding still matches the program's layout
+    assert_round_trip(
+        &golden::sample_merkleised_message(),
+        golden::MERKLEISED_MESSAGE,
+    )?;
+
+    let command_id = message.leaf.message.command_id();
+    let payload_hash = message.leaf.message.payload_hash;
+    let ix = build_approve_message_ix(program_id, &payer.pubkey(), message, payload_merkle_root);
+    let receipt = report.send(rpc, payer, "approve_message", &[ix]).await?;
+
+    report.pda(
+        "incoming_message",
+        incoming_message_pda(program_id, &command_id).0,
+    );
+    if receipt.is_some() {
+        let incoming = fetch_incoming_message(rpc, program_id, &command_id).await?;
+        if incoming.payload_hash != payload_hash {
+            return Err(anyhow!("incoming message stores a different payload hash"));
+        }
+    }
+    report.field("payload_merkle_root", payload_merkle_root.to_json());
+    Ok(receipt)
+}
+
+pub async fn execute_message(
+    rpc: &ResilientRpc,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    message: Message,
+    report: &mut Report,
+) -> Result<Option<Receipt>> {
+    let ix = build_execute_message_ix(
+        program_id,
+        &payer.pubkey(),
+        message.command_id(),
+        message.cc_id.chain,
+        message.cc_id.id,
+        message.source_address,
+        message.destination_chain,
+        message.destination_address,
+        message.payload_hash,
+    );
+    report.send(rpc, payer, "execute_message", &[ix]).await
+}
+
+/// Creates the gateway root PDA unless it exists; call_contract needs it.
+pub async fn ensure_gateway_root(
+    rpc: &ResilientRpc,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    report: &mut Report,
+) -> Result<()> {
+    let (gateway_root_pda, _) = gateway_root_pda(program_id);
+    if rpc.get_account(&gateway_root_pda).await.is_err() {
+        let ix = build_init_gateway_root_ix(program_id, &payer.pubkey());
+        report.send(rpc, payer, "init_gateway_root", &[ix]).await?;
+    }
+    report.pda("gateway_root", gateway_root_pda);
+    Ok(())
+}
+
+/// Creates the payload log PDA unless it exists; the call_contract builders
+/// pass it, so the calls record into it.
+pub async fn ensure_payload_log(
+    rpc: &ResilientRpc,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    report: &mut Report,
+) -> Result<()> {
+    let (payload_log_pda, _) = payload_log_pda(program_id);
+    if rpc.get_account(&payload_log_pda).await.is_err() {
+        let ix = build_init_payload_log_ix(program_id, &payer.pubkey());
+        report.send(rpc, payer, "init_payload_log", &[ix]).await?;
+    }
+    report.pda("payload_log", payload_log_pda);
+    Ok(())
+}
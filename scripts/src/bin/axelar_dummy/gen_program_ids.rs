@@ -0,0 +1,116 @@
+//! `gen-program-ids`: gives programs fresh ids, writing a new keypair to
+//! `target/deploy` and the new id to `declare_id!`, `Anchor.toml` and the
+//! config file, so a fork deploys its own copies; see `scripts::ids`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use scripts::cluster::Cluster;
+use scripts::config::{Config, DEFAULT_CONFIG_PATH};
+use scripts::ids::{
+    declared_id, program_crates, workspace_root, write_anchor_program_ids, write_declared_id,
+    write_program_keypair,
+};
+use serde_json::json;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct GenProgramIdsArgs {
+    /// Workspace root; the nearest directory up from here with an `Anchor.toml` by default
+    #[arg(long)]
+    root: Option<PathBuf>,
+    /// Program to give a new id, e.g. `program_tester`; every program with a `declare_id!` by default
+    #[arg(long = "program")]
+    programs: Vec<String>,
+    /// Leave the config file alone
+    #[arg(long)]
+    no_record: bool,
+}
+
+pub fn run(
+    config: &Config,
+    args: GenProgramIdsArgs,
+    config_path: Option<&Path>,
+    report: &mut Report,
+) -> Result<()> {
+    let root = match args.root {
+        Some(root) => root,
+        None => workspace_root(&std::env::current_dir()?)
+            .ok_or_else(|| anyhow!("no Anchor.toml here or above; pass --root"))?,
+    };
+    let cluster = config.cluster.unwrap_or(Cluster::Localnet);
+    let programs = if args.programs.is_empty() {
+        declaring_programs(&root)?
+    } else {
+        args.programs
+    };
+    if programs.is_empty() {
+        return Err(anyhow!(
+            "no programs with a declare_id! under {}",
+            root.join("programs").display()
+        ));
+    }
+
+    let mut ids = Vec::new();
+    let mut generated = Vec::new();
+    let (mut gateway, mut gas) = (None, None);
+    for name in &programs {
+        let keypair = Keypair::new();
+        let id = keypair.pubkey();
+        let (previous, kept) = if report.dry_run() {
+            (None, None)
+        } else {
+            let previous = write_declared_id(&root, name, &id)?;
+            let kept = write_program_keypair(&root, name, &keypair)?;
+            (Some(previous), kept)
+        };
+        if let Some(kept) = &kept {
+            report.note(format!("Kept {name}'s old keypair as {}", kept.display()));
+        }
+        match name.as_str() {
+            "program_tester" => gateway = Some(id),
+            "gas_service" => gas = Some(id),
+            _ => {}
+        }
+        ids.push((name.clone(), id));
+        generated.push(json!({
+            "program": name,
+            "program_id": id.to_string(),
+            "previous": previous.map(|id| id.to_string()),
+            "old_keypair": kept.map(|path| path.display().to_string()),
+        }));
+    }
+    report.field("root", root.display().to_string());
+    report.field("cluster", cluster.to_string());
+    report.field("programs", generated);
+    if report.dry_run() {
+        report.note("Dry run: nothing written");
+        return Ok(());
+    }
+
+    write_anchor_program_ids(&root.join("Anchor.toml"), cluster, &ids)?;
+    if !args.no_record && (gateway.is_some() || gas.is_some()) {
+        let path = config_path.unwrap_or(Path::new(DEFAULT_CONFIG_PATH));
+        Config::record_program_ids(path, gateway, gas)?;
+        report.field("config", path.display().to_string());
+    }
+    report.note("Run `anchor build` and deploy to use the new ids");
+    Ok(())
+}
+
+/// The program crates under `root` with a `declare_id!`.
+fn declaring_programs(root: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for name in program_crates(root)? {
+        let lib = root.join("programs").join(&name).join("src/lib.rs");
+        let source = std::fs::read_to_string(&lib)
+            .map_err(|e| anyhow!("failed to read {}: {e}", lib.display()))?;
+        if declared_id(&source).is_some() {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
@@ -0,0 +1,202 @@
+//! `governance`: sets up governance_executor and drives a proposal through it.
+//!
+//! `propose` plays the governance chain: it approves a message from the
+//! governance address carrying the proposal, then schedules it. `execute` runs
+//! the proposal once its timelock has passed.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use governance_executor::{GovernanceAction, GovernancePayload};
+use program_tester::{CrossChainId, Message};
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::governance::{
+    build_execute_proposal_ix, build_init_config_ix, build_schedule_proposal_ix,
+};
+use scripts::pdas::{consumed_message_pda, governance_config_pda, governance_proposal_pda};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::gateway::{approve_message, ensure_gateway_root};
+use crate::report::Report;
+
+/// Where `init` takes proposals from and `propose` sends them from by default.
+const GOVERNANCE_CHAIN: &str = "axelar";
+const GOVERNANCE_ADDRESS: &str = "axelar-governance";
+
+#[derive(Subcommand)]
+pub enum GovernanceCommand {
+    /// Create the governance config; the payer must be the program's upgrade authority
+    Init(InitArgs),
+    /// Approve a message from the governance address carrying a proposal, then schedule it
+    Propose(ProposeArgs),
+    /// Execute a scheduled proposal whose timelock has passed
+    Execute(ExecuteArgs),
+}
+
+#[derive(Args)]
+pub struct ProgramArgs {
+    #[arg(long, env = "GOVERNANCE_PROGRAM_ID", default_value_t = governance_executor::ID)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Operator of the config; defaults to the payer
+    #[arg(long)]
+    operator: Option<Pubkey>,
+    /// Chain proposals are taken from
+    #[arg(long, default_value = GOVERNANCE_CHAIN)]
+    governance_chain: String,
+    /// Address on that chain proposals are taken from
+    #[arg(long, default_value = GOVERNANCE_ADDRESS)]
+    governance_address: String,
+    /// Seconds between scheduling and executing a proposal
+    #[arg(long, default_value_t = 0)]
+    minimum_time_delay: u64,
+}
+
+/// The action of a proposal; exactly one is given.
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct ActionArgs {
+    /// Change the minimum time delay to this many seconds
+    #[arg(long)]
+    new_delay: Option<u64>,
+    /// Hand operatorship of the config to this account
+    #[arg(long)]
+    new_operator: Option<Pubkey>,
+}
+
+impl ActionArgs {
+    fn action(&self) -> GovernanceAction {
+        match (self.new_delay, self.new_operator) {
+            (Some(new_delay), _) => GovernanceAction::UpdateMinimumTimeDelay { new_delay },
+            (None, Some(new_operator)) => GovernanceAction::TransferOperatorship { new_operator },
+            (None, None) => unreachable!("clap requires one action"),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ProposeArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    #[command(flatten)]
+    action: ActionArgs,
+    /// Requested execution time, unix seconds; raised to the minimum delay from now
+    #[arg(long, default_value_t = 0)]
+    eta: i64,
+    #[arg(long, default_value = GOVERNANCE_CHAIN)]
+    governance_chain: String,
+    #[arg(long, default_value = GOVERNANCE_ADDRESS)]
+    governance_address: String,
+    /// Id of the message on the governance chain; a fresh one per run by default.
+    /// Reusing one shows the replay being rejected
+    #[arg(long)]
+    message_id: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ExecuteArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    #[command(flatten)]
+    action: ActionArgs,
+}
+
+pub async fn run(config: &Config, command: GovernanceCommand, report: &mut Report) -> Result<()> {
+    match command {
+        GovernanceCommand::Init(args) => init(config, args, report).await,
+        GovernanceCommand::Propose(args) => propose(config, args, report).await,
+        GovernanceCommand::Execute(args) => execute(config, args, report).await,
+    }
+}
+
+async fn init(config: &Config, args: InitArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let ix = build_init_config_ix(
+        &program_id,
+        &payer.pubkey(),
+        args.operator.unwrap_or_else(|| payer.pubkey()),
+        args.governance_chain.clone(),
+        args.governance_address.clone(),
+        args.minimum_time_delay,
+    );
+    report.send(&rpc, &payer, "init_config", &[ix]).await?;
+
+    report.pda("governance_config", governance_config_pda(&program_id).0);
+    report.field("governance_chain", args.governance_chain);
+    report.field("governance_address", args.governance_address);
+    Ok(())
+}
+
+async fn propose(config: &Config, args: ProposeArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let gateway = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let payload = GovernancePayload {
+        action: args.action.action(),
+        eta: args.eta,
+    };
+    let message_id = args.message_id.unwrap_or_else(|| {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        format!("governance-{timestamp}")
+    });
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: args.governance_chain,
+            id: message_id,
+        },
+        source_address: args.governance_address,
+        destination_chain: "solana".to_string(),
+        destination_address: program_id.to_string(),
+        payload_hash: payload.hash(),
+    };
+    let command_id = message.command_id();
+    let proposal_hash = payload.action.hash();
+
+    ensure_gateway_root(&rpc, &gateway, &payer, report).await?;
+    approve_message(&rpc, &gateway, &payer, message.clone(), report).await?;
+
+    let ix = build_schedule_proposal_ix(&program_id, &gateway, &payer.pubkey(), message, payload);
+    report
+        .send(&rpc, &payer, "schedule_proposal", &[ix])
+        .await?;
+
+    report.pda(
+        "consumed_message",
+        consumed_message_pda(&program_id, &command_id).0,
+    );
+    report.pda(
+        "proposal",
+        governance_proposal_pda(&program_id, &proposal_hash).0,
+    );
+    report.field("command_id", command_id.to_json());
+    report.field("proposal_hash", proposal_hash.to_json());
+    Ok(())
+}
+
+async fn execute(config: &Config, args: ExecuteArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let action = args.action.action();
+    let proposal_hash = action.hash();
+    let ix = build_execute_proposal_ix(&program_id, &payer.pubkey(), action);
+    report.send(&rpc, &payer, "execute_proposal", &[ix]).await?;
+
+    report.field("proposal_hash", proposal_hash.to_json());
+    Ok(())
+}
@@ -0,0 +1,194 @@
+//! `init`: creates every PDA the other commands expect, skipping those that
+//! exist, and prints a table of their addresses and states.
+//!
+//! That is the gateway root and payload log, the gas service config, and a
+//! verification session for each `--root`. The programs keep no ITS
+//! registries, so there is nothing to create for ITS.
+
+use anyhow::Result;
+use clap::Args;
+use program_tester::PayloadLog;
+use scripts::accounts::{
+    fetch_account, fetch_gas_config, fetch_gateway_config, fetch_verification_session,
+};
+use scripts::codec::hex_encode_prefixed;
+use scripts::config::Config;
+use scripts::gas::build_init_config_ix;
+use scripts::gateway::{
+    build_init_gateway_root_ix, build_init_payload_log_ix, build_init_verification_session_ix,
+};
+use scripts::pdas::{gas_config_pda, gateway_root_pda, payload_log_pda, verification_session_pda};
+use scripts::rpc::ResilientRpc;
+use scripts::{hex_array, Mode};
+use serde_json::json;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::report::Report;
+
+#[derive(Args, Default)]
+pub struct InitArgs {
+    /// Payload merkle root to open a verification session for, as hex
+    #[arg(long = "root", value_name = "HEX")]
+    roots: Vec<String>,
+    /// Operator of the gas service config; defaults to the payer, which must be
+    /// the gas service's upgrade authority to create it
+    #[arg(long)]
+    gas_operator: Option<Pubkey>,
+}
+
+/// One PDA of the summary.
+struct Row {
+    name: String,
+    address: Pubkey,
+    state: &'static str,
+    detail: String,
+}
+
+pub async fn run(config: &Config, args: InitArgs, report: &mut Report) -> Result<()> {
+    let gas_program_id = config.gas_program_id;
+    let gateway_program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+    let roots = args
+        .roots
+        .iter()
+        .map(|root| hex_array::<32>(root, Mode::Strict))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    report.field("gas_service", gas_program_id.to_string());
+    report.field("gateway", gateway_program_id.to_string());
+    report.field("payer", payer.pubkey().to_string());
+
+    let mut rows = vec![
+        ensure(
+            &rpc,
+            &payer,
+            report,
+            "gateway_root",
+            gateway_root_pda(&gateway_program_id).0,
+            build_init_gateway_root_ix(&gateway_program_id, &payer.pubkey()),
+        )
+        .await?,
+        // Required by call_contract
+        ensure(
+            &rpc,
+            &payer,
+            report,
+            "payload_log",
+            payload_log_pda(&gateway_program_id).0,
+            build_init_payload_log_ix(&gateway_program_id, &payer.pubkey()),
+        )
+        .await?,
+        ensure(
+            &rpc,
+            &payer,
+            report,
+            "gas_config",
+            gas_config_pda(&gas_program_id).0,
+            build_init_config_ix(
+                &gas_program_id,
+                &payer.pubkey(),
+                args.gas_operator.unwrap_or_else(|| payer.pubkey()),
+            ),
+        )
+        .await?,
+    ];
+    for root in &roots {
+        rows.push(
+            ensure(
+                &rpc,
+                &payer,
+                report,
+                &format!("verification_session {}", hex_encode_prefixed(root)),
+                verification_session_pda(&gateway_program_id, root).0,
+                build_init_verification_session_ix(&gateway_program_id, &payer.pubkey(), *root),
+            )
+            .await?,
+        );
+    }
+
+    if !report.dry_run() {
+        rows[0].detail = format!(
+            "operator {}",
+            fetch_gateway_config(&rpc, &gateway_program_id)
+                .await?
+                .operator
+        );
+        let log: PayloadLog = fetch_account(&rpc, &rows[1].address).await?;
+        rows[1].detail = format!("{} calls recorded", log.total_recorded);
+        rows[2].detail = format!(
+            "operator {}",
+            fetch_gas_config(&rpc, &gas_program_id).await?.operator
+        );
+        for (row, root) in rows[3..].iter_mut().zip(&roots) {
+            let session = fetch_verification_session(&rpc, &gateway_program_id, root).await?;
+            row.detail = format!(
+                "threshold {} accumulated",
+                session.signature_verification.accumulated_threshold
+            );
+        }
+    }
+    summarize(&rows, report);
+    Ok(())
+}
+
+/// Sends `ix` to create the PDA at `address` unless it exists.
+async fn ensure(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    report: &mut Report,
+    name: &str,
+    address: Pubkey,
+    ix: Instruction,
+) -> Result<Row> {
+    let state = if rpc.get_account(&address).await.is_ok() {
+        "existed"
+    } else {
+        let tx_name = format!("init_{}", name.split(' ').next().unwrap_or(name));
+        report.send(rpc, payer, &tx_name, &[ix]).await?;
+        if report.dry_run() {
+            "would create"
+        } else {
+            "created"
+        }
+    };
+    Ok(Row {
+        name: name.to_string(),
+        address,
+        state,
+        detail: String::new(),
+    })
+}
+
+fn summarize(rows: &[Row], report: &mut Report) {
+    if report.json() {
+        for row in rows {
+            report.pda(&row.name, row.address);
+        }
+        let accounts: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                json!({
+                    "name": row.name,
+                    "address": row.address.to_string(),
+                    "state": row.state,
+                    "detail": row.detail,
+                })
+            })
+            .collect();
+        report.field("accounts", accounts);
+        return;
+    }
+    let width = rows.iter().map(|row| row.name.len()).max().unwrap_or(0);
+    for row in rows {
+        report.note(
+            format!(
+                "{:<width$}  {:<44}  {:<12}  {}",
+                row.name, row.address, row.state, row.detail
+            )
+            .trim_end(),
+        );
+    }
+}
@@ -0,0 +1,438 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use program_tester::ItsMessage;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::gateway::{
+    build_call_contract_ix, build_call_contract_with_its_payload_ix,
+    build_interchain_token_deployment_started_ix, build_interchain_transfer_ix,
+    build_link_token_started_ix, build_token_metadata_registered_ix,
+};
+use scripts::its::{build_init_config_ix, build_set_flow_limit_ix, build_transfer_operatorship_ix};
+use scripts::pdas::its_config_pda;
+use scripts::{hex_array, hex_decode, Mode};
+use sha2::{Digest, Sha256};
+use solana_program::keccak;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_program;
+
+use crate::gateway::{ensure_gateway_root, ensure_payload_log, ZERO_ADDRESS};
+use crate::report::Report;
+
+/// ITS events go out together with the call_contract carrying their payload,
+/// as the real ITS program does.
+#[derive(Subcommand)]
+pub enum ItsCommand {
+    /// Emit InterchainTransfer
+    Transfer(TransferArgs),
+    /// Emit InterchainTokenDeploymentStarted
+    Deploy(DeployArgs),
+    /// Emit LinkTokenStarted
+    Link(LinkArgs),
+    /// Emit TokenMetadataRegistered for the payer as the token
+    Metadata(MetadataArgs),
+    /// Call the ITS hub with an encoded InterchainTransfer message as the payload
+    HubCall(HubCallArgs),
+    /// Create the its_tester config; the payer must be the program's upgrade authority
+    Init(InitArgs),
+    /// Set a token's flow limit through its_tester; only its operator may
+    SetFlowLimit(SetFlowLimitArgs),
+    /// Hand operatorship of the its_tester config to a new operator
+    TransferOperatorship(TransferOperatorshipArgs),
+}
+
+#[derive(Args)]
+pub struct MetadataArgs {
+    #[arg(long, default_value_t = 9)]
+    decimals: u8,
+    #[arg(long, env = "DEST_CHAIN", default_value = "ethereum")]
+    destination_chain: String,
+    /// Address of the ITS hub contract the call goes to
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+}
+
+#[derive(Args)]
+pub struct HubCallArgs {
+    /// Token id as hex [default: 0x01 repeated]
+    #[arg(long)]
+    token_id: Option<String>,
+    #[arg(long, default_value_t = 12345)]
+    amount: u64,
+    #[arg(long, env = "DEST_CHAIN", default_value = "axelar")]
+    destination_chain: String,
+    #[arg(
+        long,
+        env = "DEST_ADDRESS",
+        default_value = "axelar157hl7gpuknjmhtac2qnphuazv2yerfagva7lsu9vuj2pgn32z22qa26dk4"
+    )]
+    destination_address: String,
+    /// Chain the hub forwards the message to; empty sends it without the hub wrapping
+    #[arg(long, env = "HUB_DEST_CHAIN", default_value = "ethereum")]
+    hub_destination_chain: String,
+}
+
+#[derive(Args)]
+pub struct ProgramArgs {
+    #[arg(long, env = "ITS_PROGRAM_ID", default_value_t = its_tester::ID)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Operator of the config; defaults to the payer
+    #[arg(long)]
+    operator: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct SetFlowLimitArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Token id as hex [default: 0x01 repeated]
+    #[arg(long)]
+    token_id: Option<String>,
+    /// Tokens allowed to flow per epoch; omit to remove the limit
+    #[arg(long)]
+    flow_limit: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct TransferOperatorshipArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    #[arg(long)]
+    new_operator: Pubkey,
+}
+
+#[derive(Args)]
+pub struct TransferArgs {
+    /// Token id as hex [default: 0x01 repeated]
+    #[arg(long)]
+    token_id: Option<String>,
+    #[arg(long, env = "DEST_CHAIN", default_value = "solana-5")]
+    destination_chain: String,
+    /// Address of the ITS hub contract the call goes to
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+    #[arg(long, default_value_t = 12345)]
+    amount: u64,
+}
+
+#[derive(Args)]
+pub struct DeployArgs {
+    /// Token id as hex [default: 0x07 repeated]
+    #[arg(long)]
+    token_id: Option<String>,
+    #[arg(long, default_value = "My Token")]
+    name: String,
+    #[arg(long, default_value = "MTK")]
+    symbol: String,
+    #[arg(long, default_value_t = 9)]
+    decimals: u8,
+    #[arg(long, env = "DEST_CHAIN", default_value = "ethereum")]
+    destination_chain: String,
+    /// Address of the ITS hub contract the call goes to
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+}
+
+#[derive(Args)]
+pub struct LinkArgs {
+    /// Token id as hex [default: 0x09 repeated]
+    #[arg(long)]
+    token_id: Option<String>,
+    #[arg(long, env = "DEST_CHAIN", default_value = "ethereum")]
+    destination_chain: String,
+    /// Address of the ITS hub contract the call goes to
+    #[arg(long, env = "DEST_ADDRESS", default_value = ZERO_ADDRESS)]
+    destination_address: String,
+    /// Token on the destination chain, as hex
+    #[arg(long, default_value = "0b16212c")]
+    destination_token_address: String,
+    #[arg(long, default_value_t = 1)]
+    token_manager_type: u8,
+    /// Token manager params as hex
+    #[arg(long, default_value = "3742")]
+    params: String,
+}
+
+pub async fn run(config: &Config, command: ItsCommand, report: &mut Report) -> Result<()> {
+    match command {
+        ItsCommand::Transfer(args) => transfer(config, args, report).await,
+        ItsCommand::Deploy(args) => deploy(config, args, report).await,
+        ItsCommand::Link(args) => link(config, args, report).await,
+        ItsCommand::Metadata(args) => metadata(config, args, report).await,
+        ItsCommand::HubCall(args) => hub_call(config, args, report).await,
+        ItsCommand::Init(args) => init(config, args, report).await,
+        ItsCommand::SetFlowLimit(args) => set_flow_limit(config, args, report).await,
+        ItsCommand::TransferOperatorship(args) => transfer_operatorship(config, args, report).await,
+    }
+}
+
+async fn transfer(config: &Config, args: TransferArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let token_id = token_id(args.token_id.as_deref(), [1u8; 32])?;
+    let data_hash: [u8; 32] = Sha256::digest(b"dummy-payload").into();
+    let payload = vec![1u8, 2, 3];
+    let payload_hash = keccak::hash(&payload).to_bytes();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let ix_call = build_call_contract_ix(
+        &program_id,
+        &system_program::id(),
+        args.destination_chain.clone(),
+        args.destination_address,
+        payload_hash,
+        payload,
+    );
+    let ix_its = build_interchain_transfer_ix(
+        &program_id,
+        &payer.pubkey(),
+        token_id,
+        payer.pubkey(),
+        payer.pubkey(),
+        args.destination_chain,
+        vec![2u8, 3, 4, 5],
+        args.amount,
+        data_hash,
+    );
+
+    report
+        .send(
+            &rpc,
+            &payer,
+            "call_contract + interchain_transfer",
+            &[ix_its, ix_call],
+        )
+        .await?;
+    report.field("token_id", token_id.to_json());
+    Ok(())
+}
+
+async fn deploy(config: &Config, args: DeployArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let token_id = token_id(args.token_id.as_deref(), [7u8; 32])?;
+    let payload = vec![1u8, 2, 3];
+    let payload_hash = keccak::hash(&payload).to_bytes();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let ix_call = build_call_contract_ix(
+        &program_id,
+        &system_program::id(),
+        args.destination_chain.clone(),
+        args.destination_address,
+        payload_hash,
+        payload,
+    );
+    let ix_its = build_interchain_token_deployment_started_ix(
+        &program_id,
+        &payer.pubkey(),
+        token_id,
+        args.name,
+        args.symbol,
+        args.decimals,
+        vec![1u8, 2, 3, 4],
+        args.destination_chain,
+    );
+
+    report
+        .send(
+            &rpc,
+            &payer,
+            "call_contract + interchain_token_deployment_started",
+            &[ix_call, ix_its],
+        )
+        .await?;
+    report.field("token_id", token_id.to_json());
+    Ok(())
+}
+
+async fn link(config: &Config, args: LinkArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let token_id = token_id(args.token_id.as_deref(), [9u8; 32])?;
+    let destination_token_address = hex_decode(&args.destination_token_address, Mode::Strict)?;
+    let params = hex_decode(&args.params, Mode::Strict)?;
+    let payload = vec![1u8, 2, 3];
+    let payload_hash = keccak::hash(&payload).to_bytes();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let ix_call = build_call_contract_ix(
+        &program_id,
+        &system_program::id(),
+        args.destination_chain.clone(),
+        args.destination_address,
+        payload_hash,
+        payload,
+    );
+    let ix_its = build_link_token_started_ix(
+        &program_id,
+        &payer.pubkey(),
+        token_id,
+        args.destination_chain,
+        payer.pubkey(),
+        destination_token_address,
+        args.token_manager_type,
+        params,
+    );
+
+    report
+        .send(
+            &rpc,
+            &payer,
+            "call_contract + link_token_started",
+            &[ix_call, ix_its],
+        )
+        .await?;
+    report.field("token_id", token_id.to_json());
+    Ok(())
+}
+
+async fn metadata(config: &Config, args: MetadataArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let payload = vec![1u8, 2, 3];
+    let payload_hash = keccak::hash(&payload).to_bytes();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let ix_call = build_call_contract_ix(
+        &program_id,
+        &system_program::id(),
+        args.destination_chain,
+        args.destination_address,
+        payload_hash,
+        payload,
+    );
+    let ix_its = build_token_metadata_registered_ix(
+        &program_id,
+        &payer.pubkey(),
+        payer.pubkey(),
+        args.decimals,
+    );
+
+    report
+        .send(
+            &rpc,
+            &payer,
+            "call_contract + token_metadata_registered",
+            &[ix_call, ix_its],
+        )
+        .await?;
+    report.field("token_address", payer.pubkey().to_json());
+    Ok(())
+}
+
+async fn hub_call(config: &Config, args: HubCallArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let token_id = token_id(args.token_id.as_deref(), [1u8; 32])?;
+    let message = ItsMessage::InterchainTransfer {
+        token_id,
+        source_address: payer.pubkey().to_bytes().to_vec(),
+        destination_address: vec![0xde, 0xad, 0xbe, 0xef],
+        amount: args.amount,
+        data: vec![],
+    };
+    let hub_destination_chain = Some(args.hub_destination_chain).filter(|chain| !chain.is_empty());
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+    ensure_payload_log(&rpc, &program_id, &payer, report).await?;
+
+    let ix = build_call_contract_with_its_payload_ix(
+        &program_id,
+        &system_program::id(),
+        args.destination_chain,
+        args.destination_address,
+        message,
+        hub_destination_chain.clone(),
+    );
+    report
+        .send(&rpc, &payer, "call_contract_with_its_payload", &[ix])
+        .await?;
+    report.field("token_id", token_id.to_json());
+    report.field("hub_destination_chain", hub_destination_chain.to_json());
+    Ok(())
+}
+
+async fn init(config: &Config, args: InitArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let ix = build_init_config_ix(
+        &program_id,
+        &payer.pubkey(),
+        args.operator.unwrap_or_else(|| payer.pubkey()),
+    );
+    report.send(&rpc, &payer, "init_config", &[ix]).await?;
+
+    report.pda("its_config", its_config_pda(&program_id).0);
+    Ok(())
+}
+
+async fn set_flow_limit(
+    config: &Config,
+    args: SetFlowLimitArgs,
+    report: &mut Report,
+) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let token_id = token_id(args.token_id.as_deref(), [1u8; 32])?;
+    let ix = build_set_flow_limit_ix(&program_id, &payer.pubkey(), token_id, args.flow_limit);
+    report.send(&rpc, &payer, "set_flow_limit", &[ix]).await?;
+
+    report.field("token_id", token_id.to_json());
+    report.field("flow_limit", args.flow_limit.to_json());
+    Ok(())
+}
+
+async fn transfer_operatorship(
+    config: &Config,
+    args: TransferOperatorshipArgs,
+    report: &mut Report,
+) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let ix = build_transfer_operatorship_ix(&program_id, &payer.pubkey(), args.new_operator);
+    report
+        .send(&rpc, &payer, "transfer_operatorship", &[ix])
+        .await?;
+
+    report.field("new_operator", args.new_operator.to_json());
+    Ok(())
+}
+
+fn token_id(hex: Option<&str>, default: [u8; 32]) -> Result<[u8; 32]> {
+    match hex {
+        Some(hex) => Ok(hex_array(hex, Mode::Strict)?),
+        None => Ok(default),
+    }
+}
@@ -0,0 +1,1250 @@
+//! `listen`: follows the `--program`s, the gateway and gas service unless
+//! given, and sends every event they emit, tagged with its type and the
+//! program, from both `emit_cpi!` inner instructions and `Program data:` logs,
+//! to the `--sink`s; see `scripts::inspect` and `scripts::sink`.
+//!
+//! # Sources
+//!
+//! With `--ingest logs`, each program has a log subscription of its own,
+//! merged into one stream, and each transaction named is fetched for its
+//! inner instructions, falling back to the delivered logs when that fails.
+//! `--ingest blocks` subscribes to the blocks mentioning the programs
+//! instead, and `--ingest geyser` streams from a Yellowstone gRPC endpoint
+//! (see `scripts::geyser`); both carry the transactions whole, so nothing is
+//! fetched. `--ingest poll` asks `getSignaturesForAddress` what landed every
+//! `--poll-interval`, without a subscription.
+//!
+//! When the websocket drops or stays silent past `--idle-timeout`, the
+//! subscriptions are set up again after a backoff, and what landed in between
+//! is backfilled with `getSignaturesForAddress`. Backfills and bursts fetch
+//! up to `--fetch-concurrency` transactions at a time, in JSON-RPC batches of
+//! `--fetch-batch-size`. A transaction that can't be fetched stops its
+//! program's backfill, which resumes from it the next time.
+//!
+//! # Delivery
+//!
+//! Events go to the sinks in order, decoded with the program's IDL, `--idl`
+//! or the compiled-in registry. Gas events carry the id of the message they
+//! pay for, to correlate them with the gateway's.
+//!
+//! # Checkpoint and dedup
+//!
+//! `--checkpoint` keeps each program's latest transaction handled, so a
+//! restart backfills the downtime; see `scripts::checkpoint`. `--dedup-file`
+//! keeps an event from going to a sink twice across restarts and overlapping
+//! backfills; see `scripts::dedup`.
+//!
+//! # Finality
+//!
+//! Below `--commitment finalized`, the events of a transaction that never
+//! finalizes are sent again marked as retracted; see `scripts::finality`.
+//! `--metrics-addr` serves Prometheus metrics and health probes; see
+//! `scripts::metrics` and `scripts::health`.
+
+use std::collections::{HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use clap::{Args, ValueEnum};
+use futures::stream::{self, select_all, BoxStream};
+use futures::StreamExt;
+use scripts::checkpoint::{Checkpoint, Position};
+use scripts::config::Config;
+use scripts::dedup::SentEvents;
+use scripts::events::DecodedEvent;
+use scripts::failover::{Endpoint, EndpointPool};
+use scripts::finality::{finality_status, FinalityTracker};
+use scripts::geyser::{self, GeyserTransaction};
+use scripts::gmp::{to_gmp_event, EventContext, EventMetadata};
+use scripts::health::ListenerHealth;
+use scripts::idl::IdlCodec;
+use scripts::inspect::{
+    block_tx_events, fetch_tx_events, fetch_tx_events_batch, latest_signature, signatures_since,
+    EventSource, TxEvents,
+};
+use scripts::metrics::{serve, ListenerMetrics};
+use scripts::rpc::RpcMetrics;
+use scripts::sink::{EventRecord, Sink, SinkOptions, SinkSpec};
+use scripts::{GeyserError, MessageId};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{
+    RpcBlockSubscribeConfig, RpcBlockSubscribeFilter, RpcTransactionLogsConfig,
+    RpcTransactionLogsFilter,
+};
+use solana_client::rpc_response::{Response, RpcBlockUpdate, RpcLogsResponse};
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
+use tokio::net::TcpListener;
+
+use crate::decode_tx::decode;
+
+/// Signatures remembered to skip a transaction several subscriptions report.
+const SEEN_CAPACITY: usize = 1024;
+/// Wait before the first reconnect attempt, doubled on each failed one.
+const INITIAL_RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+/// How often the endpoints' slots are polled, for the lag metric and to fail
+/// over from one that falls behind.
+const SLOT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// How often the transactions sent before they were finalized are checked on.
+const FINALITY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Least time between rewrites of `--checkpoint` while transactions are being
+/// handled; whatever is left is saved once they are.
+const CHECKPOINT_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Args)]
+pub struct ListenArgs {
+    /// A program to follow, named in the events, with an IDL of its own to decode the events
+    /// the compiled-in registry doesn't know; repeatable, and without it the configured
+    /// gateway and gas service
+    #[arg(
+        long = "program",
+        env = "LISTEN_PROGRAMS",
+        value_name = "NAME=ID[:IDL]",
+        value_delimiter = ',',
+        value_parser = parse_program
+    )]
+    programs: Vec<ProgramSpec>,
+    /// How the stdout sink prints events: `text` for people, or `ndjson` for one JSON object
+    /// per line, for `jq` and log shippers, with progress on stderr; `--json` implies `ndjson`
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Axelar name of the chain the programs run on, reported in GMP events
+    #[arg(long, env = "SOURCE_CHAIN", default_value = "solana")]
+    source_chain: String,
+    /// IDL to decode events the compiled-in registry doesn't know, for programs without one
+    #[arg(long, env = "IDL_PATH")]
+    idl: Option<PathBuf>,
+    /// How transactions arrive: `logs` subscribes to each program's logs and fetches every
+    /// transaction they name; `blocks` subscribes to the blocks mentioning each program, which
+    /// carry the transactions whole, saving a round trip per transaction (the validator needs
+    /// `--rpc-pubsub-enable-block-subscription`); `geyser` streams them whole from the
+    /// Yellowstone gRPC endpoint at `--geyser-url` (with the `geyser` feature); `poll` asks for
+    /// each program's new signatures every `--poll-interval` and fetches them, for RPCs without
+    /// websockets
+    #[arg(long, value_enum, default_value_t = Ingest::Logs, conflicts_with = "logs_only")]
+    ingest: Ingest,
+    /// Another endpoint to fail over to when the configured one keeps erroring or falls behind,
+    /// as `<rpc url>` or `<rpc url>|<ws url>`; repeatable, and preferred in the order given
+    #[arg(
+        long = "fallback-endpoint",
+        env = "LISTEN_FALLBACK_ENDPOINTS",
+        value_name = "RPC_URL[|WS_URL]",
+        value_delimiter = ','
+    )]
+    fallback_endpoints: Vec<Endpoint>,
+    /// Seconds an endpoint that kept erroring or fell behind is left alone
+    #[arg(long, default_value_t = 60)]
+    endpoint_cooldown: u64,
+    /// Slots an endpoint may trail the most advanced one by before it is failed over from; 0
+    /// never is
+    #[arg(long, default_value_t = 150)]
+    max_endpoint_lag: u64,
+    /// Seconds between the rounds of `--ingest poll`
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+    poll_interval: u64,
+    /// Yellowstone gRPC endpoint `--ingest geyser` subscribes at
+    #[arg(long, env = "GEYSER_URL", default_value = "http://127.0.0.1:10000")]
+    geyser_url: String,
+    /// Token the Geyser endpoint wants in the `x-token` header
+    #[arg(long, env = "GEYSER_X_TOKEN")]
+    geyser_x_token: Option<String>,
+    /// Transactions fetched at a time during a backfill or a burst of notifications, their events
+    /// still sent in order; 1 fetches them one after another
+    #[arg(long, default_value_t = 8)]
+    fetch_concurrency: usize,
+    /// Transactions asked for in one JSON-RPC batch of `getTransaction`s, for RPCs that take
+    /// batches, which saves most round trips of a backfill; 1 asks for each on its own
+    #[arg(long, default_value_t = 1)]
+    fetch_batch_size: usize,
+    /// Decode only the `Program data:` logs the subscription delivers, without fetching each
+    /// transaction; for RPCs that strip inner instructions or lack `getTransaction`
+    #[arg(long)]
+    logs_only: bool,
+    /// Resubscribe after this many seconds without a notification, in case the connection
+    /// stalled without closing; 0 never does
+    #[arg(long, default_value_t = 0)]
+    idle_timeout: u64,
+    /// File to keep the latest transaction handled per program in, to resume from after a
+    /// restart instead of starting at the tip
+    #[arg(long, env = "LISTEN_CHECKPOINT")]
+    checkpoint: Option<PathBuf>,
+    /// File to keep the latest events sent in, by signature, location and sink, so neither a
+    /// restart nor a backfill overlapping the subscription sends one to a sink twice
+    #[arg(long, env = "LISTEN_DEDUP_FILE")]
+    dedup_file: Option<PathBuf>,
+    /// Deliveries `--dedup-file` remembers, an event sent to two sinks being two, the oldest
+    /// forgotten first
+    #[arg(long, default_value_t = 100_000)]
+    dedup_capacity: usize,
+    /// Where to send the events: `stdout`, `file:<path>` to append them as JSON lines,
+    /// `webhook:<url>` to POST each as JSON, `kafka:<topic>` to produce them to Kafka,
+    /// `nats[:<prefix>]` to publish them on `<prefix>.<event type>`, `redis:<stream>` to
+    /// append them to a Redis stream, or a `postgres://` URL or `sqlite:<path>` to store them
+    /// in that database (the last five with the feature of the same name); repeatable
+    #[arg(long = "sink", default_value = "stdout")]
+    sinks: Vec<SinkSpec>,
+    /// Times a webhook sink retries an event after a connection error, 429 or 5xx
+    #[arg(long, default_value_t = 5)]
+    webhook_retries: u32,
+    /// File to append the events a webhook sink couldn't deliver to, as JSON lines with the
+    /// error; without it such an event stops the listener
+    #[arg(long, env = "LISTEN_DEAD_LETTER")]
+    dead_letter: Option<PathBuf>,
+    /// Brokers the Kafka sinks connect to, as `host:port[,host:port...]`
+    #[arg(long, env = "KAFKA_BROKERS", default_value = "localhost:9092")]
+    kafka_brokers: String,
+    /// Server the NATS sinks connect to
+    #[arg(long, env = "NATS_URL", default_value = "nats://localhost:4222")]
+    nats_url: String,
+    /// Publish to NATS through JetStream, waiting for the stream to store each event
+    #[arg(long)]
+    nats_jetstream: bool,
+    /// Server the Redis sinks connect to
+    #[arg(long, env = "REDIS_URL", default_value = "redis://127.0.0.1/")]
+    redis_url: String,
+    /// Trim Redis streams to about this many entries; 0 keeps them all
+    #[arg(long, default_value_t = 10_000)]
+    redis_maxlen: usize,
+    /// Serve Prometheus metrics on `http://<addr>/metrics`, and liveness and readiness on
+    /// `/healthz` and `/readyz`, e.g. `0.0.0.0:9464`
+    #[arg(long, env = "LISTEN_METRICS_ADDR")]
+    metrics_addr: Option<SocketAddr>,
+    /// Report not live once the subscriptions have been down this many seconds; 0 never does
+    #[arg(long, default_value_t = 300)]
+    max_disconnected: u64,
+    /// Report not ready once no transaction was handled for this many seconds; 0 never does
+    #[arg(long, default_value_t = 0)]
+    max_checkpoint_age: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Ndjson,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Ingest {
+    Logs,
+    Blocks,
+    Geyser,
+    Poll,
+}
+
+/// What a subscription delivered, with the index of the program it is for.
+enum Notification {
+    Logs(usize, Response<RpcLogsResponse>),
+    Block(usize, Response<RpcBlockUpdate>),
+    /// A transaction from the Geyser stream, which names its programs.
+    Transaction(Result<GeyserTransaction, GeyserError>),
+}
+
+/// A `--program` as given.
+#[derive(Clone)]
+struct ProgramSpec {
+    name: String,
+    id: Pubkey,
+    idl: Option<PathBuf>,
+}
+
+fn parse_program(s: &str) -> Result<ProgramSpec, String> {
+    let (name, rest) = s
+        .split_once('=')
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| format!("expected NAME=ID[:IDL], got {s:?}"))?;
+    // Base58 has no `:`, so the first one starts the IDL path
+    let (id, idl) = match rest.split_once(':') {
+        Some((id, idl)) => (id, Some(PathBuf::from(idl))),
+        None => (rest, None),
+    };
+    Ok(ProgramSpec {
+        name: name.to_string(),
+        id: id
+            .parse()
+            .map_err(|e| format!("invalid program id {id:?}: {e}"))?,
+        idl,
+    })
+}
+
+/// A program being followed.
+struct Program {
+    id: Pubkey,
+    name: String,
+    /// Its own IDL, tried instead of `--idl`.
+    idl: Option<IdlCodec>,
+}
+
+/// A transaction a notification or backfill named, handled in turn.
+struct Pending {
+    program: usize,
+    signature: Signature,
+    slot: u64,
+    /// The logs a notification delivered, decoded when the transaction can't
+    /// be fetched.
+    logs: Option<Vec<String>>,
+    /// Its events were rolled back, so there is nothing to send.
+    failed: bool,
+}
+
+/// Why a subscription ended.
+enum Disconnect {
+    /// Connecting or subscribing failed.
+    Failed(String),
+    /// The websocket closed.
+    Closed,
+    /// Nothing arrived within the idle timeout.
+    Idle,
+}
+
+/// Signatures already handled, the oldest forgotten past `SEEN_CAPACITY`.
+#[derive(Default)]
+struct Seen {
+    signatures: HashSet<Signature>,
+    order: VecDeque<Signature>,
+}
+
+impl Seen {
+    fn first_time(&mut self, signature: Signature) -> bool {
+        if !self.signatures.insert(signature) {
+            return false;
+        }
+        self.order.push_back(signature);
+        if self.order.len() > SEEN_CAPACITY {
+            if let Some(old) = self.order.pop_front() {
+                self.signatures.remove(&old);
+            }
+        }
+        true
+    }
+
+    /// Lets `signature` be handled again, when handling it didn't work out.
+    fn forget(&mut self, signature: &Signature) {
+        if self.signatures.remove(signature) {
+            self.order.retain(|seen| seen != signature);
+        }
+    }
+}
+
+/// In NDJSON mode every decoded event is printed as one JSON object per line,
+/// and progress goes to stderr.
+pub async fn run(config: &Config, args: ListenArgs, json: bool) -> Result<()> {
+    let json = json || args.format == OutputFormat::Ndjson;
+    let idl = args.idl.as_ref().map(IdlCodec::load).transpose()?;
+    let checkpoint = match &args.checkpoint {
+        Some(path) => Checkpoint::load(path)?,
+        None => Checkpoint::default(),
+    };
+    let specs = if args.programs.is_empty() {
+        vec![
+            ProgramSpec {
+                name: "gateway".to_string(),
+                id: config.gateway_program_id,
+                idl: None,
+            },
+            ProgramSpec {
+                name: "gas_service".to_string(),
+                id: config.gas_program_id,
+                idl: None,
+            },
+        ]
+    } else {
+        args.programs.clone()
+    };
+    let mut programs: Vec<Program> = Vec::with_capacity(specs.len());
+    for spec in specs {
+        if let Some(other) = programs.iter().find(|program| program.id == spec.id) {
+            bail!(
+                "program {} is given twice, as {} and {}",
+                spec.id,
+                other.name,
+                spec.name
+            );
+        }
+        programs.push(Program {
+            id: spec.id,
+            name: spec.name,
+            idl: spec.idl.as_ref().map(IdlCodec::load).transpose()?,
+        });
+    }
+    let sent = args
+        .dedup_file
+        .as_ref()
+        .map(|path| SentEvents::open(path, args.dedup_capacity))
+        .transpose()?;
+    let cursors = programs
+        .iter()
+        .map(|program| checkpoint.position(&program.id))
+        .collect();
+    let sink_options = SinkOptions {
+        json,
+        webhook_retries: args.webhook_retries,
+        dead_letter: args.dead_letter.clone(),
+        kafka_brokers: args.kafka_brokers.clone(),
+        nats_url: args.nats_url.clone(),
+        nats_jetstream: args.nats_jetstream,
+        redis_url: args.redis_url.clone(),
+        redis_maxlen: (args.redis_maxlen > 0).then_some(args.redis_maxlen),
+    };
+    let mut sinks = Vec::with_capacity(args.sinks.len());
+    for spec in &args.sinks {
+        sinks.push(spec.open(&sink_options).await?);
+    }
+    let rpc_metrics = Arc::new(RpcMetrics::default());
+    let endpoints = std::iter::once(Endpoint {
+        rpc_url: config.rpc_url.clone(),
+        ws_url: config.ws_url.clone(),
+    })
+    .chain(args.fallback_endpoints.iter().cloned())
+    .map(|endpoint| {
+        let rpc = config
+            .rpc_at(&endpoint.rpc_url)
+            .with_metrics(rpc_metrics.clone());
+        (endpoint, rpc)
+    })
+    .collect();
+    let endpoints = Arc::new(EndpointPool::new(
+        endpoints,
+        Duration::from_secs(args.endpoint_cooldown),
+        (args.max_endpoint_lag > 0).then_some(args.max_endpoint_lag),
+    ));
+    let metrics = Arc::new(ListenerMetrics::new(rpc_metrics));
+    let seconds = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+    let health = Arc::new(ListenerHealth::new(
+        args.sinks.iter().map(ToString::to_string).collect(),
+        seconds(args.max_disconnected),
+        seconds(args.max_checkpoint_age),
+    ));
+    if let Some(addr) = args.metrics_addr {
+        let server = TcpListener::bind(addr).await?;
+        tokio::spawn(serve(server, metrics.clone(), health.clone()));
+    }
+    if args.metrics_addr.is_some() || endpoints.len() > 1 {
+        tokio::spawn(poll_slots(endpoints.clone(), metrics.clone()));
+    }
+    let mut listener = Listener {
+        config,
+        args: &args,
+        endpoints,
+        http: reqwest::Client::new(),
+        metrics: metrics.clone(),
+        health: health.clone(),
+        idl,
+        json,
+        programs,
+        seen: Seen::default(),
+        finality: (config.commitment != CommitmentLevel::Finalized).then(FinalityTracker::default),
+        sent,
+        cursors,
+        checkpoint,
+        checkpoint_saved: Instant::now(),
+        checkpoint_dirty: false,
+        sinks,
+    };
+    let mut reconnects = 0u64;
+    let mut backoff = INITIAL_RECONNECT_BACKOFF;
+
+    loop {
+        let disconnect = listener.follow().await?;
+        // Only a connection that failed outright backs off further
+        if !matches!(disconnect, Disconnect::Failed(_)) {
+            backoff = INITIAL_RECONNECT_BACKOFF;
+        }
+        let reason = match disconnect {
+            Disconnect::Failed(reason) => reason,
+            Disconnect::Closed => "websocket closed".to_string(),
+            Disconnect::Idle => format!("nothing received for {}s", args.idle_timeout),
+        };
+        health.disconnected(reason.clone());
+        reconnects += 1;
+        metrics.reconnects.fetch_add(1, Ordering::Relaxed);
+        listener.status(format!(
+            "Subscription lost ({reason}); reconnecting in {}s (reconnect {reconnects})",
+            backoff.as_secs()
+        ));
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+    }
+}
+
+struct Listener<'a> {
+    config: &'a Config,
+    args: &'a ListenArgs,
+    /// The configured endpoint, then the `--fallback-endpoint`s.
+    endpoints: Arc<EndpointPool>,
+    /// Sends the `--fetch-batch-size` batches.
+    http: reqwest::Client,
+    metrics: Arc<ListenerMetrics>,
+    health: Arc<ListenerHealth>,
+    idl: Option<IdlCodec>,
+    json: bool,
+    programs: Vec<Program>,
+    seen: Seen,
+    /// The transactions sent but not finalized yet, below `finalized`
+    /// commitment.
+    finality: Option<FinalityTracker>,
+    /// The events sent to each sink, as saved to `--dedup-file`.
+    sent: Option<SentEvents>,
+    /// For each program, the latest transaction handled, where a backfill
+    /// after a reconnect or restart resumes.
+    cursors: Vec<Option<Position>>,
+    /// The cursors as saved to `--checkpoint`.
+    checkpoint: Checkpoint,
+    checkpoint_saved: Instant,
+    /// Whether a cursor moved since `checkpoint` was saved.
+    checkpoint_dirty: bool,
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl Listener<'_> {
+    fn status(&self, line: String) {
+        if self.json {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    }
+
+    /// Moves `program`'s cursor to `position`, saving it to `--checkpoint`
+    /// at most every `CHECKPOINT_SAVE_INTERVAL`; `save_checkpoint` saves the
+    /// rest.
+    fn advance(&mut self, program: usize, position: Position) -> Result<()> {
+        self.cursors[program] = Some(position);
+        self.health.checkpoint_advanced();
+        if self.args.checkpoint.is_some() {
+            self.checkpoint
+                .advance(&self.programs[program].id, position);
+            self.checkpoint_dirty = true;
+            if self.checkpoint_saved.elapsed() >= CHECKPOINT_SAVE_INTERVAL {
+                self.save_checkpoint()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the cursors to `--checkpoint` if they moved since the last time.
+    fn save_checkpoint(&mut self) -> Result<()> {
+        let args = self.args;
+        if let Some(path) = args.checkpoint.as_ref().filter(|_| self.checkpoint_dirty) {
+            self.checkpoint.save(path)?;
+            self.checkpoint_dirty = false;
+            self.checkpoint_saved = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Subscribes to the programs' logs, blocks or Geyser transactions,
+    /// catches up on what was missed since the last subscription, and prints
+    /// their events until the subscription ends.
+    async fn follow(&mut self) -> Result<Disconnect> {
+        match self.args.ingest {
+            Ingest::Poll => self.poll().await,
+            Ingest::Geyser => self.follow_geyser().await,
+            Ingest::Logs | Ingest::Blocks => {
+                let endpoint = self.endpoints.current();
+                let ws_url = self.endpoints.endpoint(endpoint).ws_url.clone();
+                let disconnect = self.follow_websocket(&ws_url).await?;
+                if self.endpoints.failed(endpoint) && self.endpoints.len() > 1 {
+                    self.status(format!(
+                        "Leaving {} alone for {}s",
+                        self.endpoints.endpoint(endpoint),
+                        self.args.endpoint_cooldown
+                    ));
+                }
+                Ok(disconnect)
+            }
+        }
+    }
+
+    async fn follow_geyser(&mut self) -> Result<Disconnect> {
+        let args = self.args;
+        let ids = self
+            .programs
+            .iter()
+            .map(|program| program.id)
+            .collect::<Vec<_>>();
+        let subscribed = geyser::subscribe(
+            &args.geyser_url,
+            args.geyser_x_token.as_deref(),
+            &ids,
+            self.config.commitment,
+        )
+        .await;
+        let stream = match subscribed {
+            Ok(stream) => stream,
+            Err(e @ GeyserError::Unsupported) => return Err(e.into()),
+            Err(e) => return Ok(Disconnect::Failed(e.to_string())),
+        };
+        let notifications = stream.map(Notification::Transaction).boxed();
+        self.receive(notifications, &args.geyser_url).await
+    }
+
+    async fn follow_websocket(&mut self, ws_url: &str) -> Result<Disconnect> {
+        let pubsub = match PubsubClient::new(ws_url).await {
+            Ok(pubsub) => pubsub,
+            Err(e) => return Ok(Disconnect::Failed(e.to_string())),
+        };
+        // A websocket subscription follows one address, so there is one per
+        // program
+        let commitment = self.config.commitment_config();
+        let mut streams: Vec<BoxStream<'_, Notification>> = Vec::with_capacity(self.programs.len());
+        // Dropping these would end the subscriptions
+        let mut unsubscribes = Vec::with_capacity(self.programs.len());
+        for (program, Program { id, .. }) in self.programs.iter().enumerate() {
+            let subscribed = match self.args.ingest {
+                Ingest::Blocks => pubsub
+                    .block_subscribe(
+                        RpcBlockSubscribeFilter::MentionsAccountOrProgram(id.to_string()),
+                        Some(RpcBlockSubscribeConfig {
+                            commitment: Some(commitment),
+                            encoding: Some(UiTransactionEncoding::Json),
+                            transaction_details: Some(TransactionDetails::Full),
+                            show_rewards: Some(false),
+                            max_supported_transaction_version: Some(0),
+                        }),
+                    )
+                    .await
+                    .map(|(blocks, unsubscribe)| {
+                        let blocks = blocks.map(move |msg| Notification::Block(program, msg));
+                        (blocks.boxed(), unsubscribe)
+                    }),
+                // `logs`; `geyser` and `poll` don't get here
+                _ => pubsub
+                    .logs_subscribe(
+                        RpcTransactionLogsFilter::Mentions(vec![id.to_string()]),
+                        RpcTransactionLogsConfig {
+                            commitment: Some(commitment),
+                        },
+                    )
+                    .await
+                    .map(|(logs, unsubscribe)| {
+                        let logs = logs.map(move |msg| Notification::Logs(program, msg));
+                        (logs.boxed(), unsubscribe)
+                    }),
+            };
+            let (stream, unsubscribe) = match subscribed {
+                Ok(subscription) => subscription,
+                Err(e) => return Ok(Disconnect::Failed(e.to_string())),
+            };
+            streams.push(stream);
+            unsubscribes.push(unsubscribe);
+        }
+        self.receive(select_all(streams).boxed(), ws_url).await
+    }
+
+    /// Handles what the subscriptions at `endpoint` deliver, after catching up
+    /// on what they missed, until they end.
+    async fn receive(
+        &mut self,
+        notifications: BoxStream<'_, Notification>,
+        endpoint: &str,
+    ) -> Result<Disconnect> {
+        self.status(format!(
+            "Listening for events of {} on {endpoint}...",
+            self.programs
+                .iter()
+                .map(|program| program.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        // Subscribed first, so nothing lands between the backfill and the
+        // notifications; what lands during it is in both and printed once.
+        // A backfill that didn't finish is tried again after resubscribing,
+        // as the notifications would move the cursors past what it missed
+        if let Some(reason) = self.catch_up().await? {
+            return Ok(Disconnect::Failed(reason));
+        }
+        self.health.subscribed();
+
+        let idle_timeout = Duration::from_secs(self.args.idle_timeout);
+        let mut last_received = tokio::time::Instant::now();
+        let mut finality_checks = tokio::time::interval(FINALITY_CHECK_INTERVAL);
+        // Whatever has piled up is taken at once, so a burst is fetched in
+        // parallel
+        let mut notifications = notifications.ready_chunks(self.args.fetch_concurrency.max(1));
+        loop {
+            let next = tokio::select! {
+                next = notifications.next() => next,
+                _ = finality_checks.tick(), if self.finality.is_some() => {
+                    self.check_finality().await?;
+                    continue;
+                }
+                _ = tokio::time::sleep_until(last_received + idle_timeout),
+                    if !idle_timeout.is_zero() => return Ok(Disconnect::Idle),
+            };
+            last_received = tokio::time::Instant::now();
+            let Some(batch) = next else {
+                return Ok(Disconnect::Closed);
+            };
+            let mut pending = Vec::with_capacity(batch.len());
+            for notification in batch {
+                match notification {
+                    Notification::Logs(program, msg) => pending.push(Pending {
+                        program,
+                        signature: msg.value.signature.parse()?,
+                        slot: msg.context.slot,
+                        logs: Some(msg.value.logs),
+                        // A failed transaction's events were rolled back
+                        failed: msg.value.err.is_some(),
+                    }),
+                    // Whatever came before goes first
+                    Notification::Block(program, msg) => {
+                        if let Some(reason) = self.handle_all(std::mem::take(&mut pending)).await? {
+                            return Ok(Disconnect::Failed(reason));
+                        }
+                        self.handle_block(program, msg.value).await?;
+                    }
+                    Notification::Transaction(Ok(streamed)) => {
+                        if let Some(reason) = self.handle_all(std::mem::take(&mut pending)).await? {
+                            return Ok(Disconnect::Failed(reason));
+                        }
+                        self.handle_streamed(streamed).await?;
+                    }
+                    Notification::Transaction(Err(e)) => {
+                        self.handle_all(pending).await?;
+                        return Ok(Disconnect::Failed(e.to_string()));
+                    }
+                }
+            }
+            if let Some(reason) = self.handle_all(pending).await? {
+                return Ok(Disconnect::Failed(reason));
+            }
+        }
+    }
+
+    /// Handles what landed since the last round every `--poll-interval`, for
+    /// good: a round that fails is tried again on the next, and marks the
+    /// listener disconnected meanwhile.
+    async fn poll(&mut self) -> Result<Disconnect> {
+        let interval = Duration::from_secs(self.args.poll_interval);
+        let mut endpoint = self.endpoints.current();
+        self.status(format!(
+            "Polling {} on {} every {}s...",
+            self.programs
+                .iter()
+                .map(|program| program.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.endpoints.endpoint(endpoint),
+            interval.as_secs()
+        ));
+        loop {
+            let current = self.endpoints.current();
+            if current != endpoint {
+                endpoint = current;
+                self.status(format!(
+                    "Polling on {} now",
+                    self.endpoints.endpoint(endpoint)
+                ));
+            }
+            match self.catch_up().await? {
+                Some(failure) => self.health.disconnected(failure),
+                None => self.health.subscribed(),
+            }
+            self.check_finality().await?;
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Handles every successful transaction since each program's cursor,
+    /// oldest first, or starts the cursors at the latest transaction when
+    /// there is neither an earlier subscription nor a checkpoint. Returns the
+    /// last failure to list or fetch a program's transactions, which is left
+    /// for the next time.
+    async fn catch_up(&mut self) -> Result<Option<String>> {
+        if self.args.logs_only {
+            return Ok(None);
+        }
+        let mut failure = None;
+        for program in 0..self.programs.len() {
+            let program_id = self.programs[program].id;
+            let name = self.programs[program].name.clone();
+            let Some(Position {
+                signature: cursor,
+                slot,
+            }) = self.cursors[program]
+            else {
+                let latest = self
+                    .endpoints
+                    .with_failover(|rpc| latest_signature(rpc, &program_id))
+                    .await;
+                match latest {
+                    Ok(Some((signature, slot))) => {
+                        self.advance(program, Position { signature, slot })?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        let reason = format!("Failed to find the latest {name} transaction: {e}");
+                        eprintln!("{reason}");
+                        failure = Some(reason);
+                    }
+                }
+                continue;
+            };
+            let missed = self
+                .endpoints
+                .with_failover(|rpc| signatures_since(rpc, &program_id, cursor, slot))
+                .await;
+            let missed = match missed {
+                Ok(missed) => missed,
+                Err(e) => {
+                    let reason = format!("Failed to backfill {name} since {cursor}: {e}");
+                    eprintln!("{reason}");
+                    failure = Some(reason);
+                    continue;
+                }
+            };
+            // Polling is one backfill after another
+            if !missed.is_empty() && self.args.ingest != Ingest::Poll {
+                self.status(format!(
+                    "Backfilling {} {name} transaction(s) since {cursor}",
+                    missed.len()
+                ));
+            }
+            let missed = missed
+                .into_iter()
+                .map(|(signature, slot)| Pending {
+                    program,
+                    signature,
+                    slot,
+                    logs: None,
+                    failed: false,
+                })
+                .collect();
+            if let Some(reason) = self.handle_all(missed).await? {
+                eprintln!("{reason}");
+                failure = Some(reason);
+            }
+        }
+        Ok(failure)
+    }
+
+    /// Sends the events of each of `pending` not sent yet to the sinks, in
+    /// order, and moves its program's cursor past it. The transactions are
+    /// fetched in batches of `--fetch-batch-size`, up to `--fetch-concurrency`
+    /// batches at a time, ahead of the one being sent. A transaction that
+    /// can't be fetched, without logs to fall back on, stops its program's
+    /// cursor before it, so the next catch-up tries it again; the failure is
+    /// returned.
+    async fn handle_all(&mut self, pending: Vec<Pending>) -> Result<Option<String>> {
+        if pending.is_empty() {
+            return Ok(None);
+        }
+        let batch_size = self.args.fetch_batch_size.max(1);
+        let fetch_all = !self.args.logs_only;
+        // Decided up front, so one two programs report is fetched once
+        let mut batches: Vec<Vec<(Pending, bool)>> = Vec::new();
+        for tx in pending {
+            let fresh = !tx.failed && self.seen.first_time(tx.signature);
+            match batches.last_mut() {
+                Some(batch) if batch.len() < batch_size => batch.push((tx, fresh)),
+                _ => batches.push(vec![(tx, fresh)]),
+            }
+        }
+        let endpoints = self.endpoints.clone();
+        let metrics = self.metrics.clone();
+        let http = self.http.clone();
+        let mut fetched = stream::iter(batches)
+            .map(|batch| {
+                let endpoints = endpoints.clone();
+                let metrics = metrics.clone();
+                let http = http.clone();
+                async move {
+                    let started = Instant::now();
+                    let wanted: Vec<(Signature, bool)> = batch
+                        .iter()
+                        .filter(|(_, fresh)| *fresh && fetch_all)
+                        .map(|(tx, _)| (tx.signature, tx.logs.is_some()))
+                        .collect();
+                    let mut events = fetch_batch(&endpoints, &metrics, &http, &wanted)
+                        .await
+                        .into_iter();
+                    batch
+                        .into_iter()
+                        .map(|(tx, fresh)| {
+                            let tx_events = if fresh && fetch_all {
+                                events.next().flatten()
+                            } else {
+                                None
+                            };
+                            (tx, fresh, tx_events, started)
+                        })
+                        .collect::<Vec<_>>()
+                }
+            })
+            .buffered(self.args.fetch_concurrency.max(1));
+        // The programs whose cursors stopped, which stay put until then
+        let mut stopped = Vec::new();
+        let mut failure = None;
+        while let Some(batch) = fetched.next().await {
+            for (tx, fresh, events, started) in batch {
+                if stopped.contains(&tx.program) {
+                    if fresh {
+                        self.seen.forget(&tx.signature);
+                    }
+                    continue;
+                }
+                let events = match (events, tx.logs) {
+                    (Some(events), _) => Some(events),
+                    (None, Some(logs)) if fresh => {
+                        Some(TxEvents::from_logs(tx.signature, tx.slot, logs))
+                    }
+                    (None, None) if fresh && fetch_all => {
+                        self.seen.forget(&tx.signature);
+                        stopped.push(tx.program);
+                        failure = Some(format!(
+                            "Failed to fetch {} transaction {}; resuming from it next time",
+                            self.programs[tx.program].name, tx.signature
+                        ));
+                        continue;
+                    }
+                    _ => None,
+                };
+                if let Some(events) = events {
+                    self.deliver(&events, started).await?;
+                }
+                // Only once handled, so a crash before then replays it
+                self.advance(
+                    tx.program,
+                    Position {
+                        signature: tx.signature,
+                        slot: tx.slot,
+                    },
+                )?;
+            }
+        }
+        self.save_checkpoint()?;
+        Ok(failure)
+    }
+
+    /// Handles the transactions of a block notification for `program`, which
+    /// are all there is to know about them.
+    async fn handle_block(&mut self, program: usize, update: RpcBlockUpdate) -> Result<()> {
+        let Some(block) = update.block else {
+            let reason = update
+                .err
+                .map_or_else(|| "no block".to_string(), |e| e.to_string());
+            eprintln!("Skipping block {}: {reason}", update.slot);
+            return Ok(());
+        };
+        for tx in block_tx_events(update.slot, &block) {
+            let started = Instant::now();
+            // A failed transaction's events were rolled back
+            if tx.err.is_none() && self.seen.first_time(tx.signature) {
+                self.deliver(&tx, started).await?;
+            }
+            self.advance(
+                program,
+                Position {
+                    signature: tx.signature,
+                    slot: tx.slot,
+                },
+            )?;
+        }
+        self.save_checkpoint()?;
+        Ok(())
+    }
+
+    /// Handles a transaction Geyser streamed, complete as it is, moving the
+    /// cursors of the programs it mentions.
+    async fn handle_streamed(&mut self, streamed: GeyserTransaction) -> Result<()> {
+        let GeyserTransaction { programs, tx } = streamed;
+        if self.seen.first_time(tx.signature) {
+            self.deliver(&tx, Instant::now()).await?;
+        }
+        let position = Position {
+            signature: tx.signature,
+            slot: tx.slot,
+        };
+        for id in programs {
+            if let Some(program) = self.programs.iter().position(|program| program.id == id) {
+                self.advance(program, position)?;
+            }
+        }
+        self.save_checkpoint()?;
+        Ok(())
+    }
+
+    /// Sends the events of `tx`, which took since `started` to get hold of,
+    /// to the sinks.
+    async fn deliver(&mut self, tx: &TxEvents, started: Instant) -> Result<()> {
+        let records = event_records(
+            tx,
+            &self.programs,
+            &self.args.source_chain,
+            self.idl.as_ref(),
+        );
+        let mut delivered = Vec::with_capacity(records.len());
+        for record in records {
+            if self.emit_unsent(&record).await? {
+                self.metrics
+                    .record_event(&record.program, record.event_type.as_deref());
+                delivered.push(record);
+            }
+        }
+        self.metrics.record_processed(tx.slot, started.elapsed());
+        // Only what went out here is retracted from here
+        if let Some(finality) = self.finality.as_mut().filter(|_| !delivered.is_empty()) {
+            finality.track(tx.signature, tx.slot, delivered);
+        }
+        Ok(())
+    }
+
+    /// Sends `record` to each sink `--dedup-file` doesn't have it sent to
+    /// yet, recording it there as sent to that sink right away, so a sink
+    /// failing doesn't make the retry send it to the ones before again.
+    /// Returns whether any sink got it.
+    async fn emit_unsent(&mut self, record: &EventRecord) -> Result<bool> {
+        let args = self.args;
+        let mut emitted = false;
+        for (index, spec) in args.sinks.iter().enumerate() {
+            let sink = spec.to_string();
+            // Sent before a restart, or by a backfill the subscription overlapped
+            let sent = self
+                .sent
+                .as_ref()
+                .is_some_and(|sent| sent.contains(&sink, record.signature, &record.location));
+            if sent {
+                continue;
+            }
+            self.emit_to(index, record).await?;
+            if let Some(sent) = &mut self.sent {
+                sent.insert(&sink, record.signature, &record.location)?;
+            }
+            emitted = true;
+        }
+        Ok(emitted)
+    }
+
+    async fn emit(&mut self, record: &EventRecord) -> Result<()> {
+        for index in 0..self.sinks.len() {
+            self.emit_to(index, record).await?;
+        }
+        Ok(())
+    }
+
+    async fn emit_to(&mut self, index: usize, record: &EventRecord) -> Result<()> {
+        let sink = &mut self.sinks[index];
+        if let Err(e) = sink.emit(record).await {
+            self.health.sink_status(index, Some(e.to_string()));
+            return Err(e.into());
+        }
+        self.health.sink_status(index, sink.degraded());
+        Ok(())
+    }
+
+    /// Forgets the transactions sent since finalized, and sends the events of
+    /// those that never will be again, marked as retracted. A failure to
+    /// check is left for the next time.
+    async fn check_finality(&mut self) -> Result<()> {
+        let signatures = match &self.finality {
+            Some(finality) if !finality.is_empty() => finality.signatures(),
+            _ => return Ok(()),
+        };
+        let status = self
+            .endpoints
+            .with_failover(|rpc| finality_status(rpc, &signatures))
+            .await;
+        let status = match status {
+            Ok(status) => status,
+            Err(e) => {
+                eprintln!("Failed to check finality: {e}");
+                return Ok(());
+            }
+        };
+        let abandoned = match &mut self.finality {
+            Some(finality) => finality.settle(&signatures, &status),
+            None => return Ok(()),
+        };
+        for tx in abandoned {
+            self.status(format!(
+                "{} never finalized, retracting its {} event(s)",
+                tx.signature,
+                tx.records.len()
+            ));
+            for record in tx.records {
+                let record = EventRecord {
+                    retracted: true,
+                    ..record
+                };
+                self.emit(&record).await?;
+                self.metrics.retractions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The events of each of `wanted`, a signature and whether its logs are there
+/// to fall back on, in order: several in one JSON-RPC batch, and those the
+/// batch didn't bring one by one.
+async fn fetch_batch(
+    endpoints: &EndpointPool,
+    metrics: &ListenerMetrics,
+    http: &reqwest::Client,
+    wanted: &[(Signature, bool)],
+) -> Vec<Option<TxEvents>> {
+    let mut batched = Vec::new();
+    if wanted.len() > 1 {
+        let signatures: Vec<Signature> = wanted.iter().map(|(signature, _)| *signature).collect();
+        let fetched = endpoints
+            .with_failover(|rpc| fetch_tx_events_batch(rpc, http, &signatures))
+            .await;
+        match fetched {
+            Ok(found) => batched = found,
+            Err(e) => eprintln!(
+                "Failed to fetch {} transactions in a batch, fetching them one by one: {e}",
+                wanted.len()
+            ),
+        }
+    }
+    let mut batched = batched.into_iter();
+    let mut events = Vec::with_capacity(wanted.len());
+    for &(signature, logged) in wanted {
+        let tx = match batched.next() {
+            Some(Ok(tx)) => Some(tx),
+            _ => fetch(endpoints, metrics, signature, logged).await,
+        };
+        events.push(tx);
+    }
+    events
+}
+
+/// The events of `signature`, from whichever endpoint can fetch it, or `None`
+/// when none can; `logged` is whether its logs are there to fall back on.
+async fn fetch(
+    endpoints: &EndpointPool,
+    metrics: &ListenerMetrics,
+    signature: Signature,
+    logged: bool,
+) -> Option<TxEvents> {
+    let fetched = endpoints
+        .with_failover(|rpc| fetch_tx_events(rpc, &signature))
+        .await;
+    match fetched {
+        Ok(tx) => Some(tx),
+        Err(e) => {
+            metrics.fetch_failures.fetch_add(1, Ordering::Relaxed);
+            if logged {
+                eprintln!("Failed to fetch {signature}, decoding its logs only: {e}");
+            } else {
+                eprintln!("Failed to fetch {signature}: {e}");
+            }
+            None
+        }
+    }
+}
+
+/// Keeps `metrics.tip_slot` at the highest slot of the endpoints, failing over
+/// from those that fall behind it; a failed poll leaves an endpoint's last
+/// slot.
+async fn poll_slots(endpoints: Arc<EndpointPool>, metrics: Arc<ListenerMetrics>) {
+    loop {
+        let mut slots = Vec::with_capacity(endpoints.len());
+        for index in 0..endpoints.len() {
+            slots.push(endpoints.rpc(index).get_slot().await.ok());
+        }
+        for (index, lag) in endpoints.record_slots(&slots) {
+            eprintln!(
+                "{} is {lag} slots behind; leaving it alone for now",
+                endpoints.endpoint(index)
+            );
+        }
+        if let Some(tip) = endpoints.tip() {
+            metrics.tip_slot.store(tip, Ordering::Relaxed);
+        }
+        tokio::time::sleep(SLOT_POLL_INTERVAL).await;
+    }
+}
+
+/// The events `programs` emitted in `tx`, as the sinks take them, decoded
+/// with each program's IDL or else `idl`.
+fn event_records(
+    tx: &TxEvents,
+    programs: &[Program],
+    source_chain: &str,
+    idl: Option<&IdlCodec>,
+) -> Vec<EventRecord> {
+    let mut records = Vec::new();
+    for (index, found) in tx.events.iter().enumerate() {
+        let Some(program) = programs
+            .iter()
+            .find(|program| Some(program.id) == found.program_id)
+        else {
+            continue;
+        };
+        let (event_type, fields, _) = decode(found, program.idl.as_ref().or(idl));
+        let (message_id, location) = match found.source {
+            EventSource::Cpi(message_id) => (Some(message_id), message_id.to_string()),
+            EventSource::Log(line) => (None, format!("log line {}", line + 1)),
+        };
+        let pays_for = found
+            .event
+            .as_ref()
+            .and_then(|event| gas_message_id(tx, index, event));
+        let gmp = match (&found.event, message_id) {
+            (Some(event), Some(event_id)) => {
+                let ctx = EventContext {
+                    event_id,
+                    source_chain: source_chain.to_string(),
+                    cost: tx.fee.unwrap_or_default(),
+                    paid_message_id: paid_message_id(tx, index, event),
+                    meta: Some(EventMetadata {
+                        tx_id: Some(tx.signature.to_string()),
+                        ..Default::default()
+                    }),
+                };
+                to_gmp_event(event, &ctx)
+            }
+            _ => None,
+        };
+        records.push(EventRecord {
+            event_type,
+            program: program.name.clone(),
+            program_id: program.id,
+            signature: tx.signature,
+            slot: tx.slot,
+            block_time: tx.block_time,
+            message_id,
+            location,
+            pays_for,
+            fields,
+            data: found.data.clone(),
+            event: found.event.clone(),
+            gmp,
+            retracted: false,
+        });
+    }
+    records
+}
+
+/// For a `GasPaidEvent`, the message id of the contract call it pays for: the
+/// next `CallContractEvent` in the transaction.
+fn paid_message_id(tx: &TxEvents, index: usize, event: &DecodedEvent) -> Option<MessageId> {
+    if !matches!(event, DecodedEvent::GasPaidEvent(_)) {
+        return None;
+    }
+    tx.events[index + 1..]
+        .iter()
+        .find_map(|found| match (&found.event, found.source) {
+            (Some(DecodedEvent::CallContractEvent(_)), EventSource::Cpi(id)) => Some(id),
+            _ => None,
+        })
+}
+
+/// For a gas service event, the id of the message whose gas it pays, adds to
+/// or refunds, so it can be matched with the gateway's events for it.
+fn gas_message_id(tx: &TxEvents, index: usize, event: &DecodedEvent) -> Option<String> {
+    match event {
+        DecodedEvent::GasPaidEvent(_) => paid_message_id(tx, index, event).map(|id| id.to_string()),
+        DecodedEvent::GasAddedEvent(e) => Some(e.message_id.clone()),
+        DecodedEvent::GasRefundedEvent(e) => Some(e.message_id.clone()),
+        _ => None,
+    }
+}
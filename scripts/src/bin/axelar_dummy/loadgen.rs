@@ -0,0 +1,216 @@
+//! `loadgen`: sends a mix of events at a target rate for a fixed duration and
+//! reports the throughput actually confirmed, confirmation latency percentiles
+//! and failures.
+//!
+//! A transaction is started every `1 / tps` seconds whether or not earlier
+//! ones have confirmed, so a slow cluster shows up as latency and a lower
+//! achieved rate rather than as a slower send loop. Blockhashes come from a
+//! shared cache instead of one request per transaction.
+//!
+//! With `--payers N` the fees are spread round-robin over a pool of payers
+//! (see `scripts::payers`) instead of serializing on one payer's account.
+//! Pool payers running low are airdropped to on localnet and topped up from
+//! the main payer elsewhere.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use futures::stream::{FuturesUnordered, StreamExt};
+use scripts::blockhash::{BlockhashCache, DEFAULT_REFRESH_INTERVAL};
+use scripts::config::Config;
+use scripts::error::RpcError;
+use scripts::funding::airdrop;
+use scripts::payers::{PayerPool, DEFAULT_PAYER_DIR};
+use scripts::rpc::ResilientRpc;
+use scripts::send_ix;
+use serde_json::json;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+use tokio::time::MissedTickBehavior;
+
+use crate::fuzz::Fuzzer;
+use crate::gateway::{ensure_gateway_root, ensure_payload_log};
+use crate::replay::instruction;
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct LoadgenArgs {
+    /// Transactions to start per second
+    #[arg(long, env = "LOADGEN_TPS", default_value_t = 50)]
+    tps: u32,
+    /// Seconds to keep sending for
+    #[arg(long, env = "LOADGEN_DURATION", default_value_t = 60)]
+    duration: u64,
+    /// Seed of the event mix, see `fuzz-events`
+    #[arg(long, env = "FUZZ_SEED", default_value_t = 0)]
+    seed: u64,
+    /// Spread the fees over this many generated payers instead of the main one
+    #[arg(long, env = "LOADGEN_PAYERS", default_value_t = 1)]
+    payers: usize,
+    /// Where the generated payer keypairs are kept between runs
+    #[arg(long, default_value = DEFAULT_PAYER_DIR)]
+    payer_dir: PathBuf,
+    /// Lamports to fund each generated payer with once it holds less than half
+    #[arg(long, default_value_t = 100_000_000)]
+    payer_funding: u64,
+}
+
+pub async fn run(config: &Config, args: LoadgenArgs, report: &mut Report) -> Result<()> {
+    if report.dry_run() {
+        return Err(anyhow!(
+            "loadgen measures confirmed transactions; drop --dry-run"
+        ));
+    }
+    if args.tps == 0 || args.payers == 0 {
+        return Err(anyhow!("--tps and --payers must be at least 1"));
+    }
+    let gateway = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &gateway, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway, &payer, report).await?;
+
+    let cache = BlockhashCache::spawn(
+        config.rpc_url.clone(),
+        config.commitment_config(),
+        DEFAULT_REFRESH_INTERVAL,
+    )
+    .await?;
+    // Concurrent sends can't share one nonce, and the pool's payers don't own it
+    let rpc = rpc.with_blockhash_cache(cache).with_durable_nonce(None);
+
+    let pool = match args.payers {
+        1 => None,
+        count => {
+            let pool = PayerPool::load_or_generate(&args.payer_dir, count)?;
+            fund(config, &rpc, &payer, &pool, args.payer_funding, report).await?;
+            Some(pool)
+        }
+    };
+
+    let total = u64::from(args.tps) * args.duration;
+    report.note(format!(
+        "Sending {} transactions at {} tps for {}s",
+        total, args.tps, args.duration
+    ));
+
+    let mut fuzzer = Fuzzer::new(args.seed);
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / f64::from(args.tps)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+    let mut in_flight = FuturesUnordered::new();
+    let mut stats = Stats::default();
+    let started = Instant::now();
+    let mut sent = 0;
+    while sent < total {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let sender = pool.as_ref().map_or(&payer, PayerPool::next);
+                let ix = instruction(config, &sender.pubkey(), &fuzzer.event())?;
+                let rpc = &rpc;
+                in_flight.push(async move {
+                    let start = Instant::now();
+                    let result = send_ix(rpc, sender, &[ix]).await;
+                    (start.elapsed(), result)
+                });
+                sent += 1;
+            }
+            Some((latency, result)) = in_flight.next() => stats.record(latency, result),
+        }
+    }
+    let sending = started.elapsed();
+    while let Some((latency, result)) = in_flight.next().await {
+        stats.record(latency, result);
+    }
+    let elapsed = started.elapsed();
+
+    report.field("target_tps", args.tps);
+    report.field("sent", sent);
+    report.field("send_secs", sending.as_secs_f64());
+    report.field("elapsed_secs", elapsed.as_secs_f64());
+    report.field("confirmed", stats.latencies.len());
+    report.field("failed", stats.failures);
+    report.field(
+        "achieved_tps",
+        stats.latencies.len() as f64 / elapsed.as_secs_f64(),
+    );
+    report.field("latency_ms", stats.percentiles());
+    report.field("errors", json!(stats.errors));
+    report.field("rpc", rpc.metrics().snapshot().to_string());
+    Ok(())
+}
+
+/// Brings every pool payer holding less than half of `lamports` up to it.
+async fn fund(
+    config: &Config,
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    pool: &PayerPool,
+    lamports: u64,
+    report: &mut Report,
+) -> Result<()> {
+    let underfunded = pool.underfunded(rpc, lamports / 2).await?;
+    if config.is_localnet() {
+        for pubkey in &underfunded {
+            airdrop(rpc, pubkey, lamports).await?;
+        }
+    } else {
+        let transfers: Vec<_> = underfunded
+            .iter()
+            .map(|to| system_instruction::transfer(&payer.pubkey(), to, lamports))
+            .collect();
+        // Well within the transaction size limit
+        for chunk in transfers.chunks(10) {
+            report.send(rpc, payer, "fund_payers", chunk).await?;
+        }
+    }
+    let payers: Vec<String> = pool
+        .payers()
+        .iter()
+        .map(|p| p.pubkey().to_string())
+        .collect();
+    report.field("payers", payers);
+    report.field("funded_payers", underfunded.len());
+    Ok(())
+}
+
+#[derive(Default)]
+struct Stats {
+    /// Confirmation latency of every confirmed transaction.
+    latencies: Vec<Duration>,
+    failures: u64,
+    /// Failure count per error message.
+    errors: BTreeMap<String, u64>,
+}
+
+impl Stats {
+    fn record(&mut self, latency: Duration, result: Result<Signature, RpcError>) {
+        match result {
+            Ok(_) => self.latencies.push(latency),
+            Err(e) => {
+                self.failures += 1;
+                *self.errors.entry(e.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    fn percentiles(&mut self) -> serde_json::Value {
+        if self.latencies.is_empty() {
+            return serde_json::Value::Null;
+        }
+        self.latencies.sort();
+        let at = |p: f64| {
+            let index = ((self.latencies.len() - 1) as f64 * p).round() as usize;
+            self.latencies[index].as_secs_f64() * 1000.0
+        };
+        json!({
+            "p50": at(0.50),
+            "p90": at(0.90),
+            "p99": at(0.99),
+            "max": at(1.0),
+        })
+    }
+}
@@ -0,0 +1,24 @@
+//! `lookup-table`: creates an address lookup table with the program ids and
+//! singleton PDAs of the gateway and gas service, for `gas pay --lookup-table`.
+
+use anyhow::Result;
+use scripts::alt::{common_addresses, create_table};
+use scripts::config::Config;
+
+use crate::report::Report;
+
+pub async fn run(config: &Config, report: &mut Report) -> Result<()> {
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let addresses = common_addresses(&config.gateway_program_id, &config.gas_program_id);
+    let table = create_table(&rpc, &payer, &addresses).await?;
+
+    report.note(format!(
+        "Created lookup table {table} with {} addresses",
+        addresses.len()
+    ));
+    report.field("lookup_table", table.to_string());
+    report.field("addresses", addresses.len());
+    Ok(())
+}
@@ -0,0 +1,433 @@
+//! `axelar-dummy`: triggers the events of the dummy gateway, gas service and
+//! ITS programs, and listens for them.
+//!
+//! Every command shares the global flags below on top of `config.toml` and
+//! the environment, see `scripts::config`. With `--json` each command prints a
+//! single JSON object instead of prose, see `report`.
+
+// See the crate-level allows of `scripts`
+#![allow(deprecated, clippy::result_large_err)]
+
+mod burn_compute;
+mod check_golden;
+mod check_ids;
+mod decode_data;
+mod decode_tx;
+mod deploy;
+mod derive_pda;
+mod discriminator;
+mod duplicate;
+mod echo;
+mod export_events;
+mod export_idl;
+mod fund;
+mod fuzz;
+mod gas;
+mod gateway;
+mod gen_program_ids;
+mod governance;
+mod init;
+mod its;
+mod listen;
+mod loadgen;
+mod lookup_table;
+mod multisig;
+mod negative;
+mod nonce;
+mod offline;
+mod pipeline;
+mod replay;
+mod report;
+mod scenario;
+mod show_account;
+mod signers;
+mod trigger_all;
+mod whitelist;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use scripts::cluster::Cluster;
+use scripts::config::Config;
+use scripts::funding::Funder;
+use scripts::tx::{ComputeUnitLimit, PriorityFee};
+use solana_program::keccak;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::report::Report;
+
+/// Trigger and watch the events of the dummy Axelar programs
+#[derive(Parser)]
+#[command(name = "axelar-dummy", version)]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct GlobalArgs {
+    /// Config file; `config.toml` is used when present
+    #[arg(long, global = true, env = "SCRIPTS_CONFIG")]
+    config: Option<PathBuf>,
+    /// localnet, devnet, testnet or mainnet; sets the endpoints and program ids
+    #[arg(long, global = true, env = "CLUSTER")]
+    cluster: Option<Cluster>,
+    #[arg(long, global = true)]
+    rpc_url: Option<String>,
+    #[arg(long, global = true)]
+    ws_url: Option<String>,
+    #[arg(long, global = true)]
+    gateway_program_id: Option<Pubkey>,
+    #[arg(long, global = true)]
+    gas_program_id: Option<Pubkey>,
+    /// Payer keypair file, or `prompt` to type a seed phrase [default:
+    /// SOLANA_KEYPAIR, then ~/.config/solana/id.json]
+    #[arg(long, global = true)]
+    keypair: Option<String>,
+    /// processed, confirmed or finalized; what sends wait for and reads see
+    #[arg(long, global = true)]
+    commitment: Option<CommitmentLevel>,
+    /// Send without the node simulating each transaction first
+    #[arg(long, global = true)]
+    skip_preflight: bool,
+    /// Compute unit price in micro-lamports, or `auto` to pay what recent transactions writing
+    /// the same accounts paid
+    #[arg(long, global = true, value_name = "MICROLAMPORTS|auto")]
+    priority_fee: Option<PriorityFee>,
+    /// Compute unit limit, or `auto` to simulate first and add a margin
+    #[arg(long, global = true, value_name = "UNITS|auto")]
+    cu_limit: Option<ComputeUnitLimit>,
+    /// Durable nonce account to sign against instead of a recent blockhash, with the payer as
+    /// its authority
+    #[arg(long, global = true)]
+    nonce_account: Option<Pubkey>,
+    /// Print one JSON object with the transactions, PDAs and results instead of prose
+    #[arg(long, global = true)]
+    json: bool,
+    /// Simulate the transactions and print their logs, compute usage and events instead of
+    /// sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Wait for every transaction sent to be finalized and report its slot
+    #[arg(long, global = true)]
+    wait_finalized: bool,
+    /// Seconds to wait for finalization before failing
+    #[arg(long, global = true, default_value_t = 60)]
+    finalize_timeout: u64,
+    /// Check that the events of every transaction sent carry the arguments it was sent with,
+    /// and fail otherwise
+    #[arg(long, global = true)]
+    verify: bool,
+}
+
+impl GlobalArgs {
+    /// The config file and environment, overridden by the flags.
+    fn config(&self) -> Result<Config> {
+        let mut config = Config::resolve(self.config.as_deref(), self.cluster)?;
+        if let Some(url) = &self.rpc_url {
+            config.rpc_url = url.clone();
+        }
+        if let Some(url) = &self.ws_url {
+            config.ws_url = url.clone();
+        }
+        if let Some(id) = self.gateway_program_id {
+            config.gateway_program_id = id;
+        }
+        if let Some(id) = self.gas_program_id {
+            config.gas_program_id = id;
+        }
+        if let Some(keypair) = &self.keypair {
+            config.keypair = Some(keypair.clone());
+        }
+        if let Some(commitment) = self.commitment {
+            config.commitment = commitment;
+        }
+        if self.skip_preflight {
+            config.skip_preflight = true;
+        }
+        if self.verify {
+            config.verify = true;
+        }
+        if let Some(fee) = self.priority_fee {
+            config.priority_fee = Some(fee);
+        }
+        if let Some(limit) = self.cu_limit {
+            config.cu_limit = Some(limit);
+        }
+        if let Some(account) = self.nonce_account {
+            config.nonce_account = Some(account);
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the gateway, gas service and verification session PDAs that don't exist
+    Init(init::InitArgs),
+    /// Call a contract on another chain through the gateway
+    CallContract(gateway::CallContractArgs),
+    /// Approve a message through a fresh verification session
+    Approve(gateway::ApproveArgs),
+    /// Mark an approved message as executed
+    Execute(gateway::ExecuteArgs),
+    /// Call a contract, then approve and execute the resulting message
+    Lifecycle(gateway::LifecycleArgs),
+    /// Emit VerifierSetRotatedEvent for a given epoch and verifier set hash
+    RotateSigners(signers::RotateSignersArgs),
+    /// Verify every signature of a verifier set over a fresh root, registering the set first
+    VerifySignatures(signers::VerifySignaturesArgs),
+    /// Burn compute units on chain and emit ComputeBurnedEvent
+    BurnCompute(burn_compute::BurnComputeArgs),
+    /// Gas service payments and refunds
+    #[command(subcommand)]
+    Gas(gas::GasCommand),
+    /// Interchain token service events, and its_tester's operator-only calls
+    #[command(subcommand)]
+    Its(its::ItsCommand),
+    /// Approve a message for echo_executable and execute it, echoing the payload back
+    Echo(echo::EchoArgs),
+    /// Set up governance_executor and schedule or execute proposals through it
+    #[command(subcommand)]
+    Governance(governance::GovernanceCommand),
+    /// Set up relayer_whitelist and add or remove relayers
+    #[command(subcommand)]
+    Whitelist(whitelist::WhitelistCommand),
+    /// Put gateway or gas service operator actions behind an m-of-n multisig
+    #[command(subcommand)]
+    Multisig(multisig::MultisigCommand),
+    /// Re-emit the events listed in a JSON or YAML file
+    Replay(replay::ReplayArgs),
+    /// Send random events generated from a seed, for soak tests
+    FuzzEvents(fuzz::FuzzArgs),
+    /// Send a mix of events at a target rate and report throughput and latency
+    Loadgen(loadgen::LoadgenArgs),
+    /// Fire every program_tester and gas_service instruction once and fail if any event does
+    /// not decode
+    TriggerAll,
+    /// Send approve_message transactions that fail on-chain and check their errors
+    ApproveMalformed(negative::MalformedArgs),
+    /// Approve the same cc_id twice, optionally in one transaction
+    ApproveDuplicate(duplicate::DuplicateArgs),
+    /// Print every Anchor event in a transaction, decoded and attributed to its program
+    DecodeTx(decode_tx::DecodeTxArgs),
+    /// Decode raw instruction data into its instruction and arguments through the IDL
+    DecodeData(decode_data::DecodeDataArgs),
+    /// Print the decoded fields, lamports and owner of a gateway or gas service account
+    ShowAccount(show_account::ShowAccountArgs),
+    /// Compute Anchor discriminators, or find the name a discriminator belongs to
+    #[command(subcommand)]
+    Discriminator(discriminator::DiscriminatorCommand),
+    /// Print every PDA of the configured programs with its bump
+    DerivePda(derive_pda::DerivePdaArgs),
+    /// Run the steps of a scenario file, see `scenario.example.yaml`
+    RunScenario(scenario::ScenarioArgs),
+    /// Create an address lookup table of the gateway and gas service accounts
+    LookupTable,
+    /// Check the golden Borsh vectors against the programs' current layouts
+    CheckGolden,
+    /// Create, inspect or advance durable nonce accounts
+    #[command(subcommand)]
+    Nonce(nonce::NonceCommand),
+    /// Sign an operator transaction against a durable nonce and write it to a file, to submit
+    /// from another machine
+    Sign(offline::SignArgs),
+    /// Broadcast a transaction written by `sign`
+    Submit(offline::SubmitArgs),
+    /// Copy the programs' IDLs into idl/, and optionally publish them on-chain
+    ExportIdl(export_idl::ExportIdlArgs),
+    /// Give programs fresh keypairs and write the new ids to declare_id!, Anchor.toml and the
+    /// config
+    GenProgramIds(gen_program_ids::GenProgramIdsArgs),
+    /// Compare each program's declare_id! with its keypair, Anchor.toml and the config
+    CheckIds(check_ids::CheckIdsArgs),
+    /// Deploy or upgrade the built programs and record their ids in the config file
+    Deploy(deploy::DeployArgs),
+    /// Build, check ids, deploy and run trigger-all, stopping at the first failure
+    Pipeline(pipeline::PipelineArgs),
+    /// Airdrop to an account, or transfer to it from the treasury keypair
+    Fund(fund::FundArgs),
+    /// Follow the gateway, gas service or other programs and send their events to the sinks
+    Listen(Box<listen::ListenArgs>),
+    /// Write events captured by `listen` as CSV or Parquet, partitioned by day
+    ExportEvents(export_events::ExportEventsArgs),
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Init(_) => "init",
+            Command::CallContract(_) => "call-contract",
+            Command::Approve(_) => "approve",
+            Command::Execute(_) => "execute",
+            Command::Lifecycle(_) => "lifecycle",
+            Command::RotateSigners(_) => "rotate-signers",
+            Command::VerifySignatures(_) => "verify-signatures",
+            Command::BurnCompute(_) => "burn-compute",
+            Command::Gas(gas::GasCommand::Pay(_)) => "gas pay",
+            Command::Gas(gas::GasCommand::Add(_)) => "gas add",
+            Command::Gas(gas::GasCommand::Refund(_)) => "gas refund",
+            Command::Gas(gas::GasCommand::CpiCall(_)) => "gas cpi-call",
+            Command::Its(its::ItsCommand::Transfer(_)) => "its transfer",
+            Command::Its(its::ItsCommand::Deploy(_)) => "its deploy",
+            Command::Its(its::ItsCommand::Link(_)) => "its link",
+            Command::Its(its::ItsCommand::Metadata(_)) => "its metadata",
+            Command::Its(its::ItsCommand::HubCall(_)) => "its hub-call",
+            Command::Its(its::ItsCommand::Init(_)) => "its init",
+            Command::Its(its::ItsCommand::SetFlowLimit(_)) => "its set-flow-limit",
+            Command::Its(its::ItsCommand::TransferOperatorship(_)) => "its transfer-operatorship",
+            Command::Echo(_) => "echo",
+            Command::Governance(governance::GovernanceCommand::Init(_)) => "governance init",
+            Command::Governance(governance::GovernanceCommand::Propose(_)) => "governance propose",
+            Command::Governance(governance::GovernanceCommand::Execute(_)) => "governance execute",
+            Command::Whitelist(whitelist::WhitelistCommand::Init(_)) => "whitelist init",
+            Command::Whitelist(whitelist::WhitelistCommand::Add(_)) => "whitelist add",
+            Command::Whitelist(whitelist::WhitelistCommand::Remove(_)) => "whitelist remove",
+            Command::Multisig(multisig::MultisigCommand::Create(_)) => "multisig create",
+            Command::Multisig(multisig::MultisigCommand::Propose(_)) => "multisig propose",
+            Command::Multisig(multisig::MultisigCommand::Approve(_)) => "multisig approve",
+            Command::Multisig(multisig::MultisigCommand::Execute(_)) => "multisig execute",
+            Command::Replay(_) => "replay",
+            Command::FuzzEvents(_) => "fuzz-events",
+            Command::Loadgen(_) => "loadgen",
+            Command::TriggerAll => "trigger-all",
+            Command::LookupTable => "lookup-table",
+            Command::CheckGolden => "check-golden",
+            Command::ApproveMalformed(_) => "approve-malformed",
+            Command::ApproveDuplicate(_) => "approve-duplicate",
+            Command::DecodeTx(_) => "decode-tx",
+            Command::DecodeData(_) => "decode-data",
+            Command::ShowAccount(_) => "show-account",
+            Command::Discriminator(discriminator::DiscriminatorCommand::Of(_)) => {
+                "discriminator of"
+            }
+            Command::Discriminator(discriminator::DiscriminatorCommand::Lookup(_)) => {
+                "discriminator lookup"
+            }
+            Command::DerivePda(_) => "derive-pda",
+            Command::RunScenario(_) => "run-scenario",
+            Command::Nonce(nonce::NonceCommand::Create(_)) => "nonce create",
+            Command::Nonce(nonce::NonceCommand::Show(_)) => "nonce show",
+            Command::Nonce(nonce::NonceCommand::Advance(_)) => "nonce advance",
+            Command::Sign(_) => "sign",
+            Command::Submit(_) => "submit",
+            Command::ExportIdl(_) => "export-idl",
+            Command::GenProgramIds(_) => "gen-program-ids",
+            Command::CheckIds(_) => "check-ids",
+            Command::Deploy(_) => "deploy",
+            Command::Pipeline(_) => "pipeline",
+            Command::Fund(_) => "fund",
+            Command::Listen(_) => "listen",
+            Command::ExportEvents(_) => "export-events",
+        }
+    }
+}
+
+/// The payload of a contract call and its hash.
+#[derive(Args)]
+struct PayloadArgs {
+    /// Payload as UTF-8 text
+    #[arg(long, env = "PAYLOAD", conflicts_with = "payload_hex")]
+    payload: Option<String>,
+    /// Payload as hex
+    #[arg(long, env = "PAYLOAD_HEX")]
+    payload_hex: Option<String>,
+    /// Payload hash as hex, instead of the keccak256 of the payload that the gateway and
+    /// executables such as echo_executable check
+    #[arg(long, env = "PAYLOAD_HASH_HEX")]
+    payload_hash: Option<String>,
+}
+
+impl PayloadArgs {
+    fn payload(&self) -> Result<Vec<u8>> {
+        Ok(match (&self.payload, &self.payload_hex) {
+            (Some(text), _) => text.clone().into_bytes(),
+            (None, Some(hex)) => scripts::hex_decode(hex, scripts::Mode::Strict)?,
+            (None, None) => vec![1, 2, 3, 4, 5],
+        })
+    }
+
+    fn payload_hash(&self, payload: &[u8]) -> Result<[u8; 32]> {
+        Ok(match &self.payload_hash {
+            Some(hex) => scripts::hex_array(hex, scripts::Mode::Strict)?,
+            None => keccak::hash(payload).to_bytes(),
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = cli.global.config()?;
+    let mut report = Report::new(
+        cli.command.name(),
+        cli.global.json,
+        cli.global.dry_run,
+        config.explorer(),
+        cli.global
+            .wait_finalized
+            .then(|| Duration::from_secs(cli.global.finalize_timeout)),
+        config.verify,
+    );
+    if config.min_balance > 0 {
+        if let Some(funder) = Funder::from_config(&config)? {
+            report = report.with_auto_fund(funder, config.min_balance);
+        }
+    }
+    let result = match cli.command {
+        Command::Init(args) => init::run(&config, args, &mut report).await,
+        Command::CallContract(args) => gateway::call_contract(&config, args, &mut report).await,
+        Command::Approve(args) => gateway::approve(&config, args, &mut report).await,
+        Command::Execute(args) => gateway::execute(&config, args, &mut report).await,
+        Command::Lifecycle(args) => gateway::lifecycle(&config, args, &mut report).await,
+        Command::RotateSigners(args) => signers::rotate(&config, args, &mut report).await,
+        Command::VerifySignatures(args) => signers::verify(&config, args, &mut report).await,
+        Command::BurnCompute(args) => burn_compute::run(&config, args, &mut report).await,
+        Command::Gas(command) => gas::run(&config, command, &mut report).await,
+        Command::Its(command) => its::run(&config, command, &mut report).await,
+        Command::Echo(args) => echo::run(&config, args, &mut report).await,
+        Command::Governance(command) => governance::run(&config, command, &mut report).await,
+        Command::Whitelist(command) => whitelist::run(&config, command, &mut report).await,
+        Command::Multisig(command) => multisig::run(&config, command, &mut report).await,
+        Command::Replay(args) => replay::run(&config, args, &mut report).await,
+        Command::FuzzEvents(args) => fuzz::run(&config, args, &mut report).await,
+        Command::Loadgen(args) => loadgen::run(&config, args, &mut report).await,
+        Command::TriggerAll => trigger_all::run(&config, &mut report).await,
+        Command::ApproveMalformed(args) => negative::run(&config, args, &mut report).await,
+        Command::ApproveDuplicate(args) => duplicate::run(&config, args, &mut report).await,
+        Command::DecodeTx(args) => decode_tx::run(&config, args, &mut report).await,
+        Command::DecodeData(args) => decode_data::run(&config, args, &mut report).await,
+        Command::ShowAccount(args) => show_account::run(&config, args, &mut report).await,
+        Command::Discriminator(command) => discriminator::run(command, &mut report),
+        Command::LookupTable => lookup_table::run(&config, &mut report).await,
+        Command::CheckGolden => check_golden::run(&mut report),
+        Command::DerivePda(args) => derive_pda::run(&config, args, &mut report).await,
+        Command::RunScenario(args) => scenario::run(&config, args, &mut report).await,
+        Command::Nonce(command) => nonce::run(&config, command, &mut report).await,
+        Command::Sign(args) => offline::sign(&config, args, &mut report).await,
+        Command::Submit(args) => offline::submit(&config, args, &mut report).await,
+        Command::ExportIdl(args) => export_idl::run(&config, args, &mut report).await,
+        Command::GenProgramIds(args) => {
+            gen_program_ids::run(&config, args, cli.global.config.as_deref(), &mut report)
+        }
+        Command::CheckIds(args) => check_ids::run(&config, args, &mut report).await,
+        Command::Deploy(args) => {
+            deploy::run(&config, args, cli.global.config.as_deref(), &mut report).await
+        }
+        Command::Pipeline(args) => {
+            pipeline::run(&config, args, cli.global.config.as_deref(), &mut report).await
+        }
+        Command::Fund(args) => fund::run(&config, args, &mut report).await,
+        Command::ExportEvents(args) => export_events::run(args, &mut report),
+        // Streams one JSON object per event instead
+        Command::Listen(args) => return listen::run(&config, *args, report.json()).await,
+    };
+    // What was sent before a failure is still reported
+    report.finish()?;
+    result
+}
@@ -0,0 +1,216 @@
+//! `multisig`: puts gateway or gas service operator actions behind an m-of-n
+//! multisig_tester multisig.
+//!
+//! `create --operator-of` hands operatorship to the multisig's signer PDA in
+//! the same transaction. From then on an operator action is `propose`d by one
+//! owner, `approve`d by others (run with their `--keypair`) and `execute`d by
+//! anyone once the threshold is met.
+
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use multisig_tester::{Multisig, MultisigTransaction};
+use scripts::accounts::fetch_account;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::multisig::{
+    build_approve_ix, build_create_multisig_ix, build_create_transaction_ix, build_execute_ix,
+};
+use scripts::pdas::{multisig_pda, multisig_signer_pda, multisig_transaction_pda};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::report::Report;
+
+#[derive(Subcommand)]
+pub enum MultisigCommand {
+    /// Create a multisig, optionally making it the operator of the gateway or gas service
+    Create(CreateArgs),
+    /// Propose handing operatorship over to a new operator; the payer must be an owner
+    Propose(ProposeArgs),
+    /// Approve a proposed transaction; the payer must be an owner
+    Approve(TransactionArgs),
+    /// Execute a proposed transaction that has enough approvals
+    Execute(TransactionArgs),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Program {
+    Gateway,
+    Gas,
+}
+
+#[derive(Args)]
+pub struct ProgramArgs {
+    #[arg(long, env = "MULTISIG_PROGRAM_ID", default_value_t = multisig_tester::ID)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+pub struct CreateArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Owner of the multisig; repeatable, defaults to the payer
+    #[arg(long = "owner")]
+    owners: Vec<Pubkey>,
+    /// Owner approvals needed to execute a transaction
+    #[arg(long, default_value_t = 1)]
+    threshold: u8,
+    /// Key the multisig address is derived from; a fresh one by default
+    #[arg(long)]
+    create_key: Option<Pubkey>,
+    /// Hand operatorship of this program to the multisig; the payer must be its operator
+    #[arg(long, value_enum)]
+    operator_of: Option<Program>,
+}
+
+#[derive(Args)]
+pub struct ProposeArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Multisig `create` printed
+    #[arg(long)]
+    multisig: Pubkey,
+    /// Program the multisig is the operator of
+    #[arg(long, value_enum)]
+    operator_of: Program,
+    #[arg(long)]
+    new_operator: Pubkey,
+}
+
+#[derive(Args)]
+pub struct TransactionArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Transaction `propose` printed
+    #[arg(long)]
+    transaction: Pubkey,
+}
+
+pub async fn run(config: &Config, command: MultisigCommand, report: &mut Report) -> Result<()> {
+    match command {
+        MultisigCommand::Create(args) => create(config, args, report).await,
+        MultisigCommand::Propose(args) => propose(config, args, report).await,
+        MultisigCommand::Approve(args) => approve(config, args, report).await,
+        MultisigCommand::Execute(args) => execute(config, args, report).await,
+    }
+}
+
+fn transfer_operatorship_ix(
+    config: &Config,
+    program: Program,
+    operator: &Pubkey,
+    new_operator: Pubkey,
+) -> Instruction {
+    match program {
+        Program::Gateway => scripts::gateway::build_transfer_operatorship_ix(
+            &config.gateway_program_id,
+            operator,
+            new_operator,
+        ),
+        Program::Gas => scripts::gas::build_transfer_operatorship_ix(
+            &config.gas_program_id,
+            operator,
+            new_operator,
+        ),
+    }
+}
+
+async fn create(config: &Config, args: CreateArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let create_key = args.create_key.unwrap_or_else(|| Keypair::new().pubkey());
+    let owners = if args.owners.is_empty() {
+        vec![payer.pubkey()]
+    } else {
+        args.owners
+    };
+    let multisig = multisig_pda(&program_id, &create_key).0;
+    let multisig_signer = multisig_signer_pda(&program_id, &multisig).0;
+
+    let mut ixs = vec![build_create_multisig_ix(
+        &program_id,
+        &payer.pubkey(),
+        create_key,
+        owners,
+        args.threshold,
+    )];
+    if let Some(program) = args.operator_of {
+        ixs.push(transfer_operatorship_ix(
+            config,
+            program,
+            &payer.pubkey(),
+            multisig_signer,
+        ));
+    }
+    report.send(&rpc, &payer, "create_multisig", &ixs).await?;
+
+    report.pda("multisig", multisig);
+    report.pda("multisig_signer", multisig_signer);
+    report.field("create_key", create_key.to_json());
+    Ok(())
+}
+
+async fn propose(config: &Config, args: ProposeArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let multisig: Multisig = fetch_account(&rpc, &args.multisig).await?;
+    let multisig_signer = multisig_signer_pda(&program_id, &args.multisig).0;
+    let proposed = transfer_operatorship_ix(
+        config,
+        args.operator_of,
+        &multisig_signer,
+        args.new_operator,
+    );
+    let index = multisig.transaction_count;
+    let ix = build_create_transaction_ix(
+        &program_id,
+        &payer.pubkey(),
+        &args.multisig,
+        index,
+        &proposed,
+    );
+    report
+        .send(&rpc, &payer, "create_transaction", &[ix])
+        .await?;
+
+    report.pda(
+        "transaction",
+        multisig_transaction_pda(&program_id, &args.multisig, index).0,
+    );
+    report.field("threshold", multisig.threshold.to_json());
+    Ok(())
+}
+
+async fn approve(config: &Config, args: TransactionArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let transaction: MultisigTransaction = fetch_account(&rpc, &args.transaction).await?;
+    let ix = build_approve_ix(
+        &program_id,
+        &payer.pubkey(),
+        &transaction.multisig,
+        &args.transaction,
+    );
+    report.send(&rpc, &payer, "approve", &[ix]).await?;
+    Ok(())
+}
+
+async fn execute(config: &Config, args: TransactionArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let transaction: MultisigTransaction = fetch_account(&rpc, &args.transaction).await?;
+    let ix = build_execute_ix(&program_id, &args.transaction, &transaction);
+    report.send(&rpc, &payer, "execute", &[ix]).await?;
+
+    report.field("approvals", transaction.approval_count().to_json());
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! `approve-malformed`: sends `approve_message` transactions that are meant to
+//! fail on-chain and checks each fails with the expected Anchor error, to give
+//! listeners reproducible failed transactions to cope with.
+//!
+//! Every case starts from a valid approval of a fresh message and breaks one
+//! thing. The transactions skip preflight so they land as failures instead of
+//! being rejected by the node.
+
+use anchor_lang::error::ErrorCode;
+use anyhow::{anyhow, Result};
+use clap::{Args, ValueEnum};
+use program_tester::{CrossChainId, Message, MessageLeaf};
+use scripts::config::Config;
+use scripts::gateway::{build_approve_message_ix, build_init_verification_session_ix};
+use scripts::merkle::merkleise;
+use scripts::pdas::{incoming_message_pda, verification_session_pda};
+use scripts::tx::TxBuilder;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+
+use crate::gateway::ensure_gateway_root;
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct MalformedArgs {
+    /// Which breakage to send; all of them by default
+    #[arg(long, value_enum)]
+    case: Option<Case>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Case {
+    /// Instruction data cut off inside the message
+    CorruptData,
+    /// An incoming message PDA derived from another command id
+    WrongPda,
+    /// An event authority that isn't the program's event authority PDA
+    WrongEventAuthority,
+    /// No event authority and program accounts for `emit_cpi!`
+    MissingEventCpi,
+}
+
+impl Case {
+    fn name(self) -> &'static str {
+        match self {
+            Case::CorruptData => "corrupt-data",
+            Case::WrongPda => "wrong-pda",
+            Case::WrongEventAuthority => "wrong-event-authority",
+            Case::MissingEventCpi => "missing-event-cpi",
+        }
+    }
+
+    fn expected(self) -> ErrorCode {
+        match self {
+            Case::CorruptData => ErrorCode::InstructionDidNotDeserialize,
+            Case::WrongPda | Case::WrongEventAuthority => ErrorCode::ConstraintSeeds,
+            Case::MissingEventCpi => ErrorCode::AccountNotEnoughKeys,
+        }
+    }
+
+    /// Breaks the valid `approve_message` instruction `ix`.
+    fn apply(self, ix: &mut Instruction) {
+        // ApproveMessage account order, with #[event_cpi] adding the last two
+        const INCOMING_MESSAGE: usize = 3;
+        const EVENT_AUTHORITY: usize = 5;
+        match self {
+            // The discriminator and the start of the message's first string
+            Case::CorruptData => ix.data.truncate(8 + 16),
+            Case::WrongPda => {
+                ix.accounts[INCOMING_MESSAGE].pubkey =
+                    incoming_message_pda(&ix.program_id, &[0xff; 32]).0;
+            }
+            Case::WrongEventAuthority => {
+                ix.accounts[EVENT_AUTHORITY].pubkey = ix.accounts[INCOMING_MESSAGE].pubkey;
+            }
+            Case::MissingEventCpi => ix.accounts.truncate(EVENT_AUTHORITY),
+        }
+    }
+}
+
+pub async fn run(config: &Config, args: MalformedArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+
+    // A fresh message per run, so the wrong-PDA case isn't masked by an
+    // incoming message left over from an earlier one
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let message = Message {
+        cc_id: CrossChainId {
+            chain: "ethereum".to_string(),
+            id: format!("approve-malformed-{}", timestamp),
+        },
+        source_address: payer.pubkey().to_string(),
+        destination_chain: "solana".to_string(),
+        destination_address: payer.pubkey().to_string(),
+        payload_hash: Sha256::digest(timestamp.to_le_bytes()).into(),
+    };
+    let leaf = MessageLeaf {
+        message,
+        position: 0,
+        set_size: 1,
+        domain_separator: [0u8; 32],
+        signing_verifier_set: [0u8; 32],
+    };
+    let (payload_merkle_root, mut messages) =
+        merkleise(vec![leaf]).ok_or_else(|| anyhow!("no messages to approve"))?;
+    let message = messages.remove(0);
+
+    // The session has to exist for the account checks to get past it
+    report.pda(
+        "verification_session",
+        verification_session_pda(&program_id, &payload_merkle_root).0,
+    );
+    let ix = build_init_verification_session_ix(&program_id, &payer.pubkey(), payload_merkle_root);
+    report
+        .send(&rpc, &payer, "init_verification_session", &[ix])
+        .await?;
+
+    let cases = match args.case {
+        Some(case) => vec![case],
+        None => Case::value_variants().to_vec(),
+    };
+    let mut results = Vec::new();
+    let mut mismatches = Vec::new();
+    for case in cases {
+        let mut ix = build_approve_message_ix(
+            &program_id,
+            &payer.pubkey(),
+            message.clone(),
+            payload_merkle_root,
+        );
+        case.apply(&mut ix);
+        let name = format!("approve_message ({})", case.name());
+        if report.dry_run() {
+            report.send(&rpc, &payer, &name, &[ix]).await?;
+            continue;
+        }
+
+        let (signature, err) = rpc
+            .send_unchecked(&TxBuilder::new(&payer).instruction(ix))
+            .await?;
+        report.tx(&rpc, &name, signature, None).await?;
+        let expected = case.expected();
+        let matches = matches!(
+            &err,
+            Some(TransactionError::InstructionError(0, InstructionError::Custom(code)))
+                if *code == expected as u32
+        );
+        if !matches {
+            mismatches.push(format!(
+                "{}: expected {:?} ({}), got {:?}",
+                case.name(),
+                expected,
+                expected as u32,
+                err
+            ));
+        }
+        results.push(json!({
+            "case": case.name(),
+            "signature": signature.to_string(),
+            "expected": format!("{:?}", expected),
+            "error": err.map(|e| e.to_string()),
+            "ok": matches,
+        }));
+    }
+    report.field("cases", results);
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("unexpected outcome: {}", mismatches.join("; ")))
+    }
+}
@@ -0,0 +1,113 @@
+//! `nonce create|show|advance`: manages the durable nonce accounts that
+//! `--nonce-account` and `sign` sign against; see `scripts::nonce`.
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use scripts::config::Config;
+use scripts::nonce::{create_nonce_account, fetch_nonce_data};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+
+use crate::report::Report;
+
+#[derive(Subcommand)]
+pub enum NonceCommand {
+    /// Create a rent-exempt nonce account at a fresh address
+    Create(CreateArgs),
+    /// Print a nonce account's authority, stored blockhash and fee
+    Show(NonceArgs),
+    /// Advance a nonce account, voiding the transactions signed against its blockhash
+    Advance(NonceArgs),
+}
+
+#[derive(Args)]
+pub struct CreateArgs {
+    /// Authority allowed to advance the nonce; defaults to the payer
+    #[arg(long)]
+    authority: Option<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct NonceArgs {
+    /// Nonce account; defaults to `--nonce-account`
+    address: Option<Pubkey>,
+}
+
+impl NonceArgs {
+    fn address(&self, config: &Config) -> Result<Pubkey> {
+        self.address
+            .or(config.nonce_account)
+            .ok_or_else(|| anyhow!("give a nonce account, or set --nonce-account"))
+    }
+}
+
+pub async fn run(config: &Config, command: NonceCommand, report: &mut Report) -> Result<()> {
+    match command {
+        NonceCommand::Create(args) => create(config, args, report).await,
+        NonceCommand::Show(args) => show(config, args, report).await,
+        NonceCommand::Advance(args) => advance(config, args, report).await,
+    }
+}
+
+async fn create(config: &Config, args: CreateArgs, report: &mut Report) -> Result<()> {
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+    let authority = args.authority.unwrap_or_else(|| payer.pubkey());
+    let nonce = Keypair::new();
+    report.field("nonce_account", nonce.pubkey().to_string());
+    report.field("authority", authority.to_string());
+    if report.dry_run() {
+        report.note("Dry run: not creating the nonce account");
+        return Ok(());
+    }
+    create_nonce_account(&rpc, &payer, &nonce, &authority).await?;
+    let data = fetch_nonce_data(&rpc, &nonce.pubkey()).await?;
+    report.field("blockhash", data.blockhash().to_string());
+    report.note(format!(
+        "Pass --nonce-account {} (or set NONCE_ACCOUNT) to sign against it",
+        nonce.pubkey()
+    ));
+    Ok(())
+}
+
+async fn show(config: &Config, args: NonceArgs, report: &mut Report) -> Result<()> {
+    let address = args.address(config)?;
+    let rpc = config.rpc();
+    let data = fetch_nonce_data(&rpc, &address).await?;
+    report.field("nonce_account", address.to_string());
+    report.field("authority", data.authority.to_string());
+    report.field("blockhash", data.blockhash().to_string());
+    report.field(
+        "lamports_per_signature",
+        data.fee_calculator.lamports_per_signature,
+    );
+    Ok(())
+}
+
+async fn advance(config: &Config, args: NonceArgs, report: &mut Report) -> Result<()> {
+    let address = args.address(config)?;
+    let payer = config.payer()?;
+    // The advance is the transaction itself, not a prefix to another one
+    let rpc = config.rpc().with_durable_nonce(None);
+    let before = fetch_nonce_data(&rpc, &address).await?;
+    if before.authority != payer.pubkey() {
+        return Err(anyhow!(
+            "{} is the authority of {address}, not the payer {}",
+            before.authority,
+            payer.pubkey()
+        ));
+    }
+    report.field("nonce_account", address.to_string());
+    report.field("previous_blockhash", before.blockhash().to_string());
+    let ix = system_instruction::advance_nonce_account(&address, &payer.pubkey());
+    if report
+        .send(&rpc, &payer, "advance_nonce_account", &[ix])
+        .await?
+        .is_some()
+    {
+        let after = fetch_nonce_data(&rpc, &address).await?;
+        report.field("blockhash", after.blockhash().to_string());
+    }
+    Ok(())
+}
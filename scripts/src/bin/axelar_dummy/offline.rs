@@ -0,0 +1,169 @@
+//! `sign` and `submit`: sign an operator transaction on an air-gapped machine
+//! and broadcast it later from an online one; see `scripts::offline`.
+//!
+//! `sign` builds the transaction against the durable nonce of
+//! `--nonce-account`, so it doesn't expire, and needs the cluster only when
+//! `--nonce-hash` is left out.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use scripts::config::Config;
+use scripts::nonce::fetch_nonce_hash;
+use scripts::offline::{read_signed_tx, tx_nonce_account, write_signed_tx};
+use scripts::signer::read_keypair;
+use scripts::tx::TxBuilder;
+use scripts::MessageId;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct SignArgs {
+    /// File to write the signed transaction to
+    #[arg(long)]
+    output: PathBuf,
+    /// Blockhash stored in the nonce account, as `solana nonce <account>` prints it; fetched
+    /// from the cluster when left out
+    #[arg(long)]
+    nonce_hash: Option<Hash>,
+    /// Keypair file of the nonce authority; defaults to the payer
+    #[arg(long)]
+    nonce_authority: Option<PathBuf>,
+    #[command(subcommand)]
+    action: SignAction,
+}
+
+#[derive(Subcommand)]
+enum SignAction {
+    /// Hand the gateway or gas service over to a new operator
+    TransferOperatorship {
+        #[arg(long, value_enum)]
+        program: Program,
+        #[arg(long)]
+        new_operator: Pubkey,
+    },
+    /// Refund native gas paid for a message to the payer
+    Refund {
+        /// Message to refund, `<signature>-<ix>.<event>`
+        #[arg(long)]
+        message_id: MessageId,
+        /// Lamports to refund
+        #[arg(long)]
+        amount: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Program {
+    Gateway,
+    Gas,
+}
+
+#[derive(Args)]
+pub struct SubmitArgs {
+    /// File `sign` wrote
+    input: PathBuf,
+}
+
+pub async fn sign(config: &Config, args: SignArgs, report: &mut Report) -> Result<()> {
+    let nonce_account = config
+        .nonce_account
+        .ok_or_else(|| anyhow!("give the nonce account to sign against with --nonce-account"))?;
+    let payer = config.payer()?;
+    let authority = args.nonce_authority.map(read_keypair).transpose()?;
+    let (name, ix) = action_ix(config, &payer.pubkey(), args.action);
+    let recent_blockhash = match args.nonce_hash {
+        Some(hash) => hash,
+        None => {
+            let rpc = config.rpc();
+            fetch_nonce_hash(&rpc, &nonce_account).await?
+        }
+    };
+
+    let builder = TxBuilder::new(&payer)
+        .instruction(ix)
+        .compute_budget(config.compute_budget())
+        .durable_nonce(nonce_account, authority.as_ref().unwrap_or(&payer));
+    let tx = builder.build_offline(recent_blockhash)?;
+    write_signed_tx(&args.output, &tx)?;
+
+    report.field("transaction", name);
+    report.field("signature", tx.signatures[0].to_string());
+    report.field("nonce_account", nonce_account.to_string());
+    report.field("nonce_hash", recent_blockhash.to_string());
+    report.field("output", args.output.display().to_string());
+    report.note(format!(
+        "Signed {name} against nonce account {}; submit {} before the nonce is advanced",
+        nonce_account,
+        args.output.display()
+    ));
+    Ok(())
+}
+
+pub async fn submit(config: &Config, args: SubmitArgs, report: &mut Report) -> Result<()> {
+    let rpc = config.rpc();
+    let tx = read_signed_tx(&args.input)?;
+    if let Some(nonce_account) = tx_nonce_account(&tx) {
+        report.field("nonce_account", nonce_account.to_string());
+    }
+    if report.dry_run() {
+        let result = rpc.retry(|| rpc.simulate_transaction(&tx)).await?.value;
+        report.field("signature", tx.signatures[0].to_string());
+        report.field("err", result.err.as_ref().map(ToString::to_string));
+        let logs = result.logs.unwrap_or_default();
+        if report.json() {
+            report.field("logs", logs);
+        } else {
+            report.note("Logs:");
+            for log in &logs {
+                report.note(format!("  {log}"));
+            }
+        }
+        return Ok(());
+    }
+    // Resending the same signed bytes can't land twice, so retrying is safe
+    let signature = rpc.retry(|| rpc.send_and_confirm_transaction(&tx)).await?;
+    report.sent(&rpc, "submit", signature).await?;
+    Ok(())
+}
+
+fn action_ix(config: &Config, payer: &Pubkey, action: SignAction) -> (&'static str, Instruction) {
+    match action {
+        SignAction::TransferOperatorship {
+            program: Program::Gateway,
+            new_operator,
+        } => (
+            "gateway transfer_operatorship",
+            scripts::gateway::build_transfer_operatorship_ix(
+                &config.gateway_program_id,
+                payer,
+                new_operator,
+            ),
+        ),
+        SignAction::TransferOperatorship {
+            program: Program::Gas,
+            new_operator,
+        } => (
+            "gas transfer_operatorship",
+            scripts::gas::build_transfer_operatorship_ix(
+                &config.gas_program_id,
+                payer,
+                new_operator,
+            ),
+        ),
+        SignAction::Refund { message_id, amount } => (
+            "refund_native_fees",
+            scripts::gas::build_refund_ix(
+                &config.gas_program_id,
+                payer,
+                message_id.to_string(),
+                amount,
+            ),
+        ),
+    }
+}
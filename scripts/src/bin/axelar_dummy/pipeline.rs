@@ -0,0 +1,91 @@
+//! `pipeline`: builds the programs, checks their ids agree, deploys them and
+//! runs `trigger-all` against the deployment, stopping at the first step that
+//! fails. The one command to go from a checkout to a smoke-tested cluster.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use scripts::config::Config;
+use scripts::ids::workspace_root;
+use scripts::read_keypair;
+use solana_sdk::signature::Signer;
+
+use crate::deploy::DeployArgs;
+use crate::report::Report;
+use crate::{check_ids, deploy, trigger_all};
+
+#[derive(Args)]
+pub struct PipelineArgs {
+    /// Deploy what `target/deploy` already holds instead of running `anchor build`
+    #[arg(long)]
+    skip_build: bool,
+    /// Stop after deploying, without running `trigger-all`
+    #[arg(long)]
+    skip_smoke: bool,
+    #[command(flatten)]
+    deploy: DeployArgs,
+}
+
+pub async fn run(
+    config: &Config,
+    mut args: PipelineArgs,
+    config_path: Option<&Path>,
+    report: &mut Report,
+) -> Result<()> {
+    if report.dry_run() {
+        return Err(anyhow!("deployments can't be simulated; drop --dry-run"));
+    }
+    let root = workspace_root(&std::env::current_dir()?)
+        .ok_or_else(|| anyhow!("no Anchor.toml here or above"))?;
+    let mut steps = Vec::new();
+
+    if !args.skip_build {
+        report.note("== build");
+        anchor_build(&root)?;
+        steps.push("build");
+    }
+
+    // The rest targets the programs just built, which the config only names
+    // once `deploy` records them
+    let mut config = config.clone();
+    args.deploy.dir = root.join(&args.deploy.dir);
+    for (name, id) in [
+        ("program_tester", &mut config.gateway_program_id),
+        ("gas_service", &mut config.gas_program_id),
+    ] {
+        let keypair = args.deploy.dir.join(format!("{name}-keypair.json"));
+        if keypair.is_file() {
+            *id = read_keypair(&keypair)?.pubkey();
+        }
+    }
+
+    report.note("== check-ids");
+    check_ids::check(&config, &root, true, report).await?;
+    steps.push("check-ids");
+
+    report.note("== deploy");
+    deploy::run(&config, args.deploy, config_path, report).await?;
+    steps.push("deploy");
+
+    if !args.skip_smoke {
+        report.note("== trigger-all");
+        trigger_all::run(&config, report).await?;
+        steps.push("trigger-all");
+    }
+    report.field("steps", steps);
+    Ok(())
+}
+
+fn anchor_build(root: &Path) -> Result<()> {
+    let status = Command::new("anchor")
+        .current_dir(root)
+        .arg("build")
+        .status()
+        .context("failed to run `anchor build`; is the Anchor CLI installed?")?;
+    if !status.success() {
+        return Err(anyhow!("`anchor build` failed with {status}"));
+    }
+    Ok(())
+}
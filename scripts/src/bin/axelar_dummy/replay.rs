@@ -0,0 +1,232 @@
+//! `replay`: sends the instructions that re-emit a list of events read from a
+//! JSON or YAML file, e.g. production event values to reproduce on localnet.
+//!
+//! Each entry has a `type` (the event name) and `fields`, encoded as `--json`
+//! prints them, so the `name`/`data` objects of its `events` can be pasted in
+//! as they are. Fields the sender determines (the calling program, gas payer
+//! or sender) come from this run instead and may be left out.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use program_tester::{CrossChainId, Message};
+use scripts::config::Config;
+use scripts::events::FromJson;
+use scripts::gas::{build_add_native_gas_ix, build_pay_native_ix, build_refund_ix};
+use scripts::gateway::{
+    build_burn_compute_ix, build_call_contract_ix, build_call_contract_with_token_ix,
+    build_execute_message_ix, build_interchain_token_deployment_started_ix,
+    build_interchain_transfer_ix, build_link_token_started_ix, build_signers_rotated_ix,
+    build_token_metadata_registered_ix,
+};
+use scripts::rpc::ResilientRpc;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+
+use crate::gateway::{approve_message, ensure_gateway_root, ensure_payload_log};
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    /// Events file; `.yaml`/`.yml` is read as YAML, anything else as JSON
+    #[arg(long, env = "EVENTS_FILE")]
+    input: PathBuf,
+}
+
+#[derive(Deserialize)]
+pub struct InputEvent {
+    #[serde(rename = "type", alias = "name")]
+    pub kind: String,
+    #[serde(default, alias = "data")]
+    pub fields: Map<String, Value>,
+}
+
+pub async fn run(config: &Config, args: ReplayArgs, report: &mut Report) -> Result<()> {
+    let events = load(&args.input)?;
+    let gateway = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &gateway, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway, &payer, report).await?;
+
+    for (index, event) in events.iter().enumerate() {
+        let name = format!("{}#{}", event.kind, index);
+        emit(&rpc, config, &payer, event, &name, report)
+            .await
+            .with_context(|| format!("event {} ({})", index, event.kind))?;
+    }
+    report.field("replayed", events.len());
+    Ok(())
+}
+
+/// Sends the instruction that emits `event` with its fields, or for an
+/// approval the whole approval flow.
+pub async fn emit(
+    rpc: &ResilientRpc,
+    config: &Config,
+    payer: &Keypair,
+    event: &InputEvent,
+    name: &str,
+    report: &mut Report,
+) -> Result<()> {
+    if event.kind == "MessageApprovedEvent" {
+        let message = message(&event.fields)?;
+        approve_message(rpc, &config.gateway_program_id, payer, message, report).await?;
+        return Ok(());
+    }
+    let ix = instruction(config, &payer.pubkey(), event)?;
+    report.send(rpc, payer, name, &[ix]).await?;
+    Ok(())
+}
+
+/// The single instruction that emits `event`, paid for by `payer`. Approvals
+/// take several transactions, see `emit`.
+pub fn instruction(config: &Config, payer: &Pubkey, event: &InputEvent) -> Result<Instruction> {
+    let gateway = config.gateway_program_id;
+    let gas_service = config.gas_program_id;
+    let fields = &event.fields;
+    Ok(match event.kind.as_str() {
+        "CallContractEvent" => build_call_contract_ix(
+            &gateway,
+            &system_program::id(),
+            field(fields, "destination_chain")?,
+            field(fields, "destination_contract_address")?,
+            field(fields, "payload_hash")?,
+            field(fields, "payload")?,
+        ),
+        "CallContractWithTokenEvent" => build_call_contract_with_token_ix(
+            &gateway,
+            &system_program::id(),
+            field(fields, "destination_chain")?,
+            field(fields, "destination_contract_address")?,
+            field(fields, "payload_hash")?,
+            field(fields, "payload")?,
+            field(fields, "symbol")?,
+            field(fields, "amount")?,
+        ),
+        "MessageApprovedEvent" => return Err(anyhow!("approvals take several transactions")),
+        "MessageExecutedEvent" => build_execute_message_ix(
+            &gateway,
+            payer,
+            field(fields, "command_id")?,
+            field(fields, "source_chain")?,
+            field(fields, "cc_id")?,
+            field(fields, "source_address")?,
+            field(fields, "destination_chain")?,
+            field(fields, "destination_address")?,
+            field(fields, "payload_hash")?,
+        ),
+        "VerifierSetRotatedEvent" => build_signers_rotated_ix(
+            &gateway,
+            payer,
+            field(fields, "epoch")?,
+            field(fields, "verifier_set_hash")?,
+        ),
+        "InterchainTransfer" => build_interchain_transfer_ix(
+            &gateway,
+            payer,
+            field(fields, "token_id")?,
+            field(fields, "source_address")?,
+            field(fields, "source_token_account")?,
+            field(fields, "destination_chain")?,
+            field(fields, "destination_address")?,
+            field(fields, "amount")?,
+            field(fields, "data_hash")?,
+        ),
+        "LinkTokenStarted" => build_link_token_started_ix(
+            &gateway,
+            payer,
+            field(fields, "token_id")?,
+            field(fields, "destination_chain")?,
+            field(fields, "source_token_address")?,
+            field(fields, "destination_token_address")?,
+            field(fields, "token_manager_type")?,
+            field(fields, "params")?,
+        ),
+        "InterchainTokenDeploymentStarted" => build_interchain_token_deployment_started_ix(
+            &gateway,
+            payer,
+            field(fields, "token_id")?,
+            field(fields, "token_name")?,
+            field(fields, "token_symbol")?,
+            field(fields, "token_decimals")?,
+            field(fields, "minter")?,
+            field(fields, "destination_chain")?,
+        ),
+        "TokenMetadataRegistered" => build_token_metadata_registered_ix(
+            &gateway,
+            payer,
+            field(fields, "token_address")?,
+            field(fields, "decimals")?,
+        ),
+        // The result hash follows from the iterations
+        "ComputeBurnedEvent" => {
+            build_burn_compute_ix(&gateway, payer, field(fields, "iterations")?)
+        }
+        "GasPaidEvent" => build_pay_native_ix(
+            &gas_service,
+            payer,
+            field(fields, "destination_chain")?,
+            field(fields, "destination_address")?,
+            field(fields, "payload_hash")?,
+            field(fields, "amount")?,
+            field(fields, "refund_address")?,
+        ),
+        "GasAddedEvent" => build_add_native_gas_ix(
+            &gas_service,
+            payer,
+            field(fields, "message_id")?,
+            field(fields, "amount")?,
+            field(fields, "refund_address")?,
+        ),
+        "GasRefundedEvent" => build_refund_ix(
+            &gas_service,
+            &field(fields, "receiver")?,
+            field(fields, "message_id")?,
+            field(fields, "amount")?,
+        ),
+        // Needs the operator's signature, and replaying it would hand the config over
+        "OperatorshipTransferredEvent" => return Err(anyhow!("cannot be replayed")),
+        other => return Err(anyhow!("unknown event type {:?}", other)),
+    })
+}
+
+fn load(path: &Path) -> Result<Vec<InputEvent>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let yaml = matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml" | "yml")
+    );
+    if yaml {
+        serde_yaml::from_str(&text).with_context(|| format!("invalid YAML in {}", path.display()))
+    } else {
+        serde_json::from_str(&text).with_context(|| format!("invalid JSON in {}", path.display()))
+    }
+}
+
+fn field<T: FromJson>(fields: &Map<String, Value>, key: &str) -> Result<T> {
+    let value = fields.get(key).unwrap_or(&Value::Null);
+    T::from_json(value).with_context(|| format!("field {key}"))
+}
+
+/// The message a `MessageApprovedEvent` approved; its command id follows from
+/// the source chain and cc id.
+fn message(fields: &Map<String, Value>) -> Result<Message> {
+    Ok(Message {
+        cc_id: CrossChainId {
+            chain: field(fields, "source_chain")?,
+            id: field(fields, "cc_id")?,
+        },
+        source_address: field(fields, "source_address")?,
+        destination_chain: field(fields, "destination_chain")?,
+        destination_address: field(fields, "destination_address")?,
+        payload_hash: field(fields, "payload_hash")?,
+    })
+}
@@ -0,0 +1,351 @@
+//! Command output: prose for people, or with `--json` one JSON object on
+//! stdout holding the transactions sent (signature, slot and decoded events),
+//! the PDAs involved and the command's other results. Progress notes go to
+//! stderr in JSON mode so stdout stays parseable.
+//!
+//! Sent transactions come with Solana Explorer and Solscan links for the
+//! configured cluster. With `--wait-finalized` each send also waits for the
+//! transaction to be finalized and reports the finalized slot.
+//!
+//! With `--dry-run` nothing is sent: every transaction is simulated and its
+//! logs, compute usage and decoded events are reported in its place.
+//!
+//! With `--verify` the events of each confirmed transaction are checked
+//! against the instructions it was sent with (see `scripts::verify`), and the
+//! command fails on the first that doesn't match.
+//!
+//! With auto-funding on (see `with_auto_fund`), each payer's balance is
+//! checked before its first transaction and topped up if it runs low.
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use scripts::batch::BatchSender;
+use scripts::cluster::Explorer;
+use scripts::funding::{top_up, Funder, DEFAULT_FUND_AMOUNT};
+use scripts::receipt::{fetch_receipt, Receipt};
+use scripts::rpc::ResilientRpc;
+use scripts::send_ix;
+use scripts::simulate::simulate_ix;
+use scripts::verify::verify;
+use serde_json::{json, Map, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+pub struct Report {
+    json: bool,
+    dry_run: bool,
+    explorer: Explorer,
+    /// How long to wait for each sent transaction to be finalized, if at all.
+    finalize_timeout: Option<Duration>,
+    /// Whether to check sent transactions' events against their instructions.
+    verify: bool,
+    auto_fund: Option<AutoFund>,
+    command: &'static str,
+    transactions: Vec<Value>,
+    pdas: Map<String, Value>,
+    fields: Map<String, Value>,
+}
+
+struct AutoFund {
+    funder: Funder,
+    min_balance: u64,
+    /// Payers whose balance has been checked this run.
+    checked: HashSet<Pubkey>,
+}
+
+impl Report {
+    pub fn new(
+        command: &'static str,
+        json: bool,
+        dry_run: bool,
+        explorer: Explorer,
+        finalize_timeout: Option<Duration>,
+        verify: bool,
+    ) -> Self {
+        Self {
+            json,
+            dry_run,
+            explorer,
+            finalize_timeout,
+            verify,
+            auto_fund: None,
+            command,
+            transactions: Vec::new(),
+            pdas: Map::new(),
+            fields: Map::new(),
+        }
+    }
+
+    /// Tops payers holding less than `min_balance` lamports up from `funder`
+    /// before their first transaction.
+    pub fn with_auto_fund(mut self, funder: Funder, min_balance: u64) -> Self {
+        self.auto_fund = Some(AutoFund {
+            funder,
+            min_balance,
+            checked: HashSet::new(),
+        });
+        self
+    }
+
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn note(&self, message: impl Display) {
+        if self.json {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+    }
+
+    pub fn pda(&mut self, name: &str, address: Pubkey) {
+        if self.json {
+            self.pdas
+                .insert(name.to_string(), Value::String(address.to_string()));
+        } else {
+            println!("{} PDA: {}", label(name), address);
+        }
+    }
+
+    pub fn field(&mut self, key: &str, value: impl Into<Value>) {
+        let value = value.into();
+        if self.json {
+            self.fields.insert(key.to_string(), value);
+        } else {
+            match &value {
+                Value::String(s) => println!("{}: {}", label(key), s),
+                other => println!("{}: {}", label(key), other),
+            }
+        }
+    }
+
+    /// Sends `ixs` in one transaction, records it and returns its receipt. In
+    /// a dry run they are simulated instead and `None` is returned, so callers
+    /// skip whatever depends on the transaction having landed.
+    pub async fn send(
+        &mut self,
+        rpc: &ResilientRpc,
+        payer: &Keypair,
+        name: &str,
+        ixs: &[Instruction],
+    ) -> Result<Option<Receipt>> {
+        if self.dry_run {
+            self.simulate(rpc, payer, name, ixs).await?;
+            return Ok(None);
+        }
+        self.ensure_funded(rpc, &payer.pubkey()).await?;
+        let signature = send_ix(rpc, payer, ixs).await?;
+        let receipt = self.sent(rpc, name, signature).await?;
+        if self.verify {
+            verify(ixs, &receipt).map_err(|e| anyhow!("{name}: {e}"))?;
+        }
+        Ok(Some(receipt))
+    }
+
+    /// Records a transaction sent without `send`, e.g. one signed offline,
+    /// once it is finalized if `--wait-finalized` was given.
+    pub async fn sent(
+        &mut self,
+        rpc: &ResilientRpc,
+        name: &str,
+        signature: Signature,
+    ) -> Result<Receipt> {
+        let finalized_slot = match self.finalize_timeout {
+            Some(timeout) => Some(rpc.wait_finalized(&signature, timeout).await?),
+            None => None,
+        };
+        self.tx(rpc, name, signature, finalized_slot).await
+    }
+
+    /// Sends each of `txs` as its own transaction, up to `concurrency` at a
+    /// time, and records those that landed in order. Fails with the first
+    /// error once the rest have been recorded.
+    pub async fn send_batch(
+        &mut self,
+        rpc: &ResilientRpc,
+        payer: &Keypair,
+        txs: Vec<(String, Vec<Instruction>)>,
+        concurrency: usize,
+    ) -> Result<Vec<Option<Receipt>>> {
+        if self.dry_run {
+            for (name, ixs) in &txs {
+                self.simulate(rpc, payer, name, ixs).await?;
+            }
+            return Ok(vec![None; txs.len()]);
+        }
+        self.ensure_funded(rpc, &payer.pubkey()).await?;
+        let (names, ixs): (Vec<String>, Vec<Vec<Instruction>>) = txs.into_iter().unzip();
+        let results = BatchSender::new(rpc, payer)
+            .concurrency(concurrency)
+            .send_all(ixs.clone())
+            .await;
+        let mut receipts = Vec::with_capacity(results.len());
+        let mut first_error = None;
+        for ((name, ixs), result) in names.iter().zip(&ixs).zip(results) {
+            let signature = match result {
+                Ok(signature) => signature,
+                Err(e) => {
+                    first_error.get_or_insert_with(|| anyhow!("{name}: {e}"));
+                    receipts.push(None);
+                    continue;
+                }
+            };
+            let receipt = self.sent(rpc, name, signature).await?;
+            if self.verify {
+                if let Err(e) = verify(ixs, &receipt) {
+                    first_error.get_or_insert_with(|| anyhow!("{name}: {e}"));
+                }
+            }
+            receipts.push(Some(receipt));
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(receipts),
+        }
+    }
+
+    /// Tops `payer` up the first time it pays for a transaction, if
+    /// auto-funding is on and it runs low.
+    async fn ensure_funded(&mut self, rpc: &ResilientRpc, payer: &Pubkey) -> Result<()> {
+        let Some(auto_fund) = &mut self.auto_fund else {
+            return Ok(());
+        };
+        if !auto_fund.checked.insert(*payer) {
+            return Ok(());
+        }
+        let funded = top_up(
+            rpc,
+            &auto_fund.funder,
+            payer,
+            auto_fund.min_balance,
+            DEFAULT_FUND_AMOUNT.max(auto_fund.min_balance),
+        )
+        .await?;
+        if let Some(balance) = funded {
+            let message = format!(
+                "Payer {} held {} lamports; funded it from the {}",
+                payer, balance, auto_fund.funder
+            );
+            self.note(message);
+        }
+        Ok(())
+    }
+
+    async fn simulate(
+        &mut self,
+        rpc: &RpcClient,
+        payer: &Keypair,
+        name: &str,
+        ixs: &[Instruction],
+    ) -> Result<()> {
+        let simulation = simulate_ix(rpc, &payer.pubkey(), ixs).await?;
+        if !self.json {
+            println!("Simulated {} tx:", name);
+            print!("{}", simulation);
+            return Ok(());
+        }
+        let events: Vec<Value> = simulation
+            .events
+            .iter()
+            .map(|event| json!({ "name": event.name(), "data": event.to_json() }))
+            .collect();
+        self.transactions.push(json!({
+            "name": name,
+            "simulated": true,
+            "err": simulation.err,
+            "units_consumed": simulation.units_consumed,
+            "logs": simulation.logs,
+            "events": events,
+        }));
+        Ok(())
+    }
+
+    /// Records a sent transaction, fetched back for its slot and events.
+    pub async fn tx(
+        &mut self,
+        rpc: &RpcClient,
+        name: &str,
+        signature: Signature,
+        finalized_slot: Option<u64>,
+    ) -> Result<Receipt> {
+        let explorer_url = self.explorer.tx_url(&signature);
+        let solscan_url = self.explorer.solscan_tx_url(&signature);
+        let receipt = fetch_receipt(rpc, &signature).await?;
+        if !self.json {
+            println!("Sent {} tx: {}", name, signature);
+            println!("  Explorer: {}", explorer_url);
+            println!("  Solscan:  {}", solscan_url);
+            if let Some(slot) = finalized_slot {
+                println!("  Finalized in slot {}", slot);
+            }
+            for emitted in &receipt.events {
+                println!("  {} ({}):", emitted.event.name(), emitted.message_id);
+                println!("{:#?}", emitted.event);
+            }
+            for message_id in &receipt.undecoded {
+                println!("  Undecodable event ({})", message_id);
+            }
+            return Ok(receipt);
+        }
+        let events: Vec<Value> = receipt
+            .events
+            .iter()
+            .map(|e| {
+                json!({
+                    "name": e.event.name(),
+                    "message_id": e.message_id.to_string(),
+                    "data": e.event.to_json(),
+                })
+            })
+            .collect();
+        self.transactions.push(json!({
+            "name": name,
+            "signature": signature.to_string(),
+            "slot": receipt.slot,
+            "finalized_slot": finalized_slot,
+            "fee": receipt.fee,
+            "explorer_url": explorer_url,
+            "solscan_url": solscan_url,
+            "err": receipt.err,
+            "events": events,
+            "undecoded": receipt.undecoded.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+        }));
+        Ok(receipt)
+    }
+
+    /// Prints the JSON object; prose has been printed as it came.
+    pub fn finish(self) -> Result<()> {
+        if self.json {
+            let mut output = Map::new();
+            output.insert("command".to_string(), Value::from(self.command));
+            if self.dry_run {
+                output.insert("dry_run".to_string(), Value::Bool(true));
+            }
+            output.insert("transactions".to_string(), Value::from(self.transactions));
+            output.insert("pdas".to_string(), Value::Object(self.pdas));
+            output.extend(self.fields);
+            println!("{}", serde_json::to_string(&Value::Object(output))?);
+        }
+        Ok(())
+    }
+}
+
+/// `payload_hash` -> `Payload hash`
+fn label(key: &str) -> String {
+    let spaced = key.replace('_', " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
@@ -0,0 +1,559 @@
+//! `run-scenario`: runs the steps of a scenario file in order, e.g. a call
+//! that is then approved, with assertions on the events and accounts it left.
+//!
+//! A scenario has an optional `name`, `vars` and a list of `steps`, each one
+//! of `init`, `call`, `approve`, `sleep` (seconds), `assert-event` and
+//! `assert-account`; see `scenario.example.yaml`. Strings may hold `{{name}}`
+//! templates, filled in just before their step runs from:
+//! - `payer`, `gateway`, `gas_service` and `run_id` (unique per run),
+//! - the file's `vars`, then `--var name=value`,
+//! - `last`, the outputs of the previous step, and the outputs of every step
+//!   with a `save` name, e.g. `{{call.message_id}}`.
+//!
+//! A string that is a single template takes the value as it is, so numbers
+//! stay numbers.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use program_tester::{CrossChainId, IncomingMessage, Message, MessageStatus};
+use scripts::accounts::decode_account;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::gateway::build_call_contract_ix;
+use scripts::pdas::{gateway_root_pda, incoming_message_pda, payload_log_pda};
+use scripts::receipt::fetch_receipt;
+use scripts::rpc::ResilientRpc;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use solana_program::keccak;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_program;
+
+use crate::gateway::{approve_message, call_message_id, ensure_gateway_root, ZERO_ADDRESS};
+use crate::init::{self, InitArgs};
+use crate::report::Report;
+use crate::PayloadArgs;
+
+#[derive(Args)]
+pub struct ScenarioArgs {
+    /// Scenario file; `.yaml`/`.yml` is read as YAML, anything else as JSON
+    scenario: PathBuf,
+    /// Set a template variable, overriding the file's `vars`
+    #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_var)]
+    vars: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Scenario {
+    name: Option<String>,
+    #[serde(default)]
+    vars: Map<String, Value>,
+    /// Kept as JSON until each step runs, so templates see earlier outputs
+    steps: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Step {
+    Init,
+    Call(CallStep),
+    Approve(ApproveStep),
+    Sleep(f64),
+    AssertEvent(AssertEventStep),
+    AssertAccount(AssertAccountStep),
+}
+
+/// A `call_contract`, with the payload given as for `call-contract`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CallStep {
+    #[serde(default = "default_destination_chain")]
+    destination_chain: String,
+    #[serde(default = "default_destination_address")]
+    destination_address: String,
+    payload: Option<String>,
+    payload_hex: Option<String>,
+    payload_hash: Option<String>,
+    save: Option<String>,
+}
+
+/// An approval through a fresh verification session, as for `approve`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApproveStep {
+    #[serde(default = "default_source_chain")]
+    source_chain: String,
+    /// Unique to the run and step by default
+    message_id: Option<String>,
+    #[serde(default = "default_source_address")]
+    source_address: String,
+    #[serde(default = "default_approve_destination_chain")]
+    destination_chain: String,
+    /// Defaults to the payer
+    destination_address: Option<String>,
+    /// Defaults to the keccak256 of `payload`, as for `approve`
+    payload_hash: Option<String>,
+    save: Option<String>,
+}
+
+/// Checks that a transaction emitted `event` with (at least) `fields`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AssertEventStep {
+    /// Signature of the transaction; the last one a step sent by default
+    tx: Option<String>,
+    event: String,
+    #[serde(default)]
+    fields: Map<String, Value>,
+    save: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AssertAccountStep {
+    address: String,
+    #[serde(default = "default_exists")]
+    exists: bool,
+    owner: Option<String>,
+    min_lamports: Option<u64>,
+    /// The status of an incoming message account
+    status: Option<Status>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Approved,
+    Executed,
+}
+
+fn default_destination_chain() -> String {
+    "ethereum".to_string()
+}
+
+fn default_destination_address() -> String {
+    ZERO_ADDRESS.to_string()
+}
+
+fn default_source_chain() -> String {
+    "ethereum".to_string()
+}
+
+fn default_source_address() -> String {
+    "0xdead".to_string()
+}
+
+fn default_approve_destination_chain() -> String {
+    "solana".to_string()
+}
+
+fn default_exists() -> bool {
+    true
+}
+
+/// What the steps share: the connection and the last transaction sent.
+struct Runner<'a> {
+    config: &'a Config,
+    rpc: ResilientRpc,
+    payer: Keypair,
+    run_id: String,
+    last_tx: Option<Signature>,
+}
+
+pub async fn run(config: &Config, args: ScenarioArgs, report: &mut Report) -> Result<()> {
+    let scenario = load(&args.scenario)?;
+    let payer = config.payer()?;
+    let run_id = format!(
+        "0x{:x}",
+        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
+    );
+
+    let mut vars = Map::new();
+    vars.insert("payer".to_string(), payer.pubkey().to_json());
+    vars.insert("gateway".to_string(), config.gateway_program_id.to_json());
+    vars.insert("gas_service".to_string(), config.gas_program_id.to_json());
+    vars.insert("run_id".to_string(), Value::String(run_id.clone()));
+    // The file's vars may use the built-ins, not each other
+    let builtins = vars.clone();
+    for (name, value) in scenario.vars {
+        let value = render(&value, &builtins).with_context(|| format!("var {name}"))?;
+        vars.insert(name, value);
+    }
+    for (name, value) in args.vars {
+        vars.insert(name, Value::String(value));
+    }
+
+    if let Some(name) = scenario.name {
+        report.field("scenario", name);
+    }
+    let mut runner = Runner {
+        config,
+        rpc: config.rpc(),
+        payer,
+        run_id,
+        last_tx: None,
+    };
+    let mut results = Vec::new();
+    for (index, raw) in scenario.steps.iter().enumerate() {
+        let label = format!("step {} ({})", index + 1, step_kind(raw));
+        report.note(format!("Running {label}"));
+        let outcome = async {
+            let step: Step = serde_json::from_value(render(raw, &vars)?)?;
+            runner.run(index, step, report).await
+        }
+        .await;
+        let (save, outputs) = match outcome {
+            Ok(done) => done,
+            Err(e) => {
+                report.field("steps", results);
+                return Err(e.context(label));
+            }
+        };
+        results.push(json!({ "step": step_kind(raw), "outputs": outputs }));
+        if let Some(save) = save {
+            vars.insert(save, outputs.clone());
+        }
+        vars.insert("last".to_string(), outputs);
+    }
+    report.field("steps", results);
+    Ok(())
+}
+
+impl Runner<'_> {
+    /// Runs `step`, returning its `save` name and outputs.
+    async fn run(
+        &mut self,
+        index: usize,
+        step: Step,
+        report: &mut Report,
+    ) -> Result<(Option<String>, Value)> {
+        Ok(match step {
+            Step::Init => {
+                init::run(self.config, InitArgs::default(), report).await?;
+                let gateway = self.config.gateway_program_id;
+                let outputs = json!({
+                    "gateway_root": gateway_root_pda(&gateway).0.to_string(),
+                    "payload_log": payload_log_pda(&gateway).0.to_string(),
+                });
+                (None, outputs)
+            }
+            Step::Call(step) => (step.save.clone(), self.call(step, report).await?),
+            Step::Approve(step) => (step.save.clone(), self.approve(index, step, report).await?),
+            Step::Sleep(secs) => {
+                if !report.dry_run() {
+                    tokio::time::sleep(Duration::from_secs_f64(secs)).await;
+                }
+                (None, json!({}))
+            }
+            Step::AssertEvent(step) => (step.save.clone(), self.assert_event(step, report).await?),
+            Step::AssertAccount(step) => (None, self.assert_account(step, report).await?),
+        })
+    }
+
+    async fn call(&mut self, step: CallStep, report: &mut Report) -> Result<Value> {
+        let program_id = self.config.gateway_program_id;
+        let payload_args = PayloadArgs {
+            payload: step.payload,
+            payload_hex: step.payload_hex,
+            payload_hash: step.payload_hash,
+        };
+        let payload = payload_args.payload()?;
+        let payload_hash = payload_args.payload_hash(&payload)?;
+
+        ensure_gateway_root(&self.rpc, &program_id, &self.payer, report).await?;
+        let ix = build_call_contract_ix(
+            &program_id,
+            &system_program::id(),
+            step.destination_chain.clone(),
+            step.destination_address.clone(),
+            payload_hash,
+            payload.clone(),
+        );
+        let receipt = report
+            .send(&self.rpc, &self.payer, "call_contract", &[ix])
+            .await?;
+        let message_id = call_message_id(receipt.as_ref())?;
+        self.last_tx = receipt.as_ref().map(|receipt| receipt.signature);
+        Ok(json!({
+            "signature": receipt.map(|receipt| receipt.signature.to_string()),
+            "message_id": message_id.to_string(),
+            "destination_chain": step.destination_chain,
+            "destination_address": step.destination_address,
+            "payload": payload.to_json(),
+            "payload_hash": payload_hash.to_json(),
+        }))
+    }
+
+    async fn approve(
+        &mut self,
+        index: usize,
+        step: ApproveStep,
+        report: &mut Report,
+    ) -> Result<Value> {
+        let program_id = self.config.gateway_program_id;
+        let payload_hash = match &step.payload_hash {
+            Some(hex) => scripts::hex_array(hex, scripts::Mode::Strict)?,
+            None => keccak::hash(b"payload").to_bytes(),
+        };
+        let message = Message {
+            cc_id: CrossChainId {
+                chain: step.source_chain,
+                id: step
+                    .message_id
+                    .unwrap_or_else(|| format!("{}-{}", self.run_id, index)),
+            },
+            source_address: step.source_address,
+            destination_chain: step.destination_chain,
+            destination_address: step
+                .destination_address
+                .unwrap_or_else(|| self.payer.pubkey().to_string()),
+            payload_hash,
+        };
+        let command_id = message.command_id();
+        let message_id = message.cc_id.id.clone();
+
+        ensure_gateway_root(&self.rpc, &program_id, &self.payer, report).await?;
+        let receipt = approve_message(&self.rpc, &program_id, &self.payer, message, report).await?;
+        self.last_tx = receipt.as_ref().map(|receipt| receipt.signature);
+        Ok(json!({
+            "signature": receipt.map(|receipt| receipt.signature.to_string()),
+            "message_id": message_id,
+            "command_id": command_id.to_json(),
+            "incoming_message": incoming_message_pda(&program_id, &command_id).0.to_string(),
+            "payload_hash": payload_hash.to_json(),
+        }))
+    }
+
+    async fn assert_event(&mut self, step: AssertEventStep, report: &mut Report) -> Result<Value> {
+        if report.dry_run() {
+            report.note("Nothing was sent in a dry run; skipping the assertion");
+            return Ok(json!({}));
+        }
+        let signature = match &step.tx {
+            Some(tx) => {
+                Signature::from_str(tx).with_context(|| format!("invalid signature {tx}"))?
+            }
+            None => self
+                .last_tx
+                .ok_or_else(|| anyhow!("no transaction sent yet; give one with `tx`"))?,
+        };
+        let receipt = fetch_receipt(&self.rpc, &signature).await?;
+        let candidates: Vec<_> = receipt
+            .events
+            .iter()
+            .filter(|emitted| emitted.event.name() == step.event)
+            .collect();
+        let mut problems = Vec::new();
+        for emitted in &candidates {
+            let actual = emitted.event.to_json();
+            let differing: Vec<String> = step
+                .fields
+                .iter()
+                .filter_map(|(key, expected)| {
+                    let value = actual.get(key).unwrap_or(&Value::Null);
+                    (!same_value(expected, value))
+                        .then(|| format!("{key}: expected {expected}, emitted {value}"))
+                })
+                .collect();
+            if differing.is_empty() {
+                return Ok(json!({
+                    "signature": signature.to_string(),
+                    "message_id": emitted.message_id.to_string(),
+                    "fields": actual,
+                }));
+            }
+            problems.push(differing.join(", "));
+        }
+        if candidates.is_empty() {
+            Err(anyhow!("{} emitted no {}", signature, step.event))
+        } else {
+            Err(anyhow!(
+                "no {} from {} matches: {}",
+                step.event,
+                signature,
+                problems.join("; ")
+            ))
+        }
+    }
+
+    async fn assert_account(
+        &mut self,
+        step: AssertAccountStep,
+        report: &mut Report,
+    ) -> Result<Value> {
+        let address = Pubkey::from_str(&step.address)
+            .with_context(|| format!("invalid address {}", step.address))?;
+        if report.dry_run() {
+            report.note("Nothing was sent in a dry run; skipping the assertion");
+            return Ok(json!({}));
+        }
+        let account = self
+            .rpc
+            .retry(|| {
+                self.rpc
+                    .get_account_with_commitment(&address, self.rpc.commitment())
+            })
+            .await?
+            .value;
+        let account = match (account, step.exists) {
+            (None, false) => return Ok(json!({ "exists": false })),
+            (None, true) => return Err(anyhow!("{address} does not exist")),
+            (Some(_), false) => return Err(anyhow!("{address} exists")),
+            (Some(account), true) => account,
+        };
+
+        let mut problems = Vec::new();
+        if let Some(owner) = &step.owner {
+            let owner =
+                Pubkey::from_str(owner).with_context(|| format!("invalid owner {owner}"))?;
+            if account.owner != owner {
+                problems.push(format!("owned by {}, not {}", account.owner, owner));
+            }
+        }
+        if let Some(min) = step.min_lamports {
+            if account.lamports < min {
+                problems.push(format!(
+                    "holds {} lamports, under {}",
+                    account.lamports, min
+                ));
+            }
+        }
+        if let Some(status) = step.status {
+            let message: IncomingMessage = decode_account(&address, &account.data)?;
+            let wanted = match status {
+                Status::Approved => MessageStatus::approved(),
+                Status::Executed => MessageStatus::executed(),
+            };
+            if message.status != wanted {
+                let actual = if message.status.is_approved() {
+                    "approved"
+                } else {
+                    "executed"
+                };
+                let expected = match status {
+                    Status::Approved => "approved",
+                    Status::Executed => "executed",
+                };
+                problems.push(format!("message is {actual}, not {expected}"));
+            }
+        }
+        if !problems.is_empty() {
+            return Err(anyhow!("{}: {}", address, problems.join(", ")));
+        }
+        Ok(json!({
+            "exists": true,
+            "owner": account.owner.to_string(),
+            "lamports": account.lamports,
+            "data_len": account.data.len(),
+        }))
+    }
+}
+
+/// Fills in the `{{name}}` templates in every string of `value`.
+fn render(value: &Value, vars: &Map<String, Value>) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => render_str(s, vars)?,
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render(item, vars))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, item)| Ok((key.clone(), render(item, vars)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn render_str(s: &str, vars: &Map<String, Value>) -> Result<Value> {
+    // A lone template keeps the variable's type
+    if let Some(name) = s
+        .trim()
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .filter(|name| !name.contains("{{"))
+    {
+        return lookup(name.trim(), vars).cloned();
+    }
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| anyhow!("unclosed template in {s:?}"))?;
+        out.push_str(&rest[..start]);
+        match lookup(rest[start + 2..start + end].trim(), vars)? {
+            Value::String(value) => out.push_str(value),
+            value => out.push_str(&value.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+/// Looks up a dotted name like `call.message_id`.
+fn lookup<'a>(name: &str, vars: &'a Map<String, Value>) -> Result<&'a Value> {
+    let mut parts = name.split('.');
+    let first = parts.next().unwrap_or_default();
+    let mut value = vars
+        .get(first)
+        .ok_or_else(|| anyhow!("unknown variable {first:?}"))?;
+    for part in parts {
+        value = value
+            .get(part)
+            .ok_or_else(|| anyhow!("{name:?} has no {part:?}"))?;
+    }
+    Ok(value)
+}
+
+/// Equal as JSON, or as text for scalars, so `"1000"` matches `1000`.
+fn same_value(expected: &Value, actual: &Value) -> bool {
+    fn text(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+    expected == actual || text(expected).is_some_and(|e| Some(e) == text(actual))
+}
+
+/// The step's name as written, for progress and errors.
+fn step_kind(step: &Value) -> String {
+    match step {
+        Value::String(kind) => kind.clone(),
+        Value::Object(fields) if fields.len() == 1 => fields.keys().next().cloned().unwrap(),
+        _ => "?".to_string(),
+    }
+}
+
+fn parse_var(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected NAME=VALUE, got {s:?}"))
+}
+
+fn load(path: &Path) -> Result<Scenario> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let yaml = matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml" | "yml")
+    );
+    if yaml {
+        serde_yaml::from_str(&text).with_context(|| format!("invalid YAML in {}", path.display()))
+    } else {
+        serde_json::from_str(&text).with_context(|| format!("invalid JSON in {}", path.display()))
+    }
+}
@@ -0,0 +1,88 @@
+//! `show-account`: fetches an account of the dummy programs, by address or by
+//! the PDA it is, and prints its decoded fields with its lamports and owner;
+//! see `scripts::accounts::decode_known_account`.
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use scripts::accounts::decode_known_account;
+use scripts::config::Config;
+use scripts::pdas::{
+    gas_config_pda, gateway_root_pda, incoming_message_pda, payload_log_pda,
+    verification_session_pda,
+};
+use scripts::{hex_array, Mode};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct ShowAccountArgs {
+    /// Account to show; or pick a PDA with one of the flags
+    address: Option<Pubkey>,
+    /// The gateway root PDA
+    #[arg(long)]
+    gateway_config: bool,
+    /// The payload log PDA
+    #[arg(long)]
+    payload_log: bool,
+    /// The gas service config PDA
+    #[arg(long)]
+    gas_config: bool,
+    /// The verification session PDA for this payload merkle root, as hex
+    #[arg(long, value_name = "ROOT")]
+    verification_session: Option<String>,
+    /// The incoming message PDA for this command id, as hex
+    #[arg(long, value_name = "COMMAND_ID")]
+    incoming_message: Option<String>,
+}
+
+impl ShowAccountArgs {
+    fn address(&self, config: &Config) -> Result<Pubkey> {
+        let gateway = config.gateway_program_id;
+        let mut picked = Vec::new();
+        picked.extend(self.address);
+        if self.gateway_config {
+            picked.push(gateway_root_pda(&gateway).0);
+        }
+        if self.payload_log {
+            picked.push(payload_log_pda(&gateway).0);
+        }
+        if self.gas_config {
+            picked.push(gas_config_pda(&config.gas_program_id).0);
+        }
+        if let Some(root) = &self.verification_session {
+            picked.push(verification_session_pda(&gateway, &hex_array(root, Mode::Strict)?).0);
+        }
+        if let Some(command_id) = &self.incoming_message {
+            picked.push(incoming_message_pda(&gateway, &hex_array(command_id, Mode::Strict)?).0);
+        }
+        match picked[..] {
+            [address] => Ok(address),
+            [] => Err(anyhow!("give an address or one of the PDA flags")),
+            _ => Err(anyhow!("give one account at a time")),
+        }
+    }
+}
+
+pub async fn run(config: &Config, args: ShowAccountArgs, report: &mut Report) -> Result<()> {
+    let rpc = config.rpc();
+    let address = args.address(config)?;
+    let account = rpc
+        .retry(|| rpc.get_account_with_commitment(&address, rpc.commitment()))
+        .await?
+        .value
+        .ok_or_else(|| anyhow!("account {address} does not exist"))?;
+
+    report.field("address", address.to_string());
+    report.field("owner", account.owner.to_string());
+    report.field("lamports", account.lamports);
+    report.field("data_len", account.data.len());
+    let decoded = decode_known_account(&address, &account.data)?;
+    report.field("type", decoded.name());
+    if report.json() {
+        report.field("fields", decoded.to_json());
+    } else {
+        report.note(format!("{:#}", decoded.to_json()));
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! `rotate-signers` and `verify-signatures`: the gateway's verifier set events
+//! and signature checks.
+//!
+//! `verify-signatures` signs with a `VerifierSetFixture`, so the same seed
+//! always yields the same set and only the first run registers it.
+
+use anyhow::Result;
+use clap::Args;
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::gateway::{
+    build_init_verification_session_ix, build_register_verifier_set_ix, build_signers_rotated_ix,
+    build_verify_signature_ix,
+};
+use scripts::pdas::{verification_session_pda, verifier_set_tracker_pda};
+use scripts::verifier::VerifierSetFixture;
+use scripts::{hex_array, Mode};
+use sha2::{Digest, Sha256};
+use solana_sdk::signature::Signer;
+
+use crate::gateway::ensure_gateway_root;
+use crate::report::Report;
+
+#[derive(Args)]
+pub struct RotateSignersArgs {
+    /// Epoch of the new verifier set
+    #[arg(long, env = "EPOCH", default_value_t = 42)]
+    epoch: u64,
+    /// Hash of the new verifier set as hex [default: 0x11 repeated]
+    #[arg(long, env = "VERIFIER_SET_HASH")]
+    verifier_set_hash: Option<String>,
+}
+
+#[derive(Args)]
+pub struct VerifySignaturesArgs {
+    /// Seed the verifier set is derived from
+    #[arg(long, env = "VERIFIER_SEED", default_value = "dummy-verifiers")]
+    seed: String,
+    /// Number of verifiers in the set
+    #[arg(long, env = "VERIFIER_COUNT", default_value_t = 3)]
+    count: u16,
+    /// verify_signature transactions sent at a time
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
+}
+
+pub async fn rotate(config: &Config, args: RotateSignersArgs, report: &mut Report) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let verifier_set_hash = match args.verifier_set_hash {
+        Some(hex) => hex_array(&hex, Mode::Strict)?,
+        None => [0x11u8; 32],
+    };
+    // The event carries the epoch as a little-endian U256
+    let mut epoch = [0u8; 32];
+    epoch[..8].copy_from_slice(&args.epoch.to_le_bytes());
+
+    let ix = build_signers_rotated_ix(&program_id, &payer.pubkey(), epoch, verifier_set_hash);
+    report.send(&rpc, &payer, "signers_rotated", &[ix]).await?;
+
+    report.field("epoch", args.epoch);
+    report.field("verifier_set_hash", verifier_set_hash.to_json());
+    Ok(())
+}
+
+pub async fn verify(
+    config: &Config,
+    args: VerifySignaturesArgs,
+    report: &mut Report,
+) -> Result<()> {
+    let program_id = config.gateway_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    ensure_gateway_root(&rpc, &program_id, &payer, report).await?;
+
+    let fixture = VerifierSetFixture::new(args.seed.as_bytes(), args.count, 0, [0u8; 32]);
+    let verifier_set_hash = fixture.hash();
+    // verify_signature only accepts sets the operator registered
+    let (verifier_set_tracker, _) = verifier_set_tracker_pda(&program_id, &verifier_set_hash);
+    if rpc.get_account(&verifier_set_tracker).await.is_err() {
+        let ix = build_register_verifier_set_ix(
+            &program_id,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            verifier_set_hash,
+        );
+        report
+            .send(&rpc, &payer, "register_verifier_set", &[ix])
+            .await?;
+    }
+    report.pda("verifier_set_tracker", verifier_set_tracker);
+
+    // Fresh root per run so the verification session starts empty
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let payload_merkle_root: [u8; 32] =
+        Sha256::digest(format!("dummy-root-{timestamp}").as_bytes()).into();
+    let ix = build_init_verification_session_ix(&program_id, &payer.pubkey(), payload_merkle_root);
+    report
+        .send(&rpc, &payer, "init_verification_session", &[ix])
+        .await?;
+    report.pda(
+        "verification_session",
+        verification_session_pda(&program_id, &payload_merkle_root).0,
+    );
+
+    // Each signature lands in its own slot bit, so the order doesn't matter
+    let txs = fixture
+        .sign_root(&payload_merkle_root)
+        .into_iter()
+        .map(|signed| {
+            let name = format!("verify_signature {}", signed.leaf.position);
+            let ix = build_verify_signature_ix(
+                &program_id,
+                payload_merkle_root,
+                verifier_set_hash,
+                signed.leaf,
+                signed.proof,
+                signed.signature,
+                signed.recovery_id,
+            );
+            (name, vec![ix])
+        })
+        .collect();
+    report
+        .send_batch(&rpc, &payer, txs, args.concurrency)
+        .await?;
+
+    report.field("verifier_set_hash", verifier_set_hash.to_json());
+    report.field("payload_merkle_root", payload_merkle_root.to_json());
+    Ok(())
+}
@@ -0,0 +1,442 @@
+//! `trigger-all`: fires every program_tester and gas_service instruction once,
+//! creating whatever PDAs are missing first. A smoke test that the whole event
+//! surface still decodes after a redeploy: fails when a transaction fails, an
+//! expected event is missing or an emitted event does not decode.
+
+use anyhow::{anyhow, Result};
+use program_tester::{CrossChainId, ItsMessage, Message};
+use scripts::accounts::{fetch_gas_config, fetch_gateway_config};
+use scripts::config::Config;
+use scripts::gas::{
+    build_add_native_gas_ix, build_cpi_call_contract_ix, build_init_config_ix, build_pay_native_ix,
+    build_refund_ix,
+};
+use scripts::gateway::{
+    build_burn_compute_ix, build_call_contract_ix, build_call_contract_with_its_payload_ix,
+    build_call_contract_with_token_ix, build_init_verification_session_ix,
+    build_interchain_token_deployment_started_ix, build_interchain_transfer_ix,
+    build_link_token_started_ix, build_register_verifier_set_ix, build_signers_rotated_ix,
+    build_token_metadata_registered_ix, build_verify_signature_ix,
+};
+use scripts::pdas::{gas_config_pda, verifier_set_tracker_pda};
+use scripts::receipt::Receipt;
+use scripts::rpc::ResilientRpc;
+use scripts::verifier::VerifierSetFixture;
+use scripts::MessageId;
+use sha2::{Digest, Sha256};
+use solana_program::keccak;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_program;
+
+use crate::gateway::{
+    approve_message, call_message_id, ensure_gateway_root, ensure_payload_log, execute_message,
+    ZERO_ADDRESS,
+};
+use crate::report::Report;
+
+pub async fn run(config: &Config, report: &mut Report) -> Result<()> {
+    let gateway = config.gateway_program_id;
+    let gas_service = config.gas_program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+    let mut smoke = Smoke {
+        rpc: &rpc,
+        payer: &payer,
+        failures: Vec::new(),
+    };
+
+    ensure_gateway_root(&rpc, &gateway, &payer, report).await?;
+    ensure_payload_log(&rpc, &gateway, &payer, report).await?;
+    let (gas_config_pda, _) = gas_config_pda(&gas_service);
+    if rpc.get_account(&gas_config_pda).await.is_err() {
+        let ix = build_init_config_ix(&gas_service, &payer.pubkey(), payer.pubkey());
+        report.send(&rpc, &payer, "init_config", &[ix]).await?;
+    }
+    report.pda("gas_config", gas_config_pda);
+
+    let payload = vec![1, 2, 3, 4, 5];
+    let payload_hash = keccak::hash(&payload).to_bytes();
+    let destination_chain = "ethereum".to_string();
+
+    // program_tester
+    let ix = build_call_contract_ix(
+        &gateway,
+        &system_program::id(),
+        destination_chain.clone(),
+        ZERO_ADDRESS.to_string(),
+        payload_hash,
+        payload.clone(),
+    );
+    let call = smoke
+        .step(report, "call_contract", &[ix], &["CallContractEvent"])
+        .await;
+
+    let ix = build_call_contract_with_token_ix(
+        &gateway,
+        &system_program::id(),
+        destination_chain.clone(),
+        ZERO_ADDRESS.to_string(),
+        payload_hash,
+        payload.clone(),
+        "USDC".to_string(),
+        1_000,
+    );
+    smoke
+        .step(
+            report,
+            "call_contract_with_token",
+            &[ix],
+            &["CallContractWithTokenEvent"],
+        )
+        .await;
+
+    let message = ItsMessage::InterchainTransfer {
+        token_id: [1u8; 32],
+        source_address: payer.pubkey().to_bytes().to_vec(),
+        destination_address: vec![0xde, 0xad, 0xbe, 0xef],
+        amount: 12345,
+        data: vec![],
+    };
+    let ix = build_call_contract_with_its_payload_ix(
+        &gateway,
+        &system_program::id(),
+        "axelar".to_string(),
+        ZERO_ADDRESS.to_string(),
+        message,
+        Some(destination_chain.clone()),
+    );
+    smoke
+        .step(
+            report,
+            "call_contract_with_its_payload",
+            &[ix],
+            &["CallContractEvent"],
+        )
+        .await;
+
+    // The id of the first call; a dry run gets one of the same shape
+    let call_id = call
+        .as_ref()
+        .and_then(|receipt| call_message_id(receipt.as_ref()).ok());
+
+    // Approve and execute the message of the first call, when it went through
+    if let Some(call_id) = call_id {
+        let message = Message {
+            cc_id: CrossChainId {
+                chain: "solana".to_string(),
+                id: call_id.to_string(),
+            },
+            source_address: payer.pubkey().to_string(),
+            destination_chain: "solana".to_string(),
+            destination_address: payer.pubkey().to_string(),
+            payload_hash,
+        };
+        let approved = approve_message(&rpc, &gateway, &payer, message.clone(), report).await;
+        if smoke
+            .check("approve_message", approved, &["MessageApprovedEvent"])
+            .is_some()
+        {
+            let executed = execute_message(&rpc, &gateway, &payer, message, report).await;
+            smoke.check("execute_message", executed, &["MessageExecutedEvent"]);
+        }
+    }
+
+    smoke.verify_signatures(report, &gateway).await;
+
+    let ix = build_interchain_transfer_ix(
+        &gateway,
+        &payer.pubkey(),
+        [1u8; 32],
+        payer.pubkey(),
+        payer.pubkey(),
+        destination_chain.clone(),
+        vec![0xde, 0xad, 0xbe, 0xef],
+        12345,
+        [0u8; 32],
+    );
+    smoke
+        .step(
+            report,
+            "interchain_transfer",
+            &[ix],
+            &["InterchainTransfer"],
+        )
+        .await;
+
+    let ix = build_link_token_started_ix(
+        &gateway,
+        &payer.pubkey(),
+        [9u8; 32],
+        destination_chain.clone(),
+        payer.pubkey(),
+        vec![0x0b, 0x16, 0x21, 0x2c],
+        1,
+        vec![0x37, 0x42],
+    );
+    smoke
+        .step(report, "link_token_started", &[ix], &["LinkTokenStarted"])
+        .await;
+
+    let ix = build_interchain_token_deployment_started_ix(
+        &gateway,
+        &payer.pubkey(),
+        [7u8; 32],
+        "My Token".to_string(),
+        "MTK".to_string(),
+        9,
+        payer.pubkey().to_bytes().to_vec(),
+        destination_chain.clone(),
+    );
+    smoke
+        .step(
+            report,
+            "interchain_token_deployment_started",
+            &[ix],
+            &["InterchainTokenDeploymentStarted"],
+        )
+        .await;
+
+    let ix = build_token_metadata_registered_ix(&gateway, &payer.pubkey(), payer.pubkey(), 9);
+    smoke
+        .step(
+            report,
+            "token_metadata_registered",
+            &[ix],
+            &["TokenMetadataRegistered"],
+        )
+        .await;
+
+    let ix = build_burn_compute_ix(&gateway, &payer.pubkey(), 10);
+    smoke
+        .step(report, "burn_compute", &[ix], &["ComputeBurnedEvent"])
+        .await;
+
+    let ix = build_signers_rotated_ix(&gateway, &payer.pubkey(), [0u8; 32], [0u8; 32]);
+    smoke
+        .step(
+            report,
+            "signers_rotated",
+            &[ix],
+            &["VerifierSetRotatedEvent"],
+        )
+        .await;
+
+    // Only the operator may transfer operatorship; handing it to itself emits the event
+    // and leaves the config as it was
+    let gateway_operator = fetch_gateway_config(&rpc, &gateway)
+        .await
+        .ok()
+        .map(|config| config.operator);
+    if gateway_operator == Some(payer.pubkey()) {
+        let ix = scripts::gateway::build_transfer_operatorship_ix(
+            &gateway,
+            &payer.pubkey(),
+            payer.pubkey(),
+        );
+        smoke
+            .step(
+                report,
+                "gateway transfer_operatorship",
+                &[ix],
+                &["OperatorshipTransferredEvent"],
+            )
+            .await;
+    } else {
+        report.note("Skipping gateway transfer_operatorship: payer is not the operator");
+    }
+
+    // gas_service
+    let ix = build_pay_native_ix(
+        &gas_service,
+        &payer.pubkey(),
+        destination_chain.clone(),
+        ZERO_ADDRESS.to_string(),
+        payload_hash,
+        1_000,
+        payer.pubkey(),
+    );
+    smoke
+        .step(
+            report,
+            "pay_native_for_contract_call",
+            &[ix],
+            &["GasPaidEvent"],
+        )
+        .await;
+
+    let message_id = call_id
+        .unwrap_or_else(|| MessageId::new(Signature::default(), 1, 1))
+        .to_string();
+    let ix = build_add_native_gas_ix(
+        &gas_service,
+        &payer.pubkey(),
+        message_id.clone(),
+        1_000,
+        payer.pubkey(),
+    );
+    smoke
+        .step(report, "add_native_gas", &[ix], &["GasAddedEvent"])
+        .await;
+
+    let ix = build_refund_ix(&gas_service, &payer.pubkey(), message_id, 1_000);
+    smoke
+        .step(report, "refund_native_fees", &[ix], &["GasRefundedEvent"])
+        .await;
+
+    let ix = build_cpi_call_contract_ix(
+        &gas_service,
+        &gateway,
+        &payer.pubkey(),
+        destination_chain,
+        ZERO_ADDRESS.to_string(),
+        payload_hash,
+        payload,
+    );
+    smoke
+        .step(report, "cpi_call_contract", &[ix], &["CallContractEvent"])
+        .await;
+
+    let gas_operator = fetch_gas_config(&rpc, &gas_service)
+        .await
+        .ok()
+        .map(|config| config.operator);
+    if gas_operator == Some(payer.pubkey()) {
+        let ix = scripts::gas::build_transfer_operatorship_ix(
+            &gas_service,
+            &payer.pubkey(),
+            payer.pubkey(),
+        );
+        smoke
+            .step(
+                report,
+                "gas transfer_operatorship",
+                &[ix],
+                &["OperatorshipTransferredEvent"],
+            )
+            .await;
+    } else {
+        report.note("Skipping gas transfer_operatorship: payer is not the operator");
+    }
+
+    let failures = smoke.failures;
+    report.field("failures", failures.clone());
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        for failure in &failures {
+            eprintln!("{failure}");
+        }
+        Err(anyhow!("{} check(s) failed", failures.len()))
+    }
+}
+
+/// Sends the steps, carrying on past failures so one run reports them all.
+struct Smoke<'a> {
+    rpc: &'a ResilientRpc,
+    payer: &'a Keypair,
+    failures: Vec<String>,
+}
+
+impl Smoke<'_> {
+    async fn step(
+        &mut self,
+        report: &mut Report,
+        name: &str,
+        ixs: &[Instruction],
+        expected: &[&str],
+    ) -> Option<Option<Receipt>> {
+        let sent = report.send(self.rpc, self.payer, name, ixs).await;
+        self.check(name, sent, expected)
+    }
+
+    /// Records what went wrong with a step: the send failing, an `expected`
+    /// event missing or an event not decoding. Returns the receipt (`None` in
+    /// a dry run) if the transaction went through.
+    fn check(
+        &mut self,
+        name: &str,
+        sent: Result<Option<Receipt>>,
+        expected: &[&str],
+    ) -> Option<Option<Receipt>> {
+        let receipt = match sent {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                self.failures.push(format!("{name}: {e:#}"));
+                return None;
+            }
+        };
+        if let Some(receipt) = &receipt {
+            for message_id in &receipt.undecoded {
+                self.failures
+                    .push(format!("{name}: event {message_id} does not decode"));
+            }
+            for event in expected {
+                if receipt.message_id(event).is_none() {
+                    self.failures.push(format!("{name}: no {event} emitted"));
+                }
+            }
+        }
+        Some(receipt)
+    }
+
+    /// Opens a session for a fresh root and verifies every signature of the
+    /// default verifier set against it, registering the set first if needed;
+    /// verify_signature emits no events.
+    async fn verify_signatures(&mut self, report: &mut Report, gateway: &Pubkey) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let payload_merkle_root: [u8; 32] =
+            Sha256::digest(format!("trigger-all-{}", timestamp).as_bytes()).into();
+        let ix =
+            build_init_verification_session_ix(gateway, &self.payer.pubkey(), payload_merkle_root);
+        if self
+            .step(report, "init_verification_session", &[ix], &[])
+            .await
+            .is_none()
+        {
+            return;
+        }
+
+        let fixture = VerifierSetFixture::new(b"dummy-verifiers", 3, 0, [0u8; 32]);
+        let verifier_set_hash = fixture.hash();
+        let (tracker, _) = verifier_set_tracker_pda(gateway, &verifier_set_hash);
+        if self.rpc.get_account(&tracker).await.is_err() {
+            let ix = build_register_verifier_set_ix(
+                gateway,
+                &self.payer.pubkey(),
+                &self.payer.pubkey(),
+                verifier_set_hash,
+            );
+            if self
+                .step(
+                    report,
+                    "register_verifier_set",
+                    &[ix],
+                    &["VerifierSetRotatedEvent"],
+                )
+                .await
+                .is_none()
+            {
+                return;
+            }
+        }
+        report.pda("verifier_set_tracker", tracker);
+
+        for signed in fixture.sign_root(&payload_merkle_root) {
+            let ix = build_verify_signature_ix(
+                gateway,
+                payload_merkle_root,
+                verifier_set_hash,
+                signed.leaf,
+                signed.proof,
+                signed.signature,
+                signed.recovery_id,
+            );
+            self.step(report, "verify_signature", &[ix], &[]).await;
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! `whitelist`: sets up relayer_whitelist and manages the relayers on it.
+//!
+//! program_tester only consults the whitelist when built with its
+//! `relayer-whitelist` feature.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use scripts::config::Config;
+use scripts::events::ToJson;
+use scripts::pdas::relayer_whitelist_pda;
+use scripts::whitelist::{build_add_relayer_ix, build_init_whitelist_ix, build_remove_relayer_ix};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::report::Report;
+
+#[derive(Subcommand)]
+pub enum WhitelistCommand {
+    /// Create the whitelist, then add any given relayers; the payer must be the program's
+    /// upgrade authority and becomes the whitelist's authority
+    Init(InitArgs),
+    /// Add relayers to the whitelist
+    Add(RelayersArgs),
+    /// Remove relayers from the whitelist
+    Remove(RelayersArgs),
+}
+
+#[derive(Args)]
+pub struct ProgramArgs {
+    #[arg(long, env = "WHITELIST_PROGRAM_ID", default_value_t = relayer_whitelist::ID)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Relayer to whitelist right away; repeatable
+    #[arg(long = "relayer")]
+    relayers: Vec<Pubkey>,
+}
+
+#[derive(Args)]
+pub struct RelayersArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+    /// Relayer to add or remove; repeatable, defaults to the payer
+    #[arg(long = "relayer")]
+    relayers: Vec<Pubkey>,
+}
+
+pub async fn run(config: &Config, command: WhitelistCommand, report: &mut Report) -> Result<()> {
+    match command {
+        WhitelistCommand::Init(args) => init(config, args, report).await,
+        WhitelistCommand::Add(args) => add(config, args, report).await,
+        WhitelistCommand::Remove(args) => remove(config, args, report).await,
+    }
+}
+
+async fn init(config: &Config, args: InitArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let mut ixs = vec![build_init_whitelist_ix(&program_id, &payer.pubkey())];
+    ixs.extend(
+        args.relayers
+            .iter()
+            .map(|relayer| build_add_relayer_ix(&program_id, &payer.pubkey(), *relayer)),
+    );
+    report.send(&rpc, &payer, "init_whitelist", &ixs).await?;
+
+    report.pda("relayer_whitelist", relayer_whitelist_pda(&program_id).0);
+    report.field("relayers", relayers_json(&args.relayers));
+    Ok(())
+}
+
+async fn add(config: &Config, args: RelayersArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let relayers = relayers_or_payer(args.relayers, payer.pubkey());
+    let ixs: Vec<_> = relayers
+        .iter()
+        .map(|relayer| build_add_relayer_ix(&program_id, &payer.pubkey(), *relayer))
+        .collect();
+    report.send(&rpc, &payer, "add_relayer", &ixs).await?;
+
+    report.field("relayers", relayers_json(&relayers));
+    Ok(())
+}
+
+async fn remove(config: &Config, args: RelayersArgs, report: &mut Report) -> Result<()> {
+    let program_id = args.program.program_id;
+    let payer = config.payer()?;
+    let rpc = config.rpc();
+
+    let relayers = relayers_or_payer(args.relayers, payer.pubkey());
+    let ixs: Vec<_> = relayers
+        .iter()
+        .map(|relayer| build_remove_relayer_ix(&program_id, &payer.pubkey(), *relayer))
+        .collect();
+    report.send(&rpc, &payer, "remove_relayer", &ixs).await?;
+
+    report.field("relayers", relayers_json(&relayers));
+    Ok(())
+}
+
+fn relayers_or_payer(relayers: Vec<Pubkey>, payer: Pubkey) -> Vec<Pubkey> {
+    if relayers.is_empty() {
+        vec![payer]
+    } else {
+        relayers
+    }
+}
+
+fn relayers_json(relayers: &[Pubkey]) -> serde_json::Value {
+    relayers.iter().map(ToJson::to_json).collect()
+}
@@ -0,0 +1,91 @@
+//! A shared, periodically refreshed recent blockhash.
+//!
+//! High-throughput senders would otherwise spend one `getLatestBlockhash` call
+//! per transaction. A blockhash stays valid for 150 blocks, so refreshing it
+//! every ~20 slots leaves plenty of headroom while cutting that call out of the
+//! send path.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use tokio::task::JoinHandle;
+
+use crate::error::RpcError;
+
+/// About 20 slots at the 400ms target slot time.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(20 * 400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachedBlockhash {
+    pub blockhash: Hash,
+    /// Last block height at which transactions using `blockhash` are accepted.
+    pub last_valid_block_height: u64,
+}
+
+/// Dropping the last handle stops the refresh task.
+#[derive(Clone)]
+pub struct BlockhashCache {
+    latest: Arc<RwLock<CachedBlockhash>>,
+    _refresher: Arc<Refresher>,
+}
+
+struct Refresher(JoinHandle<()>);
+
+impl Drop for Refresher {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl BlockhashCache {
+    /// Fetches an initial blockhash, then refreshes it every `interval` in a
+    /// background task. Failed refreshes are logged and keep the previous value.
+    pub async fn spawn(
+        rpc_url: String,
+        commitment: CommitmentConfig,
+        interval: Duration,
+    ) -> Result<Self, RpcError> {
+        let rpc = RpcClient::new_with_commitment(rpc_url, commitment);
+        let latest = Arc::new(RwLock::new(fetch(&rpc).await?));
+
+        let task_latest = Arc::clone(&latest);
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately and we already have a value
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match fetch(&rpc).await {
+                    Ok(fresh) => *task_latest.write().unwrap() = fresh,
+                    Err(e) => eprintln!("blockhash refresh failed: {e}"),
+                }
+            }
+        });
+
+        Ok(Self {
+            latest,
+            _refresher: Arc::new(Refresher(handle)),
+        })
+    }
+
+    pub fn latest(&self) -> CachedBlockhash {
+        *self.latest.read().unwrap()
+    }
+
+    pub fn blockhash(&self) -> Hash {
+        self.latest().blockhash
+    }
+}
+
+async fn fetch(rpc: &RpcClient) -> Result<CachedBlockhash, RpcError> {
+    let (blockhash, last_valid_block_height) = rpc
+        .get_latest_blockhash_with_commitment(rpc.commitment())
+        .await?;
+    Ok(CachedBlockhash {
+        blockhash,
+        last_valid_block_height,
+    })
+}
@@ -0,0 +1,156 @@
+//! Where a listener left off, kept in a JSON file so a restart resumes there.
+//!
+//! For each program followed, the file holds the latest transaction handled
+//! and its slot. On startup the listener backfills from those instead of
+//! starting at the tip, so the downtime is neither skipped nor history
+//! replayed. The file is replaced atomically, so a crash leaves the previous
+//! checkpoint rather than a truncated one.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::error::CheckpointError;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Keyed by program id, so a checkpoint taken for other programs is ignored.
+    #[serde(default)]
+    pub programs: BTreeMap<String, Position>,
+}
+
+/// The latest transaction handled for a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    #[serde(with = "signature_string")]
+    pub signature: Signature,
+    pub slot: u64,
+}
+
+impl Checkpoint {
+    /// Reads the checkpoint at `path`, empty if there is no file yet.
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path).map_err(|e| CheckpointError::Read {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        serde_json::from_str(&raw).map_err(|e| CheckpointError::Parse {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Writes the checkpoint to `path` through a temporary file next to it.
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let write_error = |e: std::io::Error| CheckpointError::Write {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        };
+        // Serializing strings and numbers doesn't fail
+        let raw = serde_json::to_string_pretty(self).unwrap();
+        let tmp = temp_path(path);
+        std::fs::write(&tmp, raw).map_err(write_error)?;
+        std::fs::rename(&tmp, path).map_err(write_error)
+    }
+
+    pub fn position(&self, program_id: &Pubkey) -> Option<Position> {
+        self.programs.get(&program_id.to_string()).copied()
+    }
+
+    pub fn advance(&mut self, program_id: &Pubkey, position: Position) {
+        self.programs.insert(program_id.to_string(), position);
+    }
+}
+
+/// The temporary file `path` is written through: `<file name>.tmp` next to
+/// it, so files sharing a stem, like `listen.json` and `listen.sent`, don't
+/// share one.
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+mod signature_string {
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use solana_sdk::signature::Signature;
+
+    pub fn serialize<S: Serializer>(
+        signature: &Signature,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(signature)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Signature, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Signature::from_str(&raw).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scripts-checkpoint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn missing_file_is_an_empty_checkpoint() {
+        let path = scratch("missing.json");
+        assert_eq!(Checkpoint::load(&path).unwrap(), Checkpoint::default());
+    }
+
+    #[test]
+    fn saved_positions_load_back() {
+        let path = scratch("listen.json");
+        let program = Pubkey::new_unique();
+        let position = Position {
+            signature: Signature::from([3u8; 64]),
+            slot: 42,
+        };
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.advance(&program, position);
+        checkpoint.save(&path).unwrap();
+
+        let loaded = Checkpoint::load(&path).unwrap();
+        assert_eq!(loaded.position(&program), Some(position));
+        assert_eq!(loaded.position(&Pubkey::new_unique()), None);
+        assert!(!temp_path(&path).exists());
+    }
+
+    #[test]
+    fn temp_path_keeps_the_extension() {
+        assert_eq!(
+            temp_path(Path::new("state/listen.json")),
+            Path::new("state/listen.json.tmp")
+        );
+        assert_ne!(
+            temp_path(Path::new("listen.json")),
+            temp_path(Path::new("listen.sent"))
+        );
+    }
+
+    #[test]
+    fn invalid_file_is_a_parse_error() {
+        let path = scratch("invalid.json");
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            Checkpoint::load(&path),
+            Err(CheckpointError::Parse { .. })
+        ));
+    }
+}
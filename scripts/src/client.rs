@@ -0,0 +1,146 @@
+//! High-level client on top of `anchor-client`.
+//!
+//! Requests are assembled with `program.request().accounts(..).args(..)` from the
+//! generated program crates, which also sign and send them. The raw builders in
+//! `gateway` and `gas` remain the way to produce bare `Instruction`s, e.g. for
+//! malformed-input tests or multi-instruction transactions.
+
+use std::sync::Arc;
+
+use anchor_client::{Client, ClientError, Cluster, Program};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::system_program;
+
+use crate::gateway::call_contract_accounts;
+use crate::pdas::{event_authority_pda, gas_config_pda, gateway_root_pda};
+
+pub struct AnchorClient {
+    pub gateway: Program<Arc<Keypair>>,
+    pub gas: Program<Arc<Keypair>>,
+}
+
+impl AnchorClient {
+    pub fn new(
+        cluster: Cluster,
+        payer: Arc<Keypair>,
+        gateway_program_id: Pubkey,
+        gas_program_id: Pubkey,
+    ) -> Result<Self, ClientError> {
+        let client = Client::new_with_options(cluster, payer, CommitmentConfig::confirmed());
+        Ok(Self {
+            gateway: client.program(gateway_program_id)?,
+            gas: client.program(gas_program_id)?,
+        })
+    }
+
+    pub async fn init_gateway_root(&self) -> Result<Signature, ClientError> {
+        self.gateway
+            .request()
+            .accounts(program_tester::accounts::InitGatewayRoot {
+                funder: self.gateway.payer(),
+                gateway_root_pda: gateway_root_pda(&self.gateway.id()).0,
+                system_program: system_program::id(),
+            })
+            .args(program_tester::instruction::InitGatewayRoot {})
+            .send()
+            .await
+    }
+
+    /// Calls `call_contract` with the payer reported as the calling program.
+    pub async fn call_contract(
+        &self,
+        destination_chain: String,
+        destination_contract_address: String,
+        payload_hash: [u8; 32],
+        payload: Vec<u8>,
+    ) -> Result<Signature, ClientError> {
+        self.gateway
+            .request()
+            .accounts(call_contract_accounts(
+                &self.gateway.id(),
+                &self.gateway.payer(),
+            ))
+            .args(program_tester::instruction::CallContract {
+                destination_chain,
+                destination_contract_address,
+                payload_hash,
+                payload,
+            })
+            .send()
+            .await
+    }
+
+    pub async fn pay_native_for_contract_call(
+        &self,
+        destination_chain: String,
+        destination_address: String,
+        payload_hash: [u8; 32],
+        amount: u64,
+        refund_address: Pubkey,
+    ) -> Result<Signature, ClientError> {
+        let program_id = self.gas.id();
+        self.gas
+            .request()
+            .accounts(gas_service::accounts::PayNativeForContractCall {
+                payer: self.gas.payer(),
+                config_pda: gas_config_pda(&program_id).0,
+                system_program: system_program::id(),
+                event_authority: event_authority_pda(&program_id).0,
+                program: program_id,
+            })
+            .args(gas_service::instruction::PayNativeForContractCall {
+                destination_chain,
+                destination_address,
+                payload_hash,
+                amount,
+                refund_address,
+            })
+            .send()
+            .await
+    }
+
+    pub async fn add_native_gas(
+        &self,
+        message_id: String,
+        amount: u64,
+        refund_address: Pubkey,
+    ) -> Result<Signature, ClientError> {
+        let program_id = self.gas.id();
+        self.gas
+            .request()
+            .accounts(gas_service::accounts::AddNativeGas {
+                sender: self.gas.payer(),
+                config_pda: gas_config_pda(&program_id).0,
+                system_program: system_program::id(),
+                event_authority: event_authority_pda(&program_id).0,
+                program: program_id,
+            })
+            .args(gas_service::instruction::AddNativeGas {
+                message_id,
+                amount,
+                refund_address,
+            })
+            .send()
+            .await
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.gateway.payer()
+    }
+}
+
+/// Builds a custom cluster from an HTTP RPC URL, see `ws_url_for`.
+pub fn cluster_from_rpc_url(rpc_url: &str) -> Cluster {
+    Cluster::Custom(rpc_url.to_string(), ws_url_for(rpc_url))
+}
+
+/// The websocket URL that goes with an HTTP RPC URL, the way
+/// `solana-test-validator` lays out its ports (RPC port + 1).
+pub fn ws_url_for(rpc_url: &str) -> String {
+    rpc_url
+        .replacen("https://", "wss://", 1)
+        .replacen("http://", "ws://", 1)
+        .replace(":8899", ":8900")
+}
@@ -0,0 +1,152 @@
+//! Well-known clusters and the endpoints and program ids deployed on them.
+//!
+//! Selecting a cluster sets the RPC and websocket URLs and, where the programs
+//! are deployed, both program ids at once, so they can't end up pointing at
+//! different clusters. The ids mirror the `[programs.*]` sections of
+//! `Anchor.toml`, which `Config` reads over them inside the workspace; the
+//! presets serve when the scripts run elsewhere.
+//!
+//! `Explorer` links transactions on Solana Explorer and Solscan. Clusters
+//! without a preset link (localnet, or no cluster at all) go through the
+//! explorers' custom RPC mode, pointed at the configured `rpc_url`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::error::ConfigError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cluster {
+    #[serde(alias = "localhost")]
+    Localnet,
+    Devnet,
+    Testnet,
+    #[serde(alias = "mainnet-beta")]
+    Mainnet,
+}
+
+/// The gateway (`program_tester`) and gas service ids on a cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramIds {
+    pub gateway: Pubkey,
+    pub gas: Pubkey,
+}
+
+impl Cluster {
+    pub const ALL: [Cluster; 4] = [
+        Cluster::Localnet,
+        Cluster::Devnet,
+        Cluster::Testnet,
+        Cluster::Mainnet,
+    ];
+
+    pub fn rpc_url(self) -> &'static str {
+        match self {
+            Cluster::Localnet => "http://127.0.0.1:8899",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Testnet => "https://api.testnet.solana.com",
+            Cluster::Mainnet => "https://api.mainnet-beta.solana.com",
+        }
+    }
+
+    pub fn ws_url(self) -> &'static str {
+        match self {
+            Cluster::Localnet => "ws://127.0.0.1:8900",
+            Cluster::Devnet => "wss://api.devnet.solana.com",
+            Cluster::Testnet => "wss://api.testnet.solana.com",
+            Cluster::Mainnet => "wss://api.mainnet-beta.solana.com",
+        }
+    }
+
+    /// `None` where the programs aren't deployed; the ids then have to be
+    /// given explicitly.
+    pub fn program_ids(self) -> Option<ProgramIds> {
+        match self {
+            Cluster::Localnet => Some(ProgramIds {
+                gateway: solana_sdk::pubkey!("8YsLGnLV2KoyxdksgiAi3gh1WvhMrznA2toKWqyz91bR"),
+                gas: solana_sdk::pubkey!("CJ9f8WFdm3q38pmg426xQf7uum7RqvrmS9R58usHwNX7"),
+            }),
+            Cluster::Devnet => Some(ProgramIds {
+                gateway: solana_sdk::pubkey!("7RdSDLUUy37Wqc6s9ebgo52AwhGiw4XbJWZJgidQ1fJc"),
+                gas: solana_sdk::pubkey!("H9XpBVCnYxr7cHd66nqtD8RSTrKY6JC32XVu2zT2kBmP"),
+            }),
+            Cluster::Testnet | Cluster::Mainnet => None,
+        }
+    }
+
+    /// Whether the cluster's RPC answers `requestAirdrop`.
+    pub fn has_faucet(self) -> bool {
+        self != Cluster::Mainnet
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cluster::Localnet => "localnet",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+            Cluster::Mainnet => "mainnet",
+        })
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = ConfigError;
+
+    /// Accepts the names `solana config set --url` does, including the
+    /// one-letter monikers.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "localnet" | "localhost" | "l" => Ok(Cluster::Localnet),
+            "devnet" | "d" => Ok(Cluster::Devnet),
+            "testnet" | "t" => Ok(Cluster::Testnet),
+            "mainnet" | "mainnet-beta" | "m" => Ok(Cluster::Mainnet),
+            _ => Err(ConfigError::UnknownCluster(s.to_string())),
+        }
+    }
+}
+
+/// Builds explorer links for the transactions sent to a cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explorer {
+    cluster: Option<Cluster>,
+    rpc_url: String,
+}
+
+impl Explorer {
+    pub fn new(cluster: Option<Cluster>, rpc_url: impl Into<String>) -> Self {
+        Self {
+            cluster,
+            rpc_url: rpc_url.into(),
+        }
+    }
+
+    pub fn tx_url(&self, signature: &Signature) -> String {
+        self.url(&format!("https://explorer.solana.com/tx/{signature}"))
+    }
+
+    pub fn solscan_tx_url(&self, signature: &Signature) -> String {
+        self.url(&format!("https://solscan.io/tx/{signature}"))
+    }
+
+    fn url(&self, base: &str) -> String {
+        let params = match self.cluster {
+            Some(Cluster::Mainnet) => return base.to_string(),
+            Some(cluster @ (Cluster::Devnet | Cluster::Testnet)) => {
+                vec![("cluster", cluster.to_string())]
+            }
+            Some(Cluster::Localnet) | None => vec![
+                ("cluster", "custom".to_string()),
+                ("customUrl", self.rpc_url.clone()),
+            ],
+        };
+        Url::parse_with_params(base, &params).map_or_else(|_| base.to_string(), String::from)
+    }
+}
@@ -0,0 +1,164 @@
+//! Hex and base58 conversions shared by the scripts.
+//!
+//! `Strict` parsing rejects anything but well-formed input of the expected
+//! length. `Lenient` is for hand-typed values: it ignores whitespace, pads an
+//! odd-length hex string with a leading zero nibble, and fits fixed-size arrays
+//! by zero-padding or truncating on the right.
+
+use crate::error::EncodingError;
+
+type Result<T> = std::result::Result<T, EncodingError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+/// Lowercase hex without a prefix.
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lowercase hex with a `0x` prefix.
+pub fn hex_encode_prefixed(bytes: &[u8]) -> String {
+    format!("0x{}", hex_encode(bytes))
+}
+
+/// Decodes hex with an optional `0x`/`0X` prefix.
+pub fn hex_decode(input: &str, mode: Mode) -> Result<Vec<u8>> {
+    let trimmed = match mode {
+        Mode::Strict => input.to_string(),
+        Mode::Lenient => input.chars().filter(|c| !c.is_whitespace()).collect(),
+    };
+    let digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(&trimmed);
+    let digits = match mode {
+        Mode::Lenient if digits.len() % 2 != 0 => format!("0{digits}"),
+        _ => digits.to_string(),
+    };
+    if digits.len() % 2 != 0 {
+        return Err(EncodingError::OddHexLength {
+            input: input.to_string(),
+        });
+    }
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| EncodingError::InvalidHex {
+                    input: input.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Decodes hex into exactly `N` bytes, e.g. a `[u8; 32]` hash or `[u8; 64]` signature.
+pub fn hex_array<const N: usize>(input: &str, mode: Mode) -> Result<[u8; N]> {
+    fit(hex_decode(input, mode)?, mode, input)
+}
+
+pub fn bs58_encode(bytes: &[u8]) -> String {
+    bs58::encode(bytes).into_string()
+}
+
+pub fn bs58_decode(input: &str, mode: Mode) -> Result<Vec<u8>> {
+    let input = match mode {
+        Mode::Strict => input,
+        Mode::Lenient => input.trim(),
+    };
+    bs58::decode(input)
+        .into_vec()
+        .map_err(|e| EncodingError::InvalidBase58 {
+            input: input.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Decodes base58 into exactly `N` bytes, e.g. a pubkey or a transaction signature.
+pub fn bs58_array<const N: usize>(input: &str, mode: Mode) -> Result<[u8; N]> {
+    fit(bs58_decode(input, mode)?, mode, input)
+}
+
+fn fit<const N: usize>(bytes: Vec<u8>, mode: Mode, input: &str) -> Result<[u8; N]> {
+    if mode == Mode::Strict && bytes.len() != N {
+        return Err(EncodingError::InvalidLength {
+            input: input.to_string(),
+            expected: N,
+            actual: bytes.len(),
+        });
+    }
+    let mut out = [0u8; N];
+    let len = bytes.len().min(N);
+    out[..len].copy_from_slice(&bytes[..len]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trips_with_and_without_prefix() {
+        let bytes = [0x00, 0xab, 0xff];
+        assert_eq!(hex_encode(&bytes), "00abff");
+        assert_eq!(hex_encode_prefixed(&bytes), "0x00abff");
+        for input in ["00abff", "0x00abff", "0X00ABFF"] {
+            assert_eq!(hex_decode(input, Mode::Strict).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn strict_hex_rejects_what_lenient_accepts() {
+        assert!(matches!(
+            hex_decode("0xabc", Mode::Strict),
+            Err(EncodingError::OddHexLength { .. })
+        ));
+        assert_eq!(hex_decode("0xabc", Mode::Lenient).unwrap(), [0x0a, 0xbc]);
+
+        assert!(matches!(
+            hex_decode(" abcd ", Mode::Strict),
+            Err(EncodingError::InvalidHex { .. })
+        ));
+        assert_eq!(hex_decode(" abcd ", Mode::Lenient).unwrap(), [0xab, 0xcd]);
+        assert_eq!(hex_decode(" ab\ncd ", Mode::Lenient).unwrap(), [0xab, 0xcd]);
+
+        assert!(hex_decode("zz", Mode::Lenient).is_err());
+    }
+
+    #[test]
+    fn strict_arrays_need_the_exact_length() {
+        assert!(matches!(
+            hex_array::<4>("0x010203", Mode::Strict),
+            Err(EncodingError::InvalidLength {
+                expected: 4,
+                actual: 3,
+                ..
+            })
+        ));
+        assert_eq!(
+            hex_array::<4>("0x010203", Mode::Lenient).unwrap(),
+            [1, 2, 3, 0]
+        );
+        assert_eq!(hex_array::<2>("0x010203", Mode::Lenient).unwrap(), [1, 2]);
+        assert_eq!(hex_array::<3>("0x010203", Mode::Strict).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn base58_trims_only_when_lenient() {
+        let bytes = [7u8; 32];
+        let encoded = bs58_encode(&bytes);
+        assert_eq!(bs58_array::<32>(&encoded, Mode::Strict).unwrap(), bytes);
+
+        let padded = format!(" {encoded}\n");
+        assert!(bs58_decode(&padded, Mode::Strict).is_err());
+        assert_eq!(bs58_array::<32>(&padded, Mode::Lenient).unwrap(), bytes);
+        assert!(bs58_array::<64>(&encoded, Mode::Strict).is_err());
+    }
+}
@@ -0,0 +1,437 @@
+//! Shared settings for the binaries.
+//!
+//! Values come from `config.toml` in the working directory (or the file named
+//! by `SCRIPTS_CONFIG`), then a cluster preset, then environment variables:
+//!
+//! | key                  | env                              |
+//! |----------------------|----------------------------------|
+//! | `cluster`            | `CLUSTER`, `--cluster <name>`    |
+//! | `rpc_url`            | `RPC_URL`                        |
+//! | `ws_url`             | `WS_URL`                         |
+//! | `gateway_program_id` | `GATEWAY_PROGRAM_ID`, `PROGRAM_ID` |
+//! | `gas_program_id`     | `GAS_PROGRAM_ID`                 |
+//! | `keypair`            | `SOLANA_KEYPAIR`, `PAYER`        |
+//! | `commitment`         | `COMMITMENT`                     |
+//! | `skip_preflight`     | `SKIP_PREFLIGHT` (`1`/`true`)    |
+//! | `verify`             | `VERIFY` (`1`/`true`)            |
+//! | `treasury`           | `TREASURY_KEYPAIR`               |
+//! | `min_balance`        | `MIN_BALANCE` (lamports)         |
+//! | `priority_fee`       | `CU_PRICE` (number or `auto`)    |
+//! | `cu_limit`           | `CU_LIMIT` (number or `auto`)    |
+//! | `nonce_account`      | `NONCE_ACCOUNT`                  |
+//!
+//! A cluster replaces the endpoints and program ids with its preset (see
+//! `cluster`), so a file sets either `cluster` or those keys. Anything unset
+//! falls back to the localnet preset.
+//!
+//! The program ids the file doesn't set, and all of them once a cluster is
+//! chosen, are then taken from the `[programs.<cluster>]` section of the
+//! workspace's `Anchor.toml` (`[programs.localnet]` without a cluster), so a
+//! redeploy recorded there needs no config change.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use crate::cluster::{Cluster, Explorer};
+use crate::error::{ConfigError, KeypairError};
+use crate::funding::DEFAULT_MIN_BALANCE;
+use crate::ids::{anchor_program_ids, workspace_root};
+use crate::rpc::{ResilientRpc, RetryConfig};
+use crate::signer::load_signer;
+use crate::tx::{ComputeBudget, ComputeUnitLimit, PriorityFee};
+
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<Cluster>,
+    pub rpc_url: String,
+    pub ws_url: String,
+    #[serde(with = "pubkey_string")]
+    pub gateway_program_id: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub gas_program_id: Pubkey,
+    /// Keypair file, or `prompt`; see `signer::load_signer` for what happens
+    /// when unset.
+    pub keypair: Option<String>,
+    pub commitment: CommitmentLevel,
+    /// Send without a preflight simulation, see `ResilientRpc::with_skip_preflight`.
+    pub skip_preflight: bool,
+    /// Check the events of sent transactions against their instructions, see
+    /// `verify`.
+    pub verify: bool,
+    /// Keypair file (or `prompt`) that funds payers where there's no faucet,
+    /// see `funding`.
+    pub treasury: Option<String>,
+    /// Payers holding less than this many lamports are funded before their
+    /// first transaction; 0 turns that off.
+    pub min_balance: u64,
+    /// Compute unit price in micro-lamports, or `"auto"`; unset sends without
+    /// a priority fee.
+    pub priority_fee: Option<PriorityFee>,
+    /// Compute unit limit, or `"auto"` to simulate first and add a margin;
+    /// unset leaves the runtime's default.
+    pub cu_limit: Option<ComputeUnitLimit>,
+    /// Durable nonce account that sends sign against instead of a recent
+    /// blockhash, with the payer as its authority; see `nonce`.
+    #[serde(with = "option_pubkey_string", skip_serializing_if = "Option::is_none")]
+    pub nonce_account: Option<Pubkey>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let localnet = Cluster::Localnet;
+        // Localnet always has both programs
+        let ids = localnet.program_ids().unwrap();
+        Self {
+            cluster: None,
+            rpc_url: localnet.rpc_url().to_string(),
+            ws_url: localnet.ws_url().to_string(),
+            gateway_program_id: ids.gateway,
+            gas_program_id: ids.gas,
+            keypair: None,
+            commitment: CommitmentLevel::Confirmed,
+            skip_preflight: false,
+            verify: false,
+            treasury: None,
+            min_balance: DEFAULT_MIN_BALANCE,
+            priority_fee: None,
+            cu_limit: None,
+            nonce_account: None,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file if there is one, then applies the cluster preset
+    /// and env overrides.
+    pub fn load() -> Result<Self, ConfigError> {
+        let cluster = match cluster_arg()? {
+            Some(cluster) => Some(cluster),
+            None => env_first(&["CLUSTER"]).map(|c| c.parse()).transpose()?,
+        };
+        let path = std::env::var_os("SCRIPTS_CONFIG").map(PathBuf::from);
+        Self::resolve(path.as_deref(), cluster)
+    }
+
+    /// Like `load`, with the file and cluster given by the caller instead of
+    /// `SCRIPTS_CONFIG`, `CLUSTER` and `--cluster`. Without a path,
+    /// `config.toml` is read if it exists; without a cluster, the file's.
+    pub fn resolve(path: Option<&Path>, cluster: Option<Cluster>) -> Result<Self, ConfigError> {
+        let path = path.or_else(|| Some(Path::new(DEFAULT_CONFIG_PATH)).filter(|p| p.exists()));
+        let table = match path {
+            Some(path) => read_table(path)?,
+            None => toml::Table::new(),
+        };
+        let mut config = match path {
+            Some(path) => Self::from_table(path, table.clone())?,
+            None => Self::default(),
+        };
+        let cluster = cluster.or(config.cluster);
+        if let Some(cluster) = cluster {
+            config.apply_cluster(cluster);
+        }
+        // A file without a cluster may pin ids of its own; a cluster replaces them
+        config.apply_anchor_toml(cluster.unwrap_or(Cluster::Localnet), |key| {
+            cluster.is_none() && table.contains_key(key)
+        })?;
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        Self::from_table(path, read_table(path)?)
+    }
+
+    fn from_table(path: &Path, table: toml::Table) -> Result<Self, ConfigError> {
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| ConfigError::Parse {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })
+    }
+
+    /// Writes `gateway_program_id` and `gas_program_id` (where given) into the
+    /// config file at `path`, creating it if needed and keeping its other
+    /// keys. A `cluster` preset with program ids of its own would override
+    /// them, so it is replaced by its endpoints.
+    pub fn record_program_ids(
+        path: impl AsRef<Path>,
+        gateway: Option<Pubkey>,
+        gas: Option<Pubkey>,
+    ) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let mut table = if path.exists() {
+            read_table(path)?
+        } else {
+            toml::Table::new()
+        };
+        let preset = table
+            .get("cluster")
+            .and_then(toml::Value::as_str)
+            .map(Cluster::from_str)
+            .transpose()?;
+        if let Some(cluster) = preset.filter(|c| c.program_ids().is_some()) {
+            table.remove("cluster");
+            for (key, url) in [("rpc_url", cluster.rpc_url()), ("ws_url", cluster.ws_url())] {
+                table.entry(key).or_insert_with(|| url.into());
+            }
+        }
+        for (key, id) in [("gateway_program_id", gateway), ("gas_program_id", gas)] {
+            if let Some(id) = id {
+                table.insert(key.to_string(), id.to_string().into());
+            }
+        }
+        let raw = toml::to_string_pretty(&table).map_err(|e| ConfigError::Write {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        std::fs::write(path, raw).map_err(|e| ConfigError::Write {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Points the endpoints, and the program ids where `cluster` has them, at
+    /// `cluster`.
+    pub fn apply_cluster(&mut self, cluster: Cluster) {
+        self.cluster = Some(cluster);
+        self.rpc_url = cluster.rpc_url().to_string();
+        self.ws_url = cluster.ws_url().to_string();
+        if let Some(ids) = cluster.program_ids() {
+            self.gateway_program_id = ids.gateway;
+            self.gas_program_id = ids.gas;
+        }
+    }
+
+    /// Takes the gateway (`program_tester`) and gas service (`gas_service`)
+    /// ids from the `[programs.<cluster>]` section of the workspace's
+    /// `Anchor.toml`, where there is one, except for the keys `keep` holds on
+    /// to.
+    pub fn apply_anchor_toml(
+        &mut self,
+        cluster: Cluster,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<(), ConfigError> {
+        let Some(root) = std::env::current_dir()
+            .ok()
+            .and_then(|dir| workspace_root(&dir))
+        else {
+            return Ok(());
+        };
+        for (name, id) in anchor_program_ids(&root.join("Anchor.toml"), cluster)? {
+            match name.as_str() {
+                "program_tester" if !keep("gateway_program_id") => self.gateway_program_id = id,
+                "gas_service" if !keep("gas_program_id") => self.gas_program_id = id,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        if let Some(url) = env_first(&["RPC_URL"]) {
+            self.rpc_url = url;
+        }
+        if let Some(url) = env_first(&["WS_URL"]) {
+            self.ws_url = url;
+        }
+        if let Some(id) = env_first(&["GATEWAY_PROGRAM_ID", "PROGRAM_ID"]) {
+            self.gateway_program_id = parse_env("GATEWAY_PROGRAM_ID", &id)?;
+        }
+        if let Some(id) = env_first(&["GAS_PROGRAM_ID"]) {
+            self.gas_program_id = parse_env("GAS_PROGRAM_ID", &id)?;
+        }
+        if let Some(keypair) = env_first(&["SOLANA_KEYPAIR", "PAYER"]) {
+            self.keypair = Some(keypair);
+        }
+        if let Some(level) = env_first(&["COMMITMENT"]) {
+            self.commitment = parse_env("COMMITMENT", &level)?;
+        }
+        if let Some(limit) = env_first(&["CU_LIMIT"]) {
+            self.cu_limit = Some(parse_env("CU_LIMIT", &limit)?);
+        }
+        if let Some(fee) = env_first(&["CU_PRICE"]) {
+            self.priority_fee = Some(parse_env("CU_PRICE", &fee)?);
+        }
+        if let Some(skip) = env_first(&["SKIP_PREFLIGHT"]) {
+            self.skip_preflight = skip == "1" || skip == "true";
+        }
+        if let Some(verify) = env_first(&["VERIFY"]) {
+            self.verify = verify == "1" || verify == "true";
+        }
+        if let Some(treasury) = env_first(&["TREASURY_KEYPAIR"]) {
+            self.treasury = Some(treasury);
+        }
+        if let Some(min_balance) = env_first(&["MIN_BALANCE"]) {
+            self.min_balance = parse_env("MIN_BALANCE", &min_balance)?;
+        }
+        if let Some(account) = env_first(&["NONCE_ACCOUNT"]) {
+            self.nonce_account = Some(parse_env("NONCE_ACCOUNT", &account)?);
+        }
+        Ok(())
+    }
+
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        CommitmentConfig {
+            commitment: self.commitment,
+        }
+    }
+
+    /// The compute budget env settings, overridden by `cu_limit` and
+    /// `priority_fee`.
+    pub fn compute_budget(&self) -> ComputeBudget {
+        let mut budget = ComputeBudget::from_env();
+        if let Some(limit) = self.cu_limit {
+            budget = budget.compute_unit_limit(limit);
+        }
+        if let Some(fee) = self.priority_fee {
+            budget = budget.priority_fee(fee);
+        }
+        budget
+    }
+
+    /// A client for `rpc_url` with the retry env settings, `compute_budget`,
+    /// `nonce_account` and `skip_preflight`.
+    pub fn rpc(&self) -> ResilientRpc {
+        self.rpc_at(&self.rpc_url)
+    }
+
+    /// Like `rpc`, for another endpoint of the same cluster.
+    pub fn rpc_at(&self, url: &str) -> ResilientRpc {
+        ResilientRpc::new(
+            url.to_string(),
+            self.commitment_config(),
+            RetryConfig::from_env(),
+        )
+        .with_compute_budget(self.compute_budget())
+        .with_durable_nonce(self.nonce_account)
+        .with_skip_preflight(self.skip_preflight)
+    }
+
+    /// Whether `rpc_url` points at a validator on this machine.
+    pub fn is_localnet(&self) -> bool {
+        reqwest::Url::parse(&self.rpc_url)
+            .is_ok_and(|url| matches!(url.host_str(), Some("127.0.0.1" | "localhost")))
+    }
+
+    /// Whether airdrops can be requested, going by the cluster or, without
+    /// one, by whether the validator is local.
+    pub fn has_faucet(&self) -> bool {
+        self.cluster
+            .map_or_else(|| self.is_localnet(), Cluster::has_faucet)
+    }
+
+    /// Links to transactions on `cluster`, or on `rpc_url` without one.
+    pub fn explorer(&self) -> Explorer {
+        Explorer::new(self.cluster, self.rpc_url.clone())
+    }
+
+    pub fn payer(&self) -> Result<Keypair, KeypairError> {
+        load_signer(self.keypair.as_deref())
+    }
+}
+
+/// The value of `--cluster <name>` or `--cluster=<name>` on the command line.
+fn cluster_arg() -> Result<Option<Cluster>, ConfigError> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = if arg == "--cluster" {
+            args.next()
+        } else if let Some(name) = arg.strip_prefix("--cluster=") {
+            Some(name.to_string())
+        } else {
+            continue;
+        };
+        return match value {
+            Some(name) => name.parse().map(Some),
+            None => Err(ConfigError::InvalidValue {
+                key: "--cluster",
+                value: arg,
+                reason: "expected a cluster name".to_string(),
+            }),
+        };
+    }
+    Ok(None)
+}
+
+fn env_first(vars: &[&str]) -> Option<String> {
+    vars.iter().find_map(|var| std::env::var(var).ok())
+}
+
+fn read_table(path: &Path) -> Result<toml::Table, ConfigError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| ConfigError::Read {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    raw.parse()
+        .map_err(|e: toml::de::Error| ConfigError::Parse {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+}
+
+fn parse_env<T>(key: &'static str, value: &str) -> Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e: T::Err| ConfigError::InvalidValue {
+            key,
+            value: value.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Pubkeys as base58 strings rather than serde's default byte array.
+mod pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Pubkey::from_str(&raw).map_err(de::Error::custom)
+    }
+}
+
+mod option_pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(
+        key: &Option<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match key {
+            Some(key) => serializer.collect_str(key),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|raw| Pubkey::from_str(&raw).map_err(de::Error::custom))
+            .transpose()
+    }
+}
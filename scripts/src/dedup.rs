@@ -0,0 +1,227 @@
+//! The events a listener already sent, kept in a file so neither a restart
+//! nor a backfill overlapping the live subscription sends one twice.
+//!
+//! An event is known by its transaction's signature and its location in it,
+//! the message id or log line, as in `EventRecord`. It is recorded for each
+//! sink it reached, so when a later sink fails, the retry doesn't send it to
+//! the earlier ones again. Each delivery is appended to the file as a line
+//! `<signature>\t<location>\t<sink>`, and only the latest `capacity` are
+//! remembered, which should cover any overlap: the file is compacted to those
+//! on open and whenever it has grown to twice as many.
+
+use std::collections::{HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use solana_sdk::signature::Signature;
+
+use crate::checkpoint::temp_path;
+use crate::error::DedupError;
+
+/// What identifies an event sent to a sink: its transaction, where in it it
+/// was found, and the sink.
+type EventKey = (Signature, String, String);
+
+pub struct SentEvents {
+    path: PathBuf,
+    file: File,
+    capacity: usize,
+    keys: HashSet<EventKey>,
+    order: VecDeque<EventKey>,
+    /// Lines in the file, the forgotten ones included.
+    lines: usize,
+}
+
+impl SentEvents {
+    /// Reads the deliveries at `path`, none if there is no file yet, keeping
+    /// the latest `capacity` (at least one).
+    pub fn open(path: &Path, capacity: usize) -> Result<Self, DedupError> {
+        let capacity = capacity.max(1);
+        let mut keys = HashSet::new();
+        let mut order = VecDeque::new();
+        if path.exists() {
+            let raw = std::fs::read_to_string(path).map_err(|e| DedupError::Read {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })?;
+            for (index, line) in raw.lines().enumerate() {
+                if line.is_empty() {
+                    continue;
+                }
+                let key = parse_line(line).ok_or_else(|| DedupError::Parse {
+                    path: path.display().to_string(),
+                    line: index + 1,
+                })?;
+                remember(&mut keys, &mut order, capacity, key);
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: rewrite(path, &order)?,
+            capacity,
+            lines: order.len(),
+            keys,
+            order,
+        })
+    }
+
+    /// Whether the event at `location` in `signature` was sent to `sink`
+    /// already.
+    pub fn contains(&self, sink: &str, signature: Signature, location: &str) -> bool {
+        self.keys
+            .contains(&(signature, location.to_string(), sink.to_string()))
+    }
+
+    /// Records the event at `location` in `signature` as sent to `sink`, in
+    /// the file right away.
+    pub fn insert(
+        &mut self,
+        sink: &str,
+        signature: Signature,
+        location: &str,
+    ) -> Result<(), DedupError> {
+        let key = (signature, location.to_string(), sink.to_string());
+        if self.keys.contains(&key) {
+            return Ok(());
+        }
+        writeln!(self.file, "{signature}\t{location}\t{sink}")
+            .and_then(|()| self.file.flush())
+            .map_err(|e| write_error(&self.path, e))?;
+        self.lines += 1;
+        remember(&mut self.keys, &mut self.order, self.capacity, key);
+        if self.lines >= 2 * self.capacity {
+            self.file = rewrite(&self.path, &self.order)?;
+            self.lines = self.order.len();
+        }
+        Ok(())
+    }
+}
+
+/// Adds `key` to the remembered events, forgetting the oldest past
+/// `capacity`.
+fn remember(
+    keys: &mut HashSet<EventKey>,
+    order: &mut VecDeque<EventKey>,
+    capacity: usize,
+    key: EventKey,
+) {
+    if !keys.insert(key.clone()) {
+        return;
+    }
+    order.push_back(key);
+    if order.len() > capacity {
+        if let Some(old) = order.pop_front() {
+            keys.remove(&old);
+        }
+    }
+}
+
+/// Replaces the file at `path` with just the events in `order`, through a
+/// temporary file next to it so a crash leaves the previous one, and opens it
+/// to append to.
+fn rewrite(path: &Path, order: &VecDeque<EventKey>) -> Result<File, DedupError> {
+    let mut raw = String::new();
+    for (signature, location, sink) in order {
+        raw.push_str(&format!("{signature}\t{location}\t{sink}\n"));
+    }
+    let tmp = temp_path(path);
+    std::fs::write(&tmp, raw)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .and_then(|()| OpenOptions::new().append(true).open(path))
+        .map_err(|e| write_error(path, e))
+}
+
+fn parse_line(line: &str) -> Option<EventKey> {
+    // Neither a signature nor a location has a tab, so the sink gets the rest
+    let (signature, rest) = line.split_once('\t')?;
+    let (location, sink) = rest.split_once('\t')?;
+    Some((
+        Signature::from_str(signature).ok()?,
+        location.to_string(),
+        sink.to_string(),
+    ))
+}
+
+fn write_error(path: &Path, e: std::io::Error) -> DedupError {
+    DedupError::Write {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scripts-dedup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn signature(byte: u8) -> Signature {
+        Signature::from([byte; 64])
+    }
+
+    #[test]
+    fn deliveries_are_kept_per_sink() {
+        let path = scratch("per-sink.sent");
+        let mut sent = SentEvents::open(&path, 16).unwrap();
+        sent.insert("stdout", signature(1), "1.1").unwrap();
+
+        assert!(sent.contains("stdout", signature(1), "1.1"));
+        assert!(!sent.contains("webhook", signature(1), "1.1"));
+        assert!(!sent.contains("stdout", signature(1), "1.2"));
+        assert!(!sent.contains("stdout", signature(2), "1.1"));
+    }
+
+    #[test]
+    fn deliveries_survive_reopening() {
+        let path = scratch("reopen.sent");
+        let mut sent = SentEvents::open(&path, 16).unwrap();
+        sent.insert("stdout", signature(1), "1.1").unwrap();
+        sent.insert("webhook", signature(2), "Program data: abc")
+            .unwrap();
+        drop(sent);
+
+        let sent = SentEvents::open(&path, 16).unwrap();
+        assert!(sent.contains("stdout", signature(1), "1.1"));
+        assert!(sent.contains("webhook", signature(2), "Program data: abc"));
+    }
+
+    #[test]
+    fn only_the_latest_capacity_are_remembered() {
+        let path = scratch("capacity.sent");
+        let mut sent = SentEvents::open(&path, 2).unwrap();
+        for byte in 1..=5 {
+            sent.insert("stdout", signature(byte), "1.1").unwrap();
+        }
+        assert!(!sent.contains("stdout", signature(3), "1.1"));
+        assert!(sent.contains("stdout", signature(4), "1.1"));
+        assert!(sent.contains("stdout", signature(5), "1.1"));
+
+        // Compacted at twice the capacity, and again on open
+        drop(sent);
+        let sent = SentEvents::open(&path, 2).unwrap();
+        assert!(sent.contains("stdout", signature(5), "1.1"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        let path = scratch("malformed.sent");
+        std::fs::write(
+            &path,
+            format!("{}\t1.1\tstdout\nnot a line\n", signature(1)),
+        )
+        .unwrap();
+        assert!(matches!(
+            SentEvents::open(&path, 16),
+            Err(DedupError::Parse { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+//! Program deployment through the upgradeable BPF loader, without the Solana
+//! CLI.
+//!
+//! The `.so` is written into a fresh buffer account in chunks sent in
+//! parallel, then either deployed to the program keypair's address or, when a
+//! program already lives there, swapped in as an upgrade (growing the program
+//! data account first if the new build is larger). The payer is the buffer and
+//! upgrade authority. When a write fails the buffer is closed again, so its
+//! rent isn't stranded. A deployed program only runs from the slot after its
+//! deployment, so `deploy_program` waits for that slot before returning.
+
+use std::path::Path;
+use std::time::Duration;
+
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+use crate::batch::BatchSender;
+use crate::error::{DeployError, RpcError};
+use crate::rpc::ResilientRpc;
+use crate::tx::TxBuilder;
+
+/// Program bytes per write, which keeps each transaction under the size limit.
+const WRITE_CHUNK: usize = 900;
+const VISIBILITY_TIMEOUT: Duration = Duration::from_secs(60);
+const VISIBILITY_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deployment {
+    pub program_id: Pubkey,
+    /// Whether an existing program was upgraded rather than a new one deployed.
+    pub upgraded: bool,
+    pub signature: Signature,
+    /// Slot of the deployment; the program runs from the next one.
+    pub slot: u64,
+}
+
+/// Reads a compiled program.
+pub fn read_program(path: &Path) -> Result<Vec<u8>, DeployError> {
+    std::fs::read(path).map_err(|e| DeployError::Read {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Deploys `program` at `program_keypair`'s address, or upgrades the program
+/// already there, and waits until it can be invoked.
+pub async fn deploy_program(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    program_keypair: &Keypair,
+    program: &[u8],
+    concurrency: usize,
+) -> Result<Deployment, DeployError> {
+    let program_id = program_keypair.pubkey();
+    let authority = payer.pubkey();
+    let existing = rpc
+        .retry(|| rpc.get_account_with_commitment(&program_id, rpc.commitment()))
+        .await?
+        .value;
+    if let Some(account) = &existing {
+        if account.owner != bpf_loader_upgradeable::id() {
+            return Err(DeployError::NotUpgradeable {
+                program_id,
+                owner: account.owner,
+            });
+        }
+    }
+
+    let buffer = write_buffer(rpc, payer, program, concurrency).await?;
+
+    let signature = match existing {
+        None => {
+            let program_lamports = rpc
+                .retry(|| {
+                    rpc.get_minimum_balance_for_rent_exemption(
+                        UpgradeableLoaderState::size_of_program(),
+                    )
+                })
+                .await?;
+            // Leaves room for later upgrades to grow without extending
+            let ixs = bpf_loader_upgradeable::deploy_with_max_program_len(
+                &authority,
+                &program_id,
+                &buffer,
+                &authority,
+                program_lamports,
+                program.len() * 2,
+            )
+            .map_err(|e| DeployError::Loader(e.to_string()))?;
+            let builder = TxBuilder::new(payer)
+                .instructions(ixs)
+                .signer(program_keypair);
+            rpc.send(&builder).await?
+        }
+        Some(_) => {
+            let program_data = bpf_loader_upgradeable::get_program_data_address(&program_id);
+            let current = rpc.retry(|| rpc.get_account(&program_data)).await?;
+            check_authority(&program_id, &current.data, &authority)?;
+            let mut ixs = Vec::new();
+            let capacity = current
+                .data
+                .len()
+                .saturating_sub(UpgradeableLoaderState::size_of_programdata_metadata());
+            if program.len() > capacity {
+                ixs.push(bpf_loader_upgradeable::extend_program(
+                    &program_id,
+                    Some(&authority),
+                    (program.len() - capacity) as u32,
+                ));
+            }
+            ixs.push(bpf_loader_upgradeable::upgrade(
+                &program_id,
+                &buffer,
+                &authority,
+                &authority,
+            ));
+            rpc.send_ix(payer, &ixs).await?
+        }
+    };
+
+    let slot = wait_visible(rpc, &program_id).await?;
+    Ok(Deployment {
+        program_id,
+        upgraded: existing.is_some(),
+        signature,
+        slot,
+    })
+}
+
+/// Creates a buffer holding `program`, with the payer as its authority.
+async fn write_buffer(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    program: &[u8],
+    concurrency: usize,
+) -> Result<Pubkey, DeployError> {
+    let buffer = Keypair::new();
+    let authority = payer.pubkey();
+    let lamports = rpc
+        .retry(|| {
+            rpc.get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_buffer(
+                program.len(),
+            ))
+        })
+        .await?;
+    let ixs = bpf_loader_upgradeable::create_buffer(
+        &authority,
+        &buffer.pubkey(),
+        &authority,
+        lamports,
+        program.len(),
+    )
+    .map_err(|e| DeployError::Loader(e.to_string()))?;
+    rpc.send(&TxBuilder::new(payer).instructions(ixs).signer(&buffer))
+        .await?;
+
+    let writes: Vec<Vec<Instruction>> = program
+        .chunks(WRITE_CHUNK)
+        .enumerate()
+        .map(|(n, chunk)| {
+            vec![bpf_loader_upgradeable::write(
+                &buffer.pubkey(),
+                &authority,
+                (n * WRITE_CHUNK) as u32,
+                chunk.to_vec(),
+            )]
+        })
+        .collect();
+    let results = BatchSender::new(rpc, payer)
+        .concurrency(concurrency)
+        .send_all(writes)
+        .await;
+    if let Some(e) = results.into_iter().find_map(Result::err) {
+        return Err(close_buffer(rpc, payer, &buffer.pubkey(), e).await);
+    }
+    Ok(buffer.pubkey())
+}
+
+/// Closes a buffer whose writes failed, returning its lamports to the payer.
+/// Should that fail too, the error names the buffer so it can be closed by
+/// hand.
+async fn close_buffer(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    buffer: &Pubkey,
+    write_error: RpcError,
+) -> DeployError {
+    let authority = payer.pubkey();
+    let close = bpf_loader_upgradeable::close(buffer, &authority, &authority);
+    match rpc.send_ix(payer, &[close]).await {
+        Ok(_) => write_error.into(),
+        Err(close_error) => DeployError::BufferLeft {
+            buffer: *buffer,
+            reason: write_error.to_string(),
+            close_reason: close_error.to_string(),
+        },
+    }
+}
+
+/// Fails unless `program_data` names `authority` as the upgrade authority.
+fn check_authority(
+    program_id: &Pubkey,
+    program_data: &[u8],
+    authority: &Pubkey,
+) -> Result<(), DeployError> {
+    // ProgramData: u32 variant tag, u64 slot, Option<Pubkey> authority
+    let current = match program_data.get(12) {
+        Some(1) => program_data
+            .get(13..45)
+            .map(|key| Pubkey::try_from(key).unwrap()),
+        _ => None,
+    };
+    match current {
+        Some(current) if current == *authority => Ok(()),
+        current => Err(DeployError::WrongAuthority {
+            program_id: *program_id,
+            authority: current,
+        }),
+    }
+}
+
+/// Waits until the program account is executable and a slot past its
+/// deployment has been reached, returning the deployment slot.
+async fn wait_visible(rpc: &ResilientRpc, program_id: &Pubkey) -> Result<u64, DeployError> {
+    let program_data = bpf_loader_upgradeable::get_program_data_address(program_id);
+    let deadline = tokio::time::Instant::now() + VISIBILITY_TIMEOUT;
+    loop {
+        let program = rpc
+            .retry(|| rpc.get_account_with_commitment(program_id, rpc.commitment()))
+            .await?
+            .value;
+        let data = rpc
+            .retry(|| rpc.get_account_with_commitment(&program_data, rpc.commitment()))
+            .await?
+            .value;
+        let deployed_slot = data
+            .and_then(|data| data.data.get(4..12).map(|slot| slot.try_into().unwrap()))
+            .map(u64::from_le_bytes);
+        if let (Some(program), Some(deployed_slot)) = (program, deployed_slot) {
+            let slot = rpc.retry(|| rpc.get_slot()).await?;
+            if program.executable && slot > deployed_slot {
+                return Ok(deployed_slot);
+            }
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(DeployError::NotVisible {
+                program_id: *program_id,
+                secs: VISIBILITY_TIMEOUT.as_secs(),
+            });
+        }
+        tokio::time::sleep(VISIBILITY_POLL_INTERVAL).await;
+    }
+}
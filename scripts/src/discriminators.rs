@@ -0,0 +1,138 @@
+//! Anchor discriminators of instructions, events and accounts, and the names
+//! in the workspace's programs they belong to.
+//!
+//! Anchor prefixes instruction data with `sha256("global:<fn name>")[..8]`,
+//! event data with `sha256("event:<TypeName>")[..8]` and account data with
+//! `sha256("account:<TypeName>")[..8]`. The names are read from the programs'
+//! sources rather than their IDLs, so looking up a prefix works without
+//! `anchor build`.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::encoding::{anchor_account_discriminator, anchor_event_discriminator, anchor_sighash};
+use crate::error::DiscriminatorError;
+
+/// What a discriminator prefixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Instruction,
+    Event,
+    Account,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 3] = [Kind::Instruction, Kind::Event, Kind::Account];
+
+    /// The discriminator Anchor derives for `name` of this kind.
+    pub fn discriminator(self, name: &str) -> [u8; 8] {
+        match self {
+            Kind::Instruction => anchor_sighash(name),
+            Kind::Event => anchor_event_discriminator(name),
+            Kind::Account => anchor_account_discriminator(name),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `pad` so callers can align the kinds in columns
+        f.pad(match self {
+            Kind::Instruction => "instruction",
+            Kind::Event => "event",
+            Kind::Account => "account",
+        })
+    }
+}
+
+/// An instruction, event or account declared by a workspace program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownName {
+    /// The crate declaring it, e.g. `program_tester`.
+    pub program: String,
+    pub kind: Kind,
+    pub name: String,
+    pub discriminator: [u8; 8],
+}
+
+/// Every instruction, event and account declared in `root/programs/*/src/lib.rs`.
+pub fn workspace_names(root: &Path) -> Result<Vec<KnownName>, DiscriminatorError> {
+    let programs_dir = root.join("programs");
+    let entries = std::fs::read_dir(&programs_dir).map_err(|e| read_error(&programs_dir, e))?;
+    let mut libs = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| read_error(&programs_dir, e))?;
+        let lib = entry.path().join("src/lib.rs");
+        if let (true, Some(program)) = (lib.is_file(), entry.file_name().to_str()) {
+            libs.push((program.to_string(), lib));
+        }
+    }
+    libs.sort();
+
+    let mut names = Vec::new();
+    for (program, lib) in libs {
+        let source = std::fs::read_to_string(&lib).map_err(|e| read_error(&lib, e))?;
+        for (kind, name) in declared_names(&source) {
+            names.push(KnownName {
+                program: program.clone(),
+                kind,
+                discriminator: kind.discriminator(&name),
+                name,
+            });
+        }
+    }
+    Ok(names)
+}
+
+/// The entries of `names` whose discriminator starts `data`.
+pub fn lookup<'a>(names: &'a [KnownName], data: &[u8]) -> Vec<&'a KnownName> {
+    names
+        .iter()
+        .filter(|known| data.starts_with(&known.discriminator))
+        .collect()
+}
+
+/// The `#[event]` and `#[account]` structs and the instruction handlers, the
+/// `pub fn`s taking a `Context`, in a program's source.
+fn declared_names(source: &str) -> Vec<(Kind, String)> {
+    let mut names = Vec::new();
+    // Set by `#[event]` or `#[account]` until the struct they annotate
+    let mut pending = None;
+    for line in source.lines().map(str::trim) {
+        if line == "#[event]" {
+            pending = Some(Kind::Event);
+        } else if line == "#[account]" || line.starts_with("#[account(zero_copy") {
+            pending = Some(Kind::Account);
+        } else if let Some(rest) = line.strip_prefix("pub struct ") {
+            if let Some(kind) = pending.take() {
+                names.push((kind, identifier(rest).to_string()));
+            }
+        } else if !line.starts_with("#[") && !line.starts_with("//") {
+            pending = None;
+        }
+    }
+
+    let mut rest = source;
+    while let Some((_, after)) = rest.split_once("pub fn ") {
+        let signature = after.split('{').next().unwrap_or(after);
+        if signature.contains("Context<") {
+            names.push((Kind::Instruction, identifier(after).to_string()));
+        }
+        rest = after;
+    }
+    names
+}
+
+fn identifier(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+fn read_error(path: &Path, err: std::io::Error) -> DiscriminatorError {
+    DiscriminatorError::Read {
+        path: path.display().to_string(),
+        reason: err.to_string(),
+    }
+}
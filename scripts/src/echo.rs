@@ -0,0 +1,52 @@
+//! Typed instruction builder for echo_executable.
+//!
+//! Executing a message makes echo call the gateway back, so the builder takes
+//! the gateway's program id for the incoming message and call_contract accounts.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use echo_executable::{accounts, instruction};
+use program_tester::Message;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{
+    call_contract_signing_pda, echo_consumed_message_pda, event_authority_pda, gateway_root_pda,
+    incoming_message_pda, payload_log_pda,
+};
+
+/// `message` must have been approved by the gateway at `gateway_program_id`
+/// with the keccak256 of `payload` as its payload hash, and not echoed yet.
+pub fn build_execute_ix(
+    program_id: &Pubkey,
+    gateway_program_id: &Pubkey,
+    payer: &Pubkey,
+    message: Message,
+    payload: Vec<u8>,
+) -> Instruction {
+    let command_id = message.command_id();
+    let accounts = accounts::Execute {
+        payer: *payer,
+        incoming_message: incoming_message_pda(gateway_program_id, &command_id).0,
+        consumed_message_pda: echo_consumed_message_pda(program_id, &command_id).0,
+        program_tester_program: *gateway_program_id,
+        echo_program: *program_id,
+        signing_pda: call_contract_signing_pda(program_id).0,
+        gateway_root_pda: gateway_root_pda(gateway_program_id).0,
+        payload_log: Some(payload_log_pda(gateway_program_id).0),
+        gateway_event_authority: event_authority_pda(gateway_program_id).0,
+        system_program: system_program::id(),
+        event_authority: event_authority_pda(program_id).0,
+        program: *program_id,
+    };
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::Execute {
+            command_id,
+            message,
+            payload,
+        }
+        .data(),
+    }
+}
@@ -0,0 +1,83 @@
+//! Hand-rolled Anchor/borsh encoding used to build raw instruction data.
+
+use sha2::{Digest, Sha256};
+
+/// Anchor method discriminator = sha256("global:<method_name>")[..8]
+pub fn anchor_sighash(name: &str) -> [u8; 8] {
+    discriminator("global", name)
+}
+
+/// Anchor event struct discriminator = sha256("event:<TypeName>")[..8]
+pub fn anchor_event_discriminator(type_name: &str) -> [u8; 8] {
+    discriminator("event", type_name)
+}
+
+/// Anchor account struct discriminator = sha256("account:<TypeName>")[..8]
+pub fn anchor_account_discriminator(type_name: &str) -> [u8; 8] {
+    discriminator("account", type_name)
+}
+
+fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{namespace}:{name}"));
+    let digest = hasher.finalize();
+    let mut out = [0u8; 8];
+    out.copy_from_slice(&digest[..8]);
+    out
+}
+
+/// Borsh `String`: u32 little-endian length prefix followed by the UTF-8 bytes.
+pub fn serialize_string(value: &str, out: &mut Vec<u8>) {
+    serialize_vec_u8(value.as_bytes(), out);
+}
+
+/// Borsh `Vec<u8>`: u32 little-endian length prefix followed by the bytes.
+pub fn serialize_vec_u8(value: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Discriminator;
+    use program_tester::{instruction, CallContractEvent, GatewayConfig};
+
+    use super::*;
+
+    #[test]
+    fn discriminators_match_anchor() {
+        assert_eq!(
+            anchor_sighash("call_contract"),
+            instruction::CallContract::DISCRIMINATOR
+        );
+        assert_eq!(
+            anchor_event_discriminator("CallContractEvent"),
+            CallContractEvent::DISCRIMINATOR
+        );
+        assert_eq!(
+            anchor_account_discriminator("GatewayConfig"),
+            GatewayConfig::DISCRIMINATOR
+        );
+    }
+
+    #[test]
+    fn serialization_matches_borsh() {
+        for value in ["", "ethereum", "🦀 crab"] {
+            let mut out = vec![];
+            serialize_string(value, &mut out);
+            assert_eq!(out, borsh::to_vec(&value.to_string()).unwrap());
+        }
+        for value in [vec![], vec![1, 2, 3], vec![0xff; 300]] {
+            let mut out = vec![];
+            serialize_vec_u8(&value, &mut out);
+            assert_eq!(out, borsh::to_vec(&value).unwrap());
+        }
+    }
+
+    #[test]
+    fn serialization_appends() {
+        let mut out = vec![9];
+        serialize_string("ab", &mut out);
+        assert_eq!(out, [9, 2, 0, 0, 0, b'a', b'b']);
+    }
+}
@@ -0,0 +1,335 @@
+//! Error types of the scripts library.
+//!
+//! Binaries keep using `anyhow`; these types let tools built on the library
+//! tell an RPC failure from a bad keypair or malformed input.
+
+use solana_client::client_error::ClientError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    #[error(transparent)]
+    Keypair(#[from] KeypairError),
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+    #[error(transparent)]
+    PdaMismatch(#[from] PdaMismatch),
+    #[error(transparent)]
+    Idl(#[from] IdlError),
+    #[error(transparent)]
+    Account(#[from] AccountError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Verify(#[from] VerifyError),
+    #[error(transparent)]
+    Funding(#[from] FundingError),
+    #[error(transparent)]
+    Deploy(#[from] DeployError),
+    #[error(transparent)]
+    Ids(#[from] IdsError),
+    #[error(transparent)]
+    Discriminator(#[from] DiscriminatorError),
+    #[error(transparent)]
+    Offline(#[from] OfflineError),
+    #[error(transparent)]
+    IdlAccount(#[from] IdlAccountError),
+    #[error(transparent)]
+    Checkpoint(#[from] CheckpointError),
+    #[error(transparent)]
+    Dedup(#[from] DedupError),
+    #[error(transparent)]
+    Sink(#[from] SinkError),
+    #[error(transparent)]
+    Export(#[from] ExportError),
+    #[error(transparent)]
+    Geyser(#[from] GeyserError),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A request to the cluster failed.
+#[derive(Debug, Error)]
+#[error("rpc request failed: {0}")]
+pub struct RpcError(#[source] pub Box<ClientError>);
+
+impl From<ClientError> for RpcError {
+    fn from(err: ClientError) -> Self {
+        Self(Box::new(err))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum KeypairError {
+    #[error("failed to read keypair from {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("failed to write keypair to {path}: {reason}")]
+    Write { path: String, reason: String },
+    #[error("invalid {kind}: {reason}")]
+    InvalidSecret { kind: &'static str, reason: String },
+    #[error("no keypair at {path}; pass --keypair, set SOLANA_KEYPAIR or run `solana-keygen new`")]
+    NotFound { path: String },
+}
+
+#[derive(Debug, Error)]
+pub enum EncodingError {
+    #[error("hex string {input:?} has an odd number of digits")]
+    OddHexLength { input: String },
+    #[error("invalid hex {input:?}")]
+    InvalidHex { input: String },
+    #[error("invalid base58 {input:?}: {reason}")]
+    InvalidBase58 { input: String, reason: String },
+    #[error("expected {expected} bytes, {input:?} decodes to {actual}")]
+    InvalidLength {
+        input: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("invalid message id {input:?}: {reason}")]
+    InvalidMessageId { input: String, reason: String },
+    #[error("expected {expected}, got {value}")]
+    InvalidJson {
+        expected: &'static str,
+        value: String,
+    },
+}
+
+/// A program account couldn't be fetched or decoded.
+#[derive(Debug, Error)]
+pub enum AccountError {
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    #[error("account {address} does not exist")]
+    NotFound { address: Pubkey },
+    #[error("account {address} has discriminator {actual:?}, expected {expected:?}")]
+    Discriminator {
+        address: Pubkey,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
+    #[error("failed to deserialize account {address}: {reason}")]
+    Deserialize { address: Pubkey, reason: String },
+    #[error(
+        "account {address} has discriminator {discriminator:?}, which is no known account type"
+    )]
+    Unknown {
+        address: Pubkey,
+        discriminator: Vec<u8>,
+    },
+}
+
+/// An account address doesn't match the PDA derived for it.
+#[derive(Debug, Error)]
+#[error("{name}: expected PDA {expected}, got {actual}")]
+pub struct PdaMismatch {
+    pub name: &'static str,
+    pub expected: Pubkey,
+    pub actual: Pubkey,
+}
+
+#[derive(Debug, Error)]
+pub enum IdlError {
+    #[error("failed to read IDL from {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("malformed IDL: {0}")]
+    Parse(String),
+    #[error("type {0} is not defined in the IDL")]
+    UnknownType(String),
+    #[error("unsupported IDL {0}")]
+    Unsupported(String),
+    #[error("data doesn't match the IDL layout: {0}")]
+    InvalidData(String),
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config from {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("invalid config in {path}: {reason}")]
+    Parse { path: String, reason: String },
+    #[error("failed to write config to {path}: {reason}")]
+    Write { path: String, reason: String },
+    #[error("invalid {key} {value:?}: {reason}")]
+    InvalidValue {
+        key: &'static str,
+        value: String,
+        reason: String,
+    },
+    #[error("unknown cluster {0:?}, expected localnet, devnet, testnet or mainnet")]
+    UnknownCluster(String),
+    #[error(transparent)]
+    AnchorToml(#[from] IdsError),
+}
+
+/// A transaction's events don't carry the arguments it was sent with.
+#[derive(Debug, Error)]
+#[error("{signature} emitted events that don't match its instructions: {}", .problems.join("; "))]
+pub struct VerifyError {
+    pub signature: Signature,
+    pub problems: Vec<String>,
+}
+
+/// Lamports couldn't be had from the faucet or treasury.
+#[derive(Debug, Error)]
+pub enum FundingError {
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    #[error("faucet still rate-limited after {attempts} attempts; wait or set a treasury keypair")]
+    RateLimited { attempts: u32 },
+    #[error("airdrop {signature} failed: {reason}")]
+    AirdropFailed {
+        signature: Signature,
+        reason: String,
+    },
+    #[error("airdrop {signature} was not confirmed within {secs}s")]
+    NotConfirmed { signature: Signature, secs: u64 },
+    #[error("treasury {treasury} holds {balance} lamports, {needed} needed")]
+    TreasuryShort {
+        treasury: Pubkey,
+        balance: u64,
+        needed: u64,
+    },
+}
+
+/// A program couldn't be deployed or upgraded.
+#[derive(Debug, Error)]
+pub enum DeployError {
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    #[error("failed to read program from {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("invalid loader instruction: {0}")]
+    Loader(String),
+    #[error("{program_id} is owned by {owner}, not the upgradeable loader")]
+    NotUpgradeable { program_id: Pubkey, owner: Pubkey },
+    #[error("{program_id} has upgrade authority {authority:?}, not the payer")]
+    WrongAuthority {
+        program_id: Pubkey,
+        authority: Option<Pubkey>,
+    },
+    #[error("{program_id} was not invocable within {secs}s of deploying")]
+    NotVisible { program_id: Pubkey, secs: u64 },
+    #[error(
+        "writing buffer {buffer} failed ({reason}) and closing it failed ({close_reason}); \
+         reclaim its lamports with `solana program close {buffer}`"
+    )]
+    BufferLeft {
+        buffer: Pubkey,
+        reason: String,
+        close_reason: String,
+    },
+}
+
+/// The program ids recorded in the workspace couldn't be read.
+#[derive(Debug, Error)]
+pub enum IdsError {
+    #[error(transparent)]
+    Keypair(#[from] KeypairError),
+    #[error("failed to read {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("failed to parse {path}: {reason}")]
+    Parse { path: String, reason: String },
+    #[error("failed to write {path}: {reason}")]
+    Write { path: String, reason: String },
+}
+
+/// The workspace's program sources could not be scanned for names.
+#[derive(Debug, Error)]
+pub enum DiscriminatorError {
+    #[error("failed to read {path}: {reason}")]
+    Read { path: String, reason: String },
+}
+
+/// A transaction file for offline signing couldn't be written or read back.
+#[derive(Debug, Error)]
+pub enum OfflineError {
+    #[error("failed to read {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("failed to write {path}: {reason}")]
+    Write { path: String, reason: String },
+    #[error("{path} is not a serialized transaction: {reason}")]
+    Decode { path: String, reason: String },
+    #[error("{path} lacks valid signatures from {missing:?}")]
+    Unsigned { path: String, missing: Vec<Pubkey> },
+}
+
+/// A program's on-chain IDL couldn't be read or published.
+#[derive(Debug, Error)]
+pub enum IdlAccountError {
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    #[error(transparent)]
+    Account(#[from] AccountError),
+    #[error("the IDL of {program_id} belongs to {authority}, not the payer")]
+    NotAuthority {
+        program_id: Pubkey,
+        authority: Pubkey,
+    },
+    #[error("IDL account {address} holds no readable IDL: {reason}")]
+    Corrupt { address: Pubkey, reason: String },
+}
+
+/// A listener checkpoint file couldn't be read or written.
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("failed to read checkpoint {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("invalid checkpoint in {path}: {reason}")]
+    Parse { path: String, reason: String },
+    #[error("failed to write checkpoint {path}: {reason}")]
+    Write { path: String, reason: String },
+}
+
+/// The file of the events a listener sent couldn't be read or written.
+#[derive(Debug, Error)]
+pub enum DedupError {
+    #[error("failed to read sent events {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("invalid sent event in {path} at line {line}")]
+    Parse { path: String, line: usize },
+    #[error("failed to write sent events {path}: {reason}")]
+    Write { path: String, reason: String },
+}
+
+/// A listener sink couldn't be set up or take an event.
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[error("invalid sink {spec:?}; see `axelar-dummy listen --help` for the kinds")]
+    InvalidSpec { spec: String },
+    #[error("failed to open sink {sink}: {reason}")]
+    Open { sink: String, reason: String },
+    #[error("sink {sink} failed: {reason}")]
+    Emit { sink: String, reason: String },
+}
+
+/// Captured events couldn't be read or exported.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("failed to read {path}: {reason}")]
+    Read { path: String, reason: String },
+    #[error("{path}:{line}: not an event `listen` wrote: {reason}")]
+    Parse {
+        path: String,
+        line: usize,
+        reason: String,
+    },
+    #[error("failed to write {path}: {reason}")]
+    Write { path: String, reason: String },
+    #[error("{format} export needs the `{format}` feature")]
+    Unsupported { format: crate::export::ExportFormat },
+}
+
+/// A Yellowstone gRPC subscription couldn't be set up or broke off.
+#[derive(Debug, Error)]
+pub enum GeyserError {
+    #[error("failed to subscribe at {endpoint}: {reason}")]
+    Subscribe { endpoint: String, reason: String },
+    #[error("Geyser stream failed: {0}")]
+    Stream(String),
+    #[error("Geyser ingestion needs the `geyser` feature")]
+    Unsupported,
+}
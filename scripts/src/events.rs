@@ -0,0 +1,370 @@
+//! Decoding of Anchor events emitted by program_tester and gas_service.
+//!
+//! Events are matched on their 8-byte discriminator and deserialized into the
+//! program's own event structs, so there's no offset arithmetic to keep in sync
+//! with the on-chain layout.
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::codec::{hex_array, hex_decode, hex_encode_prefixed, Mode};
+use crate::error::EncodingError;
+use crate::pdas::event_authority_pda;
+
+macro_rules! event_registry {
+    ($($variant:ident => $ty:path { $($field:ident),* $(,)? }),+ $(,)?) => {
+        /// Every event the dummy programs can emit.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum DecodedEvent {
+            $($variant($ty),)+
+        }
+
+        impl DecodedEvent {
+            /// The event's type name, as used for its discriminator.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(DecodedEvent::$variant(_) => stringify!($variant),)+
+                }
+            }
+
+            /// The event's fields as a JSON object, see `ToJson` for the encoding.
+            pub fn to_json(&self) -> Value {
+                let mut fields = Map::new();
+                match self {
+                    $(DecodedEvent::$variant(event) => {
+                        $(fields.insert(stringify!($field).to_string(), event.$field.to_json());)*
+                    })+
+                }
+                Value::Object(fields)
+            }
+        }
+
+        /// Discriminators of every known event, paired with the event name.
+        pub fn registry() -> Vec<(&'static [u8], &'static str)> {
+            vec![$((<$ty as Discriminator>::DISCRIMINATOR, stringify!($variant)),)+]
+        }
+
+        /// Decodes `bytes` (discriminator followed by the borsh payload) into a
+        /// known event. Returns `None` for unknown discriminators or malformed data.
+        pub fn decode_event(bytes: &[u8]) -> Option<DecodedEvent> {
+            $(
+                if let Some(mut data) = bytes.strip_prefix(<$ty as Discriminator>::DISCRIMINATOR) {
+                    return <$ty as AnchorDeserialize>::deserialize(&mut data)
+                        .ok()
+                        .map(DecodedEvent::$variant);
+                }
+            )+
+            None
+        }
+    };
+}
+
+event_registry! {
+    CallContractEvent => program_tester::CallContractEvent {
+        sender, payload_hash, destination_chain, destination_contract_address, payload,
+    },
+    CallContractWithTokenEvent => program_tester::CallContractWithTokenEvent {
+        sender, payload_hash, destination_chain, destination_contract_address, payload, symbol,
+        amount,
+    },
+    MessageApprovedEvent => program_tester::MessageApprovedEvent {
+        command_id, destination_address, payload_hash, source_chain, cc_id, source_address,
+        destination_chain,
+    },
+    MessageExecutedEvent => program_tester::MessageExecutedEvent {
+        command_id, destination_address, payload_hash, source_chain, cc_id, source_address,
+        destination_chain,
+    },
+    VerifierSetRotatedEvent => program_tester::VerifierSetRotatedEvent {
+        epoch, verifier_set_hash,
+    },
+    InterchainTransfer => program_tester::InterchainTransfer {
+        token_id, source_address, source_token_account, destination_chain, destination_address,
+        amount, data_hash,
+    },
+    LinkTokenStarted => program_tester::LinkTokenStarted {
+        token_id, destination_chain, source_token_address, destination_token_address,
+        token_manager_type, params,
+    },
+    InterchainTokenDeploymentStarted => program_tester::InterchainTokenDeploymentStarted {
+        token_id, token_name, token_symbol, token_decimals, minter, destination_chain,
+    },
+    TokenMetadataRegistered => program_tester::TokenMetadataRegistered {
+        token_address, decimals,
+    },
+    ComputeBurnedEvent => program_tester::ComputeBurnedEvent { iterations, result_hash },
+    GasPaidEvent => gas_service::GasPaidEvent {
+        sender, destination_chain, destination_address, payload_hash, amount, refund_address,
+        spl_token_account,
+    },
+    GasAddedEvent => gas_service::GasAddedEvent {
+        sender, message_id, amount, refund_address, spl_token_account,
+    },
+    GasRefundedEvent => gas_service::GasRefundedEvent {
+        receiver, message_id, amount, spl_token_account,
+    },
+    OperatorshipTransferredEvent => common::OperatorshipTransferredEvent {
+        config, previous_operator, new_operator,
+    },
+    FlowLimitSet => its_tester::FlowLimitSet { token_id, operator, flow_limit },
+}
+
+/// The event bytes of an inner instruction `program_id` was invoked with, if
+/// it is an `emit_cpi!` self-invocation: one signed by the program's
+/// `__event_authority` PDA, with data starting with Anchor's event tag. The
+/// tag alone also matches ordinary self-CPIs whose data happens to begin
+/// with it.
+pub fn cpi_event_data<'a>(
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    ix_data: &'a [u8],
+) -> Option<&'a [u8]> {
+    if !accounts.contains(&event_authority_pda(program_id).0) {
+        return None;
+    }
+    ix_data.strip_prefix(EVENT_IX_TAG_LE)
+}
+
+/// Decodes an `emit_cpi!` self-invocation, see `cpi_event_data`.
+pub fn decode_cpi_event(
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    ix_data: &[u8],
+) -> Option<DecodedEvent> {
+    decode_event(cpi_event_data(program_id, accounts, ix_data)?)
+}
+
+/// JSON encoding of event fields: pubkeys in base58, hashes and byte strings as
+/// `0x`-prefixed hex, numbers and strings as themselves.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+impl ToJson for Pubkey {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl<const N: usize> ToJson for [u8; N] {
+    fn to_json(&self) -> Value {
+        Value::String(hex_encode_prefixed(self))
+    }
+}
+
+impl ToJson for Vec<u8> {
+    fn to_json(&self) -> Value {
+        Value::String(hex_encode_prefixed(self))
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl ToJson for program_tester::U256 {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+
+macro_rules! number_to_json {
+    ($($ty:ty),+) => {
+        $(impl ToJson for $ty {
+            fn to_json(&self) -> Value {
+                Value::from(*self)
+            }
+        })+
+    };
+}
+
+number_to_json!(u8, u32, u64);
+
+/// The inverse of `ToJson`, for event fields read back from a file.
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, EncodingError>;
+}
+
+fn json_str<'a>(value: &'a Value, expected: &'static str) -> Result<&'a str, EncodingError> {
+    value.as_str().ok_or_else(|| EncodingError::InvalidJson {
+        expected,
+        value: value.to_string(),
+    })
+}
+
+impl FromJson for Pubkey {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        let input = json_str(value, "a base58 pubkey")?;
+        Pubkey::from_str(input).map_err(|e| EncodingError::InvalidBase58 {
+            input: input.to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
+impl<const N: usize> FromJson for [u8; N] {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        hex_array(json_str(value, "a hex string")?, Mode::Strict)
+    }
+}
+
+impl FromJson for Vec<u8> {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        hex_decode(json_str(value, "a hex string")?, Mode::Strict)
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        json_str(value, "a string").map(str::to_string)
+    }
+}
+
+impl FromJson for program_tester::U256 {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        FromJson::from_json(value).map(program_tester::U256)
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+macro_rules! number_from_json {
+    ($($ty:ty),+) => {
+        $(impl FromJson for $ty {
+            fn from_json(value: &Value) -> Result<Self, EncodingError> {
+                value
+                    .as_u64()
+                    .and_then(|n| <$ty>::try_from(n).ok())
+                    .ok_or_else(|| EncodingError::InvalidJson {
+                        expected: concat!("a ", stringify!($ty)),
+                        value: value.to_string(),
+                    })
+            }
+        })+
+    };
+}
+
+number_from_json!(u8, u32);
+
+impl FromJson for u64 {
+    fn from_json(value: &Value) -> Result<Self, EncodingError> {
+        value.as_u64().ok_or_else(|| EncodingError::InvalidJson {
+            expected: "a u64",
+            value: value.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorSerialize;
+
+    use super::*;
+    use crate::golden;
+
+    fn event_bytes<T: Discriminator + AnchorSerialize>(event: &T) -> Vec<u8> {
+        let mut bytes = T::DISCRIMINATOR.to_vec();
+        bytes.extend(event.try_to_vec().unwrap());
+        bytes
+    }
+
+    #[test]
+    fn decode_event_round_trips_every_event() {
+        macro_rules! round_trip {
+            ($($variant:ident => $sample:expr),+ $(,)?) => {
+                $(
+                    let event = $sample;
+                    assert_eq!(
+                        decode_event(&event_bytes(&event)),
+                        Some(DecodedEvent::$variant(event)),
+                        stringify!($variant)
+                    );
+                )+
+            };
+        }
+        round_trip! {
+            CallContractEvent => golden::sample_call_contract_event(),
+            CallContractWithTokenEvent => golden::sample_call_contract_with_token_event(),
+            MessageApprovedEvent => golden::sample_message_approved_event(),
+            MessageExecutedEvent => golden::sample_message_executed_event(),
+            VerifierSetRotatedEvent => golden::sample_verifier_set_rotated_event(),
+            InterchainTransfer => golden::sample_interchain_transfer(),
+            LinkTokenStarted => golden::sample_link_token_started(),
+            InterchainTokenDeploymentStarted => golden::sample_interchain_token_deployment_started(),
+            TokenMetadataRegistered => golden::sample_token_metadata_registered(),
+            ComputeBurnedEvent => golden::sample_compute_burned_event(),
+            GasPaidEvent => golden::sample_gas_paid_event(),
+            GasAddedEvent => golden::sample_gas_added_event(),
+            GasRefundedEvent => golden::sample_gas_refunded_event(),
+            OperatorshipTransferredEvent => golden::sample_operatorship_transferred_event(),
+            FlowLimitSet => golden::sample_flow_limit_set(),
+        }
+    }
+
+    #[test]
+    fn decode_event_rejects_unknown_and_truncated_data() {
+        assert_eq!(decode_event(&[0u8; 8]), None);
+        assert_eq!(decode_event(&[]), None);
+
+        let bytes = event_bytes(&golden::sample_call_contract_event());
+        assert_eq!(decode_event(&bytes[..bytes.len() - 1]), None);
+    }
+
+    #[test]
+    fn cpi_events_need_the_event_authority() {
+        let program_id = Pubkey::new_unique();
+        let event = golden::sample_gas_paid_event();
+        let mut ix_data = EVENT_IX_TAG_LE.to_vec();
+        ix_data.extend(event_bytes(&event));
+
+        let authority = event_authority_pda(&program_id).0;
+        assert_eq!(
+            decode_cpi_event(&program_id, &[authority], &ix_data),
+            Some(DecodedEvent::GasPaidEvent(event))
+        );
+        assert_eq!(
+            decode_cpi_event(&program_id, &[Pubkey::new_unique()], &ix_data),
+            None
+        );
+    }
+
+    #[test]
+    fn json_fields_read_back() {
+        let event = golden::sample_call_contract_event();
+        let DecodedEvent::CallContractEvent(decoded) = decode_event(&event_bytes(&event)).unwrap()
+        else {
+            panic!("decoded to another event");
+        };
+        let json = DecodedEvent::CallContractEvent(decoded).to_json();
+        assert_eq!(Pubkey::from_json(&json["sender"]).unwrap(), event.sender);
+        assert_eq!(
+            <[u8; 32]>::from_json(&json["payload_hash"]).unwrap(),
+            event.payload_hash
+        );
+        assert_eq!(
+            Vec::<u8>::from_json(&json["payload"]).unwrap(),
+            event.payload
+        );
+        assert_eq!(Option::<u64>::from_json(&Value::Null).unwrap(), None);
+        assert!(u8::from_json(&Value::from(256)).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Event dumps for notebooks: the JSON lines `listen` writes, with `--json` or
+//! to a `file:` sink, as CSV or Parquet files partitioned by the UTC day of
+//! the block, in `day=YYYY-MM-DD/` directories that pandas, polars and
+//! DuckDB read as one dataset with a `day` column. Events without a block
+//! time, which `listen --logs-only` can't know, go to `day=unknown`. An event
+//! `listen` retracted, its transaction never finalized, is left out.
+//!
+//! Parquet needs the `parquet` feature.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::error::ExportError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// The text columns of a row, after `block_time` and `slot`.
+pub const TEXT_COLUMNS: [&str; 9] = [
+    "signature",
+    "program",
+    "type",
+    "message_id",
+    "location",
+    "pays_for",
+    "data",
+    "raw",
+    "gmp",
+];
+
+/// One event of a dump. `data` and `gmp` hold JSON, as notebooks parse it
+/// better than a flattening that differs per event type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportRow {
+    pub block_time: Option<i64>,
+    pub slot: u64,
+    /// In the order of `TEXT_COLUMNS`.
+    pub text: [Option<String>; 9],
+}
+
+impl ExportRow {
+    /// The row for one line `listen` wrote.
+    pub fn from_json(event: &Value) -> Result<Self, String> {
+        let slot = event.get("slot").and_then(Value::as_u64).ok_or("no slot")?;
+        if event.get("signature").and_then(Value::as_str).is_none() {
+            return Err("no signature".to_string());
+        }
+        Ok(Self {
+            block_time: event.get("block_time").and_then(Value::as_i64),
+            slot,
+            text: TEXT_COLUMNS.map(|name| match event.get(name) {
+                None | Some(Value::Null) => None,
+                Some(Value::String(text)) => Some(text.clone()),
+                Some(other) => Some(other.to_string()),
+            }),
+        })
+    }
+
+    /// The partition the row goes to: `YYYY-MM-DD` or `unknown`.
+    pub fn day(&self) -> String {
+        self.block_time
+            .map_or_else(|| "unknown".to_string(), utc_date)
+    }
+}
+
+/// Reads every event in a JSON lines file, skipping blank lines and dropping
+/// the ones retracted further down.
+pub fn read_events(path: &Path) -> Result<Vec<ExportRow>, ExportError> {
+    let raw = fs::read_to_string(path).map_err(|e| ExportError::Read {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    let mut rows = Vec::new();
+    for (index, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parse_error = |reason: String| ExportError::Parse {
+            path: path.display().to_string(),
+            line: index + 1,
+            reason,
+        };
+        let event: Value = serde_json::from_str(line).map_err(|e| parse_error(e.to_string()))?;
+        let row = ExportRow::from_json(&event).map_err(parse_error)?;
+        if event.get("retracted").and_then(Value::as_bool) == Some(true) {
+            // Signature and location identify an event
+            rows.retain(|kept: &ExportRow| {
+                kept.text[0] != row.text[0] || kept.text[4] != row.text[4]
+            });
+        } else {
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// Groups rows by day, each day in slot order.
+pub fn partition(rows: Vec<ExportRow>) -> BTreeMap<String, Vec<ExportRow>> {
+    let mut days: BTreeMap<String, Vec<ExportRow>> = BTreeMap::new();
+    for row in rows {
+        days.entry(row.day()).or_default().push(row);
+    }
+    for rows in days.values_mut() {
+        rows.sort_by_key(|row| row.slot);
+    }
+    days
+}
+
+/// Writes `rows` to `events.<ext>` in `out_dir/day=<day>/`, replacing the
+/// file a previous export wrote there.
+pub fn write_partition(
+    out_dir: &Path,
+    day: &str,
+    rows: &[ExportRow],
+    format: ExportFormat,
+) -> Result<PathBuf, ExportError> {
+    let dir = out_dir.join(format!("day={day}"));
+    let path = dir.join(format!("events.{}", format.extension()));
+    let write_error = |reason: String| ExportError::Write {
+        path: path.display().to_string(),
+        reason,
+    };
+    fs::create_dir_all(&dir).map_err(|e| write_error(e.to_string()))?;
+    match format {
+        ExportFormat::Csv => fs::write(&path, csv(rows)).map_err(|e| write_error(e.to_string()))?,
+        #[cfg(feature = "parquet")]
+        ExportFormat::Parquet => write_parquet(&path, rows).map_err(write_error)?,
+        #[cfg(not(feature = "parquet"))]
+        ExportFormat::Parquet => return Err(ExportError::Unsupported { format }),
+    }
+    Ok(path)
+}
+
+fn csv(rows: &[ExportRow]) -> String {
+    let mut out = ["block_time", "slot"]
+        .iter()
+        .chain(TEXT_COLUMNS.iter())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push('\n');
+    for row in rows {
+        let mut fields = vec![
+            row.block_time.map(|t| t.to_string()).unwrap_or_default(),
+            row.slot.to_string(),
+        ];
+        fields.extend(
+            row.text
+                .iter()
+                .map(|text| csv_field(text.as_deref().unwrap_or_default())),
+        );
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// `field` quoted if it needs to be, as RFC 4180 has it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(path: &Path, rows: &[ExportRow]) -> Result<(), String> {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+
+    let mut fields = vec![
+        Field::new("block_time", DataType::Int64, true),
+        Field::new("slot", DataType::UInt64, false),
+    ];
+    fields.extend(
+        TEXT_COLUMNS
+            .iter()
+            .map(|name| Field::new(*name, DataType::Utf8, true)),
+    );
+    let schema = Arc::new(Schema::new(fields));
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(
+            rows.iter().map(|row| row.block_time).collect::<Vec<_>>(),
+        )),
+        Arc::new(UInt64Array::from(
+            rows.iter().map(|row| row.slot).collect::<Vec<_>>(),
+        )),
+    ];
+    for index in 0..TEXT_COLUMNS.len() {
+        columns.push(Arc::new(StringArray::from(
+            rows.iter()
+                .map(|row| row.text[index].as_deref())
+                .collect::<Vec<_>>(),
+        )));
+    }
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| e.to_string())?;
+
+    let file = fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut writer = ArrowWriter::try_new(file, schema, None).map_err(|e| e.to_string())?;
+    writer.write(&batch).map_err(|e| e.to_string())?;
+    writer.close().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// `YYYY-MM-DD` of a Unix time in UTC.
+fn utc_date(unix: i64) -> String {
+    // Days to civil date, from Howard Hinnant's `civil_from_days`
+    let z = unix.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
@@ -0,0 +1,232 @@
+//! Failover between RPC providers for long-running clients such as `listen`,
+//! so one flaky provider doesn't stall them.
+//!
+//! Every endpoint has a score: a failure adds one, a success halves it. An
+//! endpoint whose score reaches `COOLDOWN_SCORE`, or whose slot trails the
+//! most advanced endpoint's by more than the allowed lag, sits out a cooldown
+//! and comes back with a clean score. Requests go to the endpoint with the
+//! lowest score that isn't cooling down, the earliest configured winning ties,
+//! so traffic returns to the primary once it has recovered.
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::client::ws_url_for;
+use crate::rpc::ResilientRpc;
+
+/// Score at which an endpoint is taken out of rotation: three failures in a
+/// row, or more spread out among successes.
+const COOLDOWN_SCORE: f64 = 3.0;
+
+/// An RPC endpoint and the websocket that goes with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub rpc_url: String,
+    pub ws_url: String,
+}
+
+impl Endpoint {
+    /// The endpoint at `rpc_url`, with the websocket where
+    /// `solana-test-validator` and most providers put it.
+    pub fn from_rpc_url(rpc_url: &str) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            ws_url: ws_url_for(rpc_url),
+        }
+    }
+}
+
+/// `<rpc url>` or `<rpc url>|<ws url>`.
+impl FromStr for Endpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let endpoint = match s.split_once('|') {
+            Some((rpc_url, ws_url)) => Self {
+                rpc_url: rpc_url.to_string(),
+                ws_url: ws_url.to_string(),
+            },
+            None => Self::from_rpc_url(s),
+        };
+        for url in [&endpoint.rpc_url, &endpoint.ws_url] {
+            reqwest::Url::parse(url).map_err(|e| format!("invalid URL {url:?}: {e}"))?;
+        }
+        Ok(endpoint)
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rpc_url)
+    }
+}
+
+/// Endpoints in order of preference, with their clients and health.
+pub struct EndpointPool {
+    endpoints: Vec<(Endpoint, ResilientRpc)>,
+    health: Mutex<Vec<Health>>,
+    cooldown: Duration,
+    /// Slots an endpoint may trail the most advanced one by; `None` any.
+    max_lag: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    score: f64,
+    cooling_until: Option<Instant>,
+    /// The endpoint's slot when last polled.
+    slot: Option<u64>,
+}
+
+impl EndpointPool {
+    /// A pool of `endpoints`, the first preferred, each with its client.
+    /// Panics without any.
+    pub fn new(
+        endpoints: Vec<(Endpoint, ResilientRpc)>,
+        cooldown: Duration,
+        max_lag: Option<u64>,
+    ) -> Self {
+        assert!(!endpoints.is_empty(), "an endpoint pool needs an endpoint");
+        Self {
+            health: Mutex::new(endpoints.iter().map(|_| Health::default()).collect()),
+            endpoints,
+            cooldown,
+            max_lag,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    pub fn endpoint(&self, index: usize) -> &Endpoint {
+        &self.endpoints[index].0
+    }
+
+    pub fn rpc(&self, index: usize) -> &ResilientRpc {
+        &self.endpoints[index].1
+    }
+
+    /// The endpoint to use now.
+    pub fn current(&self) -> usize {
+        self.pick(&[])
+    }
+
+    /// The healthiest endpoint not in `skip`; when all of them are cooling
+    /// down, the one that comes back first.
+    fn pick(&self, skip: &[usize]) -> usize {
+        let mut health = self.lock();
+        let now = Instant::now();
+        for endpoint in health.iter_mut() {
+            if endpoint.cooling_until.is_some_and(|until| until <= now) {
+                endpoint.cooling_until = None;
+                endpoint.score = 0.0;
+            }
+        }
+        let candidates = || {
+            health
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !skip.contains(index))
+        };
+        candidates()
+            .filter(|(_, endpoint)| endpoint.cooling_until.is_none())
+            // `min_by` keeps the first of equals
+            .min_by(|(_, a), (_, b)| a.score.total_cmp(&b.score))
+            .or_else(|| candidates().min_by_key(|(_, endpoint)| endpoint.cooling_until))
+            .map_or(0, |(index, _)| index)
+    }
+
+    pub fn succeeded(&self, index: usize) {
+        self.lock()[index].score /= 2.0;
+    }
+
+    /// Records a failure of the endpoint at `index`; whether that took it out
+    /// of rotation.
+    pub fn failed(&self, index: usize) -> bool {
+        let mut health = self.lock();
+        let endpoint = &mut health[index];
+        endpoint.score += 1.0;
+        if endpoint.cooling_until.is_none() && endpoint.score >= COOLDOWN_SCORE {
+            endpoint.cooling_until = Some(Instant::now() + self.cooldown);
+            return true;
+        }
+        false
+    }
+
+    /// Records each endpoint's slot, `None` for one that couldn't be asked,
+    /// and takes the ones lagging too far out of rotation, which are returned
+    /// with the slots they trail by.
+    pub fn record_slots(&self, slots: &[Option<u64>]) -> Vec<(usize, u64)> {
+        let mut health = self.lock();
+        for (endpoint, slot) in health.iter_mut().zip(slots) {
+            if slot.is_some() {
+                endpoint.slot = *slot;
+            }
+        }
+        let Some(max_lag) = self.max_lag else {
+            return Vec::new();
+        };
+        let tip = health.iter().filter_map(|endpoint| endpoint.slot).max();
+        let mut lagging = Vec::new();
+        for (index, endpoint) in health.iter_mut().enumerate() {
+            let lag = tip
+                .zip(endpoint.slot)
+                .map_or(0, |(tip, slot)| tip.saturating_sub(slot));
+            if lag > max_lag && endpoint.cooling_until.is_none() {
+                endpoint.cooling_until = Some(Instant::now() + self.cooldown);
+                lagging.push((index, lag));
+            }
+        }
+        lagging
+    }
+
+    /// The highest slot any endpoint reported.
+    pub fn tip(&self) -> Option<u64> {
+        self.lock()
+            .iter()
+            .filter_map(|endpoint| endpoint.slot)
+            .max()
+    }
+
+    /// Runs `request` against the current endpoint and, while it fails,
+    /// against the next healthiest, until one succeeds or each was tried
+    /// once; the last error otherwise.
+    pub async fn with_failover<'a, T, E, F, Fut>(&'a self, mut request: F) -> Result<T, E>
+    where
+        F: FnMut(&'a ResilientRpc) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut tried = Vec::with_capacity(self.len());
+        loop {
+            let index = self.pick(&tried);
+            match request(self.rpc(index)).await {
+                Ok(value) => {
+                    self.succeeded(index);
+                    return Ok(value);
+                }
+                Err(e) => {
+                    self.failed(index);
+                    tried.push(index);
+                    if tried.len() == self.len() {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Health>> {
+        // Health is still worth going by after a panic elsewhere
+        self.health
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
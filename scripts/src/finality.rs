@@ -0,0 +1,198 @@
+//! Finality of what `listen` sent below `finalized` commitment, for consumers
+//! whose accounting can't take an event back silently.
+//!
+//! Every transaction whose events went out is tracked until the cluster
+//! finalizes it. One the cluster finalized past without it was on a fork that
+//! got abandoned and never will be: it is handed back with its events, for the
+//! listener to send again marked as retracted. A transaction that landed again
+//! on the surviving fork is tracked at its new slot.
+
+use std::collections::{HashMap, VecDeque};
+
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::{TransactionConfirmationStatus, TransactionStatus};
+
+use crate::error::RpcError;
+use crate::rpc::ResilientRpc;
+use crate::sink::EventRecord;
+
+/// Most signatures `getSignatureStatuses` takes at once.
+const STATUSES_PER_REQUEST: usize = 256;
+
+/// A transaction whose events were sent before it was finalized.
+#[derive(Debug, Clone)]
+pub struct Unfinalized {
+    pub signature: Signature,
+    pub slot: u64,
+    pub records: Vec<EventRecord>,
+}
+
+/// The transactions sent but not finalized yet, oldest first.
+#[derive(Debug, Default)]
+pub struct FinalityTracker {
+    pending: VecDeque<Unfinalized>,
+}
+
+/// The cluster's finalized slot and the statuses of some signatures, as
+/// `finality_status` found them.
+#[derive(Debug, Clone)]
+pub struct FinalityStatus {
+    pub finalized_slot: u64,
+    /// `None` for a signature the cluster doesn't know.
+    pub statuses: Vec<Option<TransactionStatus>>,
+}
+
+impl FinalityTracker {
+    /// Follows `signature` until it finalizes; `records` are the events of it
+    /// that were sent, and so would have to be retracted.
+    pub fn track(&mut self, signature: Signature, slot: u64, records: Vec<EventRecord>) {
+        self.pending.push_back(Unfinalized {
+            signature,
+            slot,
+            records,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The signatures tracked, to ask `finality_status` about.
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.pending.iter().map(|tx| tx.signature).collect()
+    }
+
+    /// Forgets the transactions `status` shows finalized and returns the ones
+    /// it shows never will be; `signatures` are the ones it was asked for.
+    pub fn settle(
+        &mut self,
+        signatures: &[Signature],
+        status: &FinalityStatus,
+    ) -> Vec<Unfinalized> {
+        let statuses: HashMap<Signature, Option<&TransactionStatus>> = signatures
+            .iter()
+            .copied()
+            .zip(status.statuses.iter().map(Option::as_ref))
+            .collect();
+        let mut abandoned = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.pending.len());
+        for mut tx in self.pending.drain(..) {
+            let Some(found) = statuses.get(&tx.signature) else {
+                // Tracked since the statuses were asked for
+                kept.push_back(tx);
+                continue;
+            };
+            match found {
+                Some(found)
+                    if found.confirmation_status
+                        == Some(TransactionConfirmationStatus::Finalized) => {}
+                Some(found) if found.slot > status.finalized_slot => {
+                    tx.slot = found.slot;
+                    kept.push_back(tx);
+                }
+                None if tx.slot > status.finalized_slot => kept.push_back(tx),
+                _ => abandoned.push(tx),
+            }
+        }
+        self.pending = kept;
+        abandoned
+    }
+}
+
+/// The finalized slot, then the status of each of `signatures`, searching the
+/// ledger for those past the status cache. The slot comes first so a
+/// transaction finalized in between shows as finalized.
+pub async fn finality_status(
+    rpc: &ResilientRpc,
+    signatures: &[Signature],
+) -> Result<FinalityStatus, RpcError> {
+    let finalized_slot = rpc
+        .retry(|| rpc.get_slot_with_commitment(CommitmentConfig::finalized()))
+        .await?;
+    let mut statuses = Vec::with_capacity(signatures.len());
+    for chunk in signatures.chunks(STATUSES_PER_REQUEST) {
+        let found = rpc
+            .retry(|| rpc.get_signature_statuses_with_history(chunk))
+            .await?;
+        statuses.extend(found.value);
+    }
+    Ok(FinalityStatus {
+        finalized_slot,
+        statuses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(byte: u8) -> Signature {
+        Signature::from([byte; 64])
+    }
+
+    fn status(slot: u64, confirmation: TransactionConfirmationStatus) -> TransactionStatus {
+        TransactionStatus {
+            slot,
+            confirmations: None,
+            status: Ok(()),
+            err: None,
+            confirmation_status: Some(confirmation),
+        }
+    }
+
+    #[test]
+    fn settles_finalized_and_abandoned_transactions() {
+        let mut tracker = FinalityTracker::default();
+        // Finalized, moved to a later slot, unknown but recent, unknown and
+        // finalized past
+        tracker.track(signature(1), 10, Vec::new());
+        tracker.track(signature(2), 11, Vec::new());
+        tracker.track(signature(3), 30, Vec::new());
+        tracker.track(signature(4), 12, Vec::new());
+        let asked = tracker.signatures();
+        // Tracked after the statuses were asked for
+        tracker.track(signature(5), 13, Vec::new());
+
+        let abandoned = tracker.settle(
+            &asked,
+            &FinalityStatus {
+                finalized_slot: 20,
+                statuses: vec![
+                    Some(status(10, TransactionConfirmationStatus::Finalized)),
+                    Some(status(25, TransactionConfirmationStatus::Confirmed)),
+                    None,
+                    None,
+                ],
+            },
+        );
+
+        let abandoned: Vec<Signature> = abandoned.iter().map(|tx| tx.signature).collect();
+        assert_eq!(abandoned, vec![signature(4)]);
+        assert_eq!(
+            tracker.signatures(),
+            vec![signature(2), signature(3), signature(5)]
+        );
+        assert_eq!(tracker.pending[0].slot, 25);
+    }
+
+    #[test]
+    fn confirmed_below_the_finalized_slot_is_abandoned() {
+        let mut tracker = FinalityTracker::default();
+        tracker.track(signature(1), 10, Vec::new());
+        let asked = tracker.signatures();
+        let abandoned = tracker.settle(
+            &asked,
+            &FinalityStatus {
+                finalized_slot: 20,
+                statuses: vec![Some(status(10, TransactionConfirmationStatus::Confirmed))],
+            },
+        );
+        assert_eq!(abandoned.len(), 1);
+        assert!(tracker.is_empty());
+    }
+}
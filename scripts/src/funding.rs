@@ -0,0 +1,184 @@
+//! Funding payers: faucet airdrops where the cluster has a faucet, transfers
+//! from a treasury keypair where it doesn't (or wherever one is configured).
+//!
+//! Public faucets rate-limit by address and IP, answering with HTTP 429 or an
+//! "airdrop limit" error for a while, so airdrops back off far longer than
+//! `ResilientRpc` does for ordinary rate limiting. Every airdrop is polled
+//! until confirmed before it counts as funded.
+
+use std::fmt;
+use std::time::Duration;
+
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_request::RpcError as RpcRequestError;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+
+use crate::config::Config;
+use crate::error::{FundingError, KeypairError, RpcError};
+use crate::rpc::ResilientRpc;
+use crate::signer::load_signer;
+
+/// What `fund` sends and what payers running low are topped up with.
+pub const DEFAULT_FUND_AMOUNT: u64 = LAMPORTS_PER_SOL;
+/// Balance below which payers are topped up, see `Config::min_balance`.
+pub const DEFAULT_MIN_BALANCE: u64 = LAMPORTS_PER_SOL / 10;
+
+/// Airdrop requests before giving up on a rate-limited faucet.
+const AIRDROP_ATTEMPTS: u32 = 5;
+/// Wait after the first rate-limited request; doubled after each one.
+const AIRDROP_BACKOFF: Duration = Duration::from_secs(5);
+/// Messages the devnet faucet answers with when it turns a request down for
+/// coming too often.
+const FAUCET_LIMIT_MESSAGES: [&str; 2] = ["airdrop request limit", "reached your airdrop limit"];
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Transfers per treasury transaction, well within the size limit.
+const TRANSFERS_PER_TX: usize = 10;
+
+/// Where lamports come from.
+pub enum Funder {
+    Faucet,
+    Treasury(Box<Keypair>),
+}
+
+impl Funder {
+    /// The configured treasury, else the faucet if the cluster has one.
+    pub fn from_config(config: &Config) -> Result<Option<Self>, KeypairError> {
+        if let Some(treasury) = &config.treasury {
+            let treasury = load_signer(Some(treasury))?;
+            return Ok(Some(Funder::Treasury(Box::new(treasury))));
+        }
+        Ok(config.has_faucet().then_some(Funder::Faucet))
+    }
+
+    /// Sends `lamports` to each of `recipients` and returns the signatures
+    /// once confirmed: one per airdrop, or one per batch of treasury transfers.
+    pub async fn fund(
+        &self,
+        rpc: &ResilientRpc,
+        recipients: &[Pubkey],
+        lamports: u64,
+    ) -> Result<Vec<Signature>, FundingError> {
+        match self {
+            Funder::Faucet => {
+                let mut signatures = Vec::with_capacity(recipients.len());
+                for pubkey in recipients {
+                    signatures.push(airdrop(rpc, pubkey, lamports).await?);
+                }
+                Ok(signatures)
+            }
+            Funder::Treasury(treasury) => {
+                let treasury_key = treasury.pubkey();
+                let needed = lamports.saturating_mul(recipients.len() as u64);
+                let balance = rpc.retry(|| rpc.get_balance(&treasury_key)).await?;
+                if balance < needed {
+                    return Err(FundingError::TreasuryShort {
+                        treasury: treasury_key,
+                        balance,
+                        needed,
+                    });
+                }
+                let transfers: Vec<_> = recipients
+                    .iter()
+                    .map(|to| system_instruction::transfer(&treasury_key, to, lamports))
+                    .collect();
+                let mut signatures = Vec::new();
+                for chunk in transfers.chunks(TRANSFERS_PER_TX) {
+                    signatures.push(rpc.send_ix(treasury, chunk).await?);
+                }
+                Ok(signatures)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Funder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Funder::Faucet => f.write_str("faucet"),
+            Funder::Treasury(treasury) => write!(f, "treasury {}", treasury.pubkey()),
+        }
+    }
+}
+
+/// Funds `pubkey` with `lamports` if it holds less than `min_balance`.
+/// Returns the balance it had if it was funded.
+pub async fn top_up(
+    rpc: &ResilientRpc,
+    funder: &Funder,
+    pubkey: &Pubkey,
+    min_balance: u64,
+    lamports: u64,
+) -> Result<Option<u64>, FundingError> {
+    let balance = rpc.retry(|| rpc.get_balance(pubkey)).await?;
+    if balance >= min_balance {
+        return Ok(None);
+    }
+    funder.fund(rpc, &[*pubkey], lamports).await?;
+    Ok(Some(balance))
+}
+
+/// Airdrops `lamports` to `pubkey` and waits until it is confirmed, backing
+/// off while the faucet rate-limits.
+pub async fn airdrop(
+    rpc: &ResilientRpc,
+    pubkey: &Pubkey,
+    lamports: u64,
+) -> Result<Signature, FundingError> {
+    let mut backoff = AIRDROP_BACKOFF;
+    let mut attempt = 1;
+    let signature = loop {
+        match rpc.retry(|| rpc.request_airdrop(pubkey, lamports)).await {
+            Ok(signature) => break signature,
+            Err(e) if !is_rate_limited(&e) => return Err(e.into()),
+            Err(_) if attempt == AIRDROP_ATTEMPTS => {
+                return Err(FundingError::RateLimited { attempts: attempt })
+            }
+            Err(_) => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+        }
+    };
+    wait_confirmed(rpc, &signature).await?;
+    Ok(signature)
+}
+
+async fn wait_confirmed(rpc: &ResilientRpc, signature: &Signature) -> Result<(), FundingError> {
+    let deadline = tokio::time::Instant::now() + CONFIRM_TIMEOUT;
+    loop {
+        match rpc.retry(|| rpc.get_signature_status(signature)).await? {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(e)) => {
+                return Err(FundingError::AirdropFailed {
+                    signature: *signature,
+                    reason: e.to_string(),
+                })
+            }
+            None if tokio::time::Instant::now() >= deadline => {
+                return Err(FundingError::NotConfirmed {
+                    signature: *signature,
+                    secs: CONFIRM_TIMEOUT.as_secs(),
+                })
+            }
+            None => tokio::time::sleep(CONFIRM_POLL_INTERVAL).await,
+        }
+    }
+}
+
+/// Whether the faucet turned the request down for coming too often: an HTTP
+/// 429, or one of the faucet's own rate-limit errors.
+fn is_rate_limited(err: &RpcError) -> bool {
+    match err.0.kind() {
+        ClientErrorKind::Reqwest(e) => e.status().is_some_and(|s| s.as_u16() == 429),
+        ClientErrorKind::RpcError(RpcRequestError::RpcResponseError { message, .. }) => {
+            let message = message.to_ascii_lowercase();
+            FAUCET_LIMIT_MESSAGES.iter().any(|m| message.contains(m))
+        }
+        _ => false,
+    }
+}
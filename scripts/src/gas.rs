@@ -0,0 +1,162 @@
+//! Typed instruction builders for gas_service.
+//!
+//! The config and event-authority PDAs are derived from `program_id`, so callers
+//! only supply the signers and instruction arguments.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use gas_service::{accounts, instruction};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{
+    call_contract_signing_pda, event_authority_pda, gas_config_pda, gateway_root_pda,
+    payload_log_pda,
+};
+
+fn build_ix(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `payer` must be the program's upgrade authority.
+pub fn build_init_config_ix(program_id: &Pubkey, payer: &Pubkey, operator: Pubkey) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitConfig {
+            payer: *payer,
+            config_pda: gas_config_pda(program_id).0,
+            this_program: *program_id,
+            program_data: bpf_loader_upgradeable::get_program_data_address(program_id),
+            system_program: system_program::id(),
+        },
+        instruction::InitConfig { operator },
+    )
+}
+
+pub fn build_transfer_operatorship_ix(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    new_operator: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::TransferOperatorship {
+            operator: *operator,
+            config_pda: gas_config_pda(program_id).0,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::TransferOperatorship { new_operator },
+    )
+}
+
+pub fn build_pay_native_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    destination_chain: String,
+    destination_address: String,
+    payload_hash: [u8; 32],
+    amount: u64,
+    refund_address: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::PayNativeForContractCall {
+            payer: *payer,
+            config_pda: gas_config_pda(program_id).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::PayNativeForContractCall {
+            destination_chain,
+            destination_address,
+            payload_hash,
+            amount,
+            refund_address,
+        },
+    )
+}
+
+pub fn build_add_native_gas_ix(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    message_id: String,
+    amount: u64,
+    refund_address: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::AddNativeGas {
+            sender: *sender,
+            config_pda: gas_config_pda(program_id).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::AddNativeGas {
+            message_id,
+            amount,
+            refund_address,
+        },
+    )
+}
+
+pub fn build_refund_ix(
+    program_id: &Pubkey,
+    receiver: &Pubkey,
+    message_id: String,
+    amount: u64,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::RefundNativeFees {
+            config_pda: gas_config_pda(program_id).0,
+            receiver: *receiver,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::RefundNativeFees { message_id, amount },
+    )
+}
+
+/// gas_service CPIs into program_tester's `call_contract`, so the gateway PDAs
+/// and event authority are derived from `gateway_program_id`.
+pub fn build_cpi_call_contract_ix(
+    program_id: &Pubkey,
+    gateway_program_id: &Pubkey,
+    payer: &Pubkey,
+    destination_chain: String,
+    destination_contract_address: String,
+    payload_hash: [u8; 32],
+    payload: Vec<u8>,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::CpiCallContract {
+            payer: *payer,
+            program_tester_program: *gateway_program_id,
+            gas_service_program: *program_id,
+            signing_pda: call_contract_signing_pda(gateway_program_id).0,
+            gateway_root_pda: gateway_root_pda(gateway_program_id).0,
+            payload_log: Some(payload_log_pda(gateway_program_id).0),
+            event_authority: event_authority_pda(gateway_program_id).0,
+            system_program: system_program::id(),
+        },
+        instruction::CpiCallContract {
+            destination_chain,
+            destination_contract_address,
+            payload_hash,
+            payload,
+        },
+    )
+}
@@ -0,0 +1,439 @@
+//! Typed instruction builders for program_tester (the dummy gateway).
+//!
+//! Account lists come from the Anchor-generated `accounts` structs, so the
+//! ordering always matches the program, including the trailing event-CPI
+//! `event_authority` and `program` accounts.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use program_tester::{accounts, instruction, ItsMessage, MerkleisedMessage, VerifierSetLeaf};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{
+    call_contract_signing_pda, event_authority_pda, gateway_root_pda, incoming_message_pda,
+    payload_log_pda, verification_session_pda, verifier_set_tracker_pda,
+};
+
+fn build_ix(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Accounts shared by `call_contract` and `call_contract_with_its_payload`.
+pub(crate) fn call_contract_accounts(
+    program_id: &Pubkey,
+    calling_program: &Pubkey,
+) -> accounts::CallContract {
+    accounts::CallContract {
+        calling_program: *calling_program,
+        signing_pda: call_contract_signing_pda(program_id).0,
+        gateway_root_pda: gateway_root_pda(program_id).0,
+        payload_log: Some(payload_log_pda(program_id).0),
+        event_authority: event_authority_pda(program_id).0,
+        program: *program_id,
+    }
+}
+
+pub fn build_init_gateway_root_ix(program_id: &Pubkey, funder: &Pubkey) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitGatewayRoot {
+            funder: *funder,
+            gateway_root_pda: gateway_root_pda(program_id).0,
+            system_program: system_program::id(),
+        },
+        instruction::InitGatewayRoot {},
+    )
+}
+
+pub fn build_init_payload_log_ix(program_id: &Pubkey, funder: &Pubkey) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitPayloadLog {
+            funder: *funder,
+            payload_log: payload_log_pda(program_id).0,
+            system_program: system_program::id(),
+        },
+        instruction::InitPayloadLog {},
+    )
+}
+
+/// `calling_program` is reported as the sender; scripts calling directly pass the
+/// system program. The payload log is passed, so it must have been created.
+pub fn build_call_contract_ix(
+    program_id: &Pubkey,
+    calling_program: &Pubkey,
+    destination_chain: String,
+    destination_contract_address: String,
+    payload_hash: [u8; 32],
+    payload: Vec<u8>,
+) -> Instruction {
+    build_ix(
+        program_id,
+        call_contract_accounts(program_id, calling_program),
+        instruction::CallContract {
+            destination_chain,
+            destination_contract_address,
+            payload_hash,
+            payload,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_call_contract_with_token_ix(
+    program_id: &Pubkey,
+    calling_program: &Pubkey,
+    destination_chain: String,
+    destination_contract_address: String,
+    payload_hash: [u8; 32],
+    payload: Vec<u8>,
+    symbol: String,
+    amount: u64,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::CallContractWithToken {
+            calling_program: *calling_program,
+            signing_pda: call_contract_signing_pda(program_id).0,
+            gateway_root_pda: gateway_root_pda(program_id).0,
+            payload_log: Some(payload_log_pda(program_id).0),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::CallContractWithToken {
+            destination_chain,
+            destination_contract_address,
+            payload_hash,
+            payload,
+            symbol,
+            amount,
+        },
+    )
+}
+
+pub fn build_call_contract_with_its_payload_ix(
+    program_id: &Pubkey,
+    calling_program: &Pubkey,
+    destination_chain: String,
+    destination_contract_address: String,
+    message: ItsMessage,
+    hub_destination_chain: Option<String>,
+) -> Instruction {
+    build_ix(
+        program_id,
+        call_contract_accounts(program_id, calling_program),
+        instruction::CallContractWithItsPayload {
+            destination_chain,
+            destination_contract_address,
+            message,
+            hub_destination_chain,
+        },
+    )
+}
+
+pub fn build_init_verification_session_ix(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    payload_merkle_root: [u8; 32],
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitVerificationSession {
+            funder: *funder,
+            verification_session_account: verification_session_pda(
+                program_id,
+                &payload_merkle_root,
+            )
+            .0,
+            system_program: system_program::id(),
+        },
+        instruction::InitVerificationSession {
+            _payload_merkle_root: payload_merkle_root,
+        },
+    )
+}
+
+/// `operator` must be the gateway's operator; the tracker PDA is derived
+/// from the set's hash.
+pub fn build_register_verifier_set_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    operator: &Pubkey,
+    verifier_set_hash: [u8; 32],
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::RegisterVerifierSet {
+            payer: *payer,
+            operator: *operator,
+            gateway_root_pda: gateway_root_pda(program_id).0,
+            verifier_set_tracker_pda: verifier_set_tracker_pda(program_id, &verifier_set_hash).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::RegisterVerifierSet { verifier_set_hash },
+    )
+}
+
+/// `verifier_merkle_proof` proves `verifier_info` is in the set, as
+/// `VerifierSetFixture::proof` builds it.
+pub fn build_verify_signature_ix(
+    program_id: &Pubkey,
+    payload_merkle_root: [u8; 32],
+    verifier_set_hash: [u8; 32],
+    verifier_info: VerifierSetLeaf,
+    verifier_merkle_proof: Vec<u8>,
+    signature: [u8; 64],
+    recovery_id: u8,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::VerifySignature {
+            gateway_root_pda: gateway_root_pda(program_id).0,
+            verifier_set_tracker_pda: verifier_set_tracker_pda(program_id, &verifier_set_hash).0,
+            verification_session_account: verification_session_pda(
+                program_id,
+                &payload_merkle_root,
+            )
+            .0,
+        },
+        instruction::VerifySignature {
+            payload_merkle_root,
+            verifier_set_hash,
+            verifier_info,
+            verifier_merkle_proof,
+            signature,
+            recovery_id,
+        },
+    )
+}
+
+/// The incoming message PDA is derived from the message's command id.
+pub fn build_approve_message_ix(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    message: MerkleisedMessage,
+    payload_merkle_root: [u8; 32],
+) -> Instruction {
+    let command_id = message.leaf.message.command_id();
+    build_ix(
+        program_id,
+        accounts::ApproveMessage {
+            gateway_root_pda: gateway_root_pda(program_id).0,
+            funder: *funder,
+            verification_session_account: verification_session_pda(
+                program_id,
+                &payload_merkle_root,
+            )
+            .0,
+            incoming_message_pda: incoming_message_pda(program_id, &command_id).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::ApproveMessage {
+            message,
+            payload_merkle_root,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_execute_message_ix(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    command_id: [u8; 32],
+    source_chain: String,
+    cc_id: String,
+    source_address: String,
+    destination_chain: String,
+    destination_address: String,
+    payload_hash: [u8; 32],
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::ExecuteMessage {
+            funder: *funder,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::ExecuteMessage {
+            command_id,
+            source_chain,
+            cc_id,
+            source_address,
+            destination_chain,
+            destination_address,
+            payload_hash,
+        },
+    )
+}
+
+pub fn build_transfer_operatorship_ix(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    new_operator: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::TransferOperatorship {
+            operator: *operator,
+            gateway_root_pda: gateway_root_pda(program_id).0,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::TransferOperatorship { new_operator },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_interchain_transfer_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    token_id: [u8; 32],
+    source_address: Pubkey,
+    source_token_account: Pubkey,
+    destination_chain: String,
+    destination_address: Vec<u8>,
+    amount: u64,
+    data_hash: [u8; 32],
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InterchainTransferCtx {
+            payer: *payer,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::InterchainTransfer {
+            token_id,
+            source_address,
+            source_token_account,
+            destination_chain,
+            destination_address,
+            amount,
+            data_hash,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_link_token_started_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    token_id: [u8; 32],
+    destination_chain: String,
+    source_token_address: Pubkey,
+    destination_token_address: Vec<u8>,
+    token_manager_type: u8,
+    params: Vec<u8>,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::LinkTokenStartedCtx {
+            payer: *payer,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::LinkTokenStarted {
+            token_id,
+            destination_chain,
+            source_token_address,
+            destination_token_address,
+            token_manager_type,
+            params,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_interchain_token_deployment_started_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    token_id: [u8; 32],
+    token_name: String,
+    token_symbol: String,
+    token_decimals: u8,
+    minter: Vec<u8>,
+    destination_chain: String,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InterchainTokenDeploymentStartedCtx {
+            payer: *payer,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::InterchainTokenDeploymentStarted {
+            token_id,
+            token_name,
+            token_symbol,
+            token_decimals,
+            minter,
+            destination_chain,
+        },
+    )
+}
+
+pub fn build_token_metadata_registered_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    token_address: Pubkey,
+    decimals: u8,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::TokenMetadataRegisteredCtx {
+            payer: *payer,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::TokenMetadataRegistered {
+            token_address,
+            decimals,
+        },
+    )
+}
+
+pub fn build_burn_compute_ix(program_id: &Pubkey, payer: &Pubkey, iterations: u32) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::BurnComputeCtx {
+            payer: *payer,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::BurnCompute { iterations },
+    )
+}
+
+pub fn build_signers_rotated_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    epoch_le: [u8; 32],
+    verifier_set_hash: [u8; 32],
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::SignersRotatedCtx {
+            payer: *payer,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::SignersRotated {
+            epoch_le,
+            verifier_set_hash,
+        },
+    )
+}
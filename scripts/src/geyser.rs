@@ -0,0 +1,167 @@
+//! Transactions streamed from a Yellowstone gRPC (Geyser) endpoint, for
+//! `listen --ingest geyser` against dedicated nodes: the validator pushes each
+//! transaction whole as it lands, over one gRPC stream rather than a websocket
+//! per program. Needs the `geyser` feature.
+//!
+//! Every program gets a transaction filter named by its id, so an update says
+//! which of the programs it mentions. Vote and failed transactions are
+//! filtered out on the server.
+
+use futures::stream::BoxStream;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::GeyserError;
+use crate::inspect::TxEvents;
+
+#[cfg(feature = "geyser")]
+pub use client::subscribe;
+
+/// A transaction mentioning some of the programs subscribed to.
+#[derive(Debug, Clone)]
+pub struct GeyserTransaction {
+    /// The programs subscribed to that it mentions.
+    pub programs: Vec<Pubkey>,
+    pub tx: TxEvents,
+}
+
+pub type GeyserStream = BoxStream<'static, Result<GeyserTransaction, GeyserError>>;
+
+/// Without the `geyser` feature there is no client to subscribe with.
+#[cfg(not(feature = "geyser"))]
+pub async fn subscribe(
+    _endpoint: &str,
+    _x_token: Option<&str>,
+    _programs: &[Pubkey],
+    _commitment: CommitmentLevel,
+) -> Result<GeyserStream, GeyserError> {
+    Err(GeyserError::Unsupported)
+}
+
+#[cfg(feature = "geyser")]
+mod client {
+    use futures::{SinkExt, StreamExt};
+    use solana_sdk::signature::Signature;
+    use solana_transaction_status::UiTransactionEncoding;
+    use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
+    use yellowstone_grpc_proto::convert_from::create_tx_with_meta;
+    use yellowstone_grpc_proto::prelude::{
+        subscribe_update::UpdateOneof, CommitmentLevel as GeyserCommitment, SubscribeRequest,
+        SubscribeRequestFilterTransactions, SubscribeRequestPing, SubscribeUpdateTransactionInfo,
+    };
+
+    use super::{CommitmentLevel, GeyserError, GeyserStream, GeyserTransaction, Pubkey, TxEvents};
+
+    /// Subscribes at `endpoint`, with `x_token` where it wants one, to the
+    /// successful transactions mentioning any of `programs`.
+    pub async fn subscribe(
+        endpoint: &str,
+        x_token: Option<&str>,
+        programs: &[Pubkey],
+        commitment: CommitmentLevel,
+    ) -> Result<GeyserStream, GeyserError> {
+        let subscribe_error = |reason: String| GeyserError::Subscribe {
+            endpoint: endpoint.to_string(),
+            reason,
+        };
+        let mut builder = GeyserGrpcClient::build_from_shared(endpoint.to_string())
+            .and_then(|builder| builder.x_token(x_token.map(str::to_string)))
+            .map_err(|e| subscribe_error(e.to_string()))?;
+        if endpoint.starts_with("https://") {
+            builder = builder
+                .tls_config(ClientTlsConfig::new().with_native_roots())
+                .map_err(|e| subscribe_error(e.to_string()))?;
+        }
+        let mut client = builder
+            .connect()
+            .await
+            .map_err(|e| subscribe_error(e.to_string()))?;
+        let request = SubscribeRequest {
+            transactions: programs
+                .iter()
+                .map(|id| {
+                    let filter = SubscribeRequestFilterTransactions {
+                        vote: Some(false),
+                        failed: Some(false),
+                        account_include: vec![id.to_string()],
+                        ..Default::default()
+                    };
+                    (id.to_string(), filter)
+                })
+                .collect(),
+            commitment: Some(match commitment {
+                CommitmentLevel::Processed => GeyserCommitment::Processed,
+                CommitmentLevel::Finalized => GeyserCommitment::Finalized,
+                _ => GeyserCommitment::Confirmed,
+            } as i32),
+            ..Default::default()
+        };
+        let (requests, updates) = client
+            .subscribe_with_request(Some(request))
+            .await
+            .map_err(|e| subscribe_error(e.to_string()))?;
+
+        let state = (Box::pin(requests), Box::pin(updates));
+        let stream = futures::stream::unfold(state, |(mut requests, mut updates)| async move {
+            loop {
+                let update = match updates.next().await? {
+                    Ok(update) => update,
+                    Err(status) => {
+                        let error = GeyserError::Stream(status.to_string());
+                        return Some((Err(error), (requests, updates)));
+                    }
+                };
+                match update.update_oneof {
+                    Some(UpdateOneof::Transaction(tx)) => {
+                        let Some(info) = tx.transaction else {
+                            continue;
+                        };
+                        let found = transaction(update.filters, tx.slot, info);
+                        return Some((found, (requests, updates)));
+                    }
+                    // Answered so proxies don't take the stream for idle
+                    Some(UpdateOneof::Ping(_)) => {
+                        let pong = SubscribeRequest {
+                            ping: Some(SubscribeRequestPing { id: 1 }),
+                            ..Default::default()
+                        };
+                        if let Err(e) = requests.send(pong).await {
+                            let error = GeyserError::Stream(e.to_string());
+                            return Some((Err(error), (requests, updates)));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+
+    /// The events of a transaction update, `filters` naming the programs it
+    /// matched.
+    fn transaction(
+        filters: Vec<String>,
+        slot: u64,
+        info: SubscribeUpdateTransactionInfo,
+    ) -> Result<GeyserTransaction, GeyserError> {
+        let signature = Signature::try_from(info.signature.as_slice())
+            .map_err(|e| GeyserError::Stream(format!("invalid signature: {e}")))?;
+        let encoded = create_tx_with_meta(info)
+            .map_err(str::to_string)
+            .and_then(|tx| {
+                tx.encode(UiTransactionEncoding::Json, Some(0), false)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(|e| {
+                GeyserError::Stream(format!("undecodable transaction {signature}: {e}"))
+            })?;
+        Ok(GeyserTransaction {
+            programs: filters
+                .iter()
+                .filter_map(|filter| filter.parse().ok())
+                .collect(),
+            // Transaction updates don't carry the block's time
+            tx: TxEvents::from_encoded(signature, slot, None, &encoded),
+        })
+    }
+}
@@ -0,0 +1,249 @@
+//! Conversion of decoded events into Axelar Amplifier GMP API events.
+//!
+//! The output serializes to the JSON the GMP API's `POST /chains/{chain}/events`
+//! expects, so the listener can hand relayer-ready payloads to a publisher
+//! instead of printing debug structs. Hashes and payloads are base64, Solana
+//! addresses base58 and amounts decimal strings, as in the API schema.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::events::DecodedEvent;
+use crate::message_id::MessageId;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Event {
+    Call(CallEvent),
+    GasCredit(GasCreditEvent),
+    GasRefunded(GasRefundedEvent),
+    MessageApproved(MessageApprovedEvent),
+    MessageExecuted(MessageExecutedEvent),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallEvent {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    pub message: GatewayV2Message,
+    pub destination_chain: String,
+    pub payload: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<EventMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasCreditEvent {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(rename = "messageID")]
+    pub message_id: String,
+    pub refund_address: String,
+    pub payment: Token,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<EventMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasRefundedEvent {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(rename = "messageID")]
+    pub message_id: String,
+    pub recipient_address: String,
+    pub refunded_amount: Token,
+    pub cost: Token,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<EventMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageApprovedEvent {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    pub message: GatewayV2Message,
+    pub cost: Token,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<EventMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageExecutedEvent {
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(rename = "messageID")]
+    pub message_id: String,
+    pub source_chain: String,
+    pub status: MessageExecutionStatus,
+    pub cost: Token,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<EventMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayV2Message {
+    #[serde(rename = "messageID")]
+    pub message_id: String,
+    pub source_chain: String,
+    pub source_address: String,
+    pub destination_address: String,
+    pub payload_hash: String,
+}
+
+/// An amount of the chain's native token when `token_id` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+    #[serde(rename = "tokenID", skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+    pub amount: String,
+}
+
+impl Token {
+    pub fn native(amount: u64) -> Self {
+        Self {
+            token_id: None,
+            amount: amount.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MessageExecutionStatus {
+    Successful,
+    Reverted,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventMetadata {
+    #[serde(rename = "txID", skip_serializing_if = "Option::is_none")]
+    pub tx_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finalized: Option<bool>,
+}
+
+/// Where an event was observed, which the events themselves don't carry.
+#[derive(Debug, Clone)]
+pub struct EventContext {
+    /// Position of the event's instruction, used as the GMP event id and as
+    /// the message id of contract calls.
+    pub event_id: MessageId,
+    /// Axelar name of the chain the programs run on, e.g. `solana-devnet`.
+    pub source_chain: String,
+    /// Fee paid by the transaction, reported as the event's cost.
+    pub cost: u64,
+    /// `GasPaidEvent` has no message id; this is the id of the call it pays
+    /// for, usually the next `CallContractEvent` in the same transaction.
+    pub paid_message_id: Option<MessageId>,
+    pub meta: Option<EventMetadata>,
+}
+
+/// Maps a decoded event onto its GMP API counterpart. Returns `None` for
+/// events the API has no equivalent for (ITS, rotation, compute burns), and
+/// for a `GasPaidEvent` without `paid_message_id`.
+pub fn to_gmp_event(event: &DecodedEvent, ctx: &EventContext) -> Option<Event> {
+    let event_id = ctx.event_id.to_string();
+    let meta = ctx.meta.clone();
+    let gmp = match event {
+        DecodedEvent::CallContractEvent(e) => Event::Call(CallEvent {
+            message: GatewayV2Message {
+                message_id: event_id.clone(),
+                source_chain: ctx.source_chain.clone(),
+                source_address: e.sender.to_string(),
+                destination_address: e.destination_contract_address.clone(),
+                payload_hash: STANDARD.encode(e.payload_hash),
+            },
+            event_id,
+            destination_chain: e.destination_chain.clone(),
+            payload: STANDARD.encode(&e.payload),
+            meta,
+        }),
+        DecodedEvent::CallContractWithTokenEvent(e) => Event::Call(CallEvent {
+            message: GatewayV2Message {
+                message_id: event_id.clone(),
+                source_chain: ctx.source_chain.clone(),
+                source_address: e.sender.to_string(),
+                destination_address: e.destination_contract_address.clone(),
+                payload_hash: STANDARD.encode(e.payload_hash),
+            },
+            event_id,
+            destination_chain: e.destination_chain.clone(),
+            payload: STANDARD.encode(&e.payload),
+            meta,
+        }),
+        DecodedEvent::GasPaidEvent(e) => Event::GasCredit(GasCreditEvent {
+            event_id,
+            message_id: ctx.paid_message_id.as_ref()?.to_string(),
+            refund_address: e.refund_address.to_string(),
+            payment: Token::native(e.amount),
+            meta,
+        }),
+        DecodedEvent::GasAddedEvent(e) => Event::GasCredit(GasCreditEvent {
+            event_id,
+            message_id: e.message_id.clone(),
+            refund_address: e.refund_address.to_string(),
+            payment: Token::native(e.amount),
+            meta,
+        }),
+        DecodedEvent::GasRefundedEvent(e) => Event::GasRefunded(GasRefundedEvent {
+            event_id,
+            message_id: e.message_id.clone(),
+            recipient_address: e.receiver.to_string(),
+            refunded_amount: Token::native(e.amount),
+            cost: Token::native(ctx.cost),
+            meta,
+        }),
+        DecodedEvent::MessageApprovedEvent(e) => Event::MessageApproved(MessageApprovedEvent {
+            event_id,
+            message: incoming_message(
+                &e.cc_id,
+                &e.source_chain,
+                &e.source_address,
+                &e.destination_address,
+                e.payload_hash,
+            ),
+            cost: Token::native(ctx.cost),
+            meta,
+        }),
+        DecodedEvent::MessageExecutedEvent(e) => Event::MessageExecuted(MessageExecutedEvent {
+            event_id,
+            message_id: e.cc_id.clone(),
+            source_chain: e.source_chain.clone(),
+            status: MessageExecutionStatus::Successful,
+            cost: Token::native(ctx.cost),
+            meta,
+        }),
+        _ => return None,
+    };
+    Some(gmp)
+}
+
+fn incoming_message(
+    cc_id: &str,
+    source_chain: &str,
+    source_address: &str,
+    destination_address: &Pubkey,
+    payload_hash: [u8; 32],
+) -> GatewayV2Message {
+    GatewayV2Message {
+        message_id: cc_id.to_string(),
+        source_chain: source_chain.to_string(),
+        source_address: source_address.to_string(),
+        destination_address: destination_address.to_string(),
+        payload_hash: STANDARD.encode(payload_hash),
+    }
+}
@@ -0,0 +1,492 @@
+//! Golden Borsh vectors for the events and accounts of program_tester,
+//! gas_service and its_tester.
+//!
+//! Each vector is the canonical encoding of the matching `sample_*` value:
+//! the 8-byte Anchor discriminator followed by the borsh payload for events
+//! and accounts, and the bare borsh payload for instruction arguments such as
+//! `MerkleisedMessage`. A layout change in either program shows up as a
+//! mismatch in `check_all`.
+
+use std::fmt::Debug;
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, Discriminator};
+use anyhow::{anyhow, ensure, Result};
+use program_tester::{
+    CrossChainId, MerkleisedMessage, Message, MessageLeaf, MessageStatus, PayloadLogEntry, U256,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::codec::{hex_decode, hex_encode, Mode};
+
+/// Checks that `value` encodes to exactly `golden_hex` and decodes back to itself.
+pub fn assert_round_trip<T>(value: &T, golden_hex: &str) -> Result<()>
+where
+    T: AnchorSerialize + AnchorDeserialize + PartialEq + Debug,
+{
+    let golden = hex_decode(golden_hex, Mode::Strict)?;
+    let encoded = value.try_to_vec()?;
+    ensure!(
+        encoded == golden,
+        "encoding differs from golden vector:\n  got:    {}\n  golden: {}",
+        hex_encode(&encoded),
+        golden_hex
+    );
+    let decoded = T::try_from_slice(&golden)?;
+    ensure!(
+        decoded == *value,
+        "golden vector decodes to {decoded:?}, expected {value:?}"
+    );
+    Ok(())
+}
+
+/// Like `assert_round_trip`, for events and accounts whose encoding starts
+/// with their discriminator.
+pub fn assert_tagged_round_trip<T>(value: &T, golden_hex: &str) -> Result<()>
+where
+    T: Discriminator + AnchorSerialize + AnchorDeserialize + PartialEq + Debug,
+{
+    let golden = hex_decode(golden_hex, Mode::Strict)?;
+    let payload = golden
+        .strip_prefix(T::DISCRIMINATOR)
+        .ok_or_else(|| anyhow!("golden vector does not start with the discriminator"))?;
+    assert_round_trip(value, &hex_encode(payload))
+}
+
+/// Runs every golden vector, returning the names of the ones that match.
+pub fn check_all() -> Result<Vec<&'static str>> {
+    let mut checked = Vec::new();
+    macro_rules! check {
+        ($check:ident, $value:expr, $golden:ident) => {
+            $check(&$value, $golden).map_err(|e| anyhow!("{}: {e}", stringify!($golden)))?;
+            checked.push(stringify!($golden));
+        };
+    }
+    macro_rules! tagged {
+        ($value:expr, $golden:ident) => {
+            check!(assert_tagged_round_trip, $value, $golden)
+        };
+    }
+
+    tagged!(sample_call_contract_event(), CALL_CONTRACT_EVENT);
+    tagged!(
+        sample_call_contract_with_token_event(),
+        CALL_CONTRACT_WITH_TOKEN_EVENT
+    );
+    tagged!(sample_message_approved_event(), MESSAGE_APPROVED_EVENT);
+    tagged!(sample_message_executed_event(), MESSAGE_EXECUTED_EVENT);
+    tagged!(
+        sample_verifier_set_rotated_event(),
+        VERIFIER_SET_ROTATED_EVENT
+    );
+    tagged!(sample_interchain_transfer(), INTERCHAIN_TRANSFER);
+    tagged!(sample_link_token_started(), LINK_TOKEN_STARTED);
+    tagged!(
+        sample_interchain_token_deployment_started(),
+        INTERCHAIN_TOKEN_DEPLOYMENT_STARTED
+    );
+    tagged!(
+        sample_token_metadata_registered(),
+        TOKEN_METADATA_REGISTERED
+    );
+    tagged!(sample_compute_burned_event(), COMPUTE_BURNED_EVENT);
+    tagged!(sample_gas_paid_event(), GAS_PAID_EVENT);
+    tagged!(sample_gas_added_event(), GAS_ADDED_EVENT);
+    tagged!(sample_gas_refunded_event(), GAS_REFUNDED_EVENT);
+    tagged!(
+        sample_operatorship_transferred_event(),
+        OPERATORSHIP_TRANSFERRED_EVENT
+    );
+    tagged!(sample_flow_limit_set(), FLOW_LIMIT_SET);
+    tagged!(sample_gateway_config(), GATEWAY_CONFIG);
+    tagged!(sample_payload_log(), PAYLOAD_LOG);
+    tagged!(
+        sample_verification_session_account(),
+        VERIFICATION_SESSION_ACCOUNT
+    );
+    tagged!(sample_incoming_message(), INCOMING_MESSAGE);
+    tagged!(sample_gas_config(), GAS_CONFIG);
+    check!(
+        assert_round_trip,
+        sample_merkleised_message(),
+        MERKLEISED_MESSAGE
+    );
+
+    Ok(checked)
+}
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+pub fn sample_call_contract_event() -> program_tester::CallContractEvent {
+    program_tester::CallContractEvent {
+        sender: key(1),
+        payload_hash: [2; 32],
+        destination_chain: "ethereum".to_string(),
+        destination_contract_address: "0xdead".to_string(),
+        payload: vec![1, 2, 3],
+    }
+}
+
+pub fn sample_call_contract_with_token_event() -> program_tester::CallContractWithTokenEvent {
+    program_tester::CallContractWithTokenEvent {
+        sender: key(1),
+        payload_hash: [2; 32],
+        destination_chain: "ethereum".to_string(),
+        destination_contract_address: "0xdead".to_string(),
+        payload: vec![1, 2, 3],
+        symbol: "AXL".to_string(),
+        amount: 1000,
+    }
+}
+
+pub fn sample_message_approved_event() -> program_tester::MessageApprovedEvent {
+    program_tester::MessageApprovedEvent {
+        command_id: [3; 32],
+        destination_address: key(4),
+        payload_hash: [2; 32],
+        source_chain: "ethereum".to_string(),
+        cc_id: "ethereum-1".to_string(),
+        source_address: "0xdead".to_string(),
+        destination_chain: "solana".to_string(),
+    }
+}
+
+pub fn sample_message_executed_event() -> program_tester::MessageExecutedEvent {
+    let approved = sample_message_approved_event();
+    program_tester::MessageExecutedEvent {
+        command_id: approved.command_id,
+        destination_address: approved.destination_address,
+        payload_hash: approved.payload_hash,
+        source_chain: approved.source_chain,
+        cc_id: approved.cc_id,
+        source_address: approved.source_address,
+        destination_chain: approved.destination_chain,
+    }
+}
+
+pub fn sample_verifier_set_rotated_event() -> program_tester::VerifierSetRotatedEvent {
+    let mut epoch = [0u8; 32];
+    epoch[0] = 42;
+    program_tester::VerifierSetRotatedEvent {
+        epoch: U256(epoch),
+        verifier_set_hash: [6; 32],
+    }
+}
+
+pub fn sample_interchain_transfer() -> program_tester::InterchainTransfer {
+    program_tester::InterchainTransfer {
+        token_id: [7; 32],
+        source_address: key(1),
+        source_token_account: key(4),
+        destination_chain: "ethereum".to_string(),
+        destination_address: vec![0xde, 0xad],
+        amount: 1000,
+        data_hash: [2; 32],
+    }
+}
+
+pub fn sample_link_token_started() -> program_tester::LinkTokenStarted {
+    program_tester::LinkTokenStarted {
+        token_id: [7; 32],
+        destination_chain: "ethereum".to_string(),
+        source_token_address: key(1),
+        destination_token_address: vec![0xde, 0xad],
+        token_manager_type: 2,
+        params: vec![],
+    }
+}
+
+pub fn sample_interchain_token_deployment_started(
+) -> program_tester::InterchainTokenDeploymentStarted {
+    program_tester::InterchainTokenDeploymentStarted {
+        token_id: [7; 32],
+        token_name: "Token".to_string(),
+        token_symbol: "TKN".to_string(),
+        token_decimals: 9,
+        minter: vec![1, 2],
+        destination_chain: "ethereum".to_string(),
+    }
+}
+
+pub fn sample_token_metadata_registered() -> program_tester::TokenMetadataRegistered {
+    program_tester::TokenMetadataRegistered {
+        token_address: key(1),
+        decimals: 9,
+    }
+}
+
+pub fn sample_compute_burned_event() -> program_tester::ComputeBurnedEvent {
+    program_tester::ComputeBurnedEvent {
+        iterations: 100,
+        result_hash: [8; 32],
+    }
+}
+
+pub fn sample_gas_paid_event() -> gas_service::GasPaidEvent {
+    gas_service::GasPaidEvent {
+        sender: key(1),
+        destination_chain: "ethereum".to_string(),
+        destination_address: "0xdead".to_string(),
+        payload_hash: [2; 32],
+        amount: 1000,
+        refund_address: key(4),
+        spl_token_account: None,
+    }
+}
+
+pub fn sample_gas_added_event() -> gas_service::GasAddedEvent {
+    gas_service::GasAddedEvent {
+        sender: key(1),
+        message_id: "tx-1.1".to_string(),
+        amount: 1000,
+        refund_address: key(4),
+        spl_token_account: Some(key(9)),
+    }
+}
+
+pub fn sample_gas_refunded_event() -> gas_service::GasRefundedEvent {
+    gas_service::GasRefundedEvent {
+        receiver: key(4),
+        message_id: "tx-1.1".to_string(),
+        amount: 500,
+        spl_token_account: None,
+    }
+}
+
+pub fn sample_operatorship_transferred_event() -> common::OperatorshipTransferredEvent {
+    common::OperatorshipTransferredEvent {
+        config: key(9),
+        previous_operator: key(1),
+        new_operator: key(4),
+    }
+}
+
+pub fn sample_flow_limit_set() -> its_tester::FlowLimitSet {
+    its_tester::FlowLimitSet {
+        token_id: [7u8; 32],
+        operator: key(1),
+        flow_limit: Some(1_000_000),
+    }
+}
+
+pub fn sample_gateway_config() -> program_tester::GatewayConfig {
+    program_tester::GatewayConfig {
+        current_epoch: 1,
+        previous_verifier_set_retention: 4,
+        minimum_rotation_delay: 3600,
+        last_rotation_timestamp: 1_700_000_000,
+        operator: key(1),
+        domain_separator: [3; 32],
+        bump: 255,
+    }
+}
+
+pub fn sample_payload_log() -> program_tester::PayloadLog {
+    program_tester::PayloadLog {
+        head: 0,
+        total_recorded: 1,
+        entries: vec![PayloadLogEntry {
+            payload_hash: [2; 32],
+            sender: key(1),
+            destination_chain: "ethereum".to_string(),
+        }],
+        bump: 254,
+    }
+}
+
+pub fn sample_verification_session_account() -> program_tester::VerificationSessionAccount {
+    let mut signature_slots = [0u8; 32];
+    signature_slots[0] = 0b11;
+    program_tester::VerificationSessionAccount {
+        signature_verification: program_tester::SignatureVerification {
+            accumulated_threshold: 2,
+            signature_slots,
+            signing_verifier_set_hash: [6; 32],
+        },
+        bump: 253,
+    }
+}
+
+pub fn sample_incoming_message() -> program_tester::IncomingMessage {
+    program_tester::IncomingMessage {
+        bump: 252,
+        signing_pda_bump: 251,
+        status: MessageStatus::approved(),
+        message_hash: [3; 32],
+        payload_hash: [2; 32],
+    }
+}
+
+pub fn sample_gas_config() -> gas_service::GasConfig {
+    gas_service::GasConfig {
+        operator: key(1),
+        bump: 250,
+    }
+}
+
+pub fn sample_merkleised_message() -> MerkleisedMessage {
+    MerkleisedMessage {
+        leaf: MessageLeaf {
+            message: Message {
+                cc_id: CrossChainId {
+                    chain: "ethereum".to_string(),
+                    id: "0x1".to_string(),
+                },
+                source_address: "0xdead".to_string(),
+                destination_chain: "solana".to_string(),
+                destination_address: "0xbeef".to_string(),
+                payload_hash: [2; 32],
+            },
+            position: 0,
+            set_size: 1,
+            domain_separator: [0; 32],
+            signing_verifier_set: [0; 32],
+        },
+        proof: vec![],
+    }
+}
+
+pub const CALL_CONTRACT_EVENT: &str = concat!(
+    "d3d3507e9662b5c60101010101010101010101010101010101010101010101010101010101010101",
+    "02020202020202020202020202020202020202020202020202020202020202020800000065746865",
+    "7265756d0600000030786465616403000000010203",
+);
+
+pub const CALL_CONTRACT_WITH_TOKEN_EVENT: &str = concat!(
+    "9adc9dae76c537940101010101010101010101010101010101010101010101010101010101010101",
+    "02020202020202020202020202020202020202020202020202020202020202020800000065746865",
+    "7265756d06000000307864656164030000000102030300000041584ce803000000000000",
+);
+
+pub const MESSAGE_APPROVED_EVENT: &str = concat!(
+    "fafe1de39fcd72590303030303030303030303030303030303030303030303030303030303030303",
+    "04040404040404040404040404040404040404040404040404040404040404040202020202020202",
+    "02020202020202020202020202020202020202020202020208000000657468657265756d0a000000",
+    "657468657265756d2d310600000030786465616406000000736f6c616e61",
+);
+
+pub const MESSAGE_EXECUTED_EVENT: &str = concat!(
+    "099dbce1a81a5e520303030303030303030303030303030303030303030303030303030303030303",
+    "04040404040404040404040404040404040404040404040404040404040404040202020202020202",
+    "02020202020202020202020202020202020202020202020208000000657468657265756d0a000000",
+    "657468657265756d2d310600000030786465616406000000736f6c616e61",
+);
+
+pub const VERIFIER_SET_ROTATED_EVENT: &str = concat!(
+    "364f989b8a44e5602a00000000000000000000000000000000000000000000000000000000000000",
+    "0606060606060606060606060606060606060606060606060606060606060606",
+);
+
+pub const INTERCHAIN_TRANSFER: &str = concat!(
+    "d3f2265f94402ad50707070707070707070707070707070707070707070707070707070707070707",
+    "01010101010101010101010101010101010101010101010101010101010101010404040404040404",
+    "04040404040404040404040404040404040404040404040408000000657468657265756d02000000",
+    "deade803000000000000020202020202020202020202020202020202020202020202020202020202",
+    "0202",
+);
+
+pub const LINK_TOKEN_STARTED: &str = concat!(
+    "ef4883b5fb01de820707070707070707070707070707070707070707070707070707070707070707",
+    "08000000657468657265756d01010101010101010101010101010101010101010101010101010101",
+    "0101010102000000dead0200000000",
+);
+
+pub const INTERCHAIN_TOKEN_DEPLOYMENT_STARTED: &str = concat!(
+    "914ac7bad2e893010707070707070707070707070707070707070707070707070707070707070707",
+    "05000000546f6b656e03000000544b4e0902000000010208000000657468657265756d",
+);
+
+pub const TOKEN_METADATA_REGISTERED: &str = concat!(
+    "1b1fbdfbb729087c0101010101010101010101010101010101010101010101010101010101010101",
+    "09",
+);
+
+pub const COMPUTE_BURNED_EVENT: &str = concat!(
+    "dfccbd5c8feb80a36400000008080808080808080808080808080808080808080808080808080808",
+    "08080808",
+);
+
+pub const GAS_PAID_EVENT: &str = concat!(
+    "bfa116ab2920d4f80101010101010101010101010101010101010101010101010101010101010101",
+    "08000000657468657265756d06000000307864656164020202020202020202020202020202020202",
+    "0202020202020202020202020202e803000000000000040404040404040404040404040404040404",
+    "040404040404040404040404040400",
+);
+
+pub const GAS_ADDED_EVENT: &str = concat!(
+    "4361f520c3b44a6d0101010101010101010101010101010101010101010101010101010101010101",
+    "0600000074782d312e31e80300000000000004040404040404040404040404040404040404040404",
+    "04040404040404040404010909090909090909090909090909090909090909090909090909090909",
+    "090909",
+);
+
+pub const GAS_REFUNDED_EVENT: &str = concat!(
+    "ead071565d7bc80c0404040404040404040404040404040404040404040404040404040404040404",
+    "0600000074782d312e31f40100000000000000",
+);
+
+pub const OPERATORSHIP_TRANSFERRED_EVENT: &str = concat!(
+    "c3c82246c0bc09190909090909090909090909090909090909090909090909090909090909090909",
+    "01010101010101010101010101010101010101010101010101010101010101010404040404040404",
+    "040404040404040404040404040404040404040404040404",
+);
+
+pub const FLOW_LIMIT_SET: &str = concat!(
+    "b44d30bdf4cba9bc0707070707070707070707070707070707070707070707070707070707070707",
+    "01010101010101010101010101010101010101010101010101010101010101010140420f00000000",
+    "00",
+);
+
+pub const GATEWAY_CONFIG: &str = concat!(
+    "5bf7421b180130b001000000000000000400000000000000100e00000000000000f1536500000000",
+    "01010101010101010101010101010101010101010101010101010101010101010303030303030303",
+    "030303030303030303030303030303030303030303030303ff",
+);
+
+pub const PAYLOAD_LOG: &str = concat!(
+    "9420f9afc6b9b2670000010000000000000001000000020202020202020202020202020202020202",
+    "02020202020202020202020202020101010101010101010101010101010101010101010101010101",
+    "01010101010108000000657468657265756dfe",
+);
+
+pub const VERIFICATION_SESSION_ACCOUNT: &str = concat!(
+    "4bdf18114023b3d10200000000000000000000000000000003000000000000000000000000000000",
+    "00000000000000000000000000000000060606060606060606060606060606060606060606060606",
+    "0606060606060606fd",
+);
+
+pub const INCOMING_MESSAGE: &str = concat!(
+    "1e907d6fd3df5baafcfb000303030303030303030303030303030303030303030303030303030303",
+    "0303030202020202020202020202020202020202020202020202020202020202020202",
+);
+
+pub const GAS_CONFIG: &str = concat!(
+    "83bf28d7e4ac54b10101010101010101010101010101010101010101010101010101010101010101",
+    "fa",
+);
+
+pub const MERKLEISED_MESSAGE: &str = concat!(
+    "08000000657468657265756d030000003078310600000030786465616406000000736f6c616e6106",
+    "00000030786265656602020202020202020202020202020202020202020202020202020202020202",
+    "02000001000000000000000000000000000000000000000000000000000000000000000000000000",
+    "000000000000000000000000000000000000000000000000000000000000000000",
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_vectors_match() {
+        let checked = check_all().unwrap();
+        assert!(checked.contains(&"MERKLEISED_MESSAGE"));
+    }
+
+    #[test]
+    fn a_changed_value_misses_its_vector() {
+        let mut event = sample_call_contract_event();
+        event.payload.push(4);
+        assert!(assert_tagged_round_trip(&event, CALL_CONTRACT_EVENT).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Typed instruction builders for governance_executor.
+//!
+//! Proposals arrive as gateway-approved messages, so scheduling one takes the
+//! gateway's program id too, for the incoming message PDA.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use governance_executor::{accounts, instruction, GovernanceAction, GovernancePayload};
+use program_tester::Message;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{
+    consumed_message_pda, event_authority_pda, governance_config_pda, governance_proposal_pda,
+    incoming_message_pda,
+};
+
+fn build_ix(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `payer` must be the program's upgrade authority.
+pub fn build_init_config_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    operator: Pubkey,
+    governance_chain: String,
+    governance_address: String,
+    minimum_time_delay: u64,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitConfig {
+            payer: *payer,
+            config_pda: governance_config_pda(program_id).0,
+            this_program: *program_id,
+            program_data: bpf_loader_upgradeable::get_program_data_address(program_id),
+            system_program: system_program::id(),
+        },
+        instruction::InitConfig {
+            operator,
+            governance_chain,
+            governance_address,
+            minimum_time_delay,
+        },
+    )
+}
+
+/// `message` must be approved by the gateway at `gateway_program_id` and carry
+/// the hash of `payload`.
+pub fn build_schedule_proposal_ix(
+    program_id: &Pubkey,
+    gateway_program_id: &Pubkey,
+    payer: &Pubkey,
+    message: Message,
+    payload: GovernancePayload,
+) -> Instruction {
+    let command_id = message.command_id();
+    let proposal_hash = payload.action.hash();
+    build_ix(
+        program_id,
+        accounts::ScheduleProposal {
+            payer: *payer,
+            config_pda: governance_config_pda(program_id).0,
+            incoming_message: incoming_message_pda(gateway_program_id, &command_id).0,
+            consumed_message_pda: consumed_message_pda(program_id, &command_id).0,
+            proposal_pda: governance_proposal_pda(program_id, &proposal_hash).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::ScheduleProposal {
+            command_id,
+            proposal_hash,
+            message,
+            payload,
+        },
+    )
+}
+
+pub fn build_execute_proposal_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    action: GovernanceAction,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::ExecuteProposal {
+            payer: *payer,
+            config_pda: governance_config_pda(program_id).0,
+            proposal_pda: governance_proposal_pda(program_id, &action.hash()).0,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::ExecuteProposal { action },
+    )
+}
@@ -0,0 +1,21 @@
+//! Off-chain mirrors of the hashes program_tester computes on-chain.
+//!
+//! These call straight into the program crate, so a change to the on-chain
+//! derivation can't leave the scripts deriving different PDAs.
+
+use program_tester::{Message, MessageLeaf};
+
+/// `keccak(chain || "-" || id)`, the seed of the `incoming_message` PDA.
+pub fn command_id(chain: &str, id: &str) -> [u8; 32] {
+    program_tester::command_id(chain, id)
+}
+
+/// Keccak over the borsh encoding of `message`, as stored in `IncomingMessage`.
+pub fn message_hash(message: &Message) -> [u8; 32] {
+    message.hash()
+}
+
+/// Keccak over the borsh encoding of `leaf`, the payload Merkle tree leaf.
+pub fn leaf_hash(leaf: &MessageLeaf) -> [u8; 32] {
+    leaf.hash()
+}
@@ -0,0 +1,151 @@
+//! Liveness and readiness of `listen`, for process supervisors and
+//! orchestrators, served on `/healthz` and `/readyz` next to the metrics; see
+//! `crate::metrics::serve`.
+//!
+//! The listener is live while its subscriptions are up, or haven't been down
+//! longer than `max_disconnected`: past that a restart is the better bet than
+//! its own reconnects. It is ready once subscribed and caught up, with every
+//! sink delivering where it was asked to and, with a `max_checkpoint_age`, a
+//! cursor that moved recently enough.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+pub struct ListenerHealth {
+    state: Mutex<State>,
+    /// How long the subscriptions may stay down before the listener counts as
+    /// dead; `None` never.
+    max_disconnected: Option<Duration>,
+    /// How long the cursors may go without moving before the listener counts
+    /// as not ready; `None` never, as a quiet program doesn't move them.
+    max_checkpoint_age: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct State {
+    subscribed: bool,
+    /// When `subscribed` last changed, or startup.
+    since: Instant,
+    last_disconnect: Option<String>,
+    checkpoint_advanced: Option<Instant>,
+    /// Each sink's name and what is wrong with it, if anything.
+    sinks: Vec<(String, Option<String>)>,
+}
+
+impl ListenerHealth {
+    pub fn new(
+        sinks: Vec<String>,
+        max_disconnected: Option<Duration>,
+        max_checkpoint_age: Option<Duration>,
+    ) -> Self {
+        Self {
+            state: Mutex::new(State {
+                subscribed: false,
+                since: Instant::now(),
+                last_disconnect: None,
+                checkpoint_advanced: None,
+                sinks: sinks.into_iter().map(|name| (name, None)).collect(),
+            }),
+            max_disconnected,
+            max_checkpoint_age,
+        }
+    }
+
+    /// The subscriptions are up and the backfill is done.
+    pub fn subscribed(&self) {
+        let mut state = self.lock();
+        state.subscribed = true;
+        state.since = Instant::now();
+    }
+
+    pub fn disconnected(&self, reason: String) {
+        let mut state = self.lock();
+        if state.subscribed {
+            state.subscribed = false;
+            state.since = Instant::now();
+        }
+        state.last_disconnect = Some(reason);
+    }
+
+    pub fn checkpoint_advanced(&self) {
+        self.lock().checkpoint_advanced = Some(Instant::now());
+    }
+
+    /// Records what is wrong with the `index`-th sink after an event, or
+    /// `None` when it took it as asked.
+    pub fn sink_status(&self, index: usize, problem: Option<String>) {
+        if let Some((_, status)) = self.lock().sinks.get_mut(index) {
+            *status = problem;
+        }
+    }
+
+    /// Whether the listener is live, and the status behind it.
+    pub fn liveness(&self) -> (bool, Value) {
+        let state = self.lock();
+        let mut problems = Vec::new();
+        if let Some(max) = self.max_disconnected {
+            if !state.subscribed && state.since.elapsed() > max {
+                problems.push(format!(
+                    "not subscribed for {}s",
+                    state.since.elapsed().as_secs()
+                ));
+            }
+        }
+        (problems.is_empty(), state.to_json(problems))
+    }
+
+    /// Whether the listener is ready, and the status behind it.
+    pub fn readiness(&self) -> (bool, Value) {
+        let state = self.lock();
+        let mut problems = Vec::new();
+        if !state.subscribed {
+            problems.push("not subscribed".to_string());
+        }
+        if let Some(max) = self.max_checkpoint_age {
+            let age = state
+                .checkpoint_advanced
+                .map_or(state.since.elapsed(), |at| at.elapsed());
+            if state.subscribed && age > max {
+                problems.push(format!("no transaction handled for {}s", age.as_secs()));
+            }
+        }
+        for (name, problem) in &state.sinks {
+            if let Some(problem) = problem {
+                problems.push(format!("sink {name}: {problem}"));
+            }
+        }
+        (problems.is_empty(), state.to_json(problems))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is still worth reporting after a panic elsewhere
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl State {
+    fn to_json(&self, problems: Vec<String>) -> Value {
+        json!({
+            "ok": problems.is_empty(),
+            "problems": problems,
+            "subscribed": self.subscribed,
+            "for_secs": self.since.elapsed().as_secs(),
+            "last_disconnect": self.last_disconnect,
+            "checkpoint_age_secs": self.checkpoint_advanced.map(|at| at.elapsed().as_secs()),
+            "sinks": self
+                .sinks
+                .iter()
+                .map(|(name, problem)| json!({
+                    "sink": name,
+                    "ok": problem.is_none(),
+                    "problem": problem,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
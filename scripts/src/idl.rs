@@ -0,0 +1,339 @@
+//! Instruction and event decoding driven by an Anchor IDL loaded at runtime.
+//!
+//! Unlike `events`, which is tied to the programs compiled into this crate,
+//! this works for any Anchor program whose IDL JSON is at hand (e.g. the real
+//! gateway on devnet). Both the 0.30+ IDL format, which carries explicit
+//! discriminators, and the legacy format, where they are derived from the
+//! names, are accepted. Decoded values are returned as JSON: 64-bit and larger
+//! integers as strings, pubkeys in base58, and `bytes` as hex.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::codec::{bs58_encode, hex_encode};
+use crate::error::IdlError;
+
+type Result<T> = std::result::Result<T, IdlError>;
+
+#[derive(Debug, Clone)]
+pub struct IdlCodec {
+    instructions: Vec<Entry>,
+    events: Vec<Entry>,
+    types: HashMap<String, Value>,
+}
+
+/// An instruction or event known to the IDL.
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    discriminator: Vec<u8>,
+    /// Instruction args, or `None` for events whose layout lives in `types`.
+    fields: Option<Vec<Value>>,
+}
+
+/// The result of decoding instruction data or an event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub name: String,
+    pub value: Value,
+}
+
+impl IdlCodec {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path).map_err(|e| IdlError::Read {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        Self::from_json(&raw)
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self> {
+        let idl: Value = serde_json::from_str(raw).map_err(|e| IdlError::Parse(e.to_string()))?;
+
+        let mut types = HashMap::new();
+        for ty in array(&idl, "types") {
+            types.insert(str_field(ty, "name")?.to_string(), ty["type"].clone());
+        }
+        // Legacy IDLs list account and event layouts outside `types`
+        for ty in array(&idl, "accounts") {
+            if ty.get("type").is_some() {
+                types.insert(str_field(ty, "name")?.to_string(), ty["type"].clone());
+            }
+        }
+
+        let mut instructions = Vec::new();
+        for ix in array(&idl, "instructions") {
+            let name = str_field(ix, "name")?;
+            instructions.push(Entry {
+                name: name.to_string(),
+                discriminator: discriminator_of(ix, "global", &snake_case(name))?,
+                fields: Some(array(ix, "args").to_vec()),
+            });
+        }
+
+        let mut events = Vec::new();
+        for ev in array(&idl, "events") {
+            let name = str_field(ev, "name")?;
+            if let Some(fields) = ev.get("fields").and_then(Value::as_array) {
+                types.insert(
+                    name.to_string(),
+                    json!({ "kind": "struct", "fields": fields }),
+                );
+            }
+            events.push(Entry {
+                name: name.to_string(),
+                discriminator: discriminator_of(ev, "event", name)?,
+                fields: None,
+            });
+        }
+
+        Ok(Self {
+            instructions,
+            events,
+            types,
+        })
+    }
+
+    pub fn instruction_names(&self) -> impl Iterator<Item = &str> {
+        self.instructions.iter().map(|e| e.name.as_str())
+    }
+
+    pub fn event_names(&self) -> impl Iterator<Item = &str> {
+        self.events.iter().map(|e| e.name.as_str())
+    }
+
+    /// Decodes instruction data (discriminator followed by the borsh args).
+    /// Returns `Ok(None)` when no instruction in the IDL matches.
+    pub fn decode_instruction(&self, data: &[u8]) -> Result<Option<Decoded>> {
+        let Some((entry, mut rest)) = find(&self.instructions, data) else {
+            return Ok(None);
+        };
+        let fields = entry.fields.as_deref().unwrap_or_default();
+        let value = self.decode_fields(fields, &mut rest)?;
+        Ok(Some(Decoded {
+            name: entry.name.clone(),
+            value,
+        }))
+    }
+
+    /// Decodes event bytes (discriminator followed by the borsh payload).
+    /// Returns `Ok(None)` when no event in the IDL matches.
+    pub fn decode_event(&self, data: &[u8]) -> Result<Option<Decoded>> {
+        let Some((entry, mut rest)) = find(&self.events, data) else {
+            return Ok(None);
+        };
+        let value = self.decode_defined(&entry.name, &mut rest)?;
+        Ok(Some(Decoded {
+            name: entry.name.clone(),
+            value,
+        }))
+    }
+
+    fn decode_fields(&self, fields: &[Value], data: &mut &[u8]) -> Result<Value> {
+        let mut out = Map::new();
+        for field in fields {
+            let name = str_field(field, "name")?.to_string();
+            out.insert(name, self.decode_type(&field["type"], data)?);
+        }
+        Ok(Value::Object(out))
+    }
+
+    fn decode_defined(&self, name: &str, data: &mut &[u8]) -> Result<Value> {
+        let def = self
+            .types
+            .get(name)
+            .ok_or_else(|| IdlError::UnknownType(name.to_string()))?;
+        match def["kind"].as_str() {
+            Some("struct") => match def.get("fields").and_then(Value::as_array) {
+                None => Ok(Value::Object(Map::new())),
+                // Tuple structs list bare types instead of named fields
+                Some(fields) if fields.iter().all(|f| f.get("name").is_none()) => fields
+                    .iter()
+                    .map(|ty| self.decode_type(ty, data))
+                    .collect::<Result<Vec<_>>>()
+                    .map(Value::Array),
+                Some(fields) => self.decode_fields(fields, data),
+            },
+            Some("enum") => {
+                let variants = def["variants"].as_array().cloned().unwrap_or_default();
+                let index = take(data, 1)?[0] as usize;
+                let variant = variants
+                    .get(index)
+                    .ok_or_else(|| IdlError::InvalidData(format!("{name}: no variant {index}")))?;
+                let variant_name = str_field(variant, "name")?.to_string();
+                match variant.get("fields").and_then(Value::as_array) {
+                    None => Ok(Value::String(variant_name)),
+                    Some(fields) => {
+                        let inner = if fields.iter().all(|f| f.get("name").is_none()) {
+                            fields
+                                .iter()
+                                .map(|ty| self.decode_type(ty, data))
+                                .collect::<Result<Vec<_>>>()
+                                .map(Value::Array)?
+                        } else {
+                            self.decode_fields(fields, data)?
+                        };
+                        Ok(json!({ variant_name: inner }))
+                    }
+                }
+            }
+            Some("alias") => self.decode_type(&def["value"], data),
+            other => Err(IdlError::Unsupported(format!(
+                "type kind {other:?} of {name}"
+            ))),
+        }
+    }
+
+    fn decode_type(&self, ty: &Value, data: &mut &[u8]) -> Result<Value> {
+        if let Some(primitive) = ty.as_str() {
+            return decode_primitive(primitive, data);
+        }
+        if let Some(inner) = ty.get("vec") {
+            let len = u32::from_le_bytes(take(data, 4)?.try_into().unwrap()) as usize;
+            return (0..len)
+                .map(|_| self.decode_type(inner, data))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(inner) = ty.get("option") {
+            return match take(data, 1)?[0] {
+                0 => Ok(Value::Null),
+                1 => self.decode_type(inner, data),
+                tag => Err(IdlError::InvalidData(format!("invalid option tag {tag}"))),
+            };
+        }
+        if let Some(array) = ty.get("array").and_then(Value::as_array) {
+            let (Some(inner), Some(len)) = (array.first(), array.get(1).and_then(Value::as_u64))
+            else {
+                return Err(IdlError::Unsupported(format!("array type {ty}")));
+            };
+            if inner.as_str() == Some("u8") {
+                return Ok(Value::String(hex_encode(take(data, len as usize)?)));
+            }
+            return (0..len)
+                .map(|_| self.decode_type(inner, data))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(defined) = ty.get("defined") {
+            // 0.30+: {"defined": {"name": ..}}, legacy: {"defined": ".."}
+            let name = defined
+                .get("name")
+                .and_then(Value::as_str)
+                .or_else(|| defined.as_str())
+                .ok_or_else(|| IdlError::Unsupported(format!("defined type {ty}")))?;
+            return self.decode_defined(name, data);
+        }
+        Err(IdlError::Unsupported(format!("type {ty}")))
+    }
+}
+
+fn decode_primitive(name: &str, data: &mut &[u8]) -> Result<Value> {
+    macro_rules! int {
+        ($ty:ty) => {{
+            let bytes = take(data, std::mem::size_of::<$ty>())?;
+            <$ty>::from_le_bytes(bytes.try_into().unwrap())
+        }};
+    }
+    Ok(match name {
+        "bool" => Value::Bool(take(data, 1)?[0] != 0),
+        "u8" => json!(int!(u8)),
+        "i8" => json!(int!(i8)),
+        "u16" => json!(int!(u16)),
+        "i16" => json!(int!(i16)),
+        "u32" => json!(int!(u32)),
+        "i32" => json!(int!(i32)),
+        "u64" => Value::String(int!(u64).to_string()),
+        "i64" => Value::String(int!(i64).to_string()),
+        "u128" => Value::String(int!(u128).to_string()),
+        "i128" => Value::String(int!(i128).to_string()),
+        "f32" => json!(f32::from_le_bytes(take(data, 4)?.try_into().unwrap())),
+        "f64" => json!(f64::from_le_bytes(take(data, 8)?.try_into().unwrap())),
+        "pubkey" | "publicKey" => Value::String(bs58_encode(take(data, 32)?)),
+        "string" => {
+            let len = int!(u32) as usize;
+            let bytes = take(data, len)?;
+            Value::String(
+                std::str::from_utf8(bytes)
+                    .map_err(|e| IdlError::InvalidData(e.to_string()))?
+                    .to_string(),
+            )
+        }
+        "bytes" => {
+            let len = int!(u32) as usize;
+            Value::String(hex_encode(take(data, len)?))
+        }
+        other => return Err(IdlError::Unsupported(format!("primitive {other}"))),
+    })
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if data.len() < len {
+        return Err(IdlError::InvalidData(format!(
+            "needed {len} more bytes, only {} left",
+            data.len()
+        )));
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+fn find<'a, 'b>(entries: &'a [Entry], data: &'b [u8]) -> Option<(&'a Entry, &'b [u8])> {
+    entries.iter().find_map(|entry| {
+        data.strip_prefix(entry.discriminator.as_slice())
+            .map(|rest| (entry, rest))
+    })
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Result<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| IdlError::Parse(format!("missing {key:?} in {value}")))
+}
+
+/// Uses the IDL's explicit discriminator when present, otherwise derives it
+/// from `sha256("<namespace>:<name>")` like legacy Anchor.
+fn discriminator_of(entry: &Value, namespace: &str, name: &str) -> Result<Vec<u8>> {
+    match entry.get("discriminator").and_then(Value::as_array) {
+        Some(bytes) => bytes
+            .iter()
+            .map(|b| {
+                b.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| IdlError::Parse(format!("invalid discriminator in {entry}")))
+            })
+            .collect(),
+        None => Ok(Sha256::digest(format!("{namespace}:{name}"))[..8].to_vec()),
+    }
+}
+
+/// Legacy IDLs use camelCase instruction names, but the sighash is computed
+/// over the snake_case Rust name.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
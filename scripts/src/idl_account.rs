@@ -0,0 +1,308 @@
+//! Anchor's on-chain IDL account, read and written the way `anchor idl
+//! fetch`, `init` and `upgrade` do, without the Anchor CLI.
+//!
+//! Every Anchor program answers the IDL instructions, tagged `IDL_IX_TAG`, for
+//! an account at a fixed address derived from the program id. It holds the
+//! authority and the zlib-compressed IDL JSON. A new IDL is written into that
+//! account in chunks; an existing one is replaced through a buffer account, so
+//! readers never see it half written, or closed and created anew when the new
+//! IDL doesn't fit.
+
+use std::io::{Read, Write};
+
+use anchor_lang::idl::{IdlAccount, IdlInstruction, IDL_IX_TAG};
+use anchor_lang::AnchorSerialize;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::{system_instruction, system_program, sysvar};
+
+use crate::accounts::decode_account;
+use crate::error::{IdlAccountError, RpcError};
+use crate::rpc::ResilientRpc;
+use crate::tx::TxBuilder;
+
+/// Discriminator, authority and data length ahead of the compressed IDL.
+const HEADER_LEN: usize = 8 + 32 + 4;
+/// Compressed bytes per write, as the Anchor CLI sends them.
+const WRITE_CHUNK: usize = 600;
+/// Most the IDL account may grow by in one instruction.
+const MAX_GROWTH: usize = 10_000;
+
+/// What `publish_idl` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdlPublish {
+    Created,
+    /// Replaced through a buffer.
+    Upgraded,
+    /// Closed and created again, the new IDL being too big for the account.
+    Recreated,
+    /// The account already held this IDL.
+    Unchanged,
+}
+
+/// The address of `program_id`'s IDL account.
+pub fn idl_address(program_id: &Pubkey) -> Pubkey {
+    IdlAccount::address(program_id)
+}
+
+/// The IDL published for `program_id`, or `None` if it has no IDL account.
+pub async fn fetch_onchain_idl(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<Option<Value>, IdlAccountError> {
+    let address = idl_address(program_id);
+    let Some(account) = rpc
+        .get_account_with_commitment(&address, rpc.commitment())
+        .await
+        .map_err(RpcError::from)?
+        .value
+    else {
+        return Ok(None);
+    };
+    let header: IdlAccount = decode_account(&address, &account.data)?;
+    stored_idl(&address, &header, &account.data).map(Some)
+}
+
+/// Publishes `idl` as `program_id`'s IDL, with the payer as authority of a new
+/// IDL account. Fails if an existing one has another authority.
+pub async fn publish_idl(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    idl: &Value,
+) -> Result<IdlPublish, IdlAccountError> {
+    let data = compress(idl);
+    let address = idl_address(program_id);
+    let existing = rpc
+        .retry(|| rpc.get_account_with_commitment(&address, rpc.commitment()))
+        .await?
+        .value;
+    let Some(account) = existing else {
+        create(rpc, payer, program_id, &address, data.len()).await?;
+        write(rpc, payer, program_id, &address, &data).await?;
+        return Ok(IdlPublish::Created);
+    };
+
+    let header: IdlAccount = decode_account(&address, &account.data)?;
+    if header.authority != payer.pubkey() {
+        return Err(IdlAccountError::NotAuthority {
+            program_id: *program_id,
+            authority: header.authority,
+        });
+    }
+    // An unreadable IDL is simply replaced
+    if stored_idl(&address, &header, &account.data).ok().as_ref() == Some(idl) {
+        return Ok(IdlPublish::Unchanged);
+    }
+    if account.data.len() - HEADER_LEN >= data.len() {
+        upgrade(rpc, payer, program_id, &address, &data).await?;
+        return Ok(IdlPublish::Upgraded);
+    }
+    send(
+        rpc,
+        payer,
+        idl_ix(
+            program_id,
+            vec![
+                AccountMeta::new(address, false),
+                AccountMeta::new_readonly(payer.pubkey(), true),
+                AccountMeta::new(payer.pubkey(), false),
+            ],
+            IdlInstruction::Close,
+        ),
+    )
+    .await?;
+    create(rpc, payer, program_id, &address, data.len()).await?;
+    write(rpc, payer, program_id, &address, &data).await?;
+    Ok(IdlPublish::Recreated)
+}
+
+/// Creates the IDL account with room for `len` compressed bytes.
+async fn create(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    address: &Pubkey,
+    len: usize,
+) -> Result<(), IdlAccountError> {
+    let base = Pubkey::find_program_address(&[], program_id).0;
+    // Twice the IDL, like the Anchor CLI, to leave room for it to grow
+    send(
+        rpc,
+        payer,
+        idl_ix(
+            program_id,
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(*address, false),
+                AccountMeta::new_readonly(base, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(*program_id, false),
+            ],
+            IdlInstruction::Create {
+                data_len: 2 * len as u64,
+            },
+        ),
+    )
+    .await?;
+
+    // The program caps the first allocation, so grow the rest in steps
+    let target = HEADER_LEN + len;
+    let mut space = (HEADER_LEN + 2 * len).min(MAX_GROWTH);
+    while space < target {
+        send(
+            rpc,
+            payer,
+            idl_ix(
+                program_id,
+                vec![
+                    AccountMeta::new(*address, false),
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+                IdlInstruction::Resize {
+                    data_len: target as u64,
+                },
+            ),
+        )
+        .await?;
+        space = (space + MAX_GROWTH).min(target);
+    }
+    Ok(())
+}
+
+/// Writes `data` into a fresh buffer, then swaps it into the IDL account and
+/// closes the buffer.
+async fn upgrade(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    address: &Pubkey,
+    data: &[u8],
+) -> Result<(), IdlAccountError> {
+    let buffer = Keypair::new();
+    let space = HEADER_LEN + data.len();
+    let lamports = rpc
+        .retry(|| rpc.get_minimum_balance_for_rent_exemption(space))
+        .await?;
+    let builder = TxBuilder::new(payer)
+        .instruction(system_instruction::create_account(
+            &payer.pubkey(),
+            &buffer.pubkey(),
+            lamports,
+            space as u64,
+            program_id,
+        ))
+        .instruction(idl_ix(
+            program_id,
+            vec![
+                AccountMeta::new(buffer.pubkey(), false),
+                AccountMeta::new_readonly(payer.pubkey(), true),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            IdlInstruction::CreateBuffer,
+        ))
+        .signer(&buffer);
+    rpc.send(&builder).await?;
+    write(rpc, payer, program_id, &buffer.pubkey(), data).await?;
+
+    let authority = AccountMeta::new_readonly(payer.pubkey(), true);
+    let set_buffer = idl_ix(
+        program_id,
+        vec![
+            AccountMeta::new(buffer.pubkey(), false),
+            AccountMeta::new(*address, false),
+            authority.clone(),
+        ],
+        IdlInstruction::SetBuffer,
+    );
+    let close_buffer = idl_ix(
+        program_id,
+        vec![
+            AccountMeta::new(buffer.pubkey(), false),
+            authority,
+            AccountMeta::new(payer.pubkey(), false),
+        ],
+        IdlInstruction::Close,
+    );
+    let builder = TxBuilder::new(payer).instructions([set_buffer, close_buffer]);
+    rpc.send(&builder).await?;
+    Ok(())
+}
+
+/// Appends `data` to the IDL or buffer account at `target`.
+async fn write(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    target: &Pubkey,
+    data: &[u8],
+) -> Result<(), IdlAccountError> {
+    // In order: each write appends
+    for chunk in data.chunks(WRITE_CHUNK) {
+        send(
+            rpc,
+            payer,
+            idl_ix(
+                program_id,
+                vec![
+                    AccountMeta::new(*target, false),
+                    AccountMeta::new_readonly(payer.pubkey(), true),
+                ],
+                IdlInstruction::Write {
+                    data: chunk.to_vec(),
+                },
+            ),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn send(rpc: &ResilientRpc, payer: &Keypair, ix: Instruction) -> Result<(), RpcError> {
+    rpc.send(&TxBuilder::new(payer).instruction(ix)).await?;
+    Ok(())
+}
+
+fn idl_ix(program_id: &Pubkey, accounts: Vec<AccountMeta>, ix: IdlInstruction) -> Instruction {
+    let mut data = IDL_IX_TAG.to_le_bytes().to_vec();
+    // Writing to a Vec doesn't fail
+    ix.serialize(&mut data).unwrap();
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+fn compress(idl: &Value) -> Vec<u8> {
+    // Writing to a Vec doesn't fail
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(idl.to_string().as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn stored_idl(
+    address: &Pubkey,
+    header: &IdlAccount,
+    data: &[u8],
+) -> Result<Value, IdlAccountError> {
+    let corrupt = |reason: String| IdlAccountError::Corrupt {
+        address: *address,
+        reason,
+    };
+    let compressed = data
+        .get(HEADER_LEN..HEADER_LEN + header.data_len as usize)
+        .ok_or_else(|| corrupt(format!("data_len {} overruns the account", header.data_len)))?;
+    let mut json = Vec::new();
+    ZlibDecoder::new(compressed)
+        .read_to_end(&mut json)
+        .map_err(|e| corrupt(e.to_string()))?;
+    serde_json::from_slice(&json).map_err(|e| corrupt(e.to_string()))
+}
@@ -0,0 +1,302 @@
+//! The places a program's id is written down, and whether they agree.
+//!
+//! Each program's id is recorded in several places that drift apart:
+//! - the `declare_id!` it is built with,
+//! - its keypair in `target/deploy`, whose address `anchor deploy` and
+//!   `axelar-dummy deploy` deploy to,
+//! - the `[programs.<cluster>]` section of `Anchor.toml`,
+//! - for the gateway (`program_tester`) and gas service, the scripts' config.
+//!
+//! A program deployed anywhere but its `declare_id!` fails every instruction
+//! with `DeclaredProgramIdMismatch`, so `declare_id!` is the reference the
+//! others are compared with.
+//!
+//! The `write_*` functions move a program to a new id in each of those
+//! places, for forks that deploy their own copies.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+
+use crate::cluster::Cluster;
+use crate::config::Config;
+use crate::error::IdsError;
+use crate::signer::read_keypair;
+
+/// Where an id was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdSource {
+    DeclareId,
+    Keypair,
+    AnchorToml(Cluster),
+    Config,
+}
+
+impl fmt::Display for IdSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdSource::DeclareId => f.write_str("declare_id!"),
+            IdSource::Keypair => f.write_str("target/deploy keypair"),
+            IdSource::AnchorToml(cluster) => write!(f, "Anchor.toml [programs.{cluster}]"),
+            IdSource::Config => f.write_str("config"),
+        }
+    }
+}
+
+/// Every id recorded for one program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramIdSources {
+    pub program: String,
+    pub ids: Vec<(IdSource, Pubkey)>,
+}
+
+impl ProgramIdSources {
+    pub fn id(&self, source: IdSource) -> Option<Pubkey> {
+        self.ids
+            .iter()
+            .find(|(s, _)| *s == source)
+            .map(|(_, id)| *id)
+    }
+
+    /// The sources disagreeing with `declare_id!`, or with the first id found
+    /// if the program has none.
+    pub fn mismatches(&self) -> Vec<(IdSource, Pubkey)> {
+        let Some(expected) = self
+            .id(IdSource::DeclareId)
+            .or_else(|| self.ids.first().map(|(_, id)| *id))
+        else {
+            return Vec::new();
+        };
+        self.ids
+            .iter()
+            .filter(|(_, id)| *id != expected)
+            .copied()
+            .collect()
+    }
+}
+
+/// The nearest of `start` and its ancestors holding an `Anchor.toml`.
+pub fn workspace_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join("Anchor.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Collects the ids of every program under `root/programs` that declares
+/// one, comparing `Anchor.toml` for `cluster`.
+pub fn collect(
+    root: &Path,
+    cluster: Cluster,
+    config: &Config,
+) -> Result<Vec<ProgramIdSources>, IdsError> {
+    let anchor = anchor_program_ids(&root.join("Anchor.toml"), cluster)?;
+    let programs_dir = root.join("programs");
+    let mut programs = Vec::new();
+    for name in program_crates(root)? {
+        let lib = programs_dir.join(&name).join("src/lib.rs");
+        let source = std::fs::read_to_string(&lib).map_err(|e| read_error(&lib, e))?;
+        // Libraries like `common` have no id
+        let Some(declared) = declared_id(&source) else {
+            continue;
+        };
+        let declared = parse_id(&lib, declared)?;
+        let mut ids = vec![(IdSource::DeclareId, declared)];
+
+        let keypair = root
+            .join("target/deploy")
+            .join(format!("{name}-keypair.json"));
+        if keypair.is_file() {
+            ids.push((IdSource::Keypair, read_keypair(&keypair)?.pubkey()));
+        }
+        if let Some(id) = anchor.iter().find(|(n, _)| *n == name).map(|(_, id)| *id) {
+            ids.push((IdSource::AnchorToml(cluster), id));
+        }
+        match name.as_str() {
+            "program_tester" => ids.push((IdSource::Config, config.gateway_program_id)),
+            "gas_service" => ids.push((IdSource::Config, config.gas_program_id)),
+            _ => {}
+        }
+        programs.push(ProgramIdSources { program: name, ids });
+    }
+    Ok(programs)
+}
+
+/// The crates under `root/programs`, sorted.
+pub fn program_crates(root: &Path) -> Result<Vec<String>, IdsError> {
+    let programs_dir = root.join("programs");
+    let entries = std::fs::read_dir(&programs_dir).map_err(|e| read_error(&programs_dir, e))?;
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| read_error(&programs_dir, e))?;
+        if entry.path().join("src/lib.rs").is_file() {
+            names.extend(entry.file_name().to_str().map(str::to_string));
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// The id in a program's `declare_id!("...")`, if it has one.
+pub fn declared_id(source: &str) -> Option<&str> {
+    let (_, rest) = source.split_once("declare_id!(")?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    rest.split_once('"').map(|(id, _)| id)
+}
+
+/// `source` with the id in its `declare_id!` replaced by `id`, or `None` if
+/// it has none.
+pub fn replace_declared_id(source: &str, id: &Pubkey) -> Option<String> {
+    let old = declared_id(source)?;
+    // `old` borrows from `source`, so its offset locates it
+    let start = old.as_ptr() as usize - source.as_ptr() as usize;
+    let mut replaced = source.to_string();
+    replaced.replace_range(start..start + old.len(), &id.to_string());
+    Some(replaced)
+}
+
+/// Points the `declare_id!` of the program crate `name` under `root` at `id`,
+/// returning the id it had.
+pub fn write_declared_id(root: &Path, name: &str, id: &Pubkey) -> Result<Pubkey, IdsError> {
+    let lib = root.join("programs").join(name).join("src/lib.rs");
+    let source = std::fs::read_to_string(&lib).map_err(|e| read_error(&lib, e))?;
+    let (Some(old), Some(replaced)) = (declared_id(&source), replace_declared_id(&source, id))
+    else {
+        return Err(IdsError::Parse {
+            path: lib.display().to_string(),
+            reason: "no declare_id!".to_string(),
+        });
+    };
+    let old = parse_id(&lib, old)?;
+    std::fs::write(&lib, replaced).map_err(|e| write_error(&lib, e))?;
+    Ok(old)
+}
+
+/// Writes `keypair` as the `target/deploy` keypair of the program crate
+/// `name` under `root`. A keypair already there is kept as
+/// `<name>-keypair-<its id>.json`, whose path is returned, so what was
+/// deployed at the old address can still be upgraded or closed.
+pub fn write_program_keypair(
+    root: &Path,
+    name: &str,
+    keypair: &Keypair,
+) -> Result<Option<PathBuf>, IdsError> {
+    let dir = root.join("target/deploy");
+    std::fs::create_dir_all(&dir).map_err(|e| write_error(&dir, e))?;
+    let path = dir.join(format!("{name}-keypair.json"));
+    let mut kept = None;
+    if path.is_file() {
+        let old = read_keypair(&path)?.pubkey();
+        let backup = dir.join(format!("{name}-keypair-{old}.json"));
+        std::fs::rename(&path, &backup).map_err(|e| write_error(&backup, e))?;
+        kept = Some(backup);
+    }
+    write_keypair_file(keypair, &path).map_err(|e| IdsError::Write {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(kept)
+}
+
+/// Sets the given crates' ids in the `[programs.<cluster>]` section of the
+/// `Anchor.toml` at `path`, adding the section or entries that are missing.
+/// The file is edited line by line, so its layout and comments survive.
+pub fn write_anchor_program_ids(
+    path: &Path,
+    cluster: Cluster,
+    ids: &[(String, Pubkey)],
+) -> Result<(), IdsError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+    let mut lines: Vec<String> = raw.lines().map(str::to_string).collect();
+    let header = format!("[programs.{cluster}]");
+    let start = match lines.iter().position(|line| line.trim() == header) {
+        Some(start) => start,
+        None => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(header);
+            lines.len() - 1
+        }
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    let mut missing = Vec::new();
+    for (name, id) in ids {
+        let entry = format!("{name} = \"{id}\"");
+        let existing = lines[start + 1..end].iter().position(|line| {
+            line.split_once('=')
+                .is_some_and(|(key, _)| key.trim().trim_matches('"') == name)
+        });
+        match existing {
+            Some(offset) => lines[start + 1 + offset] = entry,
+            None => missing.push(entry),
+        }
+    }
+    // After the section's last entry, before the blank lines ending it
+    let mut at = end;
+    while at > start + 1 && lines[at - 1].trim().is_empty() {
+        at -= 1;
+    }
+    lines.splice(at..at, missing);
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    std::fs::write(path, updated).map_err(|e| write_error(path, e))
+}
+
+/// The `[programs.<cluster>]` section of the `Anchor.toml` at `path`, as
+/// crate names and ids.
+pub fn anchor_program_ids(
+    path: &Path,
+    cluster: Cluster,
+) -> Result<Vec<(String, Pubkey)>, IdsError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+    let table = raw.parse::<toml::Table>().map_err(|e| IdsError::Parse {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    let Some(section) = table
+        .get("programs")
+        .and_then(|programs| programs.get(cluster.to_string()))
+        .and_then(toml::Value::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+    let mut ids = Vec::new();
+    for (name, id) in section {
+        let id = id.as_str().ok_or_else(|| IdsError::Parse {
+            path: path.display().to_string(),
+            reason: format!("programs.{cluster}.{name} is not a string"),
+        })?;
+        ids.push((name.clone(), parse_id(path, id)?));
+    }
+    Ok(ids)
+}
+
+fn parse_id(path: &Path, id: &str) -> Result<Pubkey, IdsError> {
+    Pubkey::from_str(id).map_err(|e| IdsError::Parse {
+        path: path.display().to_string(),
+        reason: format!("invalid program id {id}: {e}"),
+    })
+}
+
+fn write_error(path: &Path, err: std::io::Error) -> IdsError {
+    IdsError::Write {
+        path: path.display().to_string(),
+        reason: err.to_string(),
+    }
+}
+
+fn read_error(path: &Path, err: std::io::Error) -> IdsError {
+    IdsError::Read {
+        path: path.display().to_string(),
+        reason: err.to_string(),
+    }
+}
@@ -0,0 +1,467 @@
+//! Every Anchor event in a confirmed transaction, attributed to the program
+//! that emitted it.
+//!
+//! `emit_cpi!` events are self-invocations signed by the program's event
+//! authority and carrying Anchor's event tag, found in the inner instructions; `emit!` events are `Program data:` log lines,
+//! attributed to the program on top of the invocation stack the logs trace.
+//! Events the registry doesn't know are kept with their raw bytes so callers
+//! can decode them another way, e.g. with an IDL.
+
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::json;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_request::{RpcError as RpcRequestError, RpcResponseErrorData};
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    EncodedTransactionWithStatusMeta, UiConfirmedBlock, UiInnerInstructions, UiInstruction,
+    UiLoadedAddresses, UiMessage, UiParsedInstruction, UiTransactionEncoding,
+};
+
+use crate::codec::{bs58_decode, Mode};
+use crate::error::RpcError;
+use crate::events::{cpi_event_data, decode_event, DecodedEvent};
+use crate::message_id::MessageId;
+use crate::receipt::lookup_commitment;
+use crate::rpc::ResilientRpc;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+/// Most signatures `getSignaturesForAddress` returns at once.
+const SIGNATURES_PAGE: usize = 1000;
+
+/// Where in the transaction an event was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSource {
+    /// An `emit_cpi!` self-invocation, with the id Axelar refers to it by.
+    Cpi(MessageId),
+    /// A `Program data:` log, with its 0-based line number.
+    Log(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct FoundEvent {
+    /// The program that emitted the event, if the transaction names it.
+    pub program_id: Option<Pubkey>,
+    pub source: EventSource,
+    /// The event bytes: the 8-byte discriminator followed by the borsh payload.
+    pub data: Vec<u8>,
+    /// `None` when the discriminator is unknown or the payload malformed.
+    pub event: Option<DecodedEvent>,
+}
+
+impl FoundEvent {
+    pub fn discriminator(&self) -> Option<&[u8]> {
+        self.data.get(..8)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TxEvents {
+    pub signature: Signature,
+    pub slot: u64,
+    /// Unix time of the block, unknown from the logs alone.
+    pub block_time: Option<i64>,
+    /// The transaction error, if it landed but failed.
+    pub err: Option<String>,
+    /// Lamports the transaction paid in fees, unknown from the logs alone.
+    pub fee: Option<u64>,
+    pub logs: Vec<String>,
+    /// CPI events in instruction order, then log events in log order.
+    pub events: Vec<FoundEvent>,
+}
+
+impl TxEvents {
+    /// The events in the logs of a transaction, as a logs subscription
+    /// delivers them, for when the transaction itself can't be fetched or its
+    /// inner instructions are missing. Only `emit!` events are logged.
+    pub fn from_logs(signature: Signature, slot: u64, logs: Vec<String>) -> Self {
+        Self {
+            signature,
+            slot,
+            block_time: None,
+            err: None,
+            fee: None,
+            events: log_events(&logs),
+            logs,
+        }
+    }
+
+    /// The events in the inner instructions and logs of a JSON-encoded
+    /// transaction, fetched or part of a block.
+    pub fn from_encoded(
+        signature: Signature,
+        slot: u64,
+        block_time: Option<i64>,
+        tx: &EncodedTransactionWithStatusMeta,
+    ) -> Self {
+        let keys = account_keys(tx);
+        let meta = tx.meta.as_ref();
+        let mut events = Vec::new();
+        let inner: Option<Vec<UiInnerInstructions>> =
+            meta.and_then(|meta| meta.inner_instructions.clone().into());
+        for group in inner.into_iter().flatten() {
+            for (position, ix) in group.instructions.iter().enumerate() {
+                let Some((program_id, data)) = inner_event(ix, &keys) else {
+                    continue;
+                };
+                events.push(FoundEvent {
+                    program_id: Some(program_id),
+                    source: EventSource::Cpi(MessageId::from_rpc_positions(
+                        signature,
+                        group.index,
+                        position,
+                    )),
+                    event: decode_event(&data),
+                    data,
+                });
+            }
+        }
+
+        let logs: Vec<String> = meta
+            .and_then(|meta| meta.log_messages.clone().into())
+            .unwrap_or_default();
+        events.extend(log_events(&logs));
+
+        Self {
+            signature,
+            slot,
+            block_time,
+            fee: meta.map(|meta| meta.fee),
+            err: meta
+                .and_then(|meta| meta.err.as_ref())
+                .map(|e| e.to_string()),
+            logs,
+            events,
+        }
+    }
+}
+
+/// Fetches `signature` and collects the events in its inner instructions and
+/// logs.
+pub async fn fetch_tx_events(rpc: &RpcClient, signature: &Signature) -> Result<TxEvents, RpcError> {
+    let tx = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(lookup_commitment(rpc.commitment())),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    Ok(TxEvents::from_encoded(
+        *signature,
+        tx.slot,
+        tx.block_time,
+        &tx.transaction,
+    ))
+}
+
+/// Fetches each of `signatures` like `fetch_tx_events`, all in one JSON-RPC
+/// batch request sent through `http`, for nodes that take batches. The
+/// request as a whole is retried; a transaction the node didn't return gets
+/// an error of its own, in the order of `signatures`.
+pub async fn fetch_tx_events_batch(
+    rpc: &ResilientRpc,
+    http: &reqwest::Client,
+    signatures: &[Signature],
+) -> Result<Vec<Result<TxEvents, RpcError>>, RpcError> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(lookup_commitment(rpc.commitment())),
+        max_supported_transaction_version: Some(0),
+    };
+    // The ids are the indices, as the node may answer in any order
+    let batch: Vec<serde_json::Value> = signatures
+        .iter()
+        .enumerate()
+        .map(|(id, signature)| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "getTransaction",
+                "params": [signature.to_string(), config],
+            })
+        })
+        .collect();
+    let url = rpc.url();
+    let answers: Vec<BatchAnswer> = rpc
+        .retry(|| async {
+            let response = http
+                .post(&url)
+                .json(&batch)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok::<_, ClientError>(response.json().await?)
+        })
+        .await?;
+
+    let mut found: Vec<Option<Result<TxEvents, RpcError>>> =
+        signatures.iter().map(|_| None).collect();
+    for answer in answers {
+        let (Some(entry), Some(signature)) = (found.get_mut(answer.id), signatures.get(answer.id))
+        else {
+            continue;
+        };
+        *entry = Some(match (answer.result, answer.error) {
+            (_, Some(error)) => Err(batch_error(ClientErrorKind::RpcError(
+                RpcRequestError::RpcResponseError {
+                    code: error.code,
+                    message: error.message,
+                    data: RpcResponseErrorData::Empty,
+                },
+            ))),
+            (Some(tx), None) => Ok(TxEvents::from_encoded(
+                *signature,
+                tx.slot,
+                tx.block_time,
+                &tx.transaction,
+            )),
+            (None, None) => Err(batch_error(ClientErrorKind::Custom(format!(
+                "transaction {signature} not found"
+            )))),
+        });
+    }
+    Ok(found
+        .into_iter()
+        .zip(signatures)
+        .map(|(found, signature)| {
+            found.unwrap_or_else(|| {
+                Err(batch_error(ClientErrorKind::Custom(format!(
+                    "no answer for {signature} in the batch"
+                ))))
+            })
+        })
+        .collect())
+}
+
+/// One answer of a JSON-RPC batch of `getTransaction` requests.
+#[derive(Deserialize)]
+struct BatchAnswer {
+    id: usize,
+    #[serde(default)]
+    result: Option<EncodedConfirmedTransactionWithStatusMeta>,
+    #[serde(default)]
+    error: Option<BatchAnswerError>,
+}
+
+#[derive(Deserialize)]
+struct BatchAnswerError {
+    code: i64,
+    message: String,
+}
+
+fn batch_error(kind: ClientErrorKind) -> RpcError {
+    ClientError::from(kind).into()
+}
+
+/// The transactions of a block, as a block subscription delivers them with
+/// full details in the JSON encoding, in block order.
+pub fn block_tx_events(slot: u64, block: &UiConfirmedBlock) -> Vec<TxEvents> {
+    block
+        .transactions
+        .iter()
+        .flatten()
+        .filter_map(|tx| {
+            let signature = match &tx.transaction {
+                EncodedTransaction::Json(ui_tx) => ui_tx.signatures.first()?.parse().ok()?,
+                _ => return None,
+            };
+            Some(TxEvents::from_encoded(
+                signature,
+                slot,
+                block.block_time,
+                tx,
+            ))
+        })
+        .collect()
+}
+
+/// The signatures and slots of the successful transactions mentioning
+/// `address` that landed after `until`, at `until_slot`, oldest first. Fails
+/// once the listing reaches a slot before `until_slot` without finding
+/// `until`, which the node no longer has or never saw finalize, rather than
+/// paging through the whole history.
+pub async fn signatures_since(
+    rpc: &ResilientRpc,
+    address: &Pubkey,
+    until: Signature,
+    until_slot: u64,
+) -> Result<Vec<(Signature, u64)>, RpcError> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = signatures_page(rpc, address, before, Some(until), None).await?;
+        if let Some(passed) = page.iter().find(|status| status.slot < until_slot) {
+            return Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "{until} at slot {until_slot} isn't in the history of {address}: the listing \
+                 reached slot {} without it",
+                passed.slot
+            )))
+            .into());
+        }
+        before = page
+            .last()
+            .and_then(|status| Signature::from_str(&status.signature).ok());
+        signatures.extend(
+            page.iter()
+                .filter(|status| status.err.is_none())
+                .filter_map(|status| {
+                    let signature = Signature::from_str(&status.signature).ok()?;
+                    Some((signature, status.slot))
+                }),
+        );
+        if page.len() < SIGNATURES_PAGE || before.is_none() {
+            break;
+        }
+    }
+    // The node returns them newest first
+    signatures.reverse();
+    Ok(signatures)
+}
+
+/// The signature and slot of the latest transaction mentioning `address`,
+/// failed or not, as a starting point for `signatures_since`.
+pub async fn latest_signature(
+    rpc: &ResilientRpc,
+    address: &Pubkey,
+) -> Result<Option<(Signature, u64)>, RpcError> {
+    let page = signatures_page(rpc, address, None, None, Some(1)).await?;
+    Ok(page.first().and_then(|status| {
+        let signature = Signature::from_str(&status.signature).ok()?;
+        Some((signature, status.slot))
+    }))
+}
+
+async fn signatures_page(
+    rpc: &ResilientRpc,
+    address: &Pubkey,
+    before: Option<Signature>,
+    until: Option<Signature>,
+    limit: Option<usize>,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, RpcError> {
+    rpc.retry(|| {
+        rpc.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit,
+                commitment: Some(lookup_commitment(rpc.commitment())),
+            },
+        )
+    })
+    .await
+}
+
+/// The account keys a transaction's instructions index: the message's, then
+/// the ones a v0 message loaded from lookup tables, writable first.
+pub fn account_keys(tx: &EncodedTransactionWithStatusMeta) -> Vec<Pubkey> {
+    // The node only returns valid keys; a default keeps the indices aligned
+    let parse = |keys: &[String]| -> Vec<Pubkey> {
+        keys.iter()
+            .map(|key| Pubkey::from_str(key).unwrap_or_default())
+            .collect()
+    };
+    let mut keys = match &tx.transaction {
+        EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
+            UiMessage::Raw(raw) => parse(&raw.account_keys),
+            // Lists the loaded keys too, marked as coming from a lookup table
+            UiMessage::Parsed(parsed) => {
+                return parsed
+                    .account_keys
+                    .iter()
+                    .map(|key| Pubkey::from_str(&key.pubkey).unwrap_or_default())
+                    .collect();
+            }
+        },
+        // Base58 or base64; `Accounts` has no instructions to index
+        encoded => encoded
+            .decode()
+            .map(|tx| tx.message.static_account_keys().to_vec())
+            .unwrap_or_default(),
+    };
+    let loaded: Option<UiLoadedAddresses> = tx
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.loaded_addresses.clone().into());
+    if let Some(loaded) = loaded {
+        keys.extend(parse(&loaded.writable));
+        keys.extend(parse(&loaded.readonly));
+    }
+    keys
+}
+
+/// The emitting program and event bytes of an inner instruction that is an
+/// `emit_cpi!` self-invocation (see `events::cpi_event_data`), `keys` being
+/// the transaction's account keys.
+pub fn inner_event(ix: &UiInstruction, keys: &[Pubkey]) -> Option<(Pubkey, Vec<u8>)> {
+    let (program_id, accounts, data) = match ix {
+        UiInstruction::Compiled(ci) => {
+            let key = |index: u8| keys.get(usize::from(index)).copied();
+            let accounts: Option<Vec<Pubkey>> = ci.accounts.iter().map(|&i| key(i)).collect();
+            (key(ci.program_id_index)?, accounts?, &ci.data)
+        }
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(pd)) => {
+            let accounts: Result<Vec<Pubkey>, _> = pd
+                .accounts
+                .iter()
+                .map(|key| Pubkey::from_str(key))
+                .collect();
+            (
+                Pubkey::from_str(&pd.program_id).ok()?,
+                accounts.ok()?,
+                &pd.data,
+            )
+        }
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => return None,
+    };
+    let bytes = bs58_decode(data, Mode::Strict).ok()?;
+    let event = cpi_event_data(&program_id, &accounts, &bytes)?;
+    Some((program_id, event.to_vec()))
+}
+
+/// The `Program data:` events in `logs`, each attributed to the program
+/// executing when it was logged.
+pub fn log_events(logs: &[String]) -> Vec<FoundEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for (line, log) in logs.iter().enumerate() {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+            let Ok(data) = STANDARD.decode(data) else {
+                continue;
+            };
+            events.push(FoundEvent {
+                program_id: stack.last().copied(),
+                source: EventSource::Log(line),
+                event: decode_event(&data),
+                data,
+            });
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            // "Program <id> invoke [n]", then "Program <id> success" or
+            // "Program <id> failed: ..."
+            let mut words = rest.split_whitespace();
+            let Some(program_id) = words.next().and_then(|id| Pubkey::from_str(id).ok()) else {
+                continue;
+            };
+            match words.next() {
+                Some("invoke") => stack.push(program_id),
+                Some("success" | "failed:") => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
@@ -0,0 +1,78 @@
+//! Typed instruction builders for its_tester.
+//!
+//! The config and event-authority PDAs are derived from `program_id`, so callers
+//! only supply the signers and instruction arguments.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use its_tester::{accounts, instruction};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{event_authority_pda, its_config_pda};
+
+fn build_ix(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `payer` must be the program's upgrade authority.
+pub fn build_init_config_ix(program_id: &Pubkey, payer: &Pubkey, operator: Pubkey) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitConfig {
+            payer: *payer,
+            config_pda: its_config_pda(program_id).0,
+            this_program: *program_id,
+            program_data: bpf_loader_upgradeable::get_program_data_address(program_id),
+            system_program: system_program::id(),
+        },
+        instruction::InitConfig { operator },
+    )
+}
+
+pub fn build_transfer_operatorship_ix(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    new_operator: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::TransferOperatorship {
+            operator: *operator,
+            config_pda: its_config_pda(program_id).0,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::TransferOperatorship { new_operator },
+    )
+}
+
+pub fn build_set_flow_limit_ix(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    token_id: [u8; 32],
+    flow_limit: Option<u64>,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::SetFlowLimit {
+            operator: *operator,
+            config_pda: its_config_pda(program_id).0,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::SetFlowLimit {
+            token_id,
+            flow_limit,
+        },
+    )
+}
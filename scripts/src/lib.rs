@@ -1 +1,71 @@
-pub fn placeholder() {}
+// solana-sdk 2.3 deprecates its system program and loader modules in favour
+// of separate interface crates, and solana-client's and anchor-client's error
+// types are large enough to trip clippy wherever they are returned
+#![allow(deprecated, clippy::result_large_err)]
+
+pub mod accounts;
+pub mod alt;
+pub mod batch;
+pub mod blockhash;
+pub mod checkpoint;
+pub mod client;
+pub mod cluster;
+pub mod codec;
+pub mod config;
+pub mod dedup;
+pub mod deploy;
+pub mod discriminators;
+pub mod echo;
+pub mod encoding;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod failover;
+pub mod finality;
+pub mod funding;
+pub mod gas;
+pub mod gateway;
+pub mod geyser;
+pub mod gmp;
+pub mod golden;
+pub mod governance;
+pub mod hashes;
+pub mod health;
+pub mod idl;
+pub mod idl_account;
+pub mod ids;
+pub mod inspect;
+pub mod its;
+pub mod merkle;
+pub mod message_id;
+pub mod metrics;
+pub mod multisig;
+pub mod nonce;
+pub mod offline;
+pub mod payers;
+pub mod pdas;
+pub mod receipt;
+pub mod rpc;
+pub mod signer;
+pub mod simulate;
+pub mod sink;
+pub mod store;
+pub mod tx;
+pub mod verifier;
+pub mod verify;
+pub mod whitelist;
+
+pub use codec::{hex_array, hex_decode, hex_encode, Mode};
+pub use encoding::{
+    anchor_account_discriminator, anchor_event_discriminator, anchor_sighash, serialize_string,
+    serialize_vec_u8,
+};
+pub use error::{
+    AccountError, CheckpointError, ConfigError, DedupError, DeployError, DiscriminatorError,
+    EncodingError, Error, ExportError, FundingError, GeyserError, IdlAccountError, IdlError,
+    IdsError, KeypairError, OfflineError, PdaMismatch, RpcError, SinkError, VerifyError,
+};
+pub use events::{decode_cpi_event, decode_event, DecodedEvent};
+pub use message_id::MessageId;
+pub use rpc::send_ix;
+pub use signer::{load_signer, read_keypair};
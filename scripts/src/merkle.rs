@@ -0,0 +1,194 @@
+//! Off-chain payload Merkle tree over `MessageLeaf`s.
+//!
+//! Leaves are hashed with `hashes::leaf_hash` (keccak over the borsh encoding),
+//! inner nodes are `keccak(left || right)`, and an unpaired node is promoted to
+//! the next layer unchanged. A proof is the concatenation of the sibling hashes
+//! from the leaf up to the root, which is what `MerkleisedMessage::proof` carries.
+//! Verifier sets are hashed into the same kind of tree, over
+//! `VerifierSetLeaf::hash`, so `from_leaf_hashes` proves their leaves too.
+
+use program_tester::{verifier_set, MerkleisedMessage, MessageLeaf};
+use solana_program::keccak;
+
+use crate::hashes::leaf_hash;
+
+pub const HASH_LEN: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct PayloadMerkleTree {
+    /// `layers[0]` holds the leaf hashes, the last layer holds only the root.
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl PayloadMerkleTree {
+    /// Returns `None` for an empty leaf set, which has no root.
+    pub fn new(leaves: &[MessageLeaf]) -> Option<Self> {
+        Self::from_leaf_hashes(leaves.iter().map(leaf_hash).collect())
+    }
+
+    pub fn from_leaf_hashes(leaf_hashes: Vec<[u8; 32]>) -> Option<Self> {
+        if leaf_hashes.is_empty() {
+            return None;
+        }
+        let mut layers = vec![leaf_hashes];
+        while layers.last().map_or(0, Vec::len) > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_nodes(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Some(Self { layers })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers[0].is_empty()
+    }
+
+    /// Sibling hashes for the leaf at `position`, concatenated bottom-up.
+    pub fn proof(&self, position: usize) -> Option<Vec<u8>> {
+        if position >= self.len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        let mut index = position;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.extend_from_slice(sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[left.as_ref(), right.as_ref()]).to_bytes()
+}
+
+/// Recomputes the root from a leaf hash and its proof. This is the program's
+/// own check, so proofs built here are accepted on chain.
+pub fn verify_proof(
+    root: &[u8; 32],
+    leaf_hash: [u8; 32],
+    position: usize,
+    set_size: usize,
+    proof: &[u8],
+) -> bool {
+    match (u16::try_from(position), u16::try_from(set_size)) {
+        (Ok(position), Ok(set_size)) => {
+            verifier_set::verify_proof(&leaf_hash, proof, position, set_size, root)
+        }
+        _ => false,
+    }
+}
+
+/// Builds the tree over `leaves` and pairs each leaf with its proof, ready to be
+/// passed to `approve_message`. Leaves are expected in position order.
+pub fn merkleise(leaves: Vec<MessageLeaf>) -> Option<([u8; 32], Vec<MerkleisedMessage>)> {
+    let tree = PayloadMerkleTree::new(&leaves)?;
+    let messages = leaves
+        .into_iter()
+        .enumerate()
+        .map(|(position, leaf)| MerkleisedMessage {
+            leaf,
+            proof: tree.proof(position).unwrap(),
+        })
+        .collect();
+    Some((tree.root(), messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use program_tester::{CrossChainId, Message};
+
+    use super::*;
+
+    fn leaves(count: u16) -> Vec<MessageLeaf> {
+        (0..count)
+            .map(|position| MessageLeaf {
+                message: Message {
+                    cc_id: CrossChainId {
+                        chain: "ethereum".to_string(),
+                        id: format!("0x{position:064x}-0"),
+                    },
+                    source_address: "0x1234".to_string(),
+                    destination_chain: "solana".to_string(),
+                    destination_address: solana_program::pubkey::Pubkey::default().to_string(),
+                    payload_hash: [position as u8; 32],
+                },
+                position,
+                set_size: count,
+                domain_separator: [0u8; 32],
+                signing_verifier_set: [0u8; 32],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn proofs_verify() {
+        for count in [1, 2, 3, 5] {
+            let leaves = leaves(count);
+            let tree = PayloadMerkleTree::new(&leaves).unwrap();
+            for (position, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(position).unwrap();
+                assert!(
+                    verify_proof(
+                        &tree.root(),
+                        leaf_hash(leaf),
+                        position,
+                        leaves.len(),
+                        &proof
+                    ),
+                    "leaf {position} of {count}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn a_tampered_leaf_is_rejected() {
+        let leaves = leaves(5);
+        let tree = PayloadMerkleTree::new(&leaves).unwrap();
+        let proof = tree.proof(2).unwrap();
+        let mut tampered = leaves[2].clone();
+        tampered.message.payload_hash[0] ^= 1;
+        assert!(!verify_proof(
+            &tree.root(),
+            leaf_hash(&tampered),
+            2,
+            5,
+            &proof
+        ));
+    }
+
+    #[test]
+    fn merkleised_messages_carry_their_proofs() {
+        let (root, messages) = merkleise(leaves(3)).unwrap();
+        for message in &messages {
+            let position = message.leaf.position as usize;
+            assert!(verify_proof(
+                &root,
+                leaf_hash(&message.leaf),
+                position,
+                3,
+                &message.proof
+            ));
+        }
+        assert!(merkleise(vec![]).is_none());
+    }
+}
@@ -0,0 +1,122 @@
+//! Axelar Solana message ids of the form `<signature>-<x>.<y>`.
+//!
+//! `x` is the 1-based index of the top-level instruction and `y` the 1-based
+//! index of the inner instruction (the event CPI) within it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use solana_sdk::signature::Signature;
+
+use crate::error::EncodingError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MessageId {
+    pub signature: Signature,
+    pub top_level_ix_index: u32,
+    pub inner_ix_index: u32,
+}
+
+impl MessageId {
+    pub fn new(signature: Signature, top_level_ix_index: u32, inner_ix_index: u32) -> Self {
+        Self {
+            signature,
+            top_level_ix_index,
+            inner_ix_index,
+        }
+    }
+
+    /// Builds the id from the 0-based positions reported by `getTransaction`
+    /// (`innerInstructions[].index` and the position within that group).
+    pub fn from_rpc_positions(signature: Signature, group_index: u8, position: usize) -> Self {
+        Self::new(signature, u32::from(group_index) + 1, position as u32 + 1)
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}.{}",
+            self.signature, self.top_level_ix_index, self.inner_ix_index
+        )
+    }
+}
+
+impl FromStr for MessageId {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, EncodingError> {
+        let invalid = |reason: String| EncodingError::InvalidMessageId {
+            input: s.to_string(),
+            reason,
+        };
+        let (signature, indices) = s
+            .rsplit_once('-')
+            .ok_or_else(|| invalid("missing the '-' separator".to_string()))?;
+        let (top_level, inner) = indices
+            .split_once('.')
+            .ok_or_else(|| invalid("missing the '.' separator".to_string()))?;
+        Ok(Self {
+            signature: Signature::from_str(signature)
+                .map_err(|e| invalid(format!("invalid signature: {e}")))?,
+            top_level_ix_index: parse_index(top_level).map_err(invalid)?,
+            inner_ix_index: parse_index(inner).map_err(invalid)?,
+        })
+    }
+}
+
+/// Indices are plain decimals: no sign, no leading zeros.
+fn parse_index(value: &str) -> Result<u32, String> {
+    if value.is_empty()
+        || !value.bytes().all(|b| b.is_ascii_digit())
+        || (value.len() > 1 && value.starts_with('0'))
+    {
+        return Err(format!("invalid index {value:?}"));
+    }
+    value
+        .parse()
+        .map_err(|e| format!("invalid index {value:?}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::hex_encode_prefixed;
+
+    fn signature() -> Signature {
+        Signature::from([7u8; 64])
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let id = MessageId::from_rpc_positions(signature(), 0, 2);
+        assert_eq!((id.top_level_ix_index, id.inner_ix_index), (1, 3));
+        let text = id.to_string();
+        assert_eq!(text, format!("{}-1.3", signature()));
+        assert_eq!(text.parse::<MessageId>().unwrap(), id);
+    }
+
+    #[test]
+    fn malformed_ids_are_rejected() {
+        let signature = signature().to_string();
+        for input in [
+            format!("{signature}-01.1"),
+            format!("{signature}-1.00"),
+            format!("{signature}-+1.1"),
+            format!("{signature}-.1"),
+            format!("{signature}-1"),
+            format!("{signature}1.1"),
+            format!("{signature}-1.99999999999"),
+            format!("{}-1.1", hex_encode_prefixed(&[7u8; 64])),
+        ] {
+            assert!(
+                matches!(
+                    input.parse::<MessageId>(),
+                    Err(EncodingError::InvalidMessageId { .. })
+                ),
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Prometheus metrics of `listen`, served on `/metrics` in the text exposition
+//! format, so the listener is scraped like the relayer's other components.
+//!
+//! The counters are plain atomics and maps the listener updates as it goes;
+//! `render` reads them when scraped. `serve` also answers `/healthz` and
+//! `/readyz` from `crate::health`. The HTTP side is just enough of HTTP/1.1
+//! for scrapers and probes: one `GET` per connection, answered and closed.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::health::ListenerHealth;
+use crate::rpc::RpcMetrics;
+
+/// Upper bounds, in seconds, of the processing latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Debug)]
+pub struct ListenerMetrics {
+    /// Events sent to the sinks, by program and type.
+    events: Mutex<BTreeMap<(String, String), u64>>,
+    /// Events neither the registry nor the IDL decoded, by program.
+    decode_failures: Mutex<BTreeMap<String, u64>>,
+    /// Transactions `getTransaction` failed for.
+    pub fetch_failures: AtomicU64,
+    pub reconnects: AtomicU64,
+    /// Events sent again as retracted, their transaction never finalized.
+    pub retractions: AtomicU64,
+    /// Slot of the latest transaction handled.
+    pub last_slot: AtomicU64,
+    /// The cluster's slot when last polled.
+    pub tip_slot: AtomicU64,
+    latency: Mutex<Histogram>,
+    rpc: Arc<RpcMetrics>,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations at most each bucket's bound, not cumulated.
+    counts: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl ListenerMetrics {
+    /// Metrics reporting the requests of the listener's RPC client as well.
+    pub fn new(rpc: Arc<RpcMetrics>) -> Self {
+        Self {
+            events: Mutex::default(),
+            decode_failures: Mutex::default(),
+            fetch_failures: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            retractions: AtomicU64::new(0),
+            last_slot: AtomicU64::new(0),
+            tip_slot: AtomicU64::new(0),
+            latency: Mutex::default(),
+            rpc,
+        }
+    }
+
+    /// Counts an event `program` emitted; `None` for one nothing decoded.
+    pub fn record_event(&self, program: &str, event_type: Option<&str>) {
+        let key = (
+            program.to_string(),
+            event_type.unwrap_or("unknown").to_string(),
+        );
+        *lock(&self.events).entry(key).or_default() += 1;
+        if event_type.is_none() {
+            *lock(&self.decode_failures)
+                .entry(program.to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Records a transaction handled at `slot` in `elapsed`.
+    pub fn record_processed(&self, slot: u64, elapsed: Duration) {
+        self.last_slot.fetch_max(slot, Ordering::Relaxed);
+        let seconds = elapsed.as_secs_f64();
+        let mut latency = lock(&self.latency);
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            latency.counts[bucket] += 1;
+        }
+        latency.count += 1;
+        latency.sum += seconds;
+    }
+
+    /// The metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        header(
+            &mut out,
+            "events_total",
+            "counter",
+            "Events sent to the sinks, by program and type.",
+        );
+        for ((program, event_type), count) in lock(&self.events).iter() {
+            let _ = writeln!(
+                out,
+                "axelar_listen_events_total{{program=\"{}\",type=\"{}\"}} {count}",
+                escape(program),
+                escape(event_type)
+            );
+        }
+        header(
+            &mut out,
+            "decode_failures_total",
+            "counter",
+            "Events neither the registry nor the IDL decoded, by program.",
+        );
+        for (program, count) in lock(&self.decode_failures).iter() {
+            let _ = writeln!(
+                out,
+                "axelar_listen_decode_failures_total{{program=\"{}\"}} {count}",
+                escape(program)
+            );
+        }
+
+        let rpc = self.rpc.snapshot();
+        let counters = [
+            (
+                "fetch_failures_total",
+                "Transactions that couldn't be fetched.",
+                self.fetch_failures.load(Ordering::Relaxed),
+            ),
+            (
+                "rpc_requests_total",
+                "RPC requests made through the retrying client.",
+                rpc.requests,
+            ),
+            ("rpc_retries_total", "RPC requests retried.", rpc.retries),
+            (
+                "rpc_timeouts_total",
+                "RPC requests that timed out.",
+                rpc.timeouts,
+            ),
+            (
+                "rpc_failures_total",
+                "RPC requests that failed after their retries.",
+                rpc.failures,
+            ),
+            (
+                "reconnects_total",
+                "Times the subscriptions were set up again.",
+                self.reconnects.load(Ordering::Relaxed),
+            ),
+            (
+                "retractions_total",
+                "Events retracted because their transaction never finalized.",
+                self.retractions.load(Ordering::Relaxed),
+            ),
+        ];
+        for (name, help, value) in counters {
+            header(&mut out, name, "counter", help);
+            let _ = writeln!(out, "axelar_listen_{name} {value}");
+        }
+
+        let last = self.last_slot.load(Ordering::Relaxed);
+        let tip = self.tip_slot.load(Ordering::Relaxed);
+        header(
+            &mut out,
+            "last_processed_slot",
+            "gauge",
+            "Slot of the latest transaction handled.",
+        );
+        let _ = writeln!(out, "axelar_listen_last_processed_slot {last}");
+        // Meaningless until both are known
+        if last > 0 && tip > 0 {
+            header(
+                &mut out,
+                "slot_lag",
+                "gauge",
+                "Slots between the cluster's tip and the latest transaction handled.",
+            );
+            let _ = writeln!(out, "axelar_listen_slot_lag {}", tip.saturating_sub(last));
+        }
+
+        header(
+            &mut out,
+            "processing_seconds",
+            "histogram",
+            "Time to fetch a transaction, decode it and hand its events to every sink.",
+        );
+        let latency = lock(&self.latency);
+        let mut cumulative = 0;
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(latency.counts) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "axelar_listen_processing_seconds_bucket{{le=\"{bound}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "axelar_listen_processing_seconds_bucket{{le=\"+Inf\"}} {}",
+            latency.count
+        );
+        let _ = writeln!(out, "axelar_listen_processing_seconds_sum {}", latency.sum);
+        let _ = writeln!(
+            out,
+            "axelar_listen_processing_seconds_count {}",
+            latency.count
+        );
+        out
+    }
+}
+
+/// Answers scrapes and health probes on `listener` until accepting fails.
+pub async fn serve(
+    listener: TcpListener,
+    metrics: Arc<ListenerMetrics>,
+    health: Arc<ListenerHealth>,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let metrics = metrics.clone();
+        let health = health.clone();
+        // A client hanging up early is its own problem
+        tokio::spawn(async move {
+            let _ = respond(stream, &metrics, &health).await;
+        });
+    }
+}
+
+async fn respond(
+    mut stream: TcpStream,
+    metrics: &ListenerMetrics,
+    health: &ListenerHealth,
+) -> std::io::Result<()> {
+    let mut request = [0u8; 1024];
+    let read = stream.read(&mut request).await?;
+    let request = String::from_utf8_lossy(&request[..read]);
+    // `GET /metrics HTTP/1.1`, maybe with a query
+    let path = request
+        .split_whitespace()
+        .nth(1)
+        .and_then(|target| target.split('?').next())
+        .unwrap_or_default();
+    let probe = match path {
+        "/healthz" => Some(health.liveness()),
+        "/readyz" => Some(health.readiness()),
+        _ => None,
+    };
+    let (status, content_type, body) = match (path, probe) {
+        ("/metrics", _) => ("200 OK", "text/plain; version=0.0.4", metrics.render()),
+        (_, Some((true, status))) => ("200 OK", "application/json", format!("{status}\n")),
+        (_, Some((false, status))) => (
+            "503 Service Unavailable",
+            "application/json",
+            format!("{status}\n"),
+        ),
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP axelar_listen_{name} {help}");
+    let _ = writeln!(out, "# TYPE axelar_listen_{name} {kind}");
+}
+
+/// A label value with `\`, `"` and newlines escaped.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The data behind `mutex`, even if a panicking thread left it poisoned; a
+/// counter is still worth reporting.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
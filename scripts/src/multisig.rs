@@ -0,0 +1,136 @@
+//! Typed instruction builders for multisig_tester.
+//!
+//! A proposed transaction wraps an ordinary instruction, e.g. one built by
+//! `gateway::build_transfer_operatorship_ix` with the multisig signer PDA as
+//! the operator. `execute` has to pass that instruction's accounts and program
+//! on as remaining accounts, which `build_execute_ix` takes from the fetched
+//! transaction account.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use multisig_tester::{accounts, instruction, MultisigTransaction, TransactionAccount};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{
+    event_authority_pda, multisig_pda, multisig_signer_pda, multisig_transaction_pda,
+};
+
+fn build_ix(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn build_create_multisig_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    create_key: Pubkey,
+    owners: Vec<Pubkey>,
+    threshold: u8,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::CreateMultisig {
+            payer: *payer,
+            multisig: multisig_pda(program_id, &create_key).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::CreateMultisig {
+            create_key,
+            owners,
+            threshold,
+        },
+    )
+}
+
+/// Proposes `proposed` as transaction number `index` of `multisig`, which must
+/// be its current `transaction_count`. `proposer` must be an owner.
+pub fn build_create_transaction_ix(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    multisig: &Pubkey,
+    index: u64,
+    proposed: &Instruction,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::CreateTransaction {
+            proposer: *proposer,
+            multisig: *multisig,
+            transaction: multisig_transaction_pda(program_id, multisig, index).0,
+            system_program: system_program::id(),
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::CreateTransaction {
+            program_id: proposed.program_id,
+            accounts: proposed
+                .accounts
+                .iter()
+                .map(|meta| TransactionAccount {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: proposed.data.clone(),
+        },
+    )
+}
+
+pub fn build_approve_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    multisig: &Pubkey,
+    transaction: &Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::Approve {
+            owner: *owner,
+            multisig: *multisig,
+            transaction: *transaction,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::Approve {},
+    )
+}
+
+/// The multisig signer PDA is passed as a plain account: the program signs
+/// for it when invoking the proposed instruction.
+pub fn build_execute_ix(
+    program_id: &Pubkey,
+    transaction_address: &Pubkey,
+    transaction: &MultisigTransaction,
+) -> Instruction {
+    let multisig_signer = multisig_signer_pda(program_id, &transaction.multisig).0;
+    let mut ix = build_ix(
+        program_id,
+        accounts::Execute {
+            multisig: transaction.multisig,
+            transaction: *transaction_address,
+            event_authority: event_authority_pda(program_id).0,
+            program: *program_id,
+        },
+        instruction::Execute {},
+    );
+    ix.accounts
+        .extend(transaction.accounts.iter().map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer && account.pubkey != multisig_signer,
+            is_writable: account.is_writable,
+        }));
+    ix.accounts
+        .push(AccountMeta::new_readonly(transaction.program_id, false));
+    ix
+}
@@ -0,0 +1,75 @@
+//! Durable nonce accounts.
+//!
+//! A transaction signed against the blockhash stored in a nonce account stays
+//! valid until the nonce is advanced, instead of expiring with its recent
+//! blockhash after ~150 slots. That lets transactions be signed ahead of time,
+//! e.g. on an offline machine, and submitted later. The first instruction of
+//! such a transaction must be `advance_nonce_account`, which `TxBuilder` adds
+//! when given a nonce account.
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::nonce_utils::data_from_account;
+use solana_sdk::hash::Hash;
+use solana_sdk::nonce::state::{Data, State};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+
+use crate::error::{AccountError, RpcError};
+use crate::rpc::{env_parse, ResilientRpc};
+use crate::tx::TxBuilder;
+
+/// Reads `NONCE_ACCOUNT`, the nonce account senders sign against when set.
+pub fn nonce_account_from_env() -> Option<Pubkey> {
+    env_parse("NONCE_ACCOUNT")
+}
+
+/// Creates `nonce` as a rent-exempt nonce account that `authority` advances.
+pub async fn create_nonce_account(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    nonce: &Keypair,
+    authority: &Pubkey,
+) -> Result<Pubkey, RpcError> {
+    let lamports = rpc
+        .retry(|| rpc.get_minimum_balance_for_rent_exemption(State::size()))
+        .await?;
+    let ixs = system_instruction::create_nonce_account(
+        &payer.pubkey(),
+        &nonce.pubkey(),
+        authority,
+        lamports,
+    );
+    let builder = TxBuilder::new(payer).instructions(ixs).signer(nonce);
+    rpc.send(&builder).await?;
+    Ok(nonce.pubkey())
+}
+
+/// The blockhash currently stored in `nonce_account`, which transactions using
+/// it are signed against.
+pub async fn fetch_nonce_hash(
+    rpc: &RpcClient,
+    nonce_account: &Pubkey,
+) -> Result<Hash, AccountError> {
+    Ok(fetch_nonce_data(rpc, nonce_account).await?.blockhash())
+}
+
+/// The state of the initialized nonce account `nonce_account`: its authority,
+/// stored blockhash and fee per signature.
+pub async fn fetch_nonce_data(
+    rpc: &RpcClient,
+    nonce_account: &Pubkey,
+) -> Result<Data, AccountError> {
+    let account = rpc
+        .get_account_with_commitment(nonce_account, rpc.commitment())
+        .await
+        .map_err(RpcError::from)?
+        .value
+        .ok_or(AccountError::NotFound {
+            address: *nonce_account,
+        })?;
+    data_from_account(&account).map_err(|e| AccountError::Deserialize {
+        address: *nonce_account,
+        reason: e.to_string(),
+    })
+}
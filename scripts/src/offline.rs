@@ -0,0 +1,73 @@
+//! Transactions signed on one machine and submitted from another.
+//!
+//! A transaction is signed on an air-gapped machine, written to a file as its
+//! wire bytes, and carried to an online machine that broadcasts it. Signed
+//! against a durable nonce (see `nonce`) rather than a recent blockhash, the
+//! file stays valid until the nonce is advanced, however long that takes.
+
+use std::path::Path;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::error::OfflineError;
+
+/// Writes `tx` to `path` in the wire format `sendTransaction` takes.
+pub fn write_signed_tx(path: &Path, tx: &VersionedTransaction) -> Result<(), OfflineError> {
+    let bytes = bincode::serialize(tx).map_err(|e| OfflineError::Write {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    std::fs::write(path, bytes).map_err(|e| OfflineError::Write {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Reads a transaction written by `write_signed_tx`, failing unless every
+/// signature it requires is present and valid.
+pub fn read_signed_tx(path: &Path) -> Result<VersionedTransaction, OfflineError> {
+    let bytes = std::fs::read(path).map_err(|e| OfflineError::Read {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    let tx: VersionedTransaction =
+        bincode::deserialize(&bytes).map_err(|e| OfflineError::Decode {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+    let keys = tx.message.static_account_keys();
+    let missing: Vec<Pubkey> = tx
+        .verify_with_results()
+        .iter()
+        .zip(keys)
+        .filter(|(valid, _)| !**valid)
+        .map(|(_, key)| *key)
+        .collect();
+    if !missing.is_empty() {
+        return Err(OfflineError::Unsigned {
+            path: path.display().to_string(),
+            missing,
+        });
+    }
+    Ok(tx)
+}
+
+/// The nonce account `tx` advances, if it is a durable nonce transaction.
+pub fn tx_nonce_account(tx: &VersionedTransaction) -> Option<Pubkey> {
+    let ix = tx.message.instructions().first()?;
+    let keys = tx.message.static_account_keys();
+    if keys.get(usize::from(ix.program_id_index)) != Some(&system_program::id()) {
+        return None;
+    }
+    match bincode::deserialize(&ix.data) {
+        Ok(SystemInstruction::AdvanceNonceAccount) => ix
+            .accounts
+            .first()
+            .and_then(|&index| keys.get(usize::from(index)))
+            .copied(),
+        _ => None,
+    }
+}
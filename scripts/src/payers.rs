@@ -0,0 +1,76 @@
+//! A pool of payer keypairs for load generation.
+//!
+//! One fee payer serializes every transaction on its account's write lock, so
+//! high-rate senders spread the fees over several payers, taken round-robin.
+//! The keypairs live as `payer-<n>.json` in a directory and are generated the
+//! first time, so the same pool is funded once and reused across runs.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+
+use crate::error::{KeypairError, RpcError};
+use crate::rpc::ResilientRpc;
+use crate::signer::read_keypair;
+
+pub const DEFAULT_PAYER_DIR: &str = ".payers";
+
+pub struct PayerPool {
+    payers: Vec<Keypair>,
+    next: AtomicUsize,
+}
+
+impl PayerPool {
+    /// Reads `count` payers from `dir`, generating and writing any missing.
+    pub fn load_or_generate(dir: &Path, count: usize) -> Result<Self, KeypairError> {
+        let write_error = |path: &Path, reason: String| KeypairError::Write {
+            path: path.display().to_string(),
+            reason,
+        };
+        std::fs::create_dir_all(dir).map_err(|e| write_error(dir, e.to_string()))?;
+        let payers = (0..count)
+            .map(|n| {
+                let path = dir.join(format!("payer-{n}.json"));
+                if path.exists() {
+                    return read_keypair(&path);
+                }
+                let keypair = Keypair::new();
+                write_keypair_file(&keypair, &path)
+                    .map_err(|e| write_error(&path, e.to_string()))?;
+                Ok(keypair)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            payers,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn payers(&self) -> &[Keypair] {
+        &self.payers
+    }
+
+    /// The next payer in round-robin order.
+    pub fn next(&self) -> &Keypair {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.payers[n % self.payers.len()]
+    }
+
+    /// Payers holding less than `min_balance` lamports.
+    pub async fn underfunded(
+        &self,
+        rpc: &ResilientRpc,
+        min_balance: u64,
+    ) -> Result<Vec<Pubkey>, RpcError> {
+        let mut underfunded = Vec::new();
+        for payer in &self.payers {
+            let pubkey = payer.pubkey();
+            if rpc.retry(|| rpc.get_balance(&pubkey)).await? < min_balance {
+                underfunded.push(pubkey);
+            }
+        }
+        Ok(underfunded)
+    }
+}
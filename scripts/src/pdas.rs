@@ -0,0 +1,162 @@
+//! PDA derivations for program_tester, gas_service and the other dummy programs.
+//!
+//! Seeds come from the programs' `seed_prefixes` modules, so scripts never
+//! repeat seed literals. Each function returns `(address, bump)` like
+//! `Pubkey::find_program_address`.
+
+use program_tester::seed_prefixes::{
+    CALL_CONTRACT_SIGNING_SEED, GATEWAY_SEED, INCOMING_MESSAGE_SEED, PAYLOAD_LOG_SEED,
+    SIGNATURE_VERIFICATION_SEED, VERIFIER_SET_TRACKER_SEED,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::PdaMismatch;
+
+/// Seed of the PDA `#[event_cpi]` adds to each instruction. anchor-lang
+/// only has it as a literal in its macros, so it is the one seed kept here.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// The `__event_authority` PDA used by `emit_cpi!` in any Anchor program.
+pub fn event_authority_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id)
+}
+
+pub fn gateway_root_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GATEWAY_SEED], program_id)
+}
+
+pub fn payload_log_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PAYLOAD_LOG_SEED], program_id)
+}
+
+pub fn call_contract_signing_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CALL_CONTRACT_SIGNING_SEED], program_id)
+}
+
+pub fn verification_session_pda(
+    program_id: &Pubkey,
+    payload_merkle_root: &[u8; 32],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SIGNATURE_VERIFICATION_SEED, payload_merkle_root.as_ref()],
+        program_id,
+    )
+}
+
+/// Exists once the verifier set is registered with the gateway.
+pub fn verifier_set_tracker_pda(program_id: &Pubkey, verifier_set_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VERIFIER_SET_TRACKER_SEED, verifier_set_hash.as_ref()],
+        program_id,
+    )
+}
+
+pub fn incoming_message_pda(program_id: &Pubkey, command_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INCOMING_MESSAGE_SEED, command_id.as_ref()], program_id)
+}
+
+/// The gas_service config is a singleton, so its seeds carry no salt.
+pub fn gas_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[gas_service::seed_prefixes::CONFIG_SEED], program_id)
+}
+
+pub fn its_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[its_tester::seed_prefixes::CONFIG_SEED], program_id)
+}
+
+pub fn relayer_whitelist_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[relayer_whitelist::seed_prefixes::WHITELIST_SEED],
+        program_id,
+    )
+}
+
+pub fn multisig_pda(program_id: &Pubkey, create_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            multisig_tester::seed_prefixes::MULTISIG_SEED,
+            create_key.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Signs the transactions `multisig` executes; make it the operator to put an
+/// operator action behind the multisig's threshold.
+pub fn multisig_signer_pda(program_id: &Pubkey, multisig: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            multisig_tester::seed_prefixes::MULTISIG_SIGNER_SEED,
+            multisig.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// The transaction proposed as number `index` of `multisig`, counting from 0.
+pub fn multisig_transaction_pda(
+    program_id: &Pubkey,
+    multisig: &Pubkey,
+    index: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            multisig_tester::seed_prefixes::TRANSACTION_SEED,
+            multisig.as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn governance_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[governance_executor::seed_prefixes::CONFIG_SEED],
+        program_id,
+    )
+}
+
+pub fn governance_proposal_pda(program_id: &Pubkey, proposal_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            governance_executor::seed_prefixes::PROPOSAL_SEED,
+            proposal_hash.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Exists once the governance message with `command_id` scheduled its proposal.
+pub fn consumed_message_pda(program_id: &Pubkey, command_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            governance_executor::seed_prefixes::CONSUMED_MESSAGE_SEED,
+            command_id.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Exists once echo_executable echoed the message with `command_id`.
+pub fn echo_consumed_message_pda(program_id: &Pubkey, command_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            echo_executable::seed_prefixes::CONSUMED_MESSAGE_SEED,
+            command_id.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Checks that `actual` is the PDA the scripts derived for `name`.
+pub fn ensure_pda(name: &'static str, expected: Pubkey, actual: Pubkey) -> Result<(), PdaMismatch> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(PdaMismatch {
+            name,
+            expected,
+            actual,
+        })
+    }
+}
@@ -0,0 +1,98 @@
+//! What a confirmed transaction did: where it landed and the events it emitted.
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::{UiInnerInstructions, UiTransactionEncoding};
+
+use crate::error::RpcError;
+use crate::events::{decode_event, DecodedEvent};
+use crate::inspect::{account_keys, inner_event};
+use crate::message_id::MessageId;
+
+#[derive(Debug, Clone)]
+pub struct Receipt {
+    pub signature: Signature,
+    pub slot: u64,
+    pub fee: Option<u64>,
+    /// The transaction error, if it landed but failed.
+    pub err: Option<String>,
+    pub events: Vec<EmittedEvent>,
+    /// Ids of `emit_cpi!` events that matched no known event or failed to
+    /// deserialize, usually a sign the IDL drifted from the deployed program.
+    pub undecoded: Vec<MessageId>,
+}
+
+impl Receipt {
+    /// The id of the first event called `name`, e.g. the `CallContractEvent`
+    /// Axelar refers to a contract call by.
+    pub fn message_id(&self, name: &str) -> Option<MessageId> {
+        self.events
+            .iter()
+            .find(|emitted| emitted.event.name() == name)
+            .map(|emitted| emitted.message_id)
+    }
+}
+
+/// A known event together with the id Axelar refers to it by.
+#[derive(Debug, Clone)]
+pub struct EmittedEvent {
+    pub message_id: MessageId,
+    pub event: DecodedEvent,
+}
+
+/// Fetches `signature` at the client's commitment (see `lookup_commitment`)
+/// and decodes the `emit_cpi!` events in its inner instructions.
+pub async fn fetch_receipt(rpc: &RpcClient, signature: &Signature) -> Result<Receipt, RpcError> {
+    let tx = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(lookup_commitment(rpc.commitment())),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+
+    let keys = account_keys(&tx.transaction);
+    let meta = tx.transaction.meta;
+    let inner: Option<Vec<UiInnerInstructions>> = meta
+        .as_ref()
+        .and_then(|meta| meta.inner_instructions.clone().into());
+
+    let mut events = Vec::new();
+    let mut undecoded = Vec::new();
+    for group in inner.into_iter().flatten() {
+        for (position, ix) in group.instructions.iter().enumerate() {
+            let Some((_, data)) = inner_event(ix, &keys) else {
+                continue;
+            };
+            let message_id = MessageId::from_rpc_positions(*signature, group.index, position);
+            match decode_event(&data) {
+                Some(event) => events.push(EmittedEvent { message_id, event }),
+                None => undecoded.push(message_id),
+            }
+        }
+    }
+
+    Ok(Receipt {
+        signature: *signature,
+        slot: tx.slot,
+        fee: meta.as_ref().map(|meta| meta.fee),
+        err: meta.and_then(|meta| meta.err).map(|e| e.to_string()),
+        events,
+        undecoded,
+    })
+}
+
+/// `getTransaction` rejects `processed`, so lookups made at that commitment
+/// use `confirmed`, the closest level it accepts.
+pub fn lookup_commitment(commitment: CommitmentConfig) -> CommitmentConfig {
+    if commitment.is_at_least_confirmed() {
+        commitment
+    } else {
+        CommitmentConfig::confirmed()
+    }
+}
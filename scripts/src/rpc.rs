@@ -0,0 +1,402 @@
+//! RPC access for the scripts.
+//!
+//! `ResilientRpc` wraps the nonblocking `RpcClient` with a per-attempt timeout
+//! and retries with exponential backoff on transient failures (rate limiting,
+//! connection errors, an unhealthy or lagging node, an expired blockhash). It
+//! derefs to the inner client, so calls that don't need retrying work as before.
+
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_client::rpc_request::RpcError as RpcRequestError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::transaction::TransactionError;
+use solana_transaction_status_client_types::TransactionConfirmationStatus;
+
+use crate::blockhash::BlockhashCache;
+use crate::error::{AccountError, RpcError};
+use crate::nonce::fetch_nonce_hash;
+use crate::tx::{ComputeBudget, TxBuilder};
+
+/// How often `wait_finalized` and `send_unchecked` check the signature status.
+const FINALIZED_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long `send_unchecked` waits for a transaction to land; its blockhash
+/// has expired by then.
+const LANDING_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// JSON-RPC error codes a node returns while it is behind or still starting.
+const NODE_UNHEALTHY: i64 = -32005;
+const BLOCK_NOT_AVAILABLE: i64 = -32004;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Retries after the first attempt, so a call runs at most `max_retries + 1` times.
+    pub max_retries: u32,
+    pub timeout: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            timeout: Duration::from_secs(30),
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Defaults, overridden by `RPC_MAX_RETRIES` and `RPC_TIMEOUT_SECS`.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(retries) = env_parse("RPC_MAX_RETRIES") {
+            config.max_retries = retries;
+        }
+        if let Some(secs) = env_parse("RPC_TIMEOUT_SECS") {
+            config.timeout = Duration::from_secs(secs);
+        }
+        config
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// Request counters of a `ResilientRpc`, shareable through `metrics()`.
+#[derive(Debug, Default)]
+pub struct RpcMetrics {
+    pub requests: AtomicU64,
+    pub retries: AtomicU64,
+    pub timeouts: AtomicU64,
+    pub failures: AtomicU64,
+}
+
+impl RpcMetrics {
+    pub fn snapshot(&self) -> RpcMetricsSnapshot {
+        RpcMetricsSnapshot {
+            requests: self.requests.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcMetricsSnapshot {
+    pub requests: u64,
+    pub retries: u64,
+    pub timeouts: u64,
+    pub failures: u64,
+}
+
+impl std::fmt::Display for RpcMetricsSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} requests, {} retries, {} timeouts, {} failures",
+            self.requests, self.retries, self.timeouts, self.failures
+        )
+    }
+}
+
+pub struct ResilientRpc {
+    client: RpcClient,
+    config: RetryConfig,
+    metrics: Arc<RpcMetrics>,
+    blockhash_cache: Option<BlockhashCache>,
+    compute_budget: ComputeBudget,
+    nonce_account: Option<Pubkey>,
+    lookup_table: Option<AddressLookupTableAccount>,
+    skip_preflight: bool,
+}
+
+impl ResilientRpc {
+    pub fn new(url: String, commitment: CommitmentConfig, config: RetryConfig) -> Self {
+        Self {
+            client: RpcClient::new_with_timeout_and_commitment(url, config.timeout, commitment),
+            config,
+            metrics: Arc::default(),
+            blockhash_cache: None,
+            compute_budget: ComputeBudget::default(),
+            nonce_account: None,
+            lookup_table: None,
+            skip_preflight: false,
+        }
+    }
+
+    /// The compute budget `send_ix` applies to every transaction.
+    pub fn with_compute_budget(mut self, budget: ComputeBudget) -> Self {
+        self.compute_budget = budget;
+        self
+    }
+
+    /// Takes blockhashes for `send_ix` from `cache` instead of fetching one per
+    /// transaction.
+    pub fn with_blockhash_cache(mut self, cache: BlockhashCache) -> Self {
+        self.blockhash_cache = Some(cache);
+        self
+    }
+
+    /// Makes `send_ix` sign against `nonce_account`, with the payer as nonce
+    /// authority. `None` keeps using recent blockhashes.
+    pub fn with_durable_nonce(mut self, nonce_account: Option<Pubkey>) -> Self {
+        self.nonce_account = nonce_account;
+        self
+    }
+
+    /// Makes `send_ix` send v0 transactions that resolve accounts through
+    /// `table`, see `alt`.
+    pub fn with_lookup_table(mut self, table: Option<AddressLookupTableAccount>) -> Self {
+        self.lookup_table = table;
+        self
+    }
+
+    /// Sends without the node simulating the transaction first, so a failing
+    /// transaction lands and its error comes from the confirmation instead.
+    pub fn with_skip_preflight(mut self, skip_preflight: bool) -> Self {
+        self.skip_preflight = skip_preflight;
+        self
+    }
+
+    /// Counts requests in `metrics`, e.g. those of another client, instead of
+    /// counters of its own.
+    pub fn with_metrics(mut self, metrics: Arc<RpcMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn metrics(&self) -> &Arc<RpcMetrics> {
+        &self.metrics
+    }
+
+    /// Runs `request` until it succeeds, fails with a non-transient error, or
+    /// runs out of retries. Every attempt is bounded by the configured timeout.
+    pub async fn retry<T, F, Fut>(&self, mut request: F) -> Result<T, RpcError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.metrics.requests.fetch_add(1, Ordering::Relaxed);
+            let err = match tokio::time::timeout(self.config.timeout, request()).await {
+                Ok(Ok(value)) => return Ok(value),
+                Ok(Err(err)) => err,
+                Err(_) => {
+                    self.metrics.timeouts.fetch_add(1, Ordering::Relaxed);
+                    ClientError::from(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("no response within {:?}", self.config.timeout),
+                    ))
+                }
+            };
+            if attempt >= self.config.max_retries || !is_transient(&err) {
+                self.metrics.failures.fetch_add(1, Ordering::Relaxed);
+                return Err(err.into());
+            }
+            self.metrics.retries.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(self.config.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends `ixs` with this client's compute budget, durable nonce and
+    /// lookup table, see `send`.
+    pub async fn send_ix(
+        &self,
+        payer: &Keypair,
+        ixs: &[Instruction],
+    ) -> Result<Signature, RpcError> {
+        let mut builder = TxBuilder::new(payer)
+            .instructions(ixs.iter().cloned())
+            .compute_budget(self.compute_budget);
+        if let Some(nonce_account) = self.nonce_account {
+            builder = builder.durable_nonce(nonce_account, payer);
+        }
+        if let Some(table) = &self.lookup_table {
+            builder = builder.lookup_table(table.clone());
+        }
+        self.send(&builder).await
+    }
+
+    /// Each attempt re-signs with a fresh blockhash. A transaction that
+    /// landed but timed out while confirming may therefore be sent twice.
+    /// Durable nonce transactions are signed against the nonce's current
+    /// blockhash instead.
+    pub async fn send(&self, builder: &TxBuilder<'_>) -> Result<Signature, RpcError> {
+        let mut first_attempt = true;
+        self.retry(|| {
+            // A retry may be due to an expired blockhash, so only the first
+            // attempt trusts the cache
+            let cached = self
+                .blockhash_cache
+                .as_ref()
+                .filter(|_| std::mem::take(&mut first_attempt))
+                .map(BlockhashCache::blockhash);
+            async move {
+                let recent_blockhash = match (builder.nonce_account(), cached) {
+                    (Some(nonce_account), _) => fetch_nonce_hash(&self.client, &nonce_account)
+                        .await
+                        .map_err(into_client_error)?,
+                    (None, Some(blockhash)) => blockhash,
+                    (None, None) => self.client.get_latest_blockhash().await?,
+                };
+                let tx = builder.build(&self.client, recent_blockhash).await?;
+                if !self.skip_preflight {
+                    return self.client.send_and_confirm_transaction(&tx).await;
+                }
+                self.client
+                    .send_and_confirm_transaction_with_spinner_and_config(
+                        &tx,
+                        self.client.commitment(),
+                        RpcSendTransactionConfig {
+                            skip_preflight: true,
+                            ..Default::default()
+                        },
+                    )
+                    .await
+            }
+        })
+        .await
+    }
+
+    /// Sends without preflight and waits for the transaction to land at the
+    /// client's commitment, returning its error instead of failing on it. For
+    /// transactions that are meant to fail on-chain. The send is not retried,
+    /// so a failure never goes out twice.
+    pub async fn send_unchecked(
+        &self,
+        builder: &TxBuilder<'_>,
+    ) -> Result<(Signature, Option<TransactionError>), RpcError> {
+        let recent_blockhash = self.retry(|| self.client.get_latest_blockhash()).await?;
+        let tx = builder.build(&self.client, recent_blockhash).await?;
+        let signature = self
+            .client
+            .send_transaction_with_config(
+                &tx,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let deadline = tokio::time::Instant::now() + LANDING_TIMEOUT;
+        loop {
+            let status = self
+                .retry(|| {
+                    self.client
+                        .get_signature_status_with_commitment(&signature, self.client.commitment())
+                })
+                .await?;
+            if let Some(result) = status {
+                return Ok((signature, result.err()));
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ClientError::from(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("{signature} did not land within {LANDING_TIMEOUT:?}"),
+                ))
+                .into());
+            }
+            tokio::time::sleep(FINALIZED_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Waits until `signature` is finalized and returns the slot it landed
+    /// in. Fails once `timeout` passes first.
+    pub async fn wait_finalized(
+        &self,
+        signature: &Signature,
+        timeout: Duration,
+    ) -> Result<u64, RpcError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let signatures = [*signature];
+        loop {
+            let statuses = self
+                .retry(|| self.client.get_signature_statuses(&signatures))
+                .await?
+                .value;
+            if let Some(status) = statuses.into_iter().flatten().next() {
+                if status.confirmation_status == Some(TransactionConfirmationStatus::Finalized) {
+                    return Ok(status.slot);
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ClientError::from(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("{signature} not finalized within {timeout:?}"),
+                ))
+                .into());
+            }
+            tokio::time::sleep(FINALIZED_POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Deref for ResilientRpc {
+    type Target = RpcClient;
+
+    fn deref(&self) -> &RpcClient {
+        &self.client
+    }
+}
+
+/// Whether retrying the same request might succeed.
+pub fn is_transient(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status()
+                    .is_some_and(|s| s.as_u16() == 429 || s.is_server_error())
+        }
+        ClientErrorKind::RpcError(RpcRequestError::RpcResponseError { code, message, .. }) => {
+            *code == NODE_UNHEALTHY
+                || *code == BLOCK_NOT_AVAILABLE
+                || message.contains("Blockhash not found")
+        }
+        ClientErrorKind::TransactionError(TransactionError::BlockhashNotFound) => true,
+        _ => err.to_string().contains("429"),
+    }
+}
+
+/// Unwraps RPC failures so `retry` can tell whether they are transient.
+fn into_client_error(err: AccountError) -> ClientError {
+    match err {
+        AccountError::Rpc(RpcError(err)) => *err,
+        other => ClientErrorKind::Custom(other.to_string()).into(),
+    }
+}
+
+/// Signs `ixs` with `payer` as fee payer and sends them in a single transaction,
+/// retrying transient failures.
+pub async fn send_ix(
+    rpc: &ResilientRpc,
+    payer: &Keypair,
+    ixs: &[Instruction],
+) -> Result<Signature, RpcError> {
+    rpc.send_ix(payer, ixs).await
+}
+
+pub(crate) fn env_parse<T: std::str::FromStr>(var: &str) -> Option<T> {
+    std::env::var(var).ok()?.parse().ok()
+}
@@ -0,0 +1,96 @@
+//! Keypair loading for the scripts.
+//!
+//! `load_signer` resolves the payer from, in order: an explicit source, the
+//! `SOLANA_KEYPAIR` or `PAYER` env vars, a base58 secret key in
+//! `PAYER_SECRET_KEY`, a BIP39 seed phrase in `PAYER_SEED_PHRASE` (with an
+//! optional `PAYER_SEED_PASSPHRASE`), and finally the Solana CLI default
+//! `~/.config/solana/id.json`.
+//!
+//! An explicit source or keypair env var is a path to a keypair file, or
+//! `prompt` to type a seed phrase on stdin.
+
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use solana_sdk::signature::{keypair_from_seed_phrase_and_passphrase, read_keypair_file, Keypair};
+
+use crate::codec::{bs58_decode, Mode};
+use crate::error::KeypairError;
+
+/// Reads a Solana CLI keypair file (a JSON array of the 64 secret key bytes).
+pub fn read_keypair(path: impl AsRef<Path>) -> Result<Keypair, KeypairError> {
+    let path = path.as_ref();
+    read_keypair_file(path).map_err(|e| KeypairError::Read {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Loads the payer keypair, see the module docs for where it is looked up.
+pub fn load_signer(source: Option<&str>) -> Result<Keypair, KeypairError> {
+    let from_env = ["SOLANA_KEYPAIR", "PAYER"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok());
+    if let Some(source) = source.map(str::to_string).or(from_env) {
+        return load_source(&source);
+    }
+    if let Ok(secret) = std::env::var("PAYER_SECRET_KEY") {
+        return keypair_from_base58(&secret);
+    }
+    if let Ok(phrase) = std::env::var("PAYER_SEED_PHRASE") {
+        let passphrase = std::env::var("PAYER_SEED_PASSPHRASE").unwrap_or_default();
+        return keypair_from_seed_phrase(&phrase, &passphrase);
+    }
+    let path = default_keypair_path()?;
+    if !path.exists() {
+        return Err(KeypairError::NotFound {
+            path: path.display().to_string(),
+        });
+    }
+    read_keypair(path)
+}
+
+fn load_source(source: &str) -> Result<Keypair, KeypairError> {
+    if source != "prompt" {
+        return read_keypair(source);
+    }
+    eprint!("Seed phrase: ");
+    let mut phrase = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut phrase)
+        .map_err(|e| KeypairError::InvalidSecret {
+            kind: "prompt",
+            reason: e.to_string(),
+        })?;
+    keypair_from_seed_phrase(&phrase, "")
+}
+
+/// A base58-encoded 64-byte secret key, as exported by Phantom and friends.
+pub fn keypair_from_base58(secret: &str) -> Result<Keypair, KeypairError> {
+    let invalid = |reason: String| KeypairError::InvalidSecret {
+        kind: "base58 secret key",
+        reason,
+    };
+    let bytes = bs58_decode(secret, Mode::Lenient).map_err(|e| invalid(e.to_string()))?;
+    Keypair::try_from(bytes.as_slice()).map_err(|e| invalid(e.to_string()))
+}
+
+/// Derives the keypair the Solana CLI would from a BIP39 seed phrase.
+pub fn keypair_from_seed_phrase(phrase: &str, passphrase: &str) -> Result<Keypair, KeypairError> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    keypair_from_seed_phrase_and_passphrase(&phrase, passphrase).map_err(|e| {
+        KeypairError::InvalidSecret {
+            kind: "seed phrase",
+            reason: e.to_string(),
+        }
+    })
+}
+
+/// `~/.config/solana/id.json`, where `solana-keygen new` writes by default.
+pub fn default_keypair_path() -> Result<PathBuf, KeypairError> {
+    let home = dirs::home_dir().ok_or_else(|| KeypairError::NotFound {
+        path: "~/.config/solana/id.json (no home directory)".to_string(),
+    })?;
+    Ok(home.join(".config").join("solana").join("id.json"))
+}
@@ -0,0 +1,101 @@
+//! Dry runs through `simulateTransaction`.
+//!
+//! The node executes the instructions without committing anything, so a
+//! script can show the logs, compute usage and events a transaction would
+//! produce without a funded payer. Events are picked up both from `emit_cpi!`
+//! self-invocations in the inner instructions and from `Program data:` logs
+//! written by `emit!`.
+
+use std::fmt;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+
+use crate::error::RpcError;
+use crate::events::{decode_event, DecodedEvent};
+use crate::inspect::{inner_event, log_events};
+
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    /// The transaction error, if execution failed.
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Known events in emission order: CPI events first, then log events.
+    pub events: Vec<DecodedEvent>,
+}
+
+impl Simulation {
+    pub fn succeeded(&self) -> bool {
+        self.err.is_none()
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.err {
+            None => writeln!(f, "Simulation succeeded")?,
+            Some(err) => writeln!(f, "Simulation failed: {err}")?,
+        }
+        if let Some(units) = self.units_consumed {
+            writeln!(f, "Compute units consumed: {units}")?;
+        }
+        writeln!(f, "Logs:")?;
+        for log in &self.logs {
+            writeln!(f, "  {log}")?;
+        }
+        for event in &self.events {
+            writeln!(f, "Event {}:", event.name())?;
+            writeln!(f, "{event:#?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulates `ixs` with `payer` as fee payer. Nothing is signed, so `payer`
+/// doesn't need a keypair at hand, only an address the node accepts.
+pub async fn simulate_ix(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    ixs: &[Instruction],
+) -> Result<Simulation, RpcError> {
+    let tx = Transaction::new_with_payer(ixs, Some(payer));
+    let result = rpc
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(rpc.commitment()),
+                inner_instructions: true,
+                ..Default::default()
+            },
+        )
+        .await?
+        .value;
+
+    let mut events = Vec::new();
+    for group in result.inner_instructions.iter().flatten() {
+        for ix in &group.instructions {
+            if let Some((_, data)) = inner_event(ix, &tx.message.account_keys) {
+                events.extend(decode_event(&data));
+            }
+        }
+    }
+    let logs = result.logs.unwrap_or_default();
+    events.extend(
+        log_events(&logs)
+            .into_iter()
+            .filter_map(|found| found.event),
+    );
+
+    Ok(Simulation {
+        err: result.err.map(|e| e.to_string()),
+        logs,
+        units_consumed: result.units_consumed,
+        events,
+    })
+}
@@ -0,0 +1,643 @@
+//! Where `listen` sends the events it decodes.
+//!
+//! The listener turns each event into an `EventRecord` and hands it to every
+//! configured `Sink` in order. Sinks are picked at startup from `SinkSpec`s
+//! like `stdout`, `file:events.jsonl`, `webhook:https://...`, `kafka:<topic>`,
+//! `nats`, `redis:<stream>`, `postgres://...` or `sqlite:<path>` (the last
+//! five behind features of the same name; the database ones live in
+//! `crate::store`), so a new destination is a new `Sink` and spec, not a
+//! change to how events are ingested. An error from a
+//! sink stops the listener before the checkpoint moves past the event, so a
+//! restart delivers it again; a webhook with a dead-letter file parks what it
+//! can't deliver there instead.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use reqwest::{StatusCode, Url};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::codec::hex_encode_prefixed;
+use crate::error::SinkError;
+use crate::events::DecodedEvent;
+use crate::gmp::Event as GmpEvent;
+use crate::message_id::MessageId;
+
+/// One event a program emitted, with where it was found.
+#[derive(Debug, Clone)]
+pub struct EventRecord {
+    /// The event's type name; `None` when neither the registry nor the IDL
+    /// knows its discriminator.
+    pub event_type: Option<String>,
+    /// The emitting program's name, e.g. `gateway`.
+    pub program: String,
+    pub program_id: Pubkey,
+    pub signature: Signature,
+    pub slot: u64,
+    /// Unix time of the block, unknown for events read from the logs alone.
+    pub block_time: Option<i64>,
+    /// Set for `emit_cpi!` events, which Axelar refers to by it.
+    pub message_id: Option<MessageId>,
+    /// The message id, or the log line of an `emit!` event.
+    pub location: String,
+    /// For gas service events, the id of the message they pay for.
+    pub pays_for: Option<String>,
+    /// The decoded fields, from the registry or the IDL.
+    pub fields: Option<Value>,
+    /// The event bytes, discriminator first.
+    pub data: Vec<u8>,
+    /// Set when the compiled-in registry decoded the event.
+    pub event: Option<DecodedEvent>,
+    pub gmp: Option<GmpEvent>,
+    /// Set when the event is sent again because its transaction never
+    /// finalized, for consumers to undo what they did with it; see
+    /// `crate::finality`.
+    pub retracted: bool,
+}
+
+impl EventRecord {
+    /// The record as one JSON object, the line `listen --format ndjson`
+    /// prints. `instruction_index` and `inner_index` are the 1-based
+    /// positions of an `emit_cpi!` event, as in its message id.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "type": self.event_type,
+            "program": self.program,
+            "signature": self.signature.to_string(),
+            "slot": self.slot,
+            "block_time": self.block_time,
+            "message_id": self.message_id.map(|id| id.to_string()),
+            "instruction_index": self.message_id.map(|id| id.top_level_ix_index),
+            "inner_index": self.message_id.map(|id| id.inner_ix_index),
+            "location": self.location,
+            "pays_for": self.pays_for,
+            "data": self.fields,
+            "raw": self.fields.is_none().then(|| hex_encode_prefixed(&self.data)),
+            "gmp": self.gmp,
+            "retracted": self.retracted,
+        })
+    }
+
+    /// What consumers that partition or deduplicate events group them by: the
+    /// command id of an approval or execution, the paid message's id for gas
+    /// events, else the event's message id or, for `emit!` events, the
+    /// transaction signature.
+    pub fn key(&self) -> String {
+        let command_id = self
+            .fields
+            .as_ref()
+            .and_then(|fields| fields.get("command_id"))
+            .and_then(Value::as_str);
+        match (command_id, &self.pays_for, self.message_id) {
+            (Some(command_id), _, _) => command_id.to_string(),
+            (None, Some(pays_for), _) => pays_for.clone(),
+            (None, None, Some(message_id)) => message_id.to_string(),
+            (None, None, None) => self.signature.to_string(),
+        }
+    }
+}
+
+/// A destination for decoded events.
+pub trait Sink: Send {
+    /// Delivers `record`, returning once it is handed over.
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>>;
+
+    /// Why the last event didn't go where it was asked to, when the sink put
+    /// it somewhere else instead of failing; the listener isn't ready then.
+    fn degraded(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Prints events: one JSON object per line in JSON mode, otherwise tagged
+/// with their type, then their fields.
+pub struct StdoutSink {
+    pub json: bool,
+}
+
+impl Sink for StdoutSink {
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+        Box::pin(async move {
+            if self.json {
+                println!("{}", record.to_json());
+                return Ok(());
+            }
+            if record.retracted {
+                println!(
+                    "[Retracted {}] {} {} ({}): never finalized",
+                    record.event_type.as_deref().unwrap_or("Unknown"),
+                    record.program,
+                    record.signature,
+                    record.location
+                );
+                return Ok(());
+            }
+            println!(
+                "[{}] {} {} ({})",
+                record.event_type.as_deref().unwrap_or("Unknown"),
+                record.program,
+                record.signature,
+                record.location
+            );
+            if let Some(pays_for) = &record.pays_for {
+                println!("  for message {pays_for}");
+            }
+            match &record.fields {
+                Some(fields) => println!("  {}", format!("{fields:#}").replace('\n', "\n  ")),
+                None => println!("  data: {}", hex_encode_prefixed(&record.data)),
+            }
+            if let Some(gmp) = &record.gmp {
+                println!("  GMP event: {}", json!(gmp));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Appends each event to a file as one JSON object per line.
+pub struct FileSink {
+    path: PathBuf,
+    file: File,
+}
+
+impl FileSink {
+    pub fn open(path: PathBuf) -> Result<Self, SinkError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| SinkError::Open {
+                sink: format!("file:{}", path.display()),
+                reason: e.to_string(),
+            })?;
+        Ok(Self { path, file })
+    }
+
+    fn write(&mut self, line: &Value) -> Result<(), SinkError> {
+        writeln!(self.file, "{line}").map_err(|e| SinkError::Emit {
+            sink: format!("file:{}", self.path.display()),
+            reason: e.to_string(),
+        })
+    }
+}
+
+impl Sink for FileSink {
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+        Box::pin(async move { self.write(&record.to_json()) })
+    }
+}
+
+/// POSTs each event as a JSON object to a URL, retrying with backoff on
+/// connection errors, timeouts, 429 and 5xx responses. An event that still
+/// isn't accepted goes to the dead-letter file, with the error, if there is
+/// one; without one the error stops the listener.
+pub struct WebhookSink {
+    url: Url,
+    client: reqwest::Client,
+    retries: u32,
+    dead_letter: Option<FileSink>,
+    /// Why the last event went to the dead-letter file.
+    parked: Option<String>,
+}
+
+/// Wait before the first webhook retry, doubled on each further one.
+const WEBHOOK_BACKOFF: Duration = Duration::from_millis(500);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl WebhookSink {
+    pub fn new(url: Url, retries: u32, dead_letter: Option<PathBuf>) -> Result<Self, SinkError> {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| SinkError::Open {
+                sink: format!("webhook:{url}"),
+                reason: e.to_string(),
+            })?;
+        Ok(Self {
+            url,
+            client,
+            retries,
+            dead_letter: dead_letter.map(FileSink::open).transpose()?,
+            parked: None,
+        })
+    }
+
+    /// POSTs `body` once, telling apart failures worth retrying.
+    async fn post(&self, body: &Value) -> Result<(), (bool, String)> {
+        let response = match self.client.post(self.url.clone()).json(body).send().await {
+            Ok(response) => response,
+            Err(e) => return Err((true, e.to_string())),
+        };
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retry = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+        let text = response.text().await.unwrap_or_default();
+        Err((retry, format!("{status}: {text}")))
+    }
+}
+
+impl Sink for WebhookSink {
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+        Box::pin(async move {
+            let body = record.to_json();
+            let mut backoff = WEBHOOK_BACKOFF;
+            let mut attempt = 0;
+            let reason = loop {
+                match self.post(&body).await {
+                    Ok(()) => {
+                        self.parked = None;
+                        return Ok(());
+                    }
+                    Err((true, _)) if attempt < self.retries => {
+                        attempt += 1;
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                    }
+                    Err((_, reason)) => break reason,
+                }
+            };
+            let Some(dead_letter) = &mut self.dead_letter else {
+                return Err(SinkError::Emit {
+                    sink: format!("webhook:{}", self.url),
+                    reason,
+                });
+            };
+            eprintln!(
+                "Webhook {} rejected an event ({reason}); writing it to {}",
+                self.url,
+                dead_letter.path.display()
+            );
+            let mut parked = body;
+            parked["error"] = Value::String(reason.clone());
+            dead_letter.write(&parked)?;
+            self.parked = Some(format!("dead-lettering events: {reason}"));
+            Ok(())
+        })
+    }
+
+    fn degraded(&self) -> Option<String> {
+        self.parked.clone()
+    }
+}
+
+/// Produces each event to a Kafka topic, keyed by `EventRecord::key` so a
+/// message's events land on one partition in order. The producer is
+/// idempotent and waits for every in-sync replica, and `emit` returns only
+/// once the broker acknowledged the event: the listener's checkpoint never
+/// passes an event Kafka doesn't have, and a restart may deliver it again.
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    topic: String,
+    producer: rdkafka::producer::FutureProducer,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    pub fn new(brokers: &str, topic: String) -> Result<Self, SinkError> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .create()
+            .map_err(|e| SinkError::Open {
+                sink: format!("kafka:{topic}"),
+                reason: e.to_string(),
+            })?;
+        Ok(Self { topic, producer })
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl Sink for KafkaSink {
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+        use rdkafka::producer::FutureRecord;
+        use rdkafka::util::Timeout;
+
+        Box::pin(async move {
+            let key = record.key();
+            let payload = record.to_json().to_string();
+            // The producer retries internally until `message.timeout.ms`
+            self.producer
+                .send(
+                    FutureRecord::to(&self.topic).key(&key).payload(&payload),
+                    Timeout::Never,
+                )
+                .await
+                .map(|_| ())
+                .map_err(|(e, _)| SinkError::Emit {
+                    sink: format!("kafka:{}", self.topic),
+                    reason: e.to_string(),
+                })
+        })
+    }
+}
+
+/// Subjects the NATS sink publishes under unless the spec names another
+/// prefix.
+pub const DEFAULT_NATS_PREFIX: &str = "axelar.solana";
+
+/// Publishes each event to NATS on `<prefix>.<event type>`, e.g.
+/// `axelar.solana.CallContractEvent`, or `<prefix>.Unknown` for events
+/// nothing decoded. Through JetStream, `emit` waits for the stream to store
+/// the event; with core NATS it only waits for the server to receive it.
+#[cfg(feature = "nats")]
+pub struct NatsSink {
+    prefix: String,
+    client: async_nats::Client,
+    jetstream: Option<async_nats::jetstream::Context>,
+}
+
+#[cfg(feature = "nats")]
+impl NatsSink {
+    pub async fn connect(url: &str, prefix: String, jetstream: bool) -> Result<Self, SinkError> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| SinkError::Open {
+                sink: format!("nats:{prefix} at {url}"),
+                reason: e.to_string(),
+            })?;
+        Ok(Self {
+            prefix,
+            jetstream: jetstream.then(|| async_nats::jetstream::new(client.clone())),
+            client,
+        })
+    }
+
+    async fn publish(&self, subject: String, payload: String) -> Result<(), String> {
+        match &self.jetstream {
+            Some(jetstream) => {
+                let ack = jetstream
+                    .publish(subject, payload.into())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                ack.await.map(|_| ()).map_err(|e| e.to_string())
+            }
+            None => {
+                self.client
+                    .publish(subject, payload.into())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                self.client.flush().await.map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+impl Sink for NatsSink {
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+        Box::pin(async move {
+            let subject = format!(
+                "{}.{}",
+                self.prefix,
+                record.event_type.as_deref().unwrap_or("Unknown")
+            );
+            self.publish(subject, record.to_json().to_string())
+                .await
+                .map_err(|reason| SinkError::Emit {
+                    sink: format!("nats:{}", self.prefix),
+                    reason,
+                })
+        })
+    }
+}
+
+/// Appends each event to a Redis stream with `XADD`, as the fields `type`,
+/// `program`, `signature`, `key` (see `EventRecord::key`) and `event`, the
+/// JSON object. With a `maxlen` the stream is trimmed to about that many
+/// entries, Redis dropping whole nodes past it rather than exactly the oldest.
+#[cfg(feature = "redis")]
+pub struct RedisSink {
+    stream: String,
+    maxlen: Option<usize>,
+    connection: redis::aio::MultiplexedConnection,
+}
+
+#[cfg(feature = "redis")]
+impl RedisSink {
+    pub async fn connect(
+        url: &str,
+        stream: String,
+        maxlen: Option<usize>,
+    ) -> Result<Self, SinkError> {
+        let open_error = |reason: String| SinkError::Open {
+            sink: format!("redis:{stream} at {url}"),
+            reason,
+        };
+        let client = redis::Client::open(url).map_err(|e| open_error(e.to_string()))?;
+        let connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| open_error(e.to_string()))?;
+        Ok(Self {
+            stream,
+            maxlen,
+            connection,
+        })
+    }
+}
+
+#[cfg(feature = "redis")]
+impl Sink for RedisSink {
+    fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+        use redis::streams::StreamMaxlen;
+        use redis::AsyncCommands;
+
+        Box::pin(async move {
+            let fields = [
+                ("type", record.event_type.clone().unwrap_or_default()),
+                ("program", record.program.clone()),
+                ("signature", record.signature.to_string()),
+                ("key", record.key()),
+                ("event", record.to_json().to_string()),
+            ];
+            let added: redis::RedisResult<String> = match self.maxlen {
+                Some(maxlen) => {
+                    self.connection
+                        .xadd_maxlen(&self.stream, StreamMaxlen::Approx(maxlen), "*", &fields)
+                        .await
+                }
+                None => self.connection.xadd(&self.stream, "*", &fields).await,
+            };
+            added.map(|_| ()).map_err(|e| SinkError::Emit {
+                sink: format!("redis:{}", self.stream),
+                reason: e.to_string(),
+            })
+        })
+    }
+}
+
+/// Settings that apply to sinks of a kind rather than to one spec.
+#[derive(Debug, Clone, Default)]
+pub struct SinkOptions {
+    /// Print JSON lines to stdout instead of text.
+    pub json: bool,
+    /// Retries after a webhook's first attempt.
+    pub webhook_retries: u32,
+    /// Where webhooks park events they couldn't deliver.
+    pub dead_letter: Option<PathBuf>,
+    /// Comma-separated `host:port` list the Kafka sinks connect to.
+    pub kafka_brokers: String,
+    /// Server the NATS sinks connect to.
+    pub nats_url: String,
+    /// Publish through JetStream and wait for its acknowledgement.
+    pub nats_jetstream: bool,
+    /// Server the Redis sinks connect to.
+    pub redis_url: String,
+    /// Entries a Redis stream is trimmed to, roughly; `None` keeps them all.
+    pub redis_maxlen: Option<usize>,
+}
+
+/// A sink as given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkSpec {
+    /// `stdout`
+    Stdout,
+    /// `file:<path>`
+    File(PathBuf),
+    /// `webhook:<url>`
+    Webhook(Url),
+    /// `kafka:<topic>`
+    Kafka(String),
+    /// `nats` or `nats:<subject prefix>`
+    Nats(String),
+    /// `redis:<stream key>`
+    Redis(String),
+    /// A `postgres://` or `postgresql://` connection URL
+    Postgres(String),
+    /// `sqlite:<path>`
+    Sqlite(PathBuf),
+}
+
+impl SinkSpec {
+    pub async fn open(&self, options: &SinkOptions) -> Result<Box<dyn Sink>, SinkError> {
+        Ok(match self {
+            SinkSpec::Stdout => Box::new(StdoutSink { json: options.json }),
+            SinkSpec::File(path) => Box::new(FileSink::open(path.clone())?),
+            SinkSpec::Webhook(url) => Box::new(WebhookSink::new(
+                url.clone(),
+                options.webhook_retries,
+                options.dead_letter.clone(),
+            )?),
+            #[cfg(feature = "kafka")]
+            SinkSpec::Kafka(topic) => {
+                Box::new(KafkaSink::new(&options.kafka_brokers, topic.clone())?)
+            }
+            #[cfg(not(feature = "kafka"))]
+            SinkSpec::Kafka(_) => {
+                return Err(SinkError::Open {
+                    sink: self.to_string(),
+                    reason: "built without the `kafka` feature".to_string(),
+                })
+            }
+            #[cfg(feature = "nats")]
+            SinkSpec::Nats(prefix) => Box::new(
+                NatsSink::connect(&options.nats_url, prefix.clone(), options.nats_jetstream)
+                    .await?,
+            ),
+            #[cfg(not(feature = "nats"))]
+            SinkSpec::Nats(_) => {
+                return Err(SinkError::Open {
+                    sink: self.to_string(),
+                    reason: "built without the `nats` feature".to_string(),
+                })
+            }
+            #[cfg(feature = "redis")]
+            SinkSpec::Redis(stream) => Box::new(
+                RedisSink::connect(&options.redis_url, stream.clone(), options.redis_maxlen)
+                    .await?,
+            ),
+            #[cfg(not(feature = "redis"))]
+            SinkSpec::Redis(_) => {
+                return Err(SinkError::Open {
+                    sink: self.to_string(),
+                    reason: "built without the `redis` feature".to_string(),
+                })
+            }
+            #[cfg(feature = "postgres")]
+            SinkSpec::Postgres(url) => Box::new(crate::store::PostgresStore::connect(url).await?),
+            #[cfg(not(feature = "postgres"))]
+            SinkSpec::Postgres(_) => {
+                return Err(SinkError::Open {
+                    sink: self.to_string(),
+                    reason: "built without the `postgres` feature".to_string(),
+                })
+            }
+            #[cfg(feature = "sqlite")]
+            SinkSpec::Sqlite(path) => Box::new(crate::store::SqliteStore::open(path).await?),
+            #[cfg(not(feature = "sqlite"))]
+            SinkSpec::Sqlite(_) => {
+                return Err(SinkError::Open {
+                    sink: self.to_string(),
+                    reason: "built without the `sqlite` feature".to_string(),
+                })
+            }
+        })
+    }
+}
+
+impl FromStr for SinkSpec {
+    type Err = SinkError;
+
+    fn from_str(spec: &str) -> Result<Self, SinkError> {
+        match spec.split_once(':') {
+            None if spec == "stdout" => Ok(SinkSpec::Stdout),
+            None if spec == "nats" => Ok(SinkSpec::Nats(DEFAULT_NATS_PREFIX.to_string())),
+            Some(("file", path)) if !path.is_empty() => Ok(SinkSpec::File(PathBuf::from(path))),
+            Some(("webhook", url)) => {
+                Url::parse(url)
+                    .map(SinkSpec::Webhook)
+                    .map_err(|_| SinkError::InvalidSpec {
+                        spec: spec.to_string(),
+                    })
+            }
+            Some(("kafka", topic)) if !topic.is_empty() => Ok(SinkSpec::Kafka(topic.to_string())),
+            Some(("nats", prefix)) if !prefix.is_empty() => Ok(SinkSpec::Nats(prefix.to_string())),
+            Some(("redis", stream)) if !stream.is_empty() => {
+                Ok(SinkSpec::Redis(stream.to_string()))
+            }
+            Some(("postgres" | "postgresql", rest)) if rest.starts_with("//") => {
+                Ok(SinkSpec::Postgres(spec.to_string()))
+            }
+            Some(("sqlite", path)) if !path.is_empty() => Ok(SinkSpec::Sqlite(PathBuf::from(path))),
+            _ => Err(SinkError::InvalidSpec {
+                spec: spec.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SinkSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinkSpec::Stdout => f.write_str("stdout"),
+            SinkSpec::File(path) => write!(f, "file:{}", path.display()),
+            SinkSpec::Webhook(url) => write!(f, "webhook:{url}"),
+            SinkSpec::Kafka(topic) => write!(f, "kafka:{topic}"),
+            SinkSpec::Nats(prefix) => write!(f, "nats:{prefix}"),
+            SinkSpec::Redis(stream) => write!(f, "redis:{stream}"),
+            SinkSpec::Postgres(url) => f.write_str(&without_password(url)),
+            SinkSpec::Sqlite(path) => write!(f, "sqlite:{}", path.display()),
+        }
+    }
+}
+
+/// `url` with any password masked, to name a database sink in messages.
+fn without_password(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut parsed) if parsed.password().is_some() => {
+            let _ = parsed.set_password(Some("***"));
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
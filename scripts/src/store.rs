@@ -0,0 +1,405 @@
+//! Relational storage of the events `listen` decodes, to reconcile them in SQL
+//! against what other chains saw.
+//!
+//! Every event becomes a row of `events`, with its decoded fields as JSON and
+//! its raw bytes. Contract calls, gateway messages and gas payments also get a
+//! row in their family's table, with the fields as columns; see `family_row`.
+//! Those columns are nullable, so an event whose IDL doesn't match the one
+//! the tables were drawn from still gets its row, with the missing fields NULL.
+//! Rows are keyed by signature and location, so an event delivered again after
+//! a restart is stored once, and a retracted one, whose transaction never
+//! finalized, is deleted from every table. The schema comes from the migrations in
+//! `migrations/`, bundled into the binary and applied on connect. Postgres and
+//! SQLite, for running the listener without any infrastructure, share it.
+
+use serde_json::Value;
+
+use crate::sink::EventRecord;
+
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+/// A row of one of the family tables, after the `signature`, `location` and
+/// `slot` every table starts with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FamilyRow {
+    pub table: &'static str,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub name: &'static str,
+    /// The value as text, numbers in decimal; `None` is NULL.
+    pub value: Option<String>,
+    /// Whether the column holds an amount, which backends with a numeric type
+    /// store as one.
+    pub numeric: bool,
+}
+
+/// The family table row for `record`, or `None` for events only `events`
+/// holds.
+pub fn family_row(record: &EventRecord) -> Option<FamilyRow> {
+    let event_type = record.event_type.as_deref()?;
+    let fields = record.fields.as_ref()?;
+    let field = |name: &'static str| Column {
+        name,
+        value: text(fields.get(name)),
+        numeric: false,
+    };
+    // Anything but a decimal amount is left NULL rather than failing the cast
+    let amount = || Column {
+        name: "amount",
+        value: text(fields.get("amount"))
+            .filter(|amount| !amount.is_empty() && amount.bytes().all(|b| b.is_ascii_digit())),
+        numeric: true,
+    };
+    let (table, columns) = match event_type {
+        "CallContractEvent" | "CallContractWithTokenEvent" => (
+            "contract_calls",
+            vec![
+                given("event_type", event_type),
+                Column {
+                    name: "message_id",
+                    value: record.message_id.map(|id| id.to_string()),
+                    numeric: false,
+                },
+                field("sender"),
+                field("destination_chain"),
+                field("destination_contract_address"),
+                field("payload_hash"),
+                field("payload"),
+                field("symbol"),
+                amount(),
+            ],
+        ),
+        "MessageApprovedEvent" | "MessageExecutedEvent" => (
+            "gateway_messages",
+            vec![
+                given(
+                    "status",
+                    if event_type == "MessageApprovedEvent" {
+                        "approved"
+                    } else {
+                        "executed"
+                    },
+                ),
+                field("command_id"),
+                field("cc_id"),
+                field("source_chain"),
+                field("source_address"),
+                field("destination_chain"),
+                field("destination_address"),
+                field("payload_hash"),
+            ],
+        ),
+        "GasPaidEvent" | "GasAddedEvent" | "GasRefundedEvent" => (
+            "gas_payments",
+            vec![
+                given("event_type", event_type),
+                Column {
+                    name: "message_id",
+                    value: record.pays_for.clone(),
+                    numeric: false,
+                },
+                field("sender"),
+                field("receiver"),
+                amount(),
+                field("refund_address"),
+                field("spl_token_account"),
+                field("destination_chain"),
+                field("destination_address"),
+                field("payload_hash"),
+            ],
+        ),
+        _ => return None,
+    };
+    Some(FamilyRow { table, columns })
+}
+
+fn given(name: &'static str, value: &str) -> Column {
+    Column {
+        name,
+        value: Some(value.to_string()),
+        numeric: false,
+    }
+}
+
+/// A field as a column value: strings as they are, anything else as JSON.
+fn text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// The `INSERT` of `row`, ignoring a row already there, with the placeholder for
+/// the n-th value (from 1) and whether it is numeric given by `placeholder`.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn family_insert(row: &FamilyRow, placeholder: impl Fn(usize, bool) -> String) -> String {
+    let names = ["signature", "location", "slot"]
+        .into_iter()
+        .chain(row.columns.iter().map(|column| column.name))
+        .collect::<Vec<_>>();
+    let values = (1..=3)
+        .map(|n| placeholder(n, false))
+        .chain(
+            row.columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| placeholder(i + 4, column.numeric)),
+        )
+        .collect::<Vec<_>>();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT DO NOTHING",
+        row.table,
+        names.join(", "),
+        values.join(", ")
+    )
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use futures::future::BoxFuture;
+    use serde_json::json;
+    use sqlx::types::Json;
+    use sqlx::PgPool;
+
+    use super::{family_insert, family_row};
+    use crate::error::SinkError;
+    use crate::sink::{EventRecord, Sink};
+
+    static MIGRATIONS: sqlx::migrate::Migrator = sqlx::migrate!("migrations/postgres");
+
+    /// Writes events to Postgres, in a transaction per event.
+    pub struct PostgresStore {
+        pool: PgPool,
+    }
+
+    impl PostgresStore {
+        /// Connects to the database at `url` and brings its schema up to date.
+        pub async fn connect(url: &str) -> Result<Self, SinkError> {
+            let open_error = |reason: String| SinkError::Open {
+                sink: "postgres".to_string(),
+                reason,
+            };
+            let pool = PgPool::connect(url)
+                .await
+                .map_err(|e| open_error(e.to_string()))?;
+            MIGRATIONS
+                .run(&pool)
+                .await
+                .map_err(|e| open_error(format!("migrating: {e}")))?;
+            Ok(Self { pool })
+        }
+
+        pub async fn insert(&self, record: &EventRecord) -> Result<(), sqlx::Error> {
+            let signature = record.signature.to_string();
+            let slot = record.slot as i64;
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO events (signature, location, slot, program, program_id, event_type, \
+                 message_id, event_key, fields, data, gmp) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) ON CONFLICT DO NOTHING",
+            )
+            .bind(&signature)
+            .bind(&record.location)
+            .bind(slot)
+            .bind(&record.program)
+            .bind(record.program_id.to_string())
+            .bind(&record.event_type)
+            .bind(record.message_id.map(|id| id.to_string()))
+            .bind(record.key())
+            .bind(record.fields.as_ref().map(Json))
+            .bind(&record.data)
+            .bind(record.gmp.as_ref().map(|gmp| Json(json!(gmp))))
+            .execute(&mut *tx)
+            .await?;
+            if let Some(row) = family_row(record) {
+                let sql = family_insert(&row, |n, numeric| {
+                    if numeric {
+                        format!("${n}::NUMERIC")
+                    } else {
+                        format!("${n}")
+                    }
+                });
+                let mut query = sqlx::query(&sql)
+                    .bind(&signature)
+                    .bind(&record.location)
+                    .bind(slot);
+                for column in &row.columns {
+                    query = query.bind(&column.value);
+                }
+                query.execute(&mut *tx).await?;
+            }
+            tx.commit().await
+        }
+
+        /// Deletes the rows of a retracted event.
+        pub async fn retract(&self, record: &EventRecord) -> Result<(), sqlx::Error> {
+            let signature = record.signature.to_string();
+            let mut tx = self.pool.begin().await?;
+            // The family row first, as it references the event
+            if let Some(row) = family_row(record) {
+                let sql = format!(
+                    "DELETE FROM {} WHERE signature = $1 AND location = $2",
+                    row.table
+                );
+                sqlx::query(&sql)
+                    .bind(&signature)
+                    .bind(&record.location)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            sqlx::query("DELETE FROM events WHERE signature = $1 AND location = $2")
+                .bind(&signature)
+                .bind(&record.location)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await
+        }
+    }
+
+    impl Sink for PostgresStore {
+        fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+            Box::pin(async move {
+                let stored = if record.retracted {
+                    self.retract(record).await
+                } else {
+                    self.insert(record).await
+                };
+                stored.map_err(|e| SinkError::Emit {
+                    sink: "postgres".to_string(),
+                    reason: e.to_string(),
+                })
+            })
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::path::Path;
+
+    use futures::future::BoxFuture;
+    use serde_json::json;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::types::Json;
+    use sqlx::SqlitePool;
+
+    use super::{family_insert, family_row};
+    use crate::error::SinkError;
+    use crate::sink::{EventRecord, Sink};
+
+    static MIGRATIONS: sqlx::migrate::Migrator = sqlx::migrate!("migrations/sqlite");
+
+    /// Writes events to an SQLite file, in a transaction per event.
+    pub struct SqliteStore {
+        path: String,
+        pool: SqlitePool,
+    }
+
+    impl SqliteStore {
+        /// Opens the database at `path`, creating it if needed, and brings its
+        /// schema up to date.
+        pub async fn open(path: &Path) -> Result<Self, SinkError> {
+            let path = path.display().to_string();
+            let open_error = |reason: String| SinkError::Open {
+                sink: format!("sqlite:{path}"),
+                reason,
+            };
+            let options = SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true)
+                .foreign_keys(true);
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|e| open_error(e.to_string()))?;
+            MIGRATIONS
+                .run(&pool)
+                .await
+                .map_err(|e| open_error(format!("migrating: {e}")))?;
+            Ok(Self { path, pool })
+        }
+
+        pub async fn insert(&self, record: &EventRecord) -> Result<(), sqlx::Error> {
+            let signature = record.signature.to_string();
+            let slot = record.slot as i64;
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO events (signature, location, slot, program, program_id, event_type, \
+                 message_id, event_key, fields, data, gmp) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT DO NOTHING",
+            )
+            .bind(&signature)
+            .bind(&record.location)
+            .bind(slot)
+            .bind(&record.program)
+            .bind(record.program_id.to_string())
+            .bind(&record.event_type)
+            .bind(record.message_id.map(|id| id.to_string()))
+            .bind(record.key())
+            .bind(record.fields.as_ref().map(Json))
+            .bind(&record.data)
+            .bind(record.gmp.as_ref().map(|gmp| Json(json!(gmp))))
+            .execute(&mut *tx)
+            .await?;
+            if let Some(row) = family_row(record) {
+                // Amounts go in as text; the NUMERIC columns convert them
+                let sql = family_insert(&row, |_, _| "?".to_string());
+                let mut query = sqlx::query(&sql)
+                    .bind(&signature)
+                    .bind(&record.location)
+                    .bind(slot);
+                for column in &row.columns {
+                    query = query.bind(&column.value);
+                }
+                query.execute(&mut *tx).await?;
+            }
+            tx.commit().await
+        }
+
+        /// Deletes the rows of a retracted event.
+        pub async fn retract(&self, record: &EventRecord) -> Result<(), sqlx::Error> {
+            let signature = record.signature.to_string();
+            let mut tx = self.pool.begin().await?;
+            // The family row first, as it references the event
+            if let Some(row) = family_row(record) {
+                let sql = format!(
+                    "DELETE FROM {} WHERE signature = ? AND location = ?",
+                    row.table
+                );
+                sqlx::query(&sql)
+                    .bind(&signature)
+                    .bind(&record.location)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            sqlx::query("DELETE FROM events WHERE signature = ? AND location = ?")
+                .bind(&signature)
+                .bind(&record.location)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await
+        }
+    }
+
+    impl Sink for SqliteStore {
+        fn emit<'a>(&'a mut self, record: &'a EventRecord) -> BoxFuture<'a, Result<(), SinkError>> {
+            Box::pin(async move {
+                let stored = if record.retracted {
+                    self.retract(record).await
+                } else {
+                    self.insert(record).await
+                };
+                stored.map_err(|e| SinkError::Emit {
+                    sink: format!("sqlite:{}", self.path),
+                    reason: e.to_string(),
+                })
+            })
+        }
+    }
+}
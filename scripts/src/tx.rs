@@ -0,0 +1,442 @@
+//! Transaction assembly with compute budget instructions.
+//!
+//! `TxBuilder` prepends `set_compute_unit_limit` / `set_compute_unit_price`
+//! when asked to. The limit is either given explicitly or taken from a
+//! simulation of the transaction plus a safety margin, which keeps the fee of a
+//! priority-priced transaction proportional to what it actually uses. The price
+//! is either fixed or follows the fees recently paid to write the same
+//! accounts (`getRecentPrioritizationFees`).
+//!
+//! Transactions are legacy by default and v0 once address lookup tables are
+//! added, for instruction sets that reference more accounts than fit in a
+//! legacy message. With a durable nonce they are signed against the nonce
+//! account's stored blockhash, see `nonce`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, AddressLookupTableAccount, Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::error::ConfigError;
+use crate::rpc::env_parse;
+
+/// The most compute units a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Extra headroom on top of the simulated compute units, in percent.
+const SIMULATION_MARGIN_PERCENT: u64 = 10;
+
+/// Percentile of the recent prioritization fees an automatic price pays.
+const AUTO_PRICE_PERCENTILE: usize = 75;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// Explicit compute unit limit; takes precedence over `simulate`.
+    pub unit_limit: Option<u32>,
+    /// Priority fee in micro-lamports per compute unit; takes precedence over
+    /// `auto_price`.
+    pub unit_price: Option<u64>,
+    /// Derive the limit from a simulation when `unit_limit` isn't set.
+    pub simulate: bool,
+    /// Derive the price from recent prioritization fees when `unit_price`
+    /// isn't set.
+    pub auto_price: bool,
+}
+
+impl ComputeBudget {
+    /// Reads `CU_LIMIT` (compute units or `auto`), `CU_PRICE` (micro-lamports
+    /// or `auto`) and `SIMULATE_CU` (`1`/`true`, the same as `CU_LIMIT=auto`).
+    pub fn from_env() -> Self {
+        let mut budget = Self {
+            simulate: std::env::var("SIMULATE_CU").is_ok_and(|v| v == "1" || v == "true"),
+            ..Self::default()
+        };
+        if let Some(limit) = env_parse("CU_LIMIT") {
+            budget = budget.compute_unit_limit(limit);
+        }
+        if let Some(fee) = env_parse("CU_PRICE") {
+            budget = budget.priority_fee(fee);
+        }
+        budget
+    }
+
+    pub fn compute_unit_limit(mut self, limit: ComputeUnitLimit) -> Self {
+        match limit {
+            ComputeUnitLimit::Units(units) => {
+                self.unit_limit = Some(units);
+                self.simulate = false;
+            }
+            ComputeUnitLimit::Auto => {
+                self.unit_limit = None;
+                self.simulate = true;
+            }
+        }
+        self
+    }
+
+    pub fn priority_fee(mut self, fee: PriorityFee) -> Self {
+        match fee {
+            PriorityFee::MicroLamports(price) => {
+                self.unit_price = Some(price);
+                self.auto_price = false;
+            }
+            PriorityFee::Auto => {
+                self.unit_price = None;
+                self.auto_price = true;
+            }
+        }
+        self
+    }
+}
+
+/// A compute unit price, or `auto` for one picked from recent fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityFee {
+    MicroLamports(u64),
+    Auto,
+}
+
+/// A compute unit limit, or `auto` for one sized by a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputeUnitLimit {
+    Units(u32),
+    Auto,
+}
+
+/// Parsing and formatting for an enum of a number or `auto`, written as a
+/// number or the string `"auto"` in config files.
+macro_rules! number_or_auto {
+    ($name:ident :: $variant:ident($ty:ty), $key:literal, $unit:literal) => {
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $name::$variant(n) => write!(f, "{n}"),
+                    $name::Auto => f.write_str("auto"),
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ConfigError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.eq_ignore_ascii_case("auto") {
+                    return Ok($name::Auto);
+                }
+                s.parse()
+                    .map($name::$variant)
+                    .map_err(|e: std::num::ParseIntError| ConfigError::InvalidValue {
+                        key: $key,
+                        value: s.to_string(),
+                        reason: format!(concat!("expected ", $unit, " or auto: {}"), e),
+                    })
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $name::$variant(n) => n.serialize(serializer),
+                    $name::Auto => serializer.serialize_str("auto"),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum Raw {
+                    Number($ty),
+                    Text(String),
+                }
+                match Raw::deserialize(deserializer)? {
+                    Raw::Number(n) => Ok($name::$variant(n)),
+                    Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+                }
+            }
+        }
+    };
+}
+
+number_or_auto!(
+    PriorityFee::MicroLamports(u64),
+    "priority fee",
+    "micro-lamports"
+);
+number_or_auto!(
+    ComputeUnitLimit::Units(u32),
+    "compute unit limit",
+    "compute units"
+);
+
+pub struct TxBuilder<'a> {
+    payer: &'a Keypair,
+    signers: Vec<&'a Keypair>,
+    ixs: Vec<Instruction>,
+    budget: ComputeBudget,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    versioned: bool,
+    nonce: Option<(Pubkey, &'a Keypair)>,
+}
+
+impl<'a> TxBuilder<'a> {
+    pub fn new(payer: &'a Keypair) -> Self {
+        Self {
+            payer,
+            signers: Vec::new(),
+            ixs: Vec::new(),
+            budget: ComputeBudget::default(),
+            lookup_tables: Vec::new(),
+            versioned: false,
+            nonce: None,
+        }
+    }
+
+    pub fn instruction(mut self, ix: Instruction) -> Self {
+        self.ixs.push(ix);
+        self
+    }
+
+    pub fn instructions(mut self, ixs: impl IntoIterator<Item = Instruction>) -> Self {
+        self.ixs.extend(ixs);
+        self
+    }
+
+    /// Adds a signer besides the fee payer.
+    pub fn signer(mut self, signer: &'a Keypair) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    pub fn compute_budget(mut self, budget: ComputeBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.budget.unit_limit = Some(units);
+        self
+    }
+
+    pub fn compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.budget.unit_price = Some(micro_lamports);
+        self
+    }
+
+    pub fn simulate_compute_units(mut self, simulate: bool) -> Self {
+        self.budget.simulate = simulate;
+        self
+    }
+
+    /// Sends a v0 transaction even without lookup tables.
+    pub fn v0(mut self) -> Self {
+        self.versioned = true;
+        self
+    }
+
+    /// Resolves accounts through `table`, which implies a v0 transaction.
+    pub fn lookup_table(mut self, table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(table);
+        self
+    }
+
+    /// Advances `nonce_account` first thing in the transaction. `build` must
+    /// then be given the nonce's stored blockhash instead of a recent one.
+    pub fn durable_nonce(mut self, nonce_account: Pubkey, authority: &'a Keypair) -> Self {
+        self.nonce = Some((nonce_account, authority));
+        self
+    }
+
+    pub fn payer(&self) -> &'a Keypair {
+        self.payer
+    }
+
+    pub fn nonce_account(&self) -> Option<Pubkey> {
+        self.nonce.map(|(account, _)| account)
+    }
+
+    /// The instructions as they will be sent, compute budget first. `rpc` is
+    /// only used when the limit has to be simulated or the price looked up.
+    pub async fn instructions_with_budget(
+        &self,
+        rpc: &RpcClient,
+    ) -> Result<Vec<Instruction>, ClientError> {
+        let unit_price = match self.budget.unit_price {
+            Some(price) => Some(price),
+            None if self.budget.auto_price => self.recent_unit_price(rpc).await?,
+            None => None,
+        };
+        let unit_limit = match self.budget.unit_limit {
+            Some(limit) => Some(limit),
+            None if self.budget.simulate => self.simulate_units(rpc).await?,
+            None => None,
+        };
+        Ok(self.with_budget(unit_limit, unit_price))
+    }
+
+    /// Builds and signs the transaction against `recent_blockhash`, as a v0
+    /// transaction if lookup tables were added or `v0` was requested.
+    pub async fn build(
+        &self,
+        rpc: &RpcClient,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, ClientError> {
+        let ixs = self.instructions_with_budget(rpc).await?;
+        self.sign(&ixs, recent_blockhash)
+    }
+
+    /// Builds and signs the transaction without the cluster, e.g. on an
+    /// offline machine. Fails when the compute limit is to be simulated or the
+    /// price looked up, as both need the cluster.
+    pub fn build_offline(
+        &self,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, ClientError> {
+        let budget = self.budget;
+        if (budget.simulate && budget.unit_limit.is_none())
+            || (budget.auto_price && budget.unit_price.is_none())
+        {
+            return Err(ClientErrorKind::Custom(
+                "an `auto` compute unit limit or price needs the cluster; give explicit values"
+                    .to_string(),
+            )
+            .into());
+        }
+        let ixs = self.with_budget(budget.unit_limit, budget.unit_price);
+        self.sign(&ixs, recent_blockhash)
+    }
+
+    pub fn is_versioned(&self) -> bool {
+        self.versioned || !self.lookup_tables.is_empty()
+    }
+
+    fn sign(
+        &self,
+        ixs: &[Instruction],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, ClientError> {
+        let message = self.compile(ixs, recent_blockhash)?;
+        let mut signers: Vec<&dyn Signer> = vec![self.payer];
+        signers.extend(self.signers.iter().map(|s| *s as &dyn Signer));
+        if let Some((_, authority)) = self.nonce {
+            if signers.iter().all(|s| s.pubkey() != authority.pubkey()) {
+                signers.push(authority);
+            }
+        }
+        Ok(VersionedTransaction::try_new(message, &signers)?)
+    }
+
+    fn compile(
+        &self,
+        ixs: &[Instruction],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedMessage, ClientError> {
+        let payer = self.payer.pubkey();
+        if !self.is_versioned() {
+            return Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+                ixs,
+                Some(&payer),
+                &recent_blockhash,
+            )));
+        }
+        v0::Message::try_compile(&payer, ixs, &self.lookup_tables, recent_blockhash)
+            .map(VersionedMessage::V0)
+            .map_err(|e| {
+                ClientErrorKind::Custom(format!("failed to compile v0 message: {e}")).into()
+            })
+    }
+
+    fn with_budget(&self, unit_limit: Option<u32>, unit_price: Option<u64>) -> Vec<Instruction> {
+        let mut ixs = Vec::with_capacity(self.ixs.len() + 3);
+        // The runtime only recognizes a nonce transaction by its first instruction
+        if let Some((nonce_account, authority)) = self.nonce {
+            ixs.push(system_instruction::advance_nonce_account(
+                &nonce_account,
+                &authority.pubkey(),
+            ));
+        }
+        if let Some(units) = unit_limit {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(price) = unit_price {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+        ixs.extend(self.ixs.iter().cloned());
+        ixs
+    }
+
+    /// Simulates with the maximum limit and returns the consumed units plus a
+    /// margin. Returns `None` when the simulation fails, leaving the real send
+    /// to surface the error.
+    async fn simulate_units(&self, rpc: &RpcClient) -> Result<Option<u32>, ClientError> {
+        let ixs = self.with_budget(Some(MAX_COMPUTE_UNIT_LIMIT), self.budget.unit_price);
+        // Unsigned; the node neither verifies signatures nor the blockhash here
+        let message = self.compile(&ixs, Hash::default())?;
+        let tx = VersionedTransaction {
+            signatures: vec![
+                Signature::default();
+                message.header().num_required_signatures as usize
+            ],
+            message,
+        };
+        let result = rpc
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(rpc.commitment()),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .value;
+        if result.err.is_some() {
+            return Ok(None);
+        }
+        Ok(result.units_consumed.map(|units| {
+            let with_margin = units + units * SIMULATION_MARGIN_PERCENT / 100;
+            with_margin.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+        }))
+    }
+
+    /// The `AUTO_PRICE_PERCENTILE`th percentile of the fees recently paid in
+    /// transactions writing the same accounts as this one. `None` when those
+    /// paid nothing, so no price instruction is added.
+    async fn recent_unit_price(&self, rpc: &RpcClient) -> Result<Option<u64>, ClientError> {
+        let mut accounts: Vec<Pubkey> = self
+            .ixs
+            .iter()
+            .flat_map(|ix| &ix.accounts)
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .chain([self.payer.pubkey()])
+            .collect();
+        accounts.sort_unstable();
+        accounts.dedup();
+
+        let mut fees: Vec<u64> = rpc
+            .get_recent_prioritization_fees(&accounts)
+            .await?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        fees.sort_unstable();
+        let Some(&fee) = fees.get((fees.len().max(1) - 1) * AUTO_PRICE_PERCENTILE / 100) else {
+            return Ok(None);
+        };
+        Ok((fee > 0).then_some(fee))
+    }
+}
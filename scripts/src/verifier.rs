@@ -0,0 +1,175 @@
+use k256::ecdsa::SigningKey;
+use program_tester::verifier_set::{signed_message_hash, verifier_set_hash};
+use program_tester::VerifierSetLeaf;
+use solana_program::keccak;
+
+use crate::merkle::PayloadMerkleTree;
+
+/// A verifier key derived deterministically from a seed.
+pub struct TestVerifier {
+    pub secret_key: SigningKey,
+    pub weight: u128,
+}
+
+impl TestVerifier {
+    /// Uncompressed secp256k1 public key without the 0x04 prefix, as stored in `VerifierSetLeaf`.
+    pub fn signer_pubkey(&self) -> [u8; 64] {
+        let point = self.secret_key.verifying_key().to_encoded_point(false);
+        let mut out = [0u8; 64];
+        out.copy_from_slice(&point.as_bytes()[1..]);
+        out
+    }
+
+    /// Signs a payload Merkle root, returning the signature and its recovery id.
+    pub fn sign_root(&self, payload_merkle_root: &[u8; 32]) -> ([u8; 64], u8) {
+        let (signature, recovery_id) = self
+            .secret_key
+            .sign_prehash_recoverable(&signed_message_hash(payload_merkle_root))
+            .expect("a 32-byte prehash is always signable");
+        (signature.to_bytes().into(), recovery_id.to_byte())
+    }
+}
+
+/// Derives `count` verifiers with weight 1 from `seed`.
+///
+/// Secret keys are `keccak(seed || index || attempt)`, retrying on the (astronomically
+/// unlikely) out-of-range scalar, so the same seed always yields the same set.
+pub fn generate_verifiers(seed: &[u8], count: u16) -> Vec<TestVerifier> {
+    (0..count)
+        .map(|index| {
+            let mut attempt: u8 = 0;
+            loop {
+                let candidate = keccak::hashv(&[seed, &index.to_le_bytes(), &[attempt]]).0;
+                if let Ok(secret_key) = SigningKey::from_slice(&candidate) {
+                    break TestVerifier {
+                        secret_key,
+                        weight: 1,
+                    };
+                }
+                attempt += 1;
+            }
+        })
+        .collect()
+}
+
+/// Builds the verifier set leaves for `verifiers`, in position order.
+pub fn verifier_set_leaves(
+    verifiers: &[TestVerifier],
+    nonce: u64,
+    quorum: u128,
+    domain_separator: [u8; 32],
+) -> Vec<VerifierSetLeaf> {
+    verifiers
+        .iter()
+        .enumerate()
+        .map(|(position, verifier)| VerifierSetLeaf {
+            nonce,
+            quorum,
+            signer_pubkey: verifier.signer_pubkey(),
+            signer_weight: verifier.weight,
+            position: position as u16,
+            set_size: verifiers.len() as u16,
+            domain_separator,
+        })
+        .collect()
+}
+
+/// One verifier's signature, with what `verify_signature` needs to check it.
+pub struct SignedLeaf {
+    pub leaf: VerifierSetLeaf,
+    pub proof: Vec<u8>,
+    pub signature: [u8; 64],
+    pub recovery_id: u8,
+}
+
+/// A deterministic verifier set: the signing keys together with their leaves.
+pub struct VerifierSetFixture {
+    pub verifiers: Vec<TestVerifier>,
+    pub leaves: Vec<VerifierSetLeaf>,
+}
+
+impl VerifierSetFixture {
+    /// Derives `count` weight-1 verifiers from `seed`, with a quorum of a simple majority.
+    pub fn new(seed: &[u8], count: u16, nonce: u64, domain_separator: [u8; 32]) -> Self {
+        let verifiers = generate_verifiers(seed, count);
+        let quorum = u128::from(count) / 2 + 1;
+        let leaves = verifier_set_leaves(&verifiers, nonce, quorum, domain_separator);
+        Self { verifiers, leaves }
+    }
+
+    /// The verifier set hash as computed by the program.
+    pub fn hash(&self) -> [u8; 32] {
+        verifier_set_hash(&self.leaves)
+    }
+
+    /// The Merkle proof of the leaf at `position`, as `verify_signature` takes it.
+    pub fn proof(&self, position: u16) -> Vec<u8> {
+        let leaf_hashes = self.leaves.iter().map(VerifierSetLeaf::hash).collect();
+        PayloadMerkleTree::from_leaf_hashes(leaf_hashes)
+            .and_then(|tree| tree.proof(position as usize))
+            .unwrap_or_default()
+    }
+
+    /// Signs `payload_merkle_root` with every verifier, pairing each signature and
+    /// recovery id with the signer's leaf and its Merkle proof.
+    pub fn sign_root(&self, payload_merkle_root: &[u8; 32]) -> Vec<SignedLeaf> {
+        self.verifiers
+            .iter()
+            .zip(&self.leaves)
+            .map(|(verifier, leaf)| {
+                let (signature, recovery_id) = verifier.sign_root(payload_merkle_root);
+                SignedLeaf {
+                    leaf: leaf.clone(),
+                    proof: self.proof(leaf.position),
+                    signature,
+                    recovery_id,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use program_tester::verifier_set::verify_proof;
+    use solana_program::secp256k1_recover::secp256k1_recover;
+
+    use super::*;
+
+    #[test]
+    fn fixture_proofs_verify_on_chain() {
+        for count in 1..=5 {
+            let fixture = VerifierSetFixture::new(b"tests", count, 0, [0u8; 32]);
+            let root = fixture.hash();
+            for leaf in &fixture.leaves {
+                let proof = fixture.proof(leaf.position);
+                assert!(
+                    verify_proof(&leaf.hash(), &proof, leaf.position, count, &root),
+                    "leaf {} of {count}",
+                    leaf.position
+                );
+                // Another position or set size walks a different path
+                if count > 1 {
+                    let other = (leaf.position + 1) % count;
+                    assert!(!verify_proof(&leaf.hash(), &proof, other, count, &root));
+                }
+                assert!(!verify_proof(&leaf.hash(), &proof, count, count, &root));
+            }
+        }
+    }
+
+    #[test]
+    fn signatures_recover_to_the_signer() {
+        let fixture = VerifierSetFixture::new(b"tests", 3, 0, [0u8; 32]);
+        let root = [5u8; 32];
+        for signed in fixture.sign_root(&root) {
+            let recovered = secp256k1_recover(
+                &signed_message_hash(&root),
+                signed.recovery_id,
+                &signed.signature,
+            )
+            .unwrap();
+            assert_eq!(recovered.to_bytes(), signed.leaf.signer_pubkey);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Round-trip checks of sent transactions: the events a transaction emitted
+//! must carry the arguments of the instructions that emitted them.
+//!
+//! Each instruction is decoded with the program's own instruction structs and
+//! turned into the fields its event should hold, encoded as `ToJson` does.
+//! Fields the program takes from accounts rather than arguments (the sender,
+//! the previous operator) are not checked. Instructions that emit nothing,
+//! like the `init_*` ones, are skipped.
+
+use std::str::FromStr;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use gas_service::instruction as gas;
+use program_tester::instruction as gateway;
+use serde_json::{Map, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::error::{Error, VerifyError};
+use crate::events::{DecodedEvent, ToJson};
+use crate::receipt::{fetch_receipt, Receipt};
+
+/// The event an instruction should emit and the fields that follow from its
+/// arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedEvent {
+    pub name: &'static str,
+    pub fields: Map<String, Value>,
+}
+
+macro_rules! fields {
+    ($($key:ident: $value:expr),* $(,)?) => {{
+        let mut fields = Map::new();
+        $(fields.insert(stringify!($key).to_string(), $value.to_json());)*
+        fields
+    }};
+}
+
+/// The event `ix` should emit, or `None` if it emits none or isn't an
+/// instruction of the dummy programs.
+pub fn expected_event(ix: &Instruction) -> Option<ExpectedEvent> {
+    let data = ix.data.as_slice();
+    // gas_service's transfer_operatorship shares the discriminator and args
+    let (name, fields) = if let Some(ix) = decode::<gateway::CallContract>(data) {
+        (
+            "CallContractEvent",
+            fields! {
+                destination_chain: ix.destination_chain,
+                destination_contract_address: ix.destination_contract_address,
+                payload_hash: ix.payload_hash,
+                payload: ix.payload,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::CallContractWithToken>(data) {
+        (
+            "CallContractWithTokenEvent",
+            fields! {
+                destination_chain: ix.destination_chain,
+                destination_contract_address: ix.destination_contract_address,
+                payload_hash: ix.payload_hash,
+                payload: ix.payload,
+                symbol: ix.symbol,
+                amount: ix.amount,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::CallContractWithItsPayload>(data) {
+        // The payload is ABI-encoded on-chain
+        (
+            "CallContractEvent",
+            fields! {
+                destination_chain: ix.destination_chain,
+                destination_contract_address: ix.destination_contract_address,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::ApproveMessage>(data) {
+        let message = ix.message.leaf.message;
+        (
+            "MessageApprovedEvent",
+            fields! {
+                command_id: message.command_id(),
+                destination_address: Pubkey::from_str(&message.destination_address).ok()?,
+                payload_hash: message.payload_hash,
+                source_chain: message.cc_id.chain,
+                cc_id: message.cc_id.id,
+                source_address: message.source_address,
+                destination_chain: message.destination_chain,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::ExecuteMessage>(data) {
+        (
+            "MessageExecutedEvent",
+            fields! {
+                command_id: ix.command_id,
+                destination_address: Pubkey::from_str(&ix.destination_address).ok()?,
+                payload_hash: ix.payload_hash,
+                source_chain: ix.source_chain,
+                cc_id: ix.cc_id,
+                source_address: ix.source_address,
+                destination_chain: ix.destination_chain,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::TransferOperatorship>(data) {
+        (
+            "OperatorshipTransferredEvent",
+            fields! { new_operator: ix.new_operator },
+        )
+    } else if let Some(ix) = decode::<gateway::InterchainTransfer>(data) {
+        (
+            "InterchainTransfer",
+            fields! {
+                token_id: ix.token_id,
+                source_address: ix.source_address,
+                source_token_account: ix.source_token_account,
+                destination_chain: ix.destination_chain,
+                destination_address: ix.destination_address,
+                amount: ix.amount,
+                data_hash: ix.data_hash,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::LinkTokenStarted>(data) {
+        (
+            "LinkTokenStarted",
+            fields! {
+                token_id: ix.token_id,
+                destination_chain: ix.destination_chain,
+                source_token_address: ix.source_token_address,
+                destination_token_address: ix.destination_token_address,
+                token_manager_type: ix.token_manager_type,
+                params: ix.params,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::InterchainTokenDeploymentStarted>(data) {
+        (
+            "InterchainTokenDeploymentStarted",
+            fields! {
+                token_id: ix.token_id,
+                token_name: ix.token_name,
+                token_symbol: ix.token_symbol,
+                token_decimals: ix.token_decimals,
+                minter: ix.minter,
+                destination_chain: ix.destination_chain,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::TokenMetadataRegistered>(data) {
+        (
+            "TokenMetadataRegistered",
+            fields! {
+                token_address: ix.token_address,
+                decimals: ix.decimals,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::BurnCompute>(data) {
+        ("ComputeBurnedEvent", fields! { iterations: ix.iterations })
+    } else if let Some(ix) = decode::<gateway::SignersRotated>(data) {
+        (
+            "VerifierSetRotatedEvent",
+            fields! {
+                epoch: ix.epoch_le,
+                verifier_set_hash: ix.verifier_set_hash,
+            },
+        )
+    } else if let Some(ix) = decode::<gateway::RegisterVerifierSet>(data) {
+        // The epoch is the gateway's next one, not an argument
+        (
+            "VerifierSetRotatedEvent",
+            fields! { verifier_set_hash: ix.verifier_set_hash },
+        )
+    } else if let Some(ix) = decode::<gas::PayNativeForContractCall>(data) {
+        (
+            "GasPaidEvent",
+            fields! {
+                destination_chain: ix.destination_chain,
+                destination_address: ix.destination_address,
+                payload_hash: ix.payload_hash,
+                amount: ix.amount,
+                refund_address: ix.refund_address,
+            },
+        )
+    } else if let Some(ix) = decode::<gas::AddNativeGas>(data) {
+        (
+            "GasAddedEvent",
+            fields! {
+                message_id: ix.message_id,
+                amount: ix.amount,
+                refund_address: ix.refund_address,
+            },
+        )
+    } else if let Some(ix) = decode::<gas::RefundNativeFees>(data) {
+        (
+            "GasRefundedEvent",
+            fields! {
+                message_id: ix.message_id,
+                amount: ix.amount,
+            },
+        )
+    } else {
+        return None;
+    };
+    Some(ExpectedEvent { name, fields })
+}
+
+/// Checks that `events` include, in order, the event each of `ixs` should
+/// emit with the fields it was sent with. Returns what didn't match.
+pub fn mismatches<'a>(
+    ixs: &[Instruction],
+    events: impl IntoIterator<Item = &'a DecodedEvent>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut events = events.into_iter();
+    for expected in ixs.iter().filter_map(expected_event) {
+        let Some(event) = events.by_ref().find(|e| e.name() == expected.name) else {
+            problems.push(format!("no {} emitted", expected.name));
+            continue;
+        };
+        let actual = event.to_json();
+        for (key, sent) in &expected.fields {
+            let emitted = actual.get(key).unwrap_or(&Value::Null);
+            if emitted != sent {
+                problems.push(format!(
+                    "{}.{}: sent {}, emitted {}",
+                    expected.name, key, sent, emitted
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Checks a confirmed transaction's events against the instructions it was
+/// sent with, see `mismatches`. Events that failed to decode count as a
+/// mismatch too.
+pub fn verify(ixs: &[Instruction], receipt: &Receipt) -> Result<(), VerifyError> {
+    let mut problems = mismatches(ixs, receipt.events.iter().map(|e| &e.event));
+    for message_id in &receipt.undecoded {
+        problems.push(format!("event {} did not decode", message_id));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(VerifyError {
+            signature: receipt.signature,
+            problems,
+        })
+    }
+}
+
+/// Fetches the transaction `signature`, sent with `ixs`, and verifies it; for
+/// binaries that send with `send_ix` rather than through a report.
+pub async fn fetch_and_verify(
+    rpc: &RpcClient,
+    signature: &Signature,
+    ixs: &[Instruction],
+) -> Result<Receipt, Error> {
+    let receipt = fetch_receipt(rpc, signature).await?;
+    verify(ixs, &receipt)?;
+    Ok(receipt)
+}
+
+/// Decodes instruction data of type `T`, or `None` for another instruction.
+fn decode<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    let mut args = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut args).ok()
+}
@@ -0,0 +1,74 @@
+//! Typed instruction builders for relayer_whitelist.
+//!
+//! The whitelist and event-authority PDAs are derived from `program_id`, so
+//! callers only supply the signers and instruction arguments.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use relayer_whitelist::{accounts, instruction};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pdas::{event_authority_pda, relayer_whitelist_pda};
+
+fn build_ix(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `authority` must be the program's upgrade authority, and becomes the
+/// whitelist's authority.
+pub fn build_init_whitelist_ix(program_id: &Pubkey, authority: &Pubkey) -> Instruction {
+    build_ix(
+        program_id,
+        accounts::InitWhitelist {
+            authority: *authority,
+            whitelist_pda: relayer_whitelist_pda(program_id).0,
+            this_program: *program_id,
+            program_data: bpf_loader_upgradeable::get_program_data_address(program_id),
+            system_program: system_program::id(),
+        },
+        instruction::InitWhitelist {},
+    )
+}
+
+fn update_accounts(program_id: &Pubkey, authority: &Pubkey) -> accounts::UpdateWhitelist {
+    accounts::UpdateWhitelist {
+        authority: *authority,
+        whitelist_pda: relayer_whitelist_pda(program_id).0,
+        event_authority: event_authority_pda(program_id).0,
+        program: *program_id,
+    }
+}
+
+pub fn build_add_relayer_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    relayer: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        update_accounts(program_id, authority),
+        instruction::AddRelayer { relayer },
+    )
+}
+
+pub fn build_remove_relayer_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    relayer: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_id,
+        update_accounts(program_id, authority),
+        instruction::RemoveRelayer { relayer },
+    )
+}